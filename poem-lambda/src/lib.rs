@@ -115,7 +115,7 @@ fn from_lambda_request(req: LambdaRequest) -> Request {
 #[poem::async_trait]
 impl<'a> FromRequest<'a> for &'a Context {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
-        let ctx = match req.extensions().get::<Context>() {
+        let ctx = match req.data::<Context>() {
             Some(ctx) => ctx,
             None => panic!("Lambda runtime is required."),
         };
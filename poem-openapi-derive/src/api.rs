@@ -1,18 +1,21 @@
 use darling::{util::SpannedValue, FromMeta};
 use indexmap::IndexMap;
+use inflector::Inflector;
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
 use syn::{
-    ext::IdentExt, visit_mut::VisitMut, AttributeArgs, Error, FnArg, ImplItem, ImplItemMethod,
-    ItemImpl, Pat, Path, ReturnType,
+    ext::IdentExt, parse_quote, visit_mut::VisitMut, AttributeArgs, Error, FnArg, GenericParam,
+    ImplItem, ImplItemMethod, ItemImpl, Pat, Path, ReturnType, Type,
 };
 
 use crate::{
-    common_args::{APIMethod, DefaultValue, ExternalDocument},
+    common_args::{APIMethod, APIPriority, DefaultValue, ExternalDocument},
     error::GeneratorResult,
+    transforms::Transforms,
     utils::{
-        convert_oai_path, get_crate_name, get_description, get_summary_and_description,
-        optional_literal, parse_oai_attrs, remove_description, remove_oai_attrs, RemoveLifetime,
+        convert_oai_path, get_cfg_predicate, get_crate_name, get_description,
+        get_summary_and_description, optional_literal, parse_oai_attrs, remove_description,
+        remove_oai_attrs, RemoveLifetime,
     },
     validators::Validators,
 };
@@ -25,6 +28,8 @@ struct APIArgs {
     prefix_path: Option<SpannedValue<String>>,
     #[darling(default, multiple, rename = "tag")]
     common_tags: Vec<Path>,
+    #[darling(default)]
+    request_builders: bool,
 }
 
 #[derive(FromMeta)]
@@ -41,6 +46,12 @@ struct APIOperation {
     operation_id: Option<String>,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default, multiple, rename = "require_permission")]
+    require_permissions: Vec<String>,
+    #[darling(default)]
+    priority: Option<APIPriority>,
+    #[darling(default)]
+    delegate: Option<Type>,
 }
 
 #[derive(FromMeta, Default)]
@@ -54,16 +65,63 @@ struct APIOperationParam {
     default: Option<DefaultValue>,
     #[darling(default)]
     validator: Option<Validators>,
+    #[darling(default)]
+    transform: Option<Transforms>,
 
     // for oauth
     #[darling(multiple, default, rename = "scope")]
     scopes: Vec<Path>,
 }
 
+/// The generated code of an operation, with the predicate of its `#[cfg(...)]`
+/// attributes.
+struct CfgItem {
+    cfg: Option<TokenStream>,
+    item: TokenStream,
+}
+
+impl CfgItem {
+    fn attr(&self) -> Option<TokenStream> {
+        self.cfg.as_ref().map(|cfg| quote!(#[cfg(#cfg)]))
+    }
+}
+
+/// Returns the `#[cfg(...)]` attribute that is enabled if any of the items is
+/// enabled.
+fn any_cfg_attr<'a>(items: impl IntoIterator<Item = &'a CfgItem>) -> Option<TokenStream> {
+    let mut predicates = Vec::new();
+    for item in items {
+        predicates.push(item.cfg.as_ref()?);
+    }
+    Some(quote!(#[cfg(any(#(#predicates),*))]))
+}
+
 struct Context {
-    add_routes: IndexMap<String, IndexMap<APIMethod, TokenStream>>,
-    operations: IndexMap<String, Vec<TokenStream>>,
+    add_routes: IndexMap<String, IndexMap<(APIMethod, String), CfgItem>>,
+    operations: IndexMap<String, Vec<CfgItem>>,
     register_items: Vec<TokenStream>,
+    request_builders: Vec<TokenStream>,
+}
+
+/// The arguments that are sent by the request builders, they are recognized by
+/// the names of their types.
+enum RequestArg {
+    Param,
+    Body,
+}
+
+fn request_arg(ty: &Type) -> Option<RequestArg> {
+    let name = match ty {
+        Type::Path(ty) => ty.path.segments.last()?.ident.to_string(),
+        _ => return None,
+    };
+    match name.as_str() {
+        "Path" | "Query" | "Header" | "Cookie" | "CookiePrivate" | "CookieSigned" => {
+            Some(RequestArg::Param)
+        }
+        "Json" | "PlainText" | "Binary" => Some(RequestArg::Body),
+        _ => None,
+    }
 }
 
 pub(crate) fn generate(
@@ -80,8 +138,17 @@ pub(crate) fn generate(
         add_routes: Default::default(),
         operations: Default::default(),
         register_items: Default::default(),
+        request_builders: Default::default(),
     };
 
+    if api_args.request_builders && !item_impl.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &item_impl.generics,
+            "The request builders cannot be generated for the generic APIs.",
+        )
+        .into());
+    }
+
     for item in &mut item_impl.items {
         if let ImplItem::Method(method) = item {
             if let Some(operation_args) = parse_oai_attrs::<APIOperation>(&method.attrs)? {
@@ -91,6 +158,24 @@ pub(crate) fn generate(
                     );
                 }
 
+                // The routes are created for the types known by the impl block.
+                let generic_method = method
+                    .sig
+                    .generics
+                    .params
+                    .iter()
+                    .any(|param| !matches!(param, GenericParam::Lifetime(_)))
+                    || method.sig.inputs.iter().any(|arg| {
+                        matches!(arg, FnArg::Typed(pat) if matches!(&*pat.ty, Type::ImplTrait(_)))
+                    });
+                if generic_method {
+                    return Err(Error::new_spanned(
+                        &method.sig.ident,
+                        "The operations cannot be generic, use the generic parameters of the impl block instead.",
+                    )
+                    .into());
+                }
+
                 generate_operation(&mut ctx, &crate_name, &api_args, operation_args, method)?;
                 remove_oai_attrs(&mut method.attrs);
             }
@@ -101,17 +186,28 @@ pub(crate) fn generate(
         add_routes,
         operations,
         register_items,
+        request_builders,
     } = ctx;
 
     let paths = {
         let mut paths = Vec::new();
 
         for (path, operation) in operations {
+            let cfg = any_cfg_attr(&operation);
+            let (operation_cfg, operation): (Vec<_>, Vec<_>) = operation
+                .iter()
+                .map(|operation| (operation.attr(), &operation.item))
+                .unzip();
             paths.push(quote! {
-                #crate_name::registry::MetaPath {
+                #cfg
+                paths.push(#crate_name::registry::MetaPath {
                     path: #path,
-                    operations: ::std::vec![#(#operation),*],
-                }
+                    operations: {
+                        let mut operations = ::std::vec::Vec::new();
+                        #(#operation_cfg operations.push(#operation);)*
+                        operations
+                    },
+                });
             });
         }
         paths
@@ -121,23 +217,43 @@ pub(crate) fn generate(
         let mut routes = Vec::new();
 
         for (path, add_route) in add_routes {
-            let add_route = add_route.values();
+            let cfg = any_cfg_attr(add_route.values());
+            let (add_route_cfg, add_route): (Vec<_>, Vec<_>) = add_route
+                .values()
+                .map(|add_route| (add_route.attr(), &add_route.item))
+                .unzip();
             routes.push(quote! {
-                at(#path, #crate_name::__private::poem::RouteMethod::new()#(.#add_route)*)
+                #cfg
+                let route = route.at(#path, {
+                    let route_method = #crate_name::__private::poem::RouteMethod::new();
+                    #(#add_route_cfg let route_method = route_method.#add_route;)*
+                    route_method
+                });
             });
         }
 
         routes
     };
 
+    // The API object is shared by the routes.
+    let mut generics = item_impl.generics.clone();
+    if !generics.params.is_empty() {
+        generics.make_where_clause().predicates.push(parse_quote! {
+            Self: ::std::marker::Send + ::std::marker::Sync + 'static
+        });
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
     let expanded = quote! {
         #item_impl
 
-        impl #crate_name::OpenApi for #ident {
+        #(#request_builders)*
+
+        impl #impl_generics #crate_name::OpenApi for #ident #where_clause {
             fn meta() -> ::std::vec::Vec<#crate_name::registry::MetaApi> {
-                ::std::vec![#crate_name::registry::MetaApi {
-                    paths: ::std::vec![#(#paths),*],
-                }]
+                let mut paths = ::std::vec::Vec::new();
+                #(#paths)*
+                ::std::vec![#crate_name::registry::MetaApi { paths }]
             }
 
             fn register(registry: &mut #crate_name::registry::Registry) {
@@ -145,8 +261,17 @@ pub(crate) fn generate(
             }
 
             fn add_routes(self, route: #crate_name::__private::poem::Route) -> #crate_name::__private::poem::Route {
+                #crate_name::OpenApi::add_routes_with(self, route, &|_, ep| ep)
+            }
+
+            fn add_routes_with(
+                self,
+                route: #crate_name::__private::poem::Route,
+                operation_wrapper: &#crate_name::__private::OperationWrapper<'_>,
+            ) -> #crate_name::__private::poem::Route {
                 let api_obj = ::std::sync::Arc::new(self);
-                route #(.#routes)*
+                #(#routes)*
+                route
             }
         }
     };
@@ -169,9 +294,16 @@ fn generate_operation(
         transform,
         operation_id,
         external_docs,
+        require_permissions,
+        priority,
+        delegate,
     } = args;
     let http_method = method.to_http_method();
     let fn_ident = &item_method.sig.ident;
+    // The operations that are disabled by `#[cfg(...)]` are neither routed nor
+    // documented.
+    let cfg = get_cfg_predicate(&item_method.attrs);
+    let register_start = ctx.register_items.len();
     let (summary, description) = get_summary_and_description(&item_method.attrs)?;
     let summary = optional_literal(&summary);
     let description = optional_literal(&description);
@@ -203,6 +335,36 @@ fn generate_operation(
         .into());
     }
 
+    // The delegated operation calls the method of the same name on the trait
+    // object in the request data, with the extracted arguments.
+    if let Some(delegate) = &delegate {
+        if !item_method.block.stmts.is_empty() {
+            return Err(Error::new_spanned(
+                &item_method.block,
+                "The body of a delegated operation must be empty.",
+            )
+            .into());
+        }
+        let args = item_method
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pat) => match &*pat.pat {
+                    Pat::Ident(ident) => Some(ident.ident.clone()),
+                    _ => None,
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .collect::<Vec<_>>();
+        item_method.sig.inputs.push(parse_quote! {
+            __delegate: #crate_name::__private::poem::web::Data<&::std::sync::Arc<#delegate>>
+        });
+        item_method.block = parse_quote!({
+            __delegate.0.#fn_ident(#(#args),*).await
+        });
+    }
+
     let mut res_ty = match &item_method.sig.output {
         ReturnType::Default => Box::new(syn::parse2(quote!(())).unwrap()),
         ReturnType::Type(_, ty) => ty.clone(),
@@ -211,9 +373,14 @@ fn generate_operation(
 
     let mut parse_args = Vec::new();
     let mut use_args = Vec::new();
+    let mut arg_tys = Vec::new();
     let mut request_meta = Vec::new();
+    let mut request_responses = Vec::new();
     let mut params_meta = Vec::new();
     let mut security = Vec::new();
+    let mut builder_params = Vec::new();
+    let mut builder_setters = Vec::new();
+    let mut builder_body_required = false;
 
     for i in 1..item_method.sig.inputs.len() {
         let arg = &mut item_method.sig.inputs[i];
@@ -244,6 +411,7 @@ fn generate_operation(
             .clone()
             .unwrap_or_else(|| arg_ident.unraw().to_string());
         use_args.push(pname.clone());
+        arg_tys.push(arg_ty.clone());
 
         // register
         ctx.register_items.push(quote! {
@@ -261,12 +429,47 @@ fn generate_operation(
             None => quote!(::std::option::Option::None),
         };
         let has_default = operation_param.default.is_some();
+
+        // request builder
+        let setter_ident =
+            format_ident!("{}", arg_ident.unraw().to_string().trim_start_matches('_'));
+        match request_arg(&arg_ty) {
+            Some(RequestArg::Param) => {
+                let idx = builder_params.len();
+                let doc = format!("Sets the `{}` parameter.", param_name);
+                builder_params.push(quote! {
+                    #crate_name::__private::RequestParam {
+                        name: <#arg_ty as #crate_name::ApiExtractor>::param_name().unwrap_or(#param_name),
+                        in_type: <#arg_ty as #crate_name::ApiExtractor>::param_in(),
+                        required: <#arg_ty as #crate_name::ApiExtractor>::PARAM_IS_REQUIRED && !#has_default,
+                    }
+                });
+                builder_setters.push(quote! {
+                    #[doc = #doc]
+                    #[must_use]
+                    pub fn #setter_ident(self, value: impl ::std::convert::Into<<#arg_ty as #crate_name::ApiExtractor<'static>>::ParamType>) -> Self {
+                        Self(self.0.param(#idx, ::std::convert::Into::into(value)))
+                    }
+                });
+            }
+            Some(RequestArg::Body) => {
+                builder_body_required = true;
+                builder_setters.push(quote! {
+                    /// Sets the body.
+                    #[must_use]
+                    pub fn #setter_ident(self, value: #arg_ty) -> Self {
+                        Self(self.0.body(value))
+                    }
+                });
+            }
+            None => {}
+        }
         let param_meta_default = match &operation_param.default {
             Some(DefaultValue::Default) => {
                 quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&<#arg_ty as ::std::default::Default>::default())))
             }
             Some(DefaultValue::Function(func_name)) => {
-                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&#func_name())))
+                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&(#func_name)())))
             }
             None => quote!(::std::option::Option::None),
         };
@@ -284,6 +487,13 @@ fn generate_operation(
         }).unwrap_or_default();
         let validators_update_meta = validator.create_update_meta(crate_name)?;
 
+        // transform
+        let transformer = operation_param
+            .transform
+            .clone()
+            .unwrap_or_default()
+            .create_transformer(crate_name, &pname);
+
         // do extract
         parse_args.push(quote! {
             let mut param_opts = #crate_name::ExtractParamOptions {
@@ -294,12 +504,14 @@ fn generate_operation(
             let #pname = match <#arg_ty as #crate_name::ApiExtractor>::from_request(&request, &mut body, param_opts).await {
                 ::std::result::Result::Ok(value) => value,
                 ::std::result::Result::Err(err) if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER => {
+                    let err = #crate_name::__private::rewrite_error_message(&request, err);
                     let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err);
                     let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
                     return ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response);
                 }
-                ::std::result::Result::Err(err) => return ::std::result::Result::Err(::std::convert::Into::into(err)),
+                ::std::result::Result::Err(err) => return ::std::result::Result::Err(#crate_name::__private::rewrite_error_message(&request, err)),
             };
+            #transformer
             #param_checker
         });
 
@@ -318,7 +530,7 @@ fn generate_operation(
                 };
 
                 let meta_param = #crate_name::registry::MetaOperationParam {
-                    name: #param_name,
+                    name: <#arg_ty as #crate_name::ApiExtractor>::param_name().unwrap_or(#param_name),
                     schema: original_schema.merge(patch_schema),
                     in_type: <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
                     description: #param_desc,
@@ -327,6 +539,25 @@ fn generate_operation(
                 };
                 params.push(meta_param);
             }
+
+            if <#arg_ty as #crate_name::ApiExtractor>::TYPE == #crate_name::ApiExtractorType::SecurityScheme {
+                ::std::iter::Extend::extend(&mut params, <#arg_ty as #crate_name::ApiExtractor>::security_params());
+            }
+
+            if <#arg_ty as #crate_name::ApiExtractor>::TYPE == #crate_name::ApiExtractorType::RequestObject {
+                ::std::iter::Extend::extend(&mut params, <#arg_ty as #crate_name::ApiExtractor>::request_params());
+            }
+        });
+
+        // request object responses
+        request_responses.push(quote! {
+            if <#arg_ty as #crate_name::ApiExtractor>::TYPE == #crate_name::ApiExtractorType::RequestObject {
+                for resp in <#arg_ty as #crate_name::ApiExtractor>::request_responses() {
+                    if !::std::iter::Iterator::any(&mut ::std::iter::IntoIterator::into_iter(&responses.responses), |r| r.status == resp.status) {
+                        responses.responses.push(resp);
+                    }
+                }
+            }
         });
 
         // request object meta
@@ -350,13 +581,119 @@ fn generate_operation(
     ctx.register_items
         .push(quote!(<#res_ty as #crate_name::ApiResponse>::register(registry);));
 
+    // permissions
+    let (check_permissions, forbidden_response) = if require_permissions.is_empty() {
+        (None, None)
+    } else {
+        let description = format!(
+            "Forbidden, requires the permissions: {}",
+            require_permissions.join(", ")
+        );
+        let extractors = arg_tys.iter().rev().fold(
+            quote!(()),
+            |extractors, arg_ty| quote!((#arg_ty, #extractors)),
+        );
+        let assert_security_scheme = quote_spanned! {fn_ident.span()=>
+            let () = #crate_name::__private::AssertSecurityScheme::<#extractors>::REQUIRE_PERMISSION_MUST_HAVE_A_SECURITY_SCHEME;
+        };
+        (
+            Some(quote! {
+                #assert_security_scheme
+                #crate_name::__private::check_permissions(
+                    &[#(#crate_name::ApiExtractor::permissions(&#use_args)),*],
+                    &[#(#require_permissions),*],
+                )?;
+            }),
+            Some(quote! {
+                if !::std::iter::Iterator::any(&mut ::std::iter::IntoIterator::into_iter(&responses.responses), |resp| resp.status == ::std::option::Option::Some(403)) {
+                    responses.responses.push(#crate_name::registry::MetaResponse {
+                        description: #description,
+                        status: ::std::option::Option::Some(403),
+                        content: ::std::vec![],
+                        headers: ::std::vec![],
+                    });
+                }
+            }),
+        )
+    };
+    let responses = quote! {{
+        let mut responses = <#res_ty as #crate_name::ApiResponse>::meta();
+        #(#request_responses)*
+        #forbidden_response
+        responses
+    }};
+
     let transform = transform.map(|transform| {
         quote! {
             let ep = #transform(ep);
         }
     });
 
-    if ctx.add_routes.entry(new_path).or_default().insert(*method, quote! {
+    let mut tag_names = Vec::new();
+    for tag in tags {
+        ctx.register_items
+            .push(quote!(#crate_name::Tags::register(&#tag, registry);));
+        tag_names.push(quote!(#crate_name::Tags::name(&#tag)));
+    }
+    let operation_id = optional_literal(&operation_id);
+
+    if api_args.request_builders {
+        let cfg_attr = cfg.as_ref().map(|cfg| quote!(#[cfg(#cfg)]));
+        let builder_ident =
+            format_ident!("{}Request", fn_ident.unraw().to_string().to_pascal_case());
+        let doc = format!(
+            "Builds the requests to the `{}` operation, `{} {}`.",
+            fn_ident.unraw(),
+            http_method,
+            oai_path
+        );
+        ctx.request_builders.push(quote! {
+            #cfg_attr
+            #[doc = #doc]
+            #[derive(::std::fmt::Debug, ::std::clone::Clone)]
+            pub struct #builder_ident(#crate_name::__private::RequestBuilder);
+
+            #cfg_attr
+            impl #builder_ident {
+                /// Create a request builder, the URI of the request is relative
+                /// by default.
+                pub fn new() -> Self {
+                    Self(#crate_name::__private::RequestBuilder::new(
+                        #crate_name::__private::poem::http::Method::#http_method,
+                        #oai_path,
+                        ::std::vec![#(#builder_params),*],
+                        #builder_body_required,
+                    ))
+                }
+
+                /// Sets the URL of the server that the path is appended to.
+                #[must_use]
+                pub fn server(self, url: impl ::std::convert::Into<::std::string::String>) -> Self {
+                    Self(self.0.server(url))
+                }
+
+                #(#builder_setters)*
+
+                /// Consumes this builder and returns the request.
+                pub fn build(self) -> ::std::result::Result<
+                    #crate_name::__private::poem::http::Request<::std::vec::Vec<u8>>,
+                    #crate_name::error::BuildRequestError,
+                > {
+                    self.0.build()
+                }
+            }
+
+            #cfg_attr
+            impl ::std::default::Default for #builder_ident {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        });
+    }
+
+    let cfg_key = cfg.as_ref().map(ToString::to_string).unwrap_or_default();
+    let add_route = quote! {
         method(#crate_name::__private::poem::http::Method::#http_method, {
             let api_obj = ::std::clone::Clone::clone(&api_obj);
             let ep = #crate_name::__private::poem::endpoint::make(move |request| {
@@ -364,25 +701,40 @@ fn generate_operation(
                 async move {
                     let (request, mut body) = request.split();
                     #(#parse_args)*
+                    #crate_name::__private::record_principal(&request, || ::std::vec![#(#crate_name::ApiExtractor::principal(&#use_args)),*]);
+                    #check_permissions
                     let res = api_obj.#fn_ident(#(#use_args),*).await;
                     let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
                     ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response)
                 }
             });
             #transform
-            ep
+            #crate_name::__private::operation_endpoint(
+                ep,
+                #crate_name::__private::poem::http::Method::#http_method,
+                #oai_path,
+                #operation_id,
+                ::std::vec![#(#tag_names),*],
+                operation_wrapper,
+            )
         })
-    }).is_some() {
+    };
+    if ctx
+        .add_routes
+        .entry(new_path)
+        .or_default()
+        .insert(
+            (*method, cfg_key),
+            CfgItem {
+                cfg: cfg.clone(),
+                item: add_route,
+            },
+        )
+        .is_some()
+    {
         return Err(Error::new(method.span(), "duplicate method").into());
     }
 
-    let mut tag_names = Vec::new();
-    for tag in tags {
-        ctx.register_items
-            .push(quote!(#crate_name::Tags::register(&#tag, registry);));
-        tag_names.push(quote!(#crate_name::Tags::name(&#tag)));
-    }
-    let operation_id = optional_literal(&operation_id);
     let external_docs = match external_docs {
         Some(external_docs) => {
             let s = external_docs.to_token_stream(crate_name);
@@ -390,8 +742,15 @@ fn generate_operation(
         }
         None => quote!(::std::option::Option::None),
     };
+    let priority = match priority {
+        Some(priority) => {
+            let priority = priority.to_token_stream(crate_name);
+            quote!(::std::option::Option::Some(#priority))
+        }
+        None => quote!(::std::option::Option::None),
+    };
 
-    ctx.operations.entry(oai_path).or_default().push(quote! {
+    let operation = quote! {
         #crate_name::registry::MetaOperation {
             tags: ::std::vec![#(#tag_names),*],
             method: #crate_name::__private::poem::http::Method::#http_method,
@@ -408,7 +767,7 @@ fn generate_operation(
                 #(#request_meta)*
                 request
             },
-            responses: <#res_ty as #crate_name::ApiResponse>::meta(),
+            responses: #responses,
             deprecated: #deprecated,
             security: {
                 let mut security = ::std::vec![];
@@ -416,7 +775,22 @@ fn generate_operation(
                 security
             },
             operation_id: #operation_id,
+            priority: #priority,
+            extensions: <#res_ty as #crate_name::ApiResponse>::operation_extensions(),
         }
+    };
+    if let Some(cfg) = &cfg {
+        let register_items = ctx.register_items.split_off(register_start);
+        ctx.register_items.push(quote! {
+            #[cfg(#cfg)]
+            {
+                #(#register_items)*
+            }
+        });
+    }
+    ctx.operations.entry(oai_path).or_default().push(CfgItem {
+        cfg,
+        item: operation,
     });
 
     Ok(())
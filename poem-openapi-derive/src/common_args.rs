@@ -2,7 +2,9 @@ use darling::FromMeta;
 use inflector::Inflector;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Lit, Meta, NestedMeta, Path};
+use syn::{Expr, Lit, Meta, NestedMeta, Path};
+
+use crate::utils::optional_literal;
 
 #[derive(Debug, Copy, Clone, FromMeta)]
 pub(crate) enum RenameRule {
@@ -70,6 +72,42 @@ impl FromMeta for PathList {
     }
 }
 
+/// The literal values of a field, such as `enum_values("draft", "published")`.
+pub(crate) struct LiteralList(pub(crate) Vec<Lit>);
+
+impl FromMeta for LiteralList {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let mut res = Vec::new();
+        for item in items {
+            match item {
+                NestedMeta::Lit(lit) => res.push(LiteralList::check(lit)?.clone()),
+                _ => return Err(darling::Error::custom("Invalid literal list")),
+            }
+        }
+        Ok(LiteralList(res))
+    }
+
+    fn from_value(value: &Lit) -> darling::Result<Self> {
+        Ok(LiteralList(vec![LiteralList::check(value)?.clone()]))
+    }
+}
+
+impl LiteralList {
+    fn check(lit: &Lit) -> darling::Result<&Lit> {
+        match lit {
+            Lit::Str(_) | Lit::Int(_) | Lit::Float(_) | Lit::Bool(_) => Ok(lit),
+            _ => Err(darling::Error::unexpected_lit_type(lit)),
+        }
+    }
+
+    pub(crate) fn to_token_stream(&self, crate_name: &TokenStream) -> TokenStream {
+        let values = &self.0;
+        quote! {
+            ::std::vec![#(#crate_name::__private::serde_json::Value::from(#values)),*]
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq, Hash)]
 #[darling(rename_all = "lowercase")]
 pub(crate) enum APIMethod {
@@ -100,6 +138,34 @@ impl APIMethod {
     }
 }
 
+#[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq)]
+#[darling(rename_all = "lowercase")]
+pub(crate) enum APIPriority {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+impl APIPriority {
+    pub(crate) fn to_token_stream(self, crate_name: &TokenStream) -> TokenStream {
+        let priority = match self {
+            APIPriority::Low => quote!(Low),
+            APIPriority::Normal => quote!(Normal),
+            APIPriority::High => quote!(High),
+            APIPriority::Critical => quote!(Critical),
+        };
+        quote!(#crate_name::__private::poem::middleware::Priority::#priority)
+    }
+}
+
+#[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq)]
+#[darling(rename_all = "lowercase")]
+pub(crate) enum DurationFormat {
+    Iso8601,
+    Seconds,
+}
+
 #[derive(Debug, Copy, Clone, FromMeta, Eq, PartialEq)]
 pub(crate) enum ParamIn {
     #[darling(rename = "path")]
@@ -115,7 +181,8 @@ pub(crate) enum ParamIn {
 #[derive(Debug)]
 pub(crate) enum DefaultValue {
     Default,
-    Function(Path),
+    /// A function path or a closure without arguments.
+    Function(Box<Expr>),
 }
 
 impl FromMeta for DefaultValue {
@@ -125,7 +192,16 @@ impl FromMeta for DefaultValue {
 
     fn from_value(value: &Lit) -> darling::Result<Self> {
         match value {
-            Lit::Str(str) => Ok(DefaultValue::Function(syn::parse_str(&str.value())?)),
+            Lit::Str(str) => match syn::parse_str(&str.value())? {
+                expr @ Expr::Path(_) => Ok(DefaultValue::Function(Box::new(expr))),
+                Expr::Closure(closure) if closure.inputs.is_empty() => {
+                    Ok(DefaultValue::Function(Box::new(Expr::Closure(closure))))
+                }
+                _ => Err(darling::Error::custom(
+                    "expected a function path or a closure without arguments",
+                )
+                .with_span(value)),
+            },
             _ => Err(darling::Error::unexpected_lit_type(value).with_span(value)),
         }
     }
@@ -168,3 +244,55 @@ impl ExternalDocument {
         }
     }
 }
+
+#[derive(FromMeta)]
+pub(crate) struct Xml {
+    #[darling(default)]
+    pub(crate) name: Option<String>,
+    #[darling(default)]
+    pub(crate) namespace: Option<String>,
+    #[darling(default)]
+    pub(crate) attribute: bool,
+    #[darling(default)]
+    pub(crate) wrapped: bool,
+}
+
+impl Xml {
+    pub(crate) fn to_token_stream(&self, crate_name: &TokenStream) -> TokenStream {
+        let name = optional_literal(&self.name);
+        let namespace = optional_literal(&self.namespace);
+        let attribute = self.attribute;
+        let wrapped = self.wrapped;
+        quote! {
+            #crate_name::registry::MetaXml {
+                name: #name,
+                namespace: #namespace,
+                attribute: #attribute,
+                wrapped: #wrapped,
+            }
+        }
+    }
+}
+
+/// A specification extension, the value is parsed as JSON, or is a string if
+/// it is not valid JSON.
+#[derive(FromMeta)]
+pub(crate) struct Extension {
+    pub(crate) name: String,
+    pub(crate) value: String,
+}
+
+impl Extension {
+    pub(crate) fn to_token_stream(&self, crate_name: &TokenStream) -> TokenStream {
+        let name = &self.name;
+        let value = &self.value;
+        quote! {
+            (
+                ::std::string::ToString::to_string(#name),
+                #crate_name::__private::serde_json::from_str(#value).unwrap_or_else(|_| {
+                    #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(#value))
+                }),
+            )
+        }
+    }
+}
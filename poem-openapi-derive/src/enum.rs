@@ -1,26 +1,39 @@
 use darling::{
-    ast::{Data, Fields},
+    ast::{Data, Fields, Style},
     util::Ignored,
     FromDeriveInput, FromVariant,
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Path};
+use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Expr, Path};
 
 use crate::{
     common_args::{ExternalDocument, RenameRule, RenameRuleExt},
     error::GeneratorResult,
-    utils::{get_crate_name, get_summary_and_description, optional_literal},
+    utils::{get_crate_name, get_description, get_summary_and_description, optional_literal},
 };
 
 #[derive(FromVariant)]
 #[darling(attributes(oai), forward_attrs(doc))]
 struct EnumItem {
     ident: Ident,
+    attrs: Vec<Attribute>,
     fields: Fields<Ignored>,
+    discriminant: Option<Expr>,
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default)]
+    fallback: bool,
+    #[darling(default)]
+    other: bool,
+}
+
+impl EnumItem {
+    /// `other` is an alias of `fallback`.
+    fn is_fallback(&self) -> bool {
+        self.fallback || self.other
+    }
 }
 
 #[derive(FromDeriveInput)]
@@ -42,6 +55,27 @@ struct EnumArgs {
     deprecated: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default)]
+    repr: Option<Ident>,
+    #[darling(default)]
+    one_of: bool,
+    #[darling(default)]
+    case_insensitive: bool,
+}
+
+/// Returns the format of the integer type that backs the enum.
+fn repr_format(repr: &Ident) -> Option<&'static str> {
+    Some(match repr.to_string().as_str() {
+        "i8" => "int8",
+        "i16" => "int16",
+        "i32" => "int32",
+        "i64" => "int64",
+        "u8" => "uint8",
+        "u16" => "uint16",
+        "u32" => "uint32",
+        "u64" => "uint64",
+        _ => return None,
+    })
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -56,10 +90,105 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     };
 
     let mut enum_items = Vec::new();
+    let mut enum_varnames = Vec::new();
+    let mut enum_descriptions = Vec::new();
     let mut ident_to_item = Vec::new();
     let mut item_to_ident = Vec::new();
+    let mut fallback_ident = None;
+    let mut repr_values = Vec::new();
+    let mut one_of_items = Vec::new();
+    let mut lowercase_names = Vec::new();
+
+    let repr_format = match &args.repr {
+        Some(repr) => match repr_format(repr) {
+            Some(format) => Some(format),
+            None => {
+                return Err(Error::new_spanned(
+                    repr,
+                    "The `repr` attribute must be one of `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32` and `u64`.",
+                )
+                .into());
+            }
+        },
+        None => None,
+    };
+    if args.repr.is_some() && args.case_insensitive {
+        return Err(Error::new_spanned(
+            ident,
+            "The `case_insensitive` attribute cannot be used with the `repr` attribute.",
+        )
+        .into());
+    }
+    if args.repr.is_some() && args.rename_all.is_some() {
+        return Err(Error::new_spanned(
+            ident,
+            "The `rename_all` attribute cannot be used with the `repr` attribute.",
+        )
+        .into());
+    }
 
     for variant in e {
+        let item_ident = &variant.ident;
+
+        if variant.is_fallback() && args.one_of {
+            return Err(Error::new_spanned(
+                item_ident,
+                "The `fallback` attribute cannot be used with the `one_of` attribute.",
+            )
+            .into());
+        }
+
+        if variant.is_fallback() && args.repr.is_some() {
+            return Err(Error::new_spanned(
+                item_ident,
+                "The `fallback` attribute cannot be used with the `repr` attribute.",
+            )
+            .into());
+        }
+
+        if variant.is_fallback() {
+            if fallback_ident.is_some() {
+                return Err(Error::new_spanned(
+                    item_ident,
+                    "Only one variant can be marked as `fallback`.",
+                )
+                .into());
+            }
+
+            // The unit fallback variant discards the unknown values, it is
+            // serialized with its own name, which is documented with the
+            // known items.
+            let keeps_value = match (variant.fields.style, variant.fields.len()) {
+                (Style::Tuple, 1) => true,
+                (Style::Unit, _) => false,
+                _ => {
+                    return Err(Error::new_spanned(
+                        item_ident,
+                        format!(
+                            "Invalid enum variant {}.\nThe fallback variant must be a unit variant or contain exactly one unnamed field of type `String`.",
+                            item_ident
+                        ),
+                    )
+                    .into());
+                }
+            };
+
+            if keeps_value {
+                ident_to_item.push(quote!(#ident::#item_ident(value) => value.as_str()));
+            } else {
+                let oai_item_name = variant
+                    .rename
+                    .clone()
+                    .unwrap_or_else(|| args.rename_all.rename(variant.ident.unraw().to_string()));
+                ident_to_item.push(quote!(#ident::#item_ident => #oai_item_name));
+                enum_items.push(quote!(#crate_name::types::ToJSON::to_json(&#ident::#item_ident)));
+                enum_varnames.push(item_ident.unraw().to_string());
+                enum_descriptions.push(get_description(&variant.attrs)?.unwrap_or_default());
+            }
+            fallback_ident = Some((item_ident, keeps_value));
+            continue;
+        }
+
         if !variant.fields.is_empty() {
             return Err(Error::new_spanned(
                 &variant.ident,
@@ -71,29 +200,110 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             .into());
         }
 
-        let item_ident = &variant.ident;
+        if args.repr.is_some() {
+            let value = match (&variant.discriminant, &variant.rename) {
+                (Some(value), None) => value,
+                (_, Some(_)) => {
+                    return Err(Error::new_spanned(
+                        item_ident,
+                        "The `rename` attribute cannot be used with the `repr` attribute.",
+                    )
+                    .into());
+                }
+                (None, None) => {
+                    return Err(Error::new_spanned(
+                        item_ident,
+                        "The items of the enums with the `repr` attribute must have explicit discriminants.",
+                    )
+                    .into());
+                }
+            };
+            repr_values.push((item_ident, value));
+        }
+
         let oai_item_name = variant
             .rename
             .clone()
             .unwrap_or_else(|| args.rename_all.rename(variant.ident.unraw().to_string()));
 
         enum_items.push(quote!(#crate_name::types::ToJSON::to_json(&#ident::#item_ident)));
+        enum_varnames.push(item_ident.unraw().to_string());
+        let item_description = get_description(&variant.attrs)?;
+        enum_descriptions.push(item_description.clone().unwrap_or_default());
+        one_of_items.push((item_ident, optional_literal(&item_description)));
         ident_to_item.push(quote!(#ident::#item_ident => #oai_item_name));
-        item_to_ident
-            .push(quote!(#oai_item_name => ::std::result::Result::Ok(#ident::#item_ident)));
+        if args.case_insensitive {
+            let lowercase_name = oai_item_name.to_ascii_lowercase();
+            if lowercase_names.contains(&lowercase_name) {
+                return Err(Error::new_spanned(
+                    item_ident,
+                    format!(
+                        "The name `{}` conflicts with another item when the case is ignored.",
+                        oai_item_name
+                    ),
+                )
+                .into());
+            }
+            lowercase_names.push(lowercase_name);
+            item_to_ident.push(quote!(item if ::std::primitive::str::eq_ignore_ascii_case(item, #oai_item_name) => ::std::result::Result::Ok(#ident::#item_ident)));
+        } else {
+            item_to_ident
+                .push(quote!(#oai_item_name => ::std::result::Result::Ok(#ident::#item_ident)));
+        }
+    }
+
+    let (unknown_json_item, unknown_param_item) = match fallback_ident {
+        Some((fallback_ident, true)) => {
+            let res = quote!(item => ::std::result::Result::Ok(#ident::#fallback_ident(::std::string::ToString::to_string(item))));
+            (res.clone(), res)
+        }
+        Some((fallback_ident, false)) => {
+            let res = quote!(_ => ::std::result::Result::Ok(#ident::#fallback_ident));
+            (res.clone(), res)
+        }
+        None => (
+            quote!(_ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value))),
+            quote!(_ => ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value."))),
+        ),
+    };
+
+    // An enum with a fallback variant accepts the values that are not listed,
+    // so they are documented as `x-extensible-enum` instead of `enum`.
+    let enum_items = if fallback_ident.is_some() {
+        quote!(extensible_enum_items: ::std::vec![#(#enum_items),*])
+    } else {
+        quote!(enum_items: ::std::vec![#(#enum_items),*])
+    };
+
+    if enum_descriptions.iter().all(String::is_empty) {
+        enum_descriptions.clear();
     }
 
     let remote_conversion = if let Some(remote_ty) = &args.remote {
         let local_to_remote_items = e.iter().map(|item| {
+            let has_value = !item.fields.is_empty();
             let item = &item.ident;
-            quote! {
-                #ident::#item => #remote_ty::#item,
+            if has_value {
+                quote! {
+                    #ident::#item(value) => #remote_ty::#item(value),
+                }
+            } else {
+                quote! {
+                    #ident::#item => #remote_ty::#item,
+                }
             }
         });
         let remote_to_local_items = e.iter().map(|item| {
+            let has_value = !item.fields.is_empty();
             let item = &item.ident;
-            quote! {
-                #remote_ty::#item => #ident::#item,
+            if has_value {
+                quote! {
+                    #remote_ty::#item(value) => #ident::#item(value),
+                }
+            } else {
+                quote! {
+                    #remote_ty::#item => #ident::#item,
+                }
             }
         });
 
@@ -128,6 +338,113 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         None => quote!(::std::option::Option::None),
     };
 
+    let schema = match repr_format {
+        Some(format) => {
+            quote!(#crate_name::registry::MetaSchema::new_with_format("integer", #format))
+        }
+        None => quote!(#crate_name::registry::MetaSchema::new("string")),
+    };
+
+    // Every item is documented as a single-value schema, so that the
+    // descriptions are displayed by the tools that ignore the extensions.
+    let one_of = if args.one_of {
+        let items = one_of_items.iter().map(|(item, description)| {
+            let title = item.unraw().to_string();
+            quote! {
+                #crate_name::registry::MetaSchemaRef::Inline(::std::boxed::Box::new(#crate_name::registry::MetaSchema {
+                    title: ::std::option::Option::Some(#title),
+                    description: #description,
+                    enum_items: ::std::vec![#crate_name::types::ToJSON::to_json(&#ident::#item)],
+                    ..#schema
+                }))
+            }
+        });
+        quote!(one_of: ::std::vec![#(#items),*],)
+    } else {
+        quote!()
+    };
+
+    // The enums with the `repr` attribute are represented by the
+    // discriminants of the items.
+    let conversions = match &args.repr {
+        Some(repr) => {
+            let items = repr_values.iter().map(|(item, _)| item).collect::<Vec<_>>();
+            let values = repr_values
+                .iter()
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            quote! {
+                impl #crate_name::types::ParseFromJSON for #ident {
+                    fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> #crate_name::types::ParseResult<Self> {
+                        let number = match <#repr as #crate_name::types::ParseFromJSON>::parse_from_json(::std::clone::Clone::clone(&value)) {
+                            ::std::result::Result::Ok(number) => number,
+                            ::std::result::Result::Err(_) => return ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+                        };
+                        #(
+                        if number == #values {
+                            return ::std::result::Result::Ok(#ident::#items);
+                        }
+                        )*
+                        ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value))
+                    }
+                }
+
+                impl #crate_name::types::ParseFromParameter for #ident {
+                    fn parse_from_parameter(value: &str) -> #crate_name::types::ParseResult<Self> {
+                        let number = <#repr as #crate_name::types::ParseFromParameter>::parse_from_parameter(value)
+                            .map_err(#crate_name::types::ParseError::propagate)?;
+                        #(
+                        if number == #values {
+                            return ::std::result::Result::Ok(#ident::#items);
+                        }
+                        )*
+                        ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value."))
+                    }
+                }
+
+                impl #crate_name::types::ToJSON for #ident {
+                    fn to_json(&self) -> #crate_name::__private::serde_json::Value {
+                        let number: #repr = match self {
+                            #(#ident::#items => #values),*
+                        };
+                        #crate_name::types::ToJSON::to_json(&number)
+                    }
+                }
+            }
+        }
+        None => quote! {
+            impl #crate_name::types::ParseFromJSON for #ident {
+                fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> #crate_name::types::ParseResult<Self> {
+                    match &value {
+                        #crate_name::__private::serde_json::Value::String(item) => match item.as_str() {
+                            #(#item_to_ident,)*
+                            #unknown_json_item,
+                        }
+                        _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+                    }
+                }
+            }
+
+            impl #crate_name::types::ParseFromParameter for #ident {
+                fn parse_from_parameter(value: &str) -> #crate_name::types::ParseResult<Self> {
+                    match value {
+                        #(#item_to_ident,)*
+                        #unknown_param_item,
+                    }
+                }
+            }
+
+            impl #crate_name::types::ToJSON for #ident {
+                fn to_json(&self) -> #crate_name::__private::serde_json::Value {
+                    let name = match self {
+                        #(#ident_to_item),*
+                    };
+                    #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(name))
+                }
+            }
+        },
+    };
+
     let expanded = quote! {
         impl #crate_name::types::Type for #ident {
             const IS_REQUIRED: bool = true;
@@ -154,8 +471,11 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     description: #description,
                     external_docs: #external_docs,
                     deprecated: #deprecated,
-                    enum_items: ::std::vec![#(#enum_items),*],
-                    ..#crate_name::registry::MetaSchema::new("string")
+                    #enum_items,
+                    enum_varnames: ::std::vec![#(#enum_varnames),*],
+                    enum_descriptions: ::std::vec![#(#enum_descriptions),*],
+                    #one_of
+                    ..#schema
                 });
             }
 
@@ -164,46 +484,20 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         }
 
-        impl #crate_name::types::ParseFromJSON for #ident {
-            fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> #crate_name::types::ParseResult<Self> {
-                match &value {
-                    #crate_name::__private::serde_json::Value::String(item) => match item.as_str() {
-                        #(#item_to_ident,)*
-                        _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
-                    }
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
-                }
-            }
-        }
-
-        impl #crate_name::types::ParseFromParameter for #ident {
-            fn parse_from_parameter(value: &str) -> #crate_name::types::ParseResult<Self> {
-                match value {
-                    #(#item_to_ident,)*
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::custom("Expect a valid enumeration value.")),
-                }
-            }
-        }
-
-        impl #crate_name::types::ToJSON for #ident {
-            fn to_json(&self) -> #crate_name::__private::serde_json::Value {
-                let name = match self {
-                    #(#ident_to_item),*
-                };
-                #crate_name::__private::serde_json::Value::String(::std::string::ToString::to_string(name))
-            }
-        }
+        #conversions
 
-        #[#crate_name::__private::poem::async_trait]
-        impl #crate_name::types::ParseFromMultipartField for #ident {
-            async fn parse_from_multipart(field: ::std::option::Option<#crate_name::__private::poem::web::Field>) -> #crate_name::types::ParseResult<Self> {
-                use poem_openapi::types::ParseFromParameter;
-                match field {
-                    ::std::option::Option::Some(field) => {
-                        let s = field.text().await?;
-                        Self::parse_from_parameter(&s)
-                    },
-                    ::std::option::Option::None => ::std::result::Result::Err(#crate_name::types::ParseError::expected_input()),
+        #crate_name::__multipart_items! {
+            #[#crate_name::__private::poem::async_trait]
+            impl #crate_name::types::ParseFromMultipartField for #ident {
+                async fn parse_from_multipart(field: ::std::option::Option<#crate_name::__private::poem::web::Field>) -> #crate_name::types::ParseResult<Self> {
+                    use poem_openapi::types::ParseFromParameter;
+                    match field {
+                        ::std::option::Option::Some(field) => {
+                            let s = field.text().await?;
+                            Self::parse_from_parameter(&s)
+                        },
+                        ::std::option::Option::None => ::std::result::Result::Err(#crate_name::types::ParseError::expected_input()),
+                    }
                 }
             }
         }
@@ -22,6 +22,7 @@ mod response;
 mod response_content;
 mod security_scheme;
 mod tags;
+mod transforms;
 mod utils;
 mod webhook;
 
@@ -27,6 +27,8 @@ struct MultipartField {
     default: Option<DefaultValue>,
     #[darling(default)]
     validator: Option<Validators>,
+    #[darling(default, multiple)]
+    content_type: Vec<String>,
 }
 
 #[derive(FromDeriveInput)]
@@ -100,8 +102,52 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         }};
 
+        for content_type in &field.content_type {
+            if mime::Mime::from_str(content_type).is_err() {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    format!("Invalid content type `{}`.", content_type),
+                )
+                .into());
+            }
+        }
+
+        // The parts without the `Content-Type` header are `text/plain`, see RFC 7578.
+        let content_type_checker = if !field.content_type.is_empty() {
+            let content_types = &field.content_type;
+            Some(quote! {
+                let content_type = field.content_type().unwrap_or("text/plain");
+                let table = #crate_name::__private::ContentTypeTable::new(&[#(#content_types),*]);
+                if table.matches(content_type).is_none() {
+                    return ::std::result::Result::Err(::std::convert::Into::into(#crate_name::error::MultipartContentTypeError {
+                        field: #field_name,
+                        content_type: ::std::string::ToString::to_string(content_type),
+                    }));
+                }
+            })
+        } else {
+            None
+        };
+        let (content_media_type, content_media_types) = match field.content_type.as_slice() {
+            [] => (quote!(::std::option::Option::None), quote!(::std::vec![])),
+            [content_type] => (
+                quote!(::std::option::Option::Some(::std::string::ToString::to_string(#content_type))),
+                quote!(::std::vec![]),
+            ),
+            content_types => (
+                quote!(::std::option::Option::None),
+                quote!(
+                    ::std::vec![#(#crate_name::registry::MetaSchemaRef::Inline(::std::boxed::Box::new(#crate_name::registry::MetaSchema {
+                    content_media_type: ::std::option::Option::Some(::std::string::ToString::to_string(#content_types)),
+                    ..#crate_name::registry::MetaSchema::ANY
+                }))),*]
+                ),
+            ),
+        };
+
         deserialize_fields.push(quote! {
             if field.name() == ::std::option::Option::Some(#field_name) {
+                #content_type_checker
                 #field_ident = match #field_ident {
                     ::std::option::Option::Some(value) => {
                         ::std::option::Option::Some(<#field_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field(value, field).await.map_err(|err| #parse_err )?)
@@ -120,7 +166,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     DefaultValue::Default => {
                         quote!(<#field_ty as ::std::default::Default>::default())
                     }
-                    DefaultValue::Function(func_name) => quote!(#func_name()),
+                    DefaultValue::Function(func_name) => quote!((#func_name)()),
                 };
 
                 deserialize_none.push(quote! {
@@ -158,7 +204,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&<#field_ty as ::std::default::Default>::default())))
             }
             Some(DefaultValue::Function(func_name)) => {
-                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&#func_name())))
+                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&(#func_name)())))
             }
             None => quote!(::std::option::Option::None),
         };
@@ -168,6 +214,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             let mut patch_schema = {
                 let mut schema = #crate_name::registry::MetaSchema::ANY;
                 schema.default = #field_meta_default;
+                schema.content_media_type = #content_media_type;
+                schema.one_of = #content_media_types;
 
                 if let ::std::option::Option::Some(title) = #field_title {
                     schema.title = ::std::option::Option::Some(title);
@@ -206,6 +254,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     };
 
+    let deny_additional_properties = args.deny_unknown_fields;
     let deny_unknown_fields = if args.deny_unknown_fields {
         Some(quote! {
             if let ::std::option::Option::Some(name) = field.name() {
@@ -231,6 +280,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         fields
                     },
                     properties: ::std::vec![#(#meta_fields),*],
+                    deny_additional_properties: #deny_additional_properties,
                     ..#crate_name::registry::MetaSchema::new("object")
                 };
                 #crate_name::registry::MetaSchemaRef::Inline(Box::new(schema))
@@ -287,16 +337,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             ) -> #crate_name::__private::poem::Result<Self> {
                 match request.content_type() {
                     ::std::option::Option::Some(content_type) => {
-                        let mime: #crate_name::__private::mime::Mime = match content_type.parse() {
-                            ::std::result::Result::Ok(mime) => mime,
-                            ::std::result::Result::Err(_) => {
-                                return ::std::result::Result::Err(::std::convert::Into::into(#crate_name::error::ContentTypeError::NotSupported {
-                                    content_type: ::std::string::ToString::to_string(&content_type),
-                                }));
-                            }
-                        };
-
-                        if mime.essence_str() != <Self as #crate_name::payload::Payload>::CONTENT_TYPE {
+                        let table = #crate_name::__private::ContentTypeTable::new(&[<Self as #crate_name::payload::Payload>::CONTENT_TYPE]);
+                        if table.matches(content_type).is_none() {
                             return ::std::result::Result::Err(::std::convert::Into::into(#crate_name::error::ContentTypeError::NotSupported {
                                 content_type: ::std::string::ToString::to_string(&content_type),
                             }));
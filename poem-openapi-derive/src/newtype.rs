@@ -118,16 +118,18 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
     let from_multipart = if args.from_multipart {
         Some(quote! {
-            #[#crate_name::__private::poem::async_trait]
-            impl #crate_name::types::ParseFromMultipartField for #ident {
-                async fn parse_from_multipart(field: ::std::option::Option<#crate_name::__private::poem::web::Field>) -> #crate_name::types::ParseResult<Self> {
-                    let value = ::std::result::Result::map_err(<#inner_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart(field).await, poem_openapi::types::ParseError::propagate)?;
-                    ::std::result::Result::Ok(#ident(value))
-                }
-
-                async fn parse_from_repeated_field(self, field: #crate_name::__private::poem::web::Field) -> #crate_name::types::ParseResult<Self> {
-                    let value = ::std::result::Result::map_err(<#inner_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field(self.0, field).await, poem_openapi::types::ParseError::propagate)?;
-                    ::std::result::Result::Ok(#ident(value))
+            #crate_name::__multipart_items! {
+                #[#crate_name::__private::poem::async_trait]
+                impl #crate_name::types::ParseFromMultipartField for #ident {
+                    async fn parse_from_multipart(field: ::std::option::Option<#crate_name::__private::poem::web::Field>) -> #crate_name::types::ParseResult<Self> {
+                        let value = ::std::result::Result::map_err(<#inner_ty as #crate_name::types::ParseFromMultipartField>::parse_from_multipart(field).await, poem_openapi::types::ParseError::propagate)?;
+                        ::std::result::Result::Ok(#ident(value))
+                    }
+
+                    async fn parse_from_repeated_field(self, field: #crate_name::__private::poem::web::Field) -> #crate_name::types::ParseResult<Self> {
+                        let value = ::std::result::Result::map_err(<#inner_ty as #crate_name::types::ParseFromMultipartField>::parse_from_repeated_field(self.0, field).await, poem_openapi::types::ParseError::propagate)?;
+                        ::std::result::Result::Ok(#ident(value))
+                    }
                 }
             }
         })
@@ -1,31 +1,40 @@
 use darling::{
-    ast::Data,
+    ast::{Data, Style},
     util::{Ignored, SpannedValue},
-    FromDeriveInput, FromField,
+    FromDeriveInput, FromField, FromMeta,
 };
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
-use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Generics, Path, Type};
+use syn::{
+    ext::IdentExt, parse_quote, Attribute, DeriveInput, Error, Expr, Generics, Lit, Meta,
+    NestedMeta, Path, Type,
+};
 
 use crate::{
-    common_args::{ConcreteType, DefaultValue, ExternalDocument, RenameRule, RenameRuleExt},
+    common_args::{
+        ConcreteType, DefaultValue, DurationFormat, Extension, ExternalDocument, LiteralList,
+        RenameRule, RenameRuleExt, Xml,
+    },
     error::GeneratorResult,
+    transforms::Transforms,
     utils::{get_crate_name, get_summary_and_description, optional_literal},
     validators::Validators,
 };
 
 #[derive(FromField)]
-#[darling(attributes(oai), forward_attrs(doc))]
+#[darling(attributes(oai), forward_attrs(doc, serde))]
 struct ObjectField {
     ident: Option<Ident>,
     ty: Type,
     attrs: Vec<Attribute>,
 
     #[darling(default)]
-    skip: bool,
+    skip: Option<DefaultValue>,
 
     #[darling(default)]
     rename: Option<String>,
+    #[darling(default, multiple, rename = "alias")]
+    aliases: Vec<String>,
     #[darling(default)]
     default: Option<DefaultValue>,
     #[darling(default)]
@@ -33,11 +42,51 @@ struct ObjectField {
     #[darling(default)]
     read_only: bool,
     #[darling(default)]
+    sensitive: bool,
+    #[darling(default)]
+    deprecated: bool,
+    #[darling(default)]
+    deprecation_note: Option<String>,
+    #[darling(default)]
+    removed_in: Option<String>,
+    #[darling(default)]
+    duration: Option<DurationFormat>,
+    #[darling(default)]
     validator: Option<Validators>,
+    #[darling(default)]
+    transform: Option<Transforms>,
+    #[darling(default)]
+    version: bool,
+    #[darling(default)]
+    flatten: bool,
+    #[darling(default)]
+    additional_properties: bool,
+    #[darling(default)]
+    skip_serializing_if: Option<Path>,
+    #[darling(default)]
+    example: Option<Expr>,
+    #[darling(default)]
+    serialize_with: Option<Path>,
+    #[darling(default)]
+    deserialize_with: Option<Path>,
+    #[darling(default)]
+    schema_type: Option<Type>,
+    #[darling(default)]
+    xml: Option<Xml>,
+    #[darling(default)]
+    enum_values: Option<LiteralList>,
+    #[darling(default, rename = "const")]
+    const_value: Option<LiteralList>,
+    #[darling(default)]
+    nullable: bool,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
+    #[darling(default)]
+    skip_validation: bool,
 }
 
 #[derive(FromDeriveInput)]
-#[darling(attributes(oai), forward_attrs(doc))]
+#[darling(attributes(oai), forward_attrs(doc, serde))]
 struct ObjectArgs {
     ident: Ident,
     generics: Generics,
@@ -51,6 +100,8 @@ struct ObjectArgs {
     #[darling(default)]
     rename: Option<String>,
     #[darling(default)]
+    rename_via: Option<Path>,
+    #[darling(default)]
     rename_all: Option<RenameRule>,
     #[darling(default, multiple, rename = "concrete")]
     concretes: Vec<ConcreteType>,
@@ -65,11 +116,26 @@ struct ObjectArgs {
     #[darling(default)]
     deny_unknown_fields: bool,
     #[darling(default)]
+    shallow_validation: bool,
+    #[darling(default)]
+    serde_compat: bool,
+    #[darling(default)]
+    sort_properties: bool,
+    #[darling(default)]
     external_docs: Option<ExternalDocument>,
+    #[darling(default)]
+    xml: Option<Xml>,
+    #[darling(default, multiple, rename = "extension")]
+    extensions: Vec<Extension>,
+    #[darling(default, multiple, rename = "computed")]
+    computed: Vec<Path>,
 }
 
-pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
-    let args: ObjectArgs = ObjectArgs::from_derive_input(&args)?;
+pub(crate) fn generate(input: DeriveInput) -> GeneratorResult<TokenStream> {
+    let mut args: ObjectArgs = ObjectArgs::from_derive_input(&input)?;
+    if args.serde_compat {
+        apply_serde_attrs(&mut args)?;
+    }
     let crate_name = get_crate_name(args.internal);
     let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
     let ident = &args.ident;
@@ -81,6 +147,9 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             );
         }
     };
+    if s.style == Style::Tuple {
+        return generate_tuple(&input, &args, &crate_name);
+    }
     let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
     let (title, description) = get_summary_and_description(&args.attrs)?;
     let mut deserialize_fields = Vec::new();
@@ -89,6 +158,8 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut fields = Vec::new();
     let mut meta_fields = Vec::new();
     let mut required_fields = Vec::new();
+    let mut version_field = None;
+    let mut additional_properties = None;
 
     if *args.inline && !args.concretes.is_empty() {
         return Err(Error::new(
@@ -98,6 +169,41 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         .into());
     }
 
+    if args.rename_via.is_some() && args.rename.is_some() {
+        return Err(Error::new_spanned(
+            ident,
+            "The `rename_via` attribute cannot be used with the `rename` attribute.",
+        )
+        .into());
+    }
+
+    let is_template = args
+        .rename
+        .as_ref()
+        .map(|rename| rename.contains('{'))
+        .unwrap_or_default();
+    if (is_template || args.rename_via.is_some()) && !args.concretes.is_empty() {
+        return Err(Error::new_spanned(
+            ident,
+            "The generic objects with the `concretes` attribute are named with the `concretes.name` attribute.",
+        )
+        .into());
+    }
+    if is_template {
+        let mut template = oai_typename.as_str();
+        while let Some((_, rest)) = template.split_once('{') {
+            let (name, rest) = rest.split_once('}').unwrap_or((rest, ""));
+            if !args.generics.type_params().any(|param| param.ident == name) {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!("`{{{}}}` is not a type parameter of the object.", name),
+                )
+                .into());
+            }
+            template = rest;
+        }
+    }
+
     if args.example.is_some() && !args.concretes.is_empty() {
         return Err(Error::new(
             args.example.as_ref().unwrap().span(),
@@ -106,15 +212,66 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         .into());
     }
 
+    let additional_properties_fields = s
+        .fields
+        .iter()
+        .filter(|field| field.additional_properties)
+        .collect::<Vec<_>>();
+    if let [_, field, ..] = &additional_properties_fields[..] {
+        return Err(Error::new_spanned(
+            field.ident.as_ref().unwrap(),
+            "Only one field can have the `additional_properties` attribute.",
+        )
+        .into());
+    }
+    if let Some(field) = additional_properties_fields.first() {
+        if args.deny_unknown_fields {
+            return Err(Error::new_spanned(
+                field.ident.as_ref().unwrap(),
+                "The `additional_properties` attribute cannot be used with the `deny_unknown_fields` attribute.",
+            )
+            .into());
+        }
+    }
+    // The properties of the flattened fields are removed before the remaining
+    // properties are checked or collected.
+    let remove_flattened = args.deny_unknown_fields || !additional_properties_fields.is_empty();
+    // The validators of the nested objects are skipped while parsing the
+    // `skip_validation` fields and the fields of the `shallow_validation` objects.
+    let without_validation = |skip: bool, parse: TokenStream| {
+        if skip {
+            quote!(#crate_name::__private::parse_without_validation(|| #parse))
+        } else {
+            parse
+        }
+    };
+
     for field in &s.fields {
         let field_ident = field.ident.as_ref().unwrap();
         let field_ty = &field.ty;
         let read_only = args.read_only_all || field.read_only;
         let write_only = args.write_only_all || field.write_only;
+        let sensitive = field.sensitive;
+        let nullable = field.nullable;
 
-        if field.skip {
+        if field.version {
+            if version_field.is_some() {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "Only one field can have the `version` attribute.",
+                )
+                .into());
+            }
+            version_field = Some(field_ident);
+        }
+
+        if let Some(skip) = &field.skip {
+            let value = match skip {
+                DefaultValue::Default => quote!(::std::default::Default::default()),
+                DefaultValue::Function(func_name) => quote!((#func_name)()),
+            };
             deserialize_fields.push(quote! {
-                let #field_ident: #field_ty = ::std::default::Default::default();
+                let #field_ident: #field_ty = #value;
             });
             fields.push(field_ident);
             continue;
@@ -128,6 +285,166 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             .into());
         }
 
+        if field.flatten {
+            if field.rename.is_some()
+                || !field.aliases.is_empty()
+                || field.default.is_some()
+                || field.deprecated
+                || field.deprecation_note.is_some()
+                || field.removed_in.is_some()
+                || field.duration.is_some()
+                || field.validator.is_some()
+                || field.transform.is_some()
+                || field.version
+                || field.example.is_some()
+                || field.serialize_with.is_some()
+                || field.deserialize_with.is_some()
+                || field.schema_type.is_some()
+                || field.xml.is_some()
+                || field.enum_values.is_some()
+                || field.const_value.is_some()
+                || field.nullable
+                || !field.extensions.is_empty()
+            {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "The `flatten` attribute cannot be used with the `rename`, `alias`, `default`, `deprecated`, `deprecation_note`, `removed_in`, `duration`, `validator`, `transform`, `version`, `example`, `serialize_with`, `deserialize_with`, `schema_type`, `xml`, `enum_values`, `const`, `nullable` and `extension` attributes.",
+                )
+                .into());
+            }
+
+            fields.push(field_ident);
+            if read_only {
+                deserialize_fields.push(quote! {
+                    let #field_ident: #field_ty = ::std::default::Default::default();
+                });
+            } else {
+                let remove_fields = remove_flattened.then(|| {
+                    quote! {
+                        for (name, _) in #crate_name::__private::flatten_schema::<#field_ty>().properties {
+                            obj.remove(name);
+                        }
+                    }
+                });
+                let parse_field = without_validation(
+                    field.skip_validation,
+                    quote! {
+                        #crate_name::types::ParseFromJSON::parse_from_json(
+                            #crate_name::__private::serde_json::Value::Object(::std::clone::Clone::clone(&obj)),
+                        )
+                    },
+                );
+                deserialize_fields.push(quote! {
+                    #[allow(non_snake_case)]
+                    let #field_ident: #field_ty = {
+                        let value = #parse_field.map_err(#crate_name::types::ParseError::propagate)?;
+                        #remove_fields
+                        value
+                    };
+                });
+            }
+            if !write_only {
+                let serialize_field = quote! {
+                    if let #crate_name::__private::serde_json::Value::Object(value) = #crate_name::types::ToJSON::to_json(&self.#field_ident) {
+                        object.extend(value);
+                    }
+                };
+                serialize_fields.push(match &field.skip_serializing_if {
+                    Some(predicate) => quote! {
+                        if !#predicate(&self.#field_ident) {
+                            #serialize_field
+                        }
+                    },
+                    None => serialize_field,
+                });
+            }
+            register_types
+                .push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+            meta_fields.push(quote! {
+                fields.extend(#crate_name::__private::flatten_schema::<#field_ty>().properties);
+            });
+            if field.skip_serializing_if.is_none() {
+                required_fields.push(quote! {
+                    fields.extend(#crate_name::__private::flatten_schema::<#field_ty>().required);
+                });
+            }
+            continue;
+        }
+
+        if field.additional_properties {
+            if field.flatten
+                || field.rename.is_some()
+                || !field.aliases.is_empty()
+                || field.default.is_some()
+                || field.deprecated
+                || field.deprecation_note.is_some()
+                || field.removed_in.is_some()
+                || field.duration.is_some()
+                || field.validator.is_some()
+                || field.transform.is_some()
+                || field.version
+                || field.example.is_some()
+                || field.serialize_with.is_some()
+                || field.deserialize_with.is_some()
+                || field.schema_type.is_some()
+                || field.xml.is_some()
+                || field.enum_values.is_some()
+                || field.const_value.is_some()
+                || field.nullable
+                || !field.extensions.is_empty()
+            {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "The `additional_properties` attribute cannot be used with the `flatten`, `rename`, `alias`, `default`, `deprecated`, `deprecation_note`, `removed_in`, `duration`, `validator`, `transform`, `version`, `example`, `serialize_with`, `deserialize_with`, `schema_type`, `xml`, `enum_values`, `const`, `nullable` and `extension` attributes.",
+                )
+                .into());
+            }
+
+            fields.push(field_ident);
+            let parse_field = if read_only {
+                quote!(::std::default::Default::default())
+            } else {
+                let parse_field = without_validation(
+                    field.skip_validation || args.shallow_validation,
+                    quote! {
+                        #crate_name::types::ParseFromJSON::parse_from_json(
+                            #crate_name::__private::serde_json::Value::Object(::std::mem::take(&mut obj)),
+                        )
+                    },
+                );
+                quote!(#parse_field.map_err(#crate_name::types::ParseError::propagate)?)
+            };
+            // The declared properties take precedence when serializing.
+            let serialize_field = (!write_only).then(|| {
+                let serialize_field = quote! {
+                    if let #crate_name::__private::serde_json::Value::Object(value) = #crate_name::types::ToJSON::to_json(&self.#field_ident) {
+                        for (name, value) in value {
+                            object.entry(name).or_insert(value);
+                        }
+                    }
+                };
+                match &field.skip_serializing_if {
+                    Some(predicate) => quote! {
+                        if !#predicate(&self.#field_ident) {
+                            #serialize_field
+                        }
+                    },
+                    None => serialize_field,
+                }
+            });
+            register_types
+                .push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+            additional_properties = Some((
+                quote! {
+                    #[allow(non_snake_case)]
+                    let #field_ident: #field_ty = #parse_field;
+                },
+                serialize_field,
+                quote!(#crate_name::__private::flatten_schema::<#field_ty>().additional_properties),
+            ));
+            continue;
+        }
+
         let field_name = field
             .rename
             .clone()
@@ -138,6 +455,111 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         let validators = field.validator.clone().unwrap_or_default();
         let validators_checker = validators.create_obj_field_checker(&crate_name, &field_name)?;
         let validators_update_meta = validators.create_update_meta(&crate_name)?;
+        // The constant is advertised as an enum with a single value, because
+        // OpenAPI 3.0 has no `const` keyword.
+        let enum_values = match (&field.enum_values, &field.const_value) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new_spanned(
+                    field_ident,
+                    "The `enum_values` and `const` attributes cannot be used both.",
+                )
+                .into());
+            }
+            (Some(values), None) | (None, Some(values)) => {
+                Some(values.to_token_stream(&crate_name))
+            }
+            (None, None) => None,
+        };
+        let enum_values_checker = enum_values.as_ref().map(|values| {
+            quote! {
+                let value_json = #crate_name::types::ToJSON::to_json(&value);
+                if !value_json.is_null() && !#values.contains(&value_json) {
+                    return Err(#crate_name::types::ParseError::<Self>::custom(format!("field `{}` verification failed. the value is not one of the enum values.", #field_name)));
+                }
+            }
+        });
+        let enum_values_update_meta = enum_values.map(|values| {
+            quote! {
+                schema.enum_items = #values;
+            }
+        });
+        let transformer = field
+            .transform
+            .clone()
+            .unwrap_or_default()
+            .create_transformer(&crate_name, &Ident::new("value", Span::call_site()));
+
+        if field.duration.is_some()
+            && (field.serialize_with.is_some()
+                || field.deserialize_with.is_some()
+                || field.schema_type.is_some()
+                || field.xml.is_some())
+        {
+            return Err(Error::new_spanned(
+                field_ident,
+                "The `duration` attribute cannot be used with the `serialize_with`, `deserialize_with`, `schema_type` and `xml` attributes.",
+            )
+            .into());
+        }
+
+        // The type that describes the field in the schema.
+        let schema_ty = field.schema_type.as_ref().unwrap_or(field_ty);
+        let (parse_from_json, mut to_json, schema_ref) = match field.duration {
+            Some(DurationFormat::Seconds) => (
+                quote!(<#field_ty as #crate_name::__private::DurationSeconds>::parse_from_json),
+                quote!(<#field_ty as #crate_name::__private::DurationSeconds>::to_json),
+                quote!(<#field_ty as #crate_name::__private::DurationSeconds>::schema_ref()),
+            ),
+            Some(DurationFormat::Iso8601) | None => (
+                quote!(#crate_name::types::ParseFromJSON::parse_from_json),
+                quote!(#crate_name::types::ToJSON::to_json),
+                quote!(<#schema_ty as #crate_name::types::Type>::schema_ref()),
+            ),
+        };
+        if let Some(serialize_with) = &field.serialize_with {
+            to_json = quote!(#serialize_with);
+        }
+        let skip_nested_validation = field.skip_validation || args.shallow_validation;
+        let parse_value = match &field.deserialize_with {
+            Some(deserialize_with) => {
+                let deserialize_value =
+                    without_validation(skip_nested_validation, quote!(#deserialize_with(value)));
+                quote! {
+                    match #deserialize_value {
+                        ::std::result::Result::Ok(value) => value,
+                        ::std::result::Result::Err(err) => {
+                            return Err(#crate_name::types::ParseError::<Self>::custom(format!("field `{}`: {}", #field_name, err)));
+                        }
+                    }
+                }
+            }
+            None => {
+                let parse_value =
+                    without_validation(skip_nested_validation, quote!(#parse_from_json(value)));
+                quote!(#parse_value.map_err(#crate_name::types::ParseError::propagate)?)
+            }
+        };
+        // The validators of the field are skipped with the `skip_validation`
+        // attribute, or if the object is nested in an object that skips them.
+        let validators_checker = if field.skip_validation {
+            quote!()
+        } else {
+            quote! {
+                if !#crate_name::__private::validation_skipped() {
+                    #validators_checker
+                    #enum_values_checker
+                }
+            }
+        };
+
+        // The aliases are accepted when parsing, the field is serialized with its
+        // name.
+        let aliases = &field.aliases;
+        let remove_field = if aliases.is_empty() {
+            quote!(obj.remove(#field_name))
+        } else {
+            quote!(#crate_name::__private::remove_aliased_field::<Self>(&mut obj, #field_name, &[#(#aliases),*])?)
+        };
 
         fields.push(field_ident);
 
@@ -145,7 +567,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             deserialize_fields.push(quote! {
                 #[allow(non_snake_case)]
                 let #field_ident: #field_ty = {
-                    if obj.contains_key(#field_name) {
+                    if obj.contains_key(#field_name) #(|| obj.contains_key(#aliases))* {
                         return Err(#crate_name::types::ParseError::custom(format!("properties `{}` is read only.", #field_name)));
                     }
                     Default::default()
@@ -158,16 +580,17 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                         DefaultValue::Default => {
                             quote!(<#field_ty as ::std::default::Default>::default())
                         }
-                        DefaultValue::Function(func_name) => quote!(#func_name()),
+                        DefaultValue::Function(func_name) => quote!((#func_name)()),
                     };
 
                     deserialize_fields.push(quote! {
                         #[allow(non_snake_case)]
                         let #field_ident: #field_ty = {
-                            match obj.remove(#field_name).unwrap_or_default() {
+                            match #remove_field.unwrap_or_default() {
                                 #crate_name::__private::serde_json::Value::Null => #default_value,
                                 value => {
-                                    let value = #crate_name::types::ParseFromJSON::parse_from_json(value).map_err(#crate_name::types::ParseError::propagate)?;
+                                    let value = #parse_value;
+                                    #transformer
                                     #validators_checker
                                     value
                                 }
@@ -176,11 +599,25 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     });
                 }
                 _ => {
+                    // The nullable fields are required, but they can be `null`.
+                    let value = if field.nullable {
+                        quote! {
+                            match #remove_field {
+                                ::std::option::Option::Some(value) => value,
+                                ::std::option::Option::None => {
+                                    return Err(#crate_name::types::ParseError::custom(format!("field `{}` is required.", #field_name)));
+                                }
+                            }
+                        }
+                    } else {
+                        quote!(#remove_field.unwrap_or_default())
+                    };
                     deserialize_fields.push(quote! {
                         #[allow(non_snake_case)]
                         let #field_ident: #field_ty = {
-                            let value = #crate_name::types::ParseFromJSON::parse_from_json(obj.remove(#field_name).unwrap_or_default())
-                                .map_err(#crate_name::types::ParseError::propagate)?;
+                            let value = #value;
+                            let value = #parse_value;
+                            #transformer
                             #validators_checker
                             value
                         };
@@ -192,31 +629,74 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         if write_only {
             serialize_fields.push(quote! {});
         } else {
-            serialize_fields.push(quote! {
-                let value = #crate_name::types::ToJSON::to_json(&self.#field_ident);
+            let serialize_field = quote! {
+                let value = #to_json(&self.#field_ident);
                 object.insert(::std::string::ToString::to_string(#field_name), value);
+            };
+            serialize_fields.push(match &field.skip_serializing_if {
+                Some(predicate) => quote! {
+                    if !#predicate(&self.#field_ident) {
+                        #serialize_field
+                    }
+                },
+                None => serialize_field,
             });
         }
 
         let field_meta_default = match &field.default {
             Some(DefaultValue::Default) => {
-                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&<#field_ty as ::std::default::Default>::default())))
+                quote!(::std::option::Option::Some(#to_json(&<#field_ty as ::std::default::Default>::default())))
             }
             Some(DefaultValue::Function(func_name)) => {
-                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&#func_name())))
+                quote!(::std::option::Option::Some(#to_json(&(#func_name)())))
             }
             None => quote!(::std::option::Option::None),
         };
+        let field_meta_example = match &field.example {
+            Some(example) => quote! {
+                ::std::option::Option::Some({
+                    let value: #field_ty = #example;
+                    #to_json(&value)
+                })
+            },
+            None => quote!(::std::option::Option::None),
+        };
+
+        // The deprecation note and the version of the removal imply that the
+        // field is deprecated.
+        let deprecated =
+            field.deprecated || field.deprecation_note.is_some() || field.removed_in.is_some();
+        let deprecation_note = optional_literal(&field.deprecation_note);
+        let removed_in = optional_literal(&field.removed_in);
+        let field_xml = match &field.xml {
+            Some(xml) => {
+                let xml = xml.to_token_stream(&crate_name);
+                quote!(::std::option::Option::Some(#xml))
+            }
+            None => quote!(::std::option::Option::None),
+        };
+
+        let field_extensions =
+            extensions_token_stream(field_ident, &field.extensions, &crate_name)?;
 
-        register_types.push(quote!(<#field_ty as #crate_name::types::Type>::register(registry);));
+        register_types.push(quote!(<#schema_ty as #crate_name::types::Type>::register(registry);));
 
-        meta_fields.push(quote! {{
-            let original_schema = <#field_ty as #crate_name::types::Type>::schema_ref();
+        meta_fields.push(quote! {
+            fields.push({
+            let original_schema = #schema_ref;
             let patch_schema = {
                 let mut schema = #crate_name::registry::MetaSchema::ANY;
                 schema.default = #field_meta_default;
+                schema.example = #field_meta_example;
                 schema.read_only = #read_only;
                 schema.write_only = #write_only;
+                schema.sensitive = #sensitive;
+                schema.nullable = #nullable;
+                schema.deprecated = #deprecated;
+                schema.deprecation_note = #deprecation_note;
+                schema.removed_in = #removed_in;
+                schema.xml = #field_xml;
+                schema.extensions = #field_extensions;
 
                 if let ::std::option::Option::Some(title) = #field_title {
                     schema.title = ::std::option::Option::Some(title);
@@ -226,23 +706,71 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     schema.description = ::std::option::Option::Some(field_description);
                 }
                 #validators_update_meta
+                #enum_values_update_meta
                 schema
             };
 
             (#field_name, original_schema.merge(patch_schema))
-        }});
+            });
+        });
 
-        let has_default = field.default.is_some();
+        // The fields that can be omitted when serializing are not required.
+        let has_default = field.default.is_some() || field.skip_serializing_if.is_some();
         required_fields.push(quote! {
-            if <#field_ty>::IS_REQUIRED && !#has_default {
+            if (#nullable || <#schema_ty>::IS_REQUIRED) && !#has_default {
                 fields.push(#field_name);
             }
         });
     }
 
+    // The computed properties are read-only, their values are returned by the
+    // methods and their types are inferred from the methods.
+    for method in &args.computed {
+        let method_ident = match method.segments.last() {
+            Some(segment) => &segment.ident,
+            None => continue,
+        };
+        let field_name = args.rename_all.rename(method_ident.unraw().to_string());
+        deserialize_fields.push(quote! {
+            if obj.contains_key(#field_name) {
+                return Err(#crate_name::types::ParseError::custom(format!("properties `{}` is read only.", #field_name)));
+            }
+        });
+        serialize_fields.push(quote! {
+            let value = #crate_name::types::ToJSON::to_json(&#method(self));
+            object.insert(::std::string::ToString::to_string(#field_name), value);
+        });
+        register_types.push(quote!(#crate_name::__private::register_computed(registry, #method);));
+        meta_fields.push(quote! {
+            fields.push((
+                #field_name,
+                #crate_name::__private::computed_schema_ref(#method).merge(#crate_name::registry::MetaSchema {
+                    read_only: true,
+                    ..#crate_name::registry::MetaSchema::ANY
+                }),
+            ));
+        });
+        required_fields.push(quote! {
+            if #crate_name::__private::computed_is_required(#method) {
+                fields.push(#field_name);
+            }
+        });
+    }
+
+    // The remaining properties are collected after the declared fields.
+    let additional_properties_meta = match additional_properties {
+        Some((deserialize_field, serialize_field, meta)) => {
+            deserialize_fields.push(deserialize_field);
+            serialize_fields.extend(serialize_field);
+            meta
+        }
+        None => quote!(::std::option::Option::None),
+    };
+
     let title = optional_literal(&title);
     let description = optional_literal(&description);
     let deprecated = args.deprecated;
+    let deny_additional_properties = args.deny_unknown_fields;
     let external_docs = match &args.external_docs {
         Some(external_docs) => {
             let s = external_docs.to_token_stream(&crate_name);
@@ -250,19 +778,55 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
         None => quote!(::std::option::Option::None),
     };
+    let xml = match &args.xml {
+        Some(xml) => {
+            if xml.attribute || xml.wrapped {
+                return Err(Error::new_spanned(
+                    ident,
+                    "The `attribute` and `wrapped` xml attributes can only be used on the fields.",
+                )
+                .into());
+            }
+            let xml = xml.to_token_stream(&crate_name);
+            quote!(::std::option::Option::Some(#xml))
+        }
+        None => quote!(::std::option::Option::None),
+    };
+    let extensions = extensions_token_stream(ident, &args.extensions, &crate_name)?;
+    // The properties are in the order of the declaration of the fields, unless
+    // they are sorted by the names.
+    let (sort_required, sort_properties) = if args.sort_properties {
+        (
+            Some(quote!(fields.sort_unstable();)),
+            Some(quote!(fields.sort_by(|(a, _), (b, _)| ::std::cmp::Ord::cmp(a, b));)),
+        )
+    } else {
+        (None, None)
+    };
     let meta = quote! {
         #crate_name::registry::MetaSchema {
             title: #title,
             description: #description,
             external_docs: #external_docs,
+            xml: #xml,
+            extensions: #extensions,
             required: {
                 #[allow(unused_mut)]
                 let mut fields = ::std::vec::Vec::new();
                 #(#required_fields)*
+                #sort_required
+                fields
+            },
+            properties: {
+                #[allow(unused_mut)]
+                let mut fields = ::std::vec::Vec::new();
+                #(#meta_fields)*
+                #sort_properties
                 fields
             },
-            properties: ::std::vec![#(#meta_fields),*],
+            additional_properties: #additional_properties_meta,
             deprecated: #deprecated,
+            deny_additional_properties: #deny_additional_properties,
             ..#crate_name::registry::MetaSchema::new("object")
         }
     };
@@ -277,11 +841,53 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     };
 
     let expanded = if args.concretes.is_empty() {
+        // The type parameters of a generic object must be objects too, and each
+        // instantiation has its own schema name, such as `Page_User`.
+        let type_params = args
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect::<Vec<_>>();
+        let mut generics = args.generics.clone();
+        if !type_params.is_empty() {
+            let where_clause = generics.make_where_clause();
+            for param in &type_params {
+                where_clause.predicates.push(parse_quote! {
+                    #param: #crate_name::types::ParseFromJSON + #crate_name::types::ToJSON
+                });
+            }
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let oai_typename = if let Some(rename_via) = &args.rename_via {
+            quote! {
+                #crate_name::registry::intern_type_name(#rename_via(
+                    &[#(<#type_params as #crate_name::types::Type>::name()),*],
+                ))
+            }
+        } else if is_template {
+            let type_param_names = type_params.iter().map(|param| param.to_string());
+            quote! {
+                #crate_name::registry::format_type_name(
+                    #oai_typename,
+                    &[#((#type_param_names, <#type_params as #crate_name::types::Type>::name())),*],
+                )
+            }
+        } else if type_params.is_empty() {
+            quote!(#oai_typename)
+        } else {
+            quote! {
+                #crate_name::registry::generic_type_name(
+                    #oai_typename,
+                    &[#(<#type_params as #crate_name::types::Type>::name()),*],
+                )
+            }
+        };
+
         let example = match &args.example {
             Some(path) => {
                 let path = &**path;
                 quote! {
-                    ::std::option::Option::Some(<Self as #impl_generics #crate_name::types::ToJSON>::to_json(&#path()))
+                    ::std::option::Option::Some(<Self as #crate_name::types::ToJSON>::to_json(&#path()))
                 }
             }
             None => quote!(::std::option::Option::None),
@@ -301,9 +907,11 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         } else {
             (
                 quote!(#crate_name::registry::MetaSchemaRef::Reference(#oai_typename)),
+                // The types of the fields are registered after the schema is
+                // created, so the recursive types are referenced.
                 quote! {
-                    #(#register_types)*
                     registry.create_schema::<Self, _>(#oai_typename, |registry| {
+                        #(#register_types)*
                         let mut meta = #meta;
                         meta.example = #example;
                         meta
@@ -336,7 +944,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     ::std::option::Option::Some(self)
                 }
 
-                fn raw_element_iter<'a>(&'a self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                fn raw_element_iter<'__iter>(&'__iter self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'__iter Self::RawElementValueType> + '__iter> {
                     ::std::boxed::Box::new(::std::iter::IntoIterator::into_iter(self.as_raw_value()))
                 }
             }
@@ -356,7 +964,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
             impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
                 fn to_json(&self) -> #crate_name::__private::serde_json::Value {
-                    let mut object = ::#crate_name::__private::serde_json::Map::new();
+                    let mut object = #crate_name::__private::serde_json::Map::new();
                     #(#serialize_fields)*
                     #crate_name::__private::serde_json::Value::Object(object)
                 }
@@ -428,12 +1036,14 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     }
 
                     fn register(registry: &mut #crate_name::registry::Registry) {
-                        let mut meta = Self::__internal_create_schema(registry);
-                        meta.example = #example;
-                        registry.create_schema::<Self, _>(#oai_typename, move |registry| meta);
+                        registry.create_schema::<Self, _>(#oai_typename, |registry| {
+                            let mut meta = Self::__internal_create_schema(registry);
+                            meta.example = #example;
+                            meta
+                        });
                     }
 
-                    fn raw_element_iter<'a>(&'a self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                    fn raw_element_iter<'__iter>(&'__iter self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'__iter Self::RawElementValueType> + '__iter> {
                         ::std::boxed::Box::new(::std::iter::IntoIterator::into_iter(self.as_raw_value()))
                     }
                 }
@@ -456,5 +1066,347 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         quote!(#(#code)*)
     };
 
-    Ok(expanded)
+    let impl_version = version_field.map(|field_ident| {
+        quote! {
+            impl #impl_generics #crate_name::payload::HasVersion for #ident #ty_generics #where_clause {
+                fn version(&self) -> ::std::string::String {
+                    ::std::string::ToString::to_string(&self.#field_ident)
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #expanded
+        #impl_version
+    })
+}
+
+/// Generates the implementations for a tuple struct, a struct with a single
+/// field is transparent and the others are fixed-length arrays.
+fn generate_tuple(
+    input: &DeriveInput,
+    args: &ObjectArgs,
+    crate_name: &TokenStream,
+) -> GeneratorResult<TokenStream> {
+    let ident = &args.ident;
+    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
+    let field_types = match &args.data {
+        Data::Struct(s) => s.fields.iter().map(|field| &field.ty).collect::<Vec<_>>(),
+        _ => unreachable!(),
+    };
+
+    if let syn::Data::Struct(s) = &input.data {
+        if let Some(attr) = s
+            .fields
+            .iter()
+            .flat_map(|field| &field.attrs)
+            .find(|attr| attr.path.is_ident("oai"))
+        {
+            return Err(Error::new_spanned(
+                attr,
+                "The field attributes cannot be used on the tuple structs.",
+            )
+            .into());
+        }
+    }
+
+    let transparent = field_types.len() == 1;
+    let unsupported = [
+        ("rename", transparent && args.rename.is_some()),
+        ("inline", transparent && *args.inline),
+        ("example", transparent && args.example.is_some()),
+        ("rename_via", args.rename_via.is_some()),
+        ("rename_all", args.rename_all.is_some()),
+        ("concrete", !args.concretes.is_empty()),
+        ("read_only_all", args.read_only_all),
+        ("write_only_all", args.write_only_all),
+        ("deny_unknown_fields", args.deny_unknown_fields),
+        ("shallow_validation", args.shallow_validation),
+        ("sort_properties", args.sort_properties),
+        ("xml", args.xml.is_some()),
+        ("extension", !args.extensions.is_empty()),
+        ("computed", !args.computed.is_empty()),
+    ];
+    if let Some((name, _)) = unsupported.iter().find(|(_, used)| *used) {
+        return Err(Error::new_spanned(
+            ident,
+            format!(
+                "The `{}` attribute cannot be used on the {} tuple structs.",
+                name,
+                if transparent {
+                    "single-field"
+                } else {
+                    "multi-field"
+                }
+            ),
+        )
+        .into());
+    }
+
+    let (title, description) = get_summary_and_description(&args.attrs)?;
+    let title = optional_literal(&title);
+    let description = optional_literal(&description);
+    let deprecated = args.deprecated;
+    let external_docs = match &args.external_docs {
+        Some(external_docs) => {
+            let s = external_docs.to_token_stream(crate_name);
+            quote!(::std::option::Option::Some(#s))
+        }
+        None => quote!(::std::option::Option::None),
+    };
+
+    if transparent {
+        let inner_ty = field_types[0];
+        return Ok(quote! {
+            impl #impl_generics #crate_name::types::Type for #ident #ty_generics #where_clause {
+                const IS_REQUIRED: bool = <#inner_ty as #crate_name::types::Type>::IS_REQUIRED;
+
+                type RawValueType = <#inner_ty as #crate_name::types::Type>::RawValueType;
+
+                type RawElementValueType = <#inner_ty as #crate_name::types::Type>::RawElementValueType;
+
+                fn name() -> ::std::borrow::Cow<'static, str> {
+                    <#inner_ty as #crate_name::types::Type>::name()
+                }
+
+                fn schema_ref() -> #crate_name::registry::MetaSchemaRef {
+                    <#inner_ty as #crate_name::types::Type>::schema_ref().merge(#crate_name::registry::MetaSchema {
+                        title: #title,
+                        description: #description,
+                        external_docs: #external_docs,
+                        deprecated: #deprecated,
+                        ..#crate_name::registry::MetaSchema::ANY
+                    })
+                }
+
+                fn register(registry: &mut #crate_name::registry::Registry) {
+                    <#inner_ty as #crate_name::types::Type>::register(registry);
+                }
+
+                fn as_raw_value(&self) -> ::std::option::Option<&Self::RawValueType> {
+                    <#inner_ty as #crate_name::types::Type>::as_raw_value(&self.0)
+                }
+
+                fn raw_element_iter<'__iter>(&'__iter self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'__iter Self::RawElementValueType> + '__iter> {
+                    <#inner_ty as #crate_name::types::Type>::raw_element_iter(&self.0)
+                }
+            }
+
+            impl #impl_generics #crate_name::types::ParseFromJSON for #ident #ty_generics #where_clause {
+                fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                    let value = ::std::result::Result::map_err(<#inner_ty as #crate_name::types::ParseFromJSON>::parse_from_json(value), #crate_name::types::ParseError::propagate)?;
+                    ::std::result::Result::Ok(Self(value))
+                }
+            }
+
+            impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
+                fn to_json(&self) -> #crate_name::__private::serde_json::Value {
+                    <#inner_ty as #crate_name::types::ToJSON>::to_json(&self.0)
+                }
+            }
+        });
+    }
+
+    let oai_typename = args.rename.clone().unwrap_or_else(|| ident.to_string());
+    let len = field_types.len();
+    let indexes = (0..len).map(syn::Index::from).collect::<Vec<_>>();
+    let items = (0..len)
+        .map(|idx| Ident::new(&format!("item{}", idx), Span::call_site()))
+        .collect::<Vec<_>>();
+    let meta = quote! {
+        #crate_name::registry::MetaSchema {
+            title: #title,
+            description: #description,
+            external_docs: #external_docs,
+            prefix_items: ::std::vec![#(<#field_types as #crate_name::types::Type>::schema_ref()),*],
+            min_items: ::std::option::Option::Some(#len),
+            max_items: ::std::option::Option::Some(#len),
+            deprecated: #deprecated,
+            ..#crate_name::registry::MetaSchema::new("array")
+        }
+    };
+    let example = match &args.example {
+        Some(path) => {
+            let path = &**path;
+            quote! {
+                ::std::option::Option::Some(<Self as #crate_name::types::ToJSON>::to_json(&#path()))
+            }
+        }
+        None => quote!(::std::option::Option::None),
+    };
+
+    let (fn_schema_ref, fn_register) = if *args.inline {
+        (
+            quote!(#crate_name::registry::MetaSchemaRef::Inline(Box::new({
+                let mut meta = #meta;
+                meta.example = #example;
+                meta
+            }))),
+            quote! {
+                #(<#field_types as #crate_name::types::Type>::register(registry);)*
+            },
+        )
+    } else {
+        (
+            quote!(#crate_name::registry::MetaSchemaRef::Reference(#oai_typename)),
+            quote! {
+                registry.create_schema::<Self, _>(#oai_typename, |registry| {
+                    #(<#field_types as #crate_name::types::Type>::register(registry);)*
+                    let mut meta = #meta;
+                    meta.example = #example;
+                    meta
+                })
+            },
+        )
+    };
+
+    Ok(quote! {
+        impl #impl_generics #crate_name::types::Type for #ident #ty_generics #where_clause {
+            const IS_REQUIRED: bool = true;
+
+            type RawValueType = Self;
+
+            type RawElementValueType = Self;
+
+            fn name() -> ::std::borrow::Cow<'static, str> {
+                ::std::convert::Into::into(#oai_typename)
+            }
+
+            fn schema_ref() -> #crate_name::registry::MetaSchemaRef {
+                #fn_schema_ref
+            }
+
+            fn register(registry: &mut #crate_name::registry::Registry) {
+                #fn_register
+            }
+
+            fn as_raw_value(&self) -> ::std::option::Option<&Self::RawValueType> {
+                ::std::option::Option::Some(self)
+            }
+
+            fn raw_element_iter<'__iter>(&'__iter self) -> ::std::boxed::Box<dyn ::std::iter::Iterator<Item = &'__iter Self::RawElementValueType> + '__iter> {
+                ::std::boxed::Box::new(::std::iter::IntoIterator::into_iter(self.as_raw_value()))
+            }
+        }
+
+        impl #impl_generics #crate_name::types::ParseFromJSON for #ident #ty_generics #where_clause {
+            fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
+                match value {
+                    #crate_name::__private::serde_json::Value::Array(items) => {
+                        match <[#crate_name::__private::serde_json::Value; #len] as ::std::convert::TryFrom<_>>::try_from(items) {
+                            ::std::result::Result::Ok([#(#items),*]) => ::std::result::Result::Ok(Self(
+                                #(::std::result::Result::map_err(<#field_types as #crate_name::types::ParseFromJSON>::parse_from_json(#items), #crate_name::types::ParseError::propagate)?),*
+                            )),
+                            ::std::result::Result::Err(items) => ::std::result::Result::Err(#crate_name::types::ParseError::custom(format!("expected {} items, found {}.", #len, items.len()))),
+                        }
+                    }
+                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+                }
+            }
+        }
+
+        impl #impl_generics #crate_name::types::ToJSON for #ident #ty_generics #where_clause {
+            fn to_json(&self) -> #crate_name::__private::serde_json::Value {
+                #crate_name::__private::serde_json::Value::Array(::std::vec![#(<#field_types as #crate_name::types::ToJSON>::to_json(&self.#indexes)),*])
+            }
+        }
+    })
+}
+
+/// Returns the map of the extensions, their names must start with `x-`.
+fn extensions_token_stream(
+    ident: &Ident,
+    extensions: &[Extension],
+    crate_name: &TokenStream,
+) -> GeneratorResult<TokenStream> {
+    if let Some(extension) = extensions
+        .iter()
+        .find(|extension| !extension.name.starts_with("x-"))
+    {
+        return Err(Error::new_spanned(
+            ident,
+            format!(
+                "The name of the extension `{}` must start with `x-`.",
+                extension.name
+            ),
+        )
+        .into());
+    }
+    let extensions = extensions
+        .iter()
+        .map(|extension| extension.to_token_stream(crate_name));
+    Ok(quote!(::std::iter::FromIterator::from_iter([#(#extensions),*])))
+}
+
+/// The subset of the serde attributes that is read by the `serde_compat`
+/// objects.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    skip: bool,
+}
+
+impl SerdeAttrs {
+    fn parse(attrs: &[Attribute]) -> GeneratorResult<Self> {
+        let mut serde_attrs = SerdeAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("serde")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                _ => continue,
+            };
+            for item in list.nested {
+                match item {
+                    NestedMeta::Meta(Meta::NameValue(nv)) => match &nv.lit {
+                        Lit::Str(value) if nv.path.is_ident("rename") => {
+                            serde_attrs.rename = Some(value.value());
+                        }
+                        Lit::Str(value) if nv.path.is_ident("rename_all") => {
+                            serde_attrs.rename_all = Some(value.value());
+                        }
+                        _ => {}
+                    },
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                        serde_attrs.skip = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(serde_attrs)
+    }
+}
+
+/// Reads the `rename`, `rename_all` and `skip` serde attributes, the `oai`
+/// attributes take precedence.
+fn apply_serde_attrs(args: &mut ObjectArgs) -> GeneratorResult<()> {
+    let serde_attrs = SerdeAttrs::parse(&args.attrs)?;
+    if args.rename.is_none() && args.rename_via.is_none() {
+        args.rename = serde_attrs.rename;
+    }
+    if let (None, Some(rule)) = (&args.rename_all, &serde_attrs.rename_all) {
+        args.rename_all = Some(RenameRule::from_string(rule).map_err(|_| {
+            Error::new_spanned(
+                &args.ident,
+                format!(
+                    "The `rename_all` rule `{}` of the serde attribute is not supported.",
+                    rule
+                ),
+            )
+        })?);
+    }
+
+    if let Data::Struct(s) = &mut args.data {
+        for field in &mut s.fields {
+            let serde_attrs = SerdeAttrs::parse(&field.attrs)?;
+            if field.rename.is_none() && !field.flatten && !field.additional_properties {
+                field.rename = serde_attrs.rename;
+            }
+            if serde_attrs.skip && field.skip.is_none() {
+                field.skip = Some(DefaultValue::Default);
+            }
+        }
+    }
+    Ok(())
 }
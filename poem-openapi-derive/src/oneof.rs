@@ -5,7 +5,7 @@ use darling::{
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{Attribute, DeriveInput, Error, Type};
+use syn::{ext::IdentExt, Attribute, DeriveInput, Error, Type};
 
 use crate::{
     common_args::ExternalDocument,
@@ -21,6 +21,8 @@ struct OneOfItem {
 
     #[darling(default)]
     mapping: Option<String>,
+    #[darling(default)]
+    other: bool,
 }
 
 #[derive(FromDeriveInput)]
@@ -32,7 +34,12 @@ struct OneOfArgs {
 
     #[darling(default)]
     internal: bool,
-    property_name: String,
+    #[darling(default)]
+    property_name: Option<String>,
+    #[darling(default)]
+    content: Option<String>,
+    #[darling(default)]
+    external: bool,
     #[darling(default)]
     external_docs: Option<ExternalDocument>,
 }
@@ -44,22 +51,106 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let (title, description) = get_summary_and_description(&args.attrs)?;
     let title = optional_literal(&title);
     let description = optional_literal(&description);
-    let property_name = &args.property_name;
+    let external_docs = match &args.external_docs {
+        Some(external_docs) => {
+            let s = external_docs.to_token_stream(&crate_name);
+            quote!(::std::option::Option::Some(#s))
+        }
+        None => quote!(::std::option::Option::None),
+    };
 
     let e = match &args.data {
         Data::Enum(e) => e,
         _ => return Err(Error::new_spanned(ident, "OneOf can only be applied to an enum.").into()),
     };
 
+    if args.external && (args.property_name.is_some() || args.content.is_some()) {
+        return Err(Error::new_spanned(
+            ident,
+            "The `external` attribute cannot be used with the `property_name` and `content` attributes.",
+        )
+        .into());
+    }
+    if !args.external && args.property_name.is_none() {
+        return Err(Error::new_spanned(
+            ident,
+            "The `property_name` attribute is required, use the `external` attribute for the externally tagged layout.",
+        )
+        .into());
+    }
+
+    let property_name = match (&args.property_name, &args.content) {
+        (Some(property_name), None) => property_name,
+        (tag, content) => {
+            let schema_fields = quote! {
+                title: #title,
+                description: #description,
+                external_docs: #external_docs,
+            };
+            return generate_tagged(
+                &crate_name,
+                ident,
+                e,
+                tag.as_deref(),
+                content.as_deref(),
+                schema_fields,
+            );
+        }
+    };
+    let path = parse_property_path(property_name)
+        .ok_or_else(|| Error::new_spanned(ident, "Invalid property name."))?;
+    let (property_name, parents) = path.split_last().unwrap();
+
+    // The discriminator nested inside the payload is located with a JSON
+    // pointer, and the missing parent objects are created when serializing.
+    let (get_property, get_object_mut) = if parents.is_empty() {
+        (
+            quote!(value.as_object().and_then(|obj| obj.get(#property_name))),
+            quote!(value.as_object_mut()),
+        )
+    } else {
+        let pointer = args.property_name.as_deref().unwrap();
+        (
+            quote!(value.pointer(#pointer)),
+            quote! {
+                ::std::iter::Iterator::try_fold(&mut [#(#parents),*].iter(), &mut value, |value, name| {
+                    value.as_object_mut().map(|obj| {
+                        obj.entry(*name).or_insert_with(|| #crate_name::__private::serde_json::Value::Object(::std::default::Default::default()))
+                    })
+                })
+                .and_then(|value| value.as_object_mut())
+            },
+        )
+    };
+
     let mut types = Vec::new();
     let mut from_json = Vec::new();
     let mut to_json = Vec::new();
     let mut names = Vec::new();
     let mut mapping = Vec::new();
+    let mut other = None;
 
     for variant in e {
         let item_ident = &variant.ident;
 
+        if variant.other {
+            if other.is_some() || variant.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "Only one variant containing a `serde_json::Value` can be marked as `other`.",
+                )
+                .into());
+            }
+
+            other = Some(quote! {
+                ::std::option::Option::Some(_) => ::std::result::Result::Ok(Self::#item_ident(value)),
+            });
+            to_json.push(quote! {
+                Self::#item_ident(value) => ::std::clone::Clone::clone(value)
+            });
+            continue;
+        }
+
         match variant.fields.len() {
             1 => {
                 let object_ty = &variant.fields.fields[0];
@@ -79,7 +170,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 to_json.push(quote! {
                     Self::#item_ident(obj) => {
                         let mut value = <#object_ty as #crate_name::types::ToJSON>::to_json(obj);
-                        if let ::std::option::Option::Some(obj) = value.as_object_mut() {
+                        if let ::std::option::Option::Some(obj) = #get_object_mut {
                             obj.insert(::std::convert::Into::into(#property_name), ::std::convert::Into::into(#mapping_name));
                         }
                         value
@@ -101,15 +192,79 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     }
 
-    let external_docs = match &args.external_docs {
-        Some(external_docs) => {
-            let s = external_docs.to_token_stream(&crate_name);
-            quote!(::std::option::Option::Some(#s))
+    // The unknown discriminator values are accepted by the `other` variant.
+    let names = if other.is_some() {
+        quote!(extensible_enum_items: ::std::vec![#(::std::convert::Into::into(#names)),*])
+    } else {
+        quote!(enum_items: ::std::vec![#(::std::convert::Into::into(#names)),*])
+    };
+
+    let mut properties = quote! {
+        (#property_name, #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
+            #names,
+            ..#crate_name::registry::MetaSchema::new("string")
+        })))
+    };
+    for parent in parents.iter().rev() {
+        properties = quote! {
+            (#parent, #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
+                properties: ::std::vec![#properties],
+                ..#crate_name::registry::MetaSchema::new("object")
+            })))
+        };
+    }
+
+    // OpenAPI requires the discriminator property to be declared at the top
+    // level of the schema.
+    let discriminator = if parents.is_empty() {
+        quote! {
+            ::std::option::Option::Some(#crate_name::registry::MetaDiscriminatorObject {
+                property_name: #property_name,
+                mapping: ::std::vec![#(#mapping),*],
+            })
         }
-        None => quote!(::std::option::Option::None),
+    } else {
+        quote!(::std::option::Option::None)
     };
 
-    let expanded = quote! {
+    let schema = quote! {
+        #crate_name::registry::MetaSchema {
+            title: #title,
+            description: #description,
+            external_docs: #external_docs,
+            one_of: ::std::vec![#(<#types as #crate_name::types::Type>::schema_ref()),*],
+            properties: ::std::vec![#properties],
+            discriminator: #discriminator,
+            ..#crate_name::registry::MetaSchema::new("object")
+        }
+    };
+    let parse_from_json = quote! {
+        match #get_property {
+            #(#from_json,)*
+            #other
+            _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+        }
+    };
+
+    Ok(expand(
+        &crate_name,
+        ident,
+        schema,
+        &types,
+        parse_from_json,
+        &to_json,
+    ))
+}
+
+fn expand(
+    crate_name: &TokenStream,
+    ident: &Ident,
+    schema: TokenStream,
+    types: &[&Type],
+    parse_from_json: TokenStream,
+    to_json: &[TokenStream],
+) -> TokenStream {
+    quote! {
         impl #crate_name::types::Type for #ident {
             const IS_REQUIRED: bool = true;
 
@@ -122,21 +277,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
 
             fn schema_ref() -> #crate_name::registry::MetaSchemaRef {
-                #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
-                    title: #title,
-                    description: #description,
-                    external_docs: #external_docs,
-                    one_of: ::std::vec![#(<#types as #crate_name::types::Type>::schema_ref()),*],
-                    properties: ::std::vec![(#property_name, #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
-                        enum_items: ::std::vec![#(::std::convert::Into::into(#names)),*],
-                        ..#crate_name::registry::MetaSchema::new("string")
-                    })))],
-                    discriminator: ::std::option::Option::Some(#crate_name::registry::MetaDiscriminatorObject {
-                        property_name: #property_name,
-                        mapping: ::std::vec![#(#mapping),*],
-                    }),
-                    ..#crate_name::registry::MetaSchema::new("object")
-                }))
+                #crate_name::registry::MetaSchemaRef::Inline(Box::new(#schema))
             }
 
             fn register(registry: &mut #crate_name::registry::Registry) {
@@ -154,10 +295,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
 
         impl #crate_name::types::ParseFromJSON for #ident {
             fn parse_from_json(value: #crate_name::__private::serde_json::Value) -> ::std::result::Result<Self, #crate_name::types::ParseError<Self>> {
-                match value.as_object().and_then(|obj| obj.get(#property_name)) {
-                    #(#from_json,)*
-                    _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
-                }
+                #parse_from_json
             }
         }
 
@@ -168,7 +306,189 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 }
             }
         }
+    }
+}
+
+/// Generates the externally tagged (`{"A": {...}}`) or the adjacently tagged
+/// (`{"type": "A", "data": {...}}`) layout, in which the items are named after
+/// the variants.
+fn generate_tagged(
+    crate_name: &TokenStream,
+    ident: &Ident,
+    e: &[OneOfItem],
+    tag: Option<&str>,
+    content: Option<&str>,
+    schema_fields: TokenStream,
+) -> GeneratorResult<TokenStream> {
+    match tag {
+        Some(tag) if tag.starts_with('/') => {
+            return Err(Error::new_spanned(
+                ident,
+                "The `property_name` attribute can not be a JSON pointer when the `content` attribute is specified.",
+            )
+            .into())
+        }
+        _ => {}
+    }
+
+    let mut types = Vec::new();
+    let mut one_of = Vec::new();
+    let mut from_json = Vec::new();
+    let mut to_json = Vec::new();
+    let mut other = None;
+
+    for variant in e {
+        let item_ident = &variant.ident;
+
+        if variant.other {
+            if other.is_some() || variant.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "Only one variant containing a `serde_json::Value` can be marked as `other`.",
+                )
+                .into());
+            }
+
+            other = Some(item_ident);
+            to_json.push(quote! {
+                Self::#item_ident(value) => ::std::clone::Clone::clone(value)
+            });
+            continue;
+        }
+
+        if variant.fields.len() != 1 {
+            return Err(Error::new_spanned(&variant.ident, "Incorrect oneof definition.").into());
+        }
+
+        let object_ty = &variant.fields.fields[0];
+        let name = variant
+            .mapping
+            .clone()
+            .unwrap_or_else(|| item_ident.unraw().to_string());
+        types.push(object_ty);
+
+        match (tag, content) {
+            (Some(tag), Some(content)) => {
+                one_of.push(quote! {
+                    #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
+                        required: ::std::vec![#tag, #content],
+                        properties: ::std::vec![
+                            (#tag, #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
+                                enum_items: ::std::vec![::std::convert::Into::into(#name)],
+                                ..#crate_name::registry::MetaSchema::new("string")
+                            }))),
+                            (#content, <#object_ty as #crate_name::types::Type>::schema_ref()),
+                        ],
+                        ..#crate_name::registry::MetaSchema::new("object")
+                    }))
+                });
+                from_json.push(quote! {
+                    ::std::option::Option::Some(tag) if tag == #name => {
+                        let mut value = value;
+                        let content = value
+                            .as_object_mut()
+                            .and_then(|obj| obj.remove(#content))
+                            .unwrap_or_default();
+                        <#object_ty as #crate_name::types::ParseFromJSON>::parse_from_json(content).map(Self::#item_ident).map_err(#crate_name::types::ParseError::propagate)
+                    }
+                });
+                to_json.push(quote! {
+                    Self::#item_ident(obj) => {
+                        let mut map = #crate_name::__private::serde_json::Map::new();
+                        map.insert(::std::convert::Into::into(#tag), ::std::convert::Into::into(#name));
+                        map.insert(::std::convert::Into::into(#content), <#object_ty as #crate_name::types::ToJSON>::to_json(obj));
+                        #crate_name::__private::serde_json::Value::Object(map)
+                    }
+                });
+            }
+            _ => {
+                one_of.push(quote! {
+                    #crate_name::registry::MetaSchemaRef::Inline(Box::new(#crate_name::registry::MetaSchema {
+                        required: ::std::vec![#name],
+                        properties: ::std::vec![(#name, <#object_ty as #crate_name::types::Type>::schema_ref())],
+                        ..#crate_name::registry::MetaSchema::new("object")
+                    }))
+                });
+                from_json.push(quote! {
+                    #name => <#object_ty as #crate_name::types::ParseFromJSON>::parse_from_json(value).map(Self::#item_ident).map_err(#crate_name::types::ParseError::propagate)
+                });
+                to_json.push(quote! {
+                    Self::#item_ident(obj) => {
+                        let mut map = #crate_name::__private::serde_json::Map::new();
+                        map.insert(::std::convert::Into::into(#name), <#object_ty as #crate_name::types::ToJSON>::to_json(obj));
+                        #crate_name::__private::serde_json::Value::Object(map)
+                    }
+                });
+            }
+        }
+    }
+
+    let unknown = match other {
+        Some(other) => quote!(::std::result::Result::Ok(Self::#other(value))),
+        None => {
+            quote!(::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)))
+        }
+    };
+
+    let parse_from_json = match (tag, content) {
+        (Some(tag), Some(_)) => quote! {
+            match value.as_object().and_then(|obj| obj.get(#tag)) {
+                #(#from_json,)*
+                ::std::option::Option::Some(_) => #unknown,
+                _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+            }
+        },
+        _ => quote! {
+            match value {
+                #crate_name::__private::serde_json::Value::Object(obj) if obj.len() == 1 => {
+                    let (name, value) = ::std::iter::IntoIterator::into_iter(obj).next().unwrap();
+                    match name.as_str() {
+                        #(#from_json,)*
+                        _ => {
+                            let value = #crate_name::__private::serde_json::Value::Object(::std::iter::FromIterator::from_iter([(name, value)]));
+                            #unknown
+                        }
+                    }
+                }
+                _ => ::std::result::Result::Err(#crate_name::types::ParseError::expected_type(value)),
+            }
+        },
+    };
+
+    let schema = quote! {
+        #crate_name::registry::MetaSchema {
+            #schema_fields
+            one_of: ::std::vec![#(#one_of),*],
+            ..#crate_name::registry::MetaSchema::new("object")
+        }
+    };
+
+    Ok(expand(
+        crate_name,
+        ident,
+        schema,
+        &types,
+        parse_from_json,
+        &to_json,
+    ))
+}
+
+/// Splits the property name into the names of the nested properties, the
+/// property name starting with `/` is a JSON pointer.
+fn parse_property_path(property_name: &str) -> Option<Vec<String>> {
+    let pointer = match property_name.strip_prefix('/') {
+        Some(pointer) => pointer,
+        None => return Some(vec![property_name.to_string()]),
     };
 
-    Ok(expanded)
+    pointer
+        .split('/')
+        .map(|name| {
+            if name.is_empty() {
+                None
+            } else {
+                Some(name.replace("~1", "/").replace("~0", "~"))
+            }
+        })
+        .collect()
 }
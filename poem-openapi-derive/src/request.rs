@@ -130,6 +130,24 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                     })
                 }
 
+                fn request_params() -> ::std::vec::Vec<#crate_name::registry::MetaOperationParam> {
+                    let mut params = ::std::vec::Vec::<#crate_name::registry::MetaOperationParam>::new();
+                    #(
+                        for param in <#schemas as #crate_name::payload::ParsePayload>::params() {
+                            if !::std::iter::Iterator::any(&mut ::std::iter::IntoIterator::into_iter(&params), |p| p.name == param.name) {
+                                params.push(param);
+                            }
+                        }
+                    )*
+                    params
+                }
+
+                fn request_responses() -> ::std::vec::Vec<#crate_name::registry::MetaResponse> {
+                    let mut responses = ::std::vec::Vec::new();
+                    #(::std::iter::Extend::extend(&mut responses, <#schemas as #crate_name::payload::ParsePayload>::responses());)*
+                    responses
+                }
+
                 async fn from_request(
                     request: &'__request #crate_name::__private::poem::Request,
                     body: &mut #crate_name::__private::poem::RequestBody,
@@ -25,6 +25,8 @@ pub(crate) enum AuthType {
     OAuth2,
     #[darling(rename = "openid_connect")]
     OpenIdConnect,
+    #[darling(rename = "hmac")]
+    Hmac,
 }
 
 #[derive(FromMeta)]
@@ -381,6 +383,20 @@ impl SecuritySchemeArgs {
                     });
                 }
             }
+            AuthType::Hmac => {
+                quote! {
+                    registry.create_security_scheme(#name, #crate_name::registry::MetaSecurityScheme {
+                        ty: "apiKey",
+                        description: #description,
+                        name: ::std::option::Option::Some(#crate_name::auth::hmac::SIGNATURE_HEADER),
+                        key_in: ::std::option::Option::Some("header"),
+                        scheme: ::std::option::Option::None,
+                        bearer_format: ::std::option::Option::None,
+                        flows: ::std::option::Option::None,
+                        openid_connect_url: ::std::option::Option::None,
+                    });
+                }
+            }
         };
         Ok(ts)
     }
@@ -408,6 +424,20 @@ impl SecuritySchemeArgs {
             AuthType::OpenIdConnect => {
                 quote!(<#crate_name::auth::Bearer as #crate_name::auth::BearerAuthorization>::from_request(req))
             }
+            AuthType::Hmac => {
+                quote!(#crate_name::auth::hmac::extract_signature(req, body).await)
+            }
+        }
+    }
+
+    fn generate_security_params(&self, crate_name: &TokenStream) -> Option<TokenStream> {
+        match self.ty {
+            AuthType::Hmac => Some(quote! {
+                fn security_params() -> ::std::vec::Vec<#crate_name::registry::MetaOperationParam> {
+                    <#crate_name::auth::HmacSignature as #crate_name::auth::HmacAuthorization>::params()
+                }
+            }),
+            _ => None,
         }
     }
 }
@@ -441,9 +471,10 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let register_security_scheme =
         args.generate_register_security_scheme(&crate_name, &oai_typename)?;
     let from_request = args.generate_from_request(&crate_name);
+    let security_params = args.generate_security_params(&crate_name);
     let checker = args.checker.as_ref().map(|path| {
         quote! {
-            let output = ::std::option::Option::ok_or(#path(&req, output).await, #crate_name::error::AuthorizationError)?;
+            let output = #crate_name::__private::CheckerOutput::into_result(#path(&req, output).await)?;
         }
     });
 
@@ -463,6 +494,18 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
                 ::std::option::Option::Some(#oai_typename)
             }
 
+            #security_params
+
+            fn permissions(&self) -> ::std::option::Option<&dyn #crate_name::auth::Permissions> {
+                use #crate_name::__private::{ViaNoPermissions as _, ViaPermissions as _};
+                (&#crate_name::__private::PermissionsProbe(&self.0)).probe_permissions()
+            }
+
+            fn principal(&self) -> ::std::option::Option<::std::string::String> {
+                use #crate_name::__private::{ViaNoPrincipal as _, ViaPrincipal as _};
+                (&#crate_name::__private::PrincipalProbe(&self.0)).probe_principal()
+            }
+
             async fn from_request(
                 req: &'a #crate_name::__private::poem::Request,
                 body: &mut #crate_name::__private::poem::RequestBody,
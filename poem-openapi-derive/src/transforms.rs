@@ -0,0 +1,66 @@
+use darling::FromMeta;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Lit, Meta, NestedMeta, Path};
+
+#[derive(Clone)]
+enum TransformItem {
+    Builtin(Ident),
+    Custom(Path),
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct Transforms(Vec<TransformItem>);
+
+impl FromMeta for Transforms {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        let mut res = Vec::new();
+
+        for item in items {
+            match item {
+                NestedMeta::Meta(Meta::Path(path)) => match path.get_ident() {
+                    Some(ident) if ident == "trim" || ident == "lowercase" || ident == "uppercase" => {
+                        res.push(TransformItem::Builtin(ident.clone()));
+                    }
+                    _ => {
+                        return Err(darling::Error::custom(
+                            "Unknown transform, the possible values are \"trim\", \"lowercase\", \"uppercase\".",
+                        )
+                        .with_span(path))
+                    }
+                },
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("custom") => {
+                    match &nv.lit {
+                        Lit::Str(s) => res.push(TransformItem::Custom(s.parse()?)),
+                        lit => return Err(darling::Error::unexpected_lit_type(lit).with_span(lit)),
+                    }
+                }
+                _ => return Err(darling::Error::custom("Invalid transform.").with_span(item)),
+            }
+        }
+
+        Ok(Transforms(res))
+    }
+}
+
+impl Transforms {
+    pub(crate) fn create_transformer(
+        &self,
+        crate_name: &TokenStream,
+        value: &Ident,
+    ) -> Option<TokenStream> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let funcs = self.0.iter().map(|item| match item {
+            TransformItem::Builtin(ident) => quote!(#crate_name::transform::#ident),
+            TransformItem::Custom(path) => quote!(#path),
+        });
+
+        Some(quote! {
+            let mut #value = #value;
+            #(#crate_name::transform::Transform::transform(&mut #value, #funcs);)*
+        })
+    }
+}
@@ -77,6 +77,24 @@ pub(crate) fn optional_literal(s: &Option<impl AsRef<str>>) -> TokenStream {
     }
 }
 
+/// Returns the predicate that combines the `#[cfg(...)]` attributes, or
+/// `None` if there are none.
+pub(crate) fn get_cfg_predicate(attrs: &[Attribute]) -> Option<TokenStream> {
+    let predicates = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"))
+        .map(|attr| {
+            let tokens = &attr.tokens;
+            quote!(all #tokens)
+        })
+        .collect::<Vec<_>>();
+    if predicates.is_empty() {
+        None
+    } else {
+        Some(quote!(all(#(#predicates),*)))
+    }
+}
+
 pub(crate) fn remove_oai_attrs(attrs: &mut Vec<Attribute>) {
     if let Some((idx, _)) = attrs
         .iter()
@@ -214,13 +214,14 @@ impl Validators {
                         name: #arg_name,
                         reason: ::std::format!("verification failed. {}", validator),
                     };
+                    let err = #crate_name::__private::rewrite_error_message(&request, ::std::convert::Into::into(err));
 
                     if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER {
-                        let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(std::convert::Into::into(err));
+                        let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err);
                         let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
                         return ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response);
                     } else {
-                        return ::std::result::Result::Err(std::convert::Into::into(err));
+                        return ::std::result::Result::Err(err);
                     }
                 }
             }
@@ -234,13 +235,14 @@ impl Validators {
                         name: #arg_name,
                         reason: ::std::format!("verification failed. {}", validator),
                     };
+                    let err = #crate_name::__private::rewrite_error_message(&request, ::std::convert::Into::into(err));
 
                     if <#res_ty as #crate_name::ApiResponse>::BAD_REQUEST_HANDLER {
-                        let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(std::convert::Into::into(err));
+                        let res = <#res_ty as #crate_name::ApiResponse>::from_parse_request_error(err);
                         let res = #crate_name::__private::poem::error::IntoResult::into_result(res);
                         return ::std::result::Result::map(res, #crate_name::__private::poem::IntoResponse::into_response);
                     } else {
-                        return ::std::result::Result::Err(std::convert::Into::into(err));
+                        return ::std::result::Result::Err(err);
                     }
                 }
             }
@@ -205,7 +205,7 @@ fn generate_operation(
                 quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&<#arg_ty as ::std::default::Default>::default())))
             }
             Some(DefaultValue::Function(func_name)) => {
-                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&#func_name())))
+                quote!(::std::option::Option::Some(#crate_name::types::ToJSON::to_json(&(#func_name)())))
             }
             None => quote!(::std::option::Option::None),
         };
@@ -233,7 +233,7 @@ fn generate_operation(
                 };
 
                 let meta_param = #crate_name::registry::MetaOperationParam {
-                    name: #param_name,
+                    name: <#arg_ty as #crate_name::ApiExtractor>::param_name().unwrap_or(#param_name),
                     schema: original_schema.merge(patch_schema),
                     in_type: <#arg_ty as #crate_name::ApiExtractor>::param_in().unwrap(),
                     description: #param_desc,
@@ -294,6 +294,8 @@ fn generate_operation(
                         deprecated: #deprecated,
                         security: ::std::vec![],
                         operation_id: #operation_id,
+                        priority: ::std::option::Option::None,
+                        extensions: <#res_ty as #crate_name::ApiResponse>::operation_extensions(),
                     }
                 }
             },
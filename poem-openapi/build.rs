@@ -0,0 +1,44 @@
+fn main() {
+    #[cfg(feature = "swagger-ui")]
+    compress_assets(
+        "src/ui/swagger_ui",
+        &["swagger-ui-bundle.js", "swagger-ui.css"],
+    );
+}
+
+/// Writes the `br` and `gzip` compressed assets to `OUT_DIR`, so that the
+/// uncompressed bytes are not embedded in the binary, and the `ETag` of each
+/// asset, which is the CRC-32 and the length of the uncompressed bytes.
+#[cfg(feature = "swagger-ui")]
+fn compress_assets(dir: &str, names: &[&str]) {
+    use std::{fs, io::Write, path::Path};
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    for name in names {
+        let path = Path::new(dir).join(name);
+        println!("cargo:rerun-if-changed={}", path.display());
+        let data = fs::read(&path).unwrap();
+
+        let mut crc = flate2::Crc::new();
+        crc.update(&data);
+        fs::write(
+            Path::new(&out_dir).join(format!("{}.etag", name)),
+            format!("\"{:08x}-{:x}\"", crc.sum(), data.len()),
+        )
+        .unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&data).unwrap();
+        fs::write(
+            Path::new(&out_dir).join(format!("{}.gz", name)),
+            encoder.finish().unwrap(),
+        )
+        .unwrap();
+
+        let mut compressed = Vec::new();
+        let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 9, 22);
+        encoder.write_all(&data).unwrap();
+        drop(encoder);
+        fs::write(Path::new(&out_dir).join(format!("{}.br", name)), compressed).unwrap();
+    }
+}
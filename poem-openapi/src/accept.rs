@@ -0,0 +1,183 @@
+use poem::{endpoint::BoxEndpoint, http::header, Endpoint, Request, Response, Result};
+
+use crate::{
+    error::NotAcceptableError,
+    registry::{MetaApi, MetaOperation, MetaResponse},
+};
+
+/// Rejects the requests with `406 Not Acceptable` if the `Accept` header
+/// excludes all the content types produced by the operation.
+struct AcceptEndpoint {
+    content_types: Vec<&'static str>,
+    inner: BoxEndpoint<'static, Response>,
+}
+
+#[poem::async_trait]
+impl Endpoint for AcceptEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let accept = req
+            .headers()
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        if accept.trim().is_empty() {
+            return self.inner.call(req).await;
+        }
+
+        if !self
+            .content_types
+            .iter()
+            .any(|content_type| is_acceptable(&accept, content_type))
+        {
+            return Err(NotAcceptableError { accept }.into());
+        }
+        self.inner.call(req).await
+    }
+}
+
+fn essence(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+/// Returns `true` if the most specific media range of the `Accept` header
+/// that matches the content type does not have `q=0`.
+fn is_acceptable(accept: &str, content_type: &str) -> bool {
+    accept_quality(accept, content_type)
+        .map(|quality| quality > 0.0)
+        .unwrap_or_default()
+}
+
+/// Returns the quality of the most specific media range of the `Accept`
+/// header that matches the content type, or `None` if no range matches.
+pub(crate) fn accept_quality(accept: &str, content_type: &str) -> Option<f32> {
+    let content_type = essence(content_type);
+    if content_type.contains('*') {
+        return Some(1.0);
+    }
+    let (ty, _) = content_type.split_once('/').unwrap_or((content_type, ""));
+
+    let mut matched: Option<(u8, f32)> = None;
+    for item in accept.split(',') {
+        let mut params = item.split(';');
+        let range = params.next().unwrap_or_default().trim();
+        let specificity = if range == "*/*" {
+            0
+        } else if range
+            .strip_suffix("/*")
+            .map(|range_ty| range_ty.eq_ignore_ascii_case(ty))
+            .unwrap_or_default()
+        {
+            1
+        } else if range.eq_ignore_ascii_case(content_type) {
+            2
+        } else {
+            continue;
+        };
+        let quality = params
+            .find_map(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+            })
+            .unwrap_or(1.0);
+        if matched.map(|(s, _)| specificity > s).unwrap_or(true) {
+            matched = Some((specificity, quality));
+        }
+    }
+    matched.map(|(_, quality)| quality)
+}
+
+fn content_types(operation: &MetaOperation) -> impl Iterator<Item = &'static str> + '_ {
+    operation
+        .responses
+        .responses
+        .iter()
+        .flat_map(|resp| &resp.content)
+        .map(|media| media.content_type)
+}
+
+pub(crate) fn apply(
+    ep: BoxEndpoint<'static, Response>,
+    operation: &MetaOperation,
+) -> BoxEndpoint<'static, Response> {
+    let mut content_types = content_types(operation).collect::<Vec<_>>();
+    if content_types.is_empty() {
+        return ep;
+    }
+    content_types.sort_unstable();
+    content_types.dedup();
+    Box::new(AcceptEndpoint {
+        content_types,
+        inner: ep,
+    })
+}
+
+/// Adds the `406` response to the operations that produce the content.
+pub(crate) fn document(apis: &mut [MetaApi]) {
+    for operation in apis
+        .iter_mut()
+        .flat_map(|api| &mut api.paths)
+        .flat_map(|path| &mut path.operations)
+    {
+        if content_types(operation).next().is_none()
+            || operation
+                .responses
+                .responses
+                .iter()
+                .any(|resp| resp.status == Some(406))
+        {
+            continue;
+        }
+        operation.responses.responses.push(MetaResponse {
+            description:
+                "The operation cannot produce a content type accepted by the `Accept` header.",
+            status: Some(406),
+            content: vec![],
+            headers: vec![],
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acceptable() {
+        assert!(is_acceptable("application/json", "application/json"));
+        assert!(is_acceptable(
+            "text/html, application/*;q=0.5",
+            "application/json; charset=utf-8"
+        ));
+        assert!(is_acceptable("*/*", "application/json"));
+        assert!(is_acceptable("text/plain", "*/*"));
+        assert!(!is_acceptable("text/html", "application/json"));
+        assert!(!is_acceptable(
+            "*/*, application/json;q=0",
+            "application/json"
+        ));
+        assert!(!is_acceptable("application/*;q=0", "application/json"));
+        assert!(is_acceptable(
+            "application/*;q=0, application/json",
+            "application/json"
+        ));
+    }
+
+    #[test]
+    fn quality() {
+        assert_eq!(
+            accept_quality("text/html, application/*;q=0.5", "application/json"),
+            Some(0.5)
+        );
+        assert_eq!(
+            accept_quality("*/*;q=0.1, application/json", "application/json"),
+            Some(1.0)
+        );
+        assert_eq!(accept_quality("text/html", "application/json"), None);
+    }
+}
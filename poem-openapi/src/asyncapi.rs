@@ -0,0 +1,139 @@
+use serde_json::{json, Map, Value};
+
+use crate::registry::{MetaOperation, MetaSchemaRef, OpenApiDocument};
+
+const ASYNCAPI_VERSION: &str = "2.6.0";
+const EVENT_STREAM: &str = "text/event-stream";
+
+/// Returns the AsyncAPI document of the event-driven interfaces of the
+/// document, they are the server-sent event endpoints, the WebSocket endpoints
+/// and the webhooks.
+///
+/// The channels are named after the paths of the endpoints and the names of
+/// the webhooks, the messages reference the schemas of the OpenAPI document.
+/// Adds the operations of the channel to the channels, the operations of the
+/// different methods of a path are merged into the same channel.
+fn merge_channel(channels: &mut Map<String, Value>, name: &str, channel: Map<String, Value>) {
+    if let Value::Object(existing) = channels
+        .entry(name)
+        .or_insert_with(|| Value::Object(Map::new()))
+    {
+        existing.extend(channel);
+    }
+}
+
+pub(crate) fn document(doc: &OpenApiDocument) -> Value {
+    let mut channels = Map::new();
+
+    for api in &doc.apis {
+        for path in &api.paths {
+            for operation in &path.operations {
+                let mut channel = Map::new();
+
+                if let Some(websocket) = operation.extensions.get("x-websocket") {
+                    for name in ["publish", "subscribe"] {
+                        if let Some(message) = websocket.get(name).and_then(|op| op.get("message"))
+                        {
+                            channel.insert(
+                                name.to_string(),
+                                operation_object(operation, message.clone()),
+                            );
+                        }
+                    }
+                } else if let Some(schema) = event_stream_schema(operation) {
+                    let message = json!({
+                        "contentType": EVENT_STREAM,
+                        "payload": schema,
+                    });
+                    channel.insert(
+                        "subscribe".to_string(),
+                        operation_object(operation, message),
+                    );
+                }
+
+                if !channel.is_empty() {
+                    merge_channel(&mut channels, path.path, channel);
+                }
+            }
+        }
+    }
+
+    for webhook in &doc.webhooks {
+        let content = webhook
+            .operation
+            .request
+            .as_ref()
+            .and_then(|request| request.content.first());
+        if let Some(content) = content {
+            let message = json!({
+                "contentType": content.content_type,
+                "payload": content.schema,
+            });
+            let mut channel = Map::new();
+            channel.insert(
+                "subscribe".to_string(),
+                operation_object(&webhook.operation, message),
+            );
+            merge_channel(&mut channels, webhook.name, channel);
+        }
+    }
+
+    let mut info = Map::new();
+    info.insert("title".to_string(), json!(doc.info.title));
+    info.insert("version".to_string(), json!(doc.info.version));
+    if let Some(description) = &doc.info.description {
+        info.insert("description".to_string(), json!(description));
+    }
+    if let Some(terms_of_service) = &doc.info.terms_of_service {
+        info.insert("termsOfService".to_string(), json!(terms_of_service));
+    }
+
+    json!({
+        "asyncapi": ASYNCAPI_VERSION,
+        "info": info,
+        "channels": channels,
+        "components": {
+            "schemas": doc.registry.schemas,
+        },
+    })
+}
+
+/// Returns the schema of the events of the server-sent event endpoint, or
+/// `None` if the operation does not return an event stream.
+fn event_stream_schema(operation: &MetaOperation) -> Option<MetaSchemaRef> {
+    let content = operation
+        .responses
+        .responses
+        .iter()
+        .flat_map(|resp| &resp.content)
+        .find(|content| content.content_type.starts_with(EVENT_STREAM))?;
+    match &content.schema {
+        MetaSchemaRef::Inline(schema) if schema.format == Some("event-stream") => {
+            schema.items.as_deref().cloned()
+        }
+        schema => Some(schema.clone()),
+    }
+}
+
+fn operation_object(operation: &MetaOperation, message: Value) -> Value {
+    let mut object = Map::new();
+    if let Some(operation_id) = operation.operation_id {
+        object.insert("operationId".to_string(), json!(operation_id));
+    }
+    if let Some(summary) = operation.summary {
+        object.insert("summary".to_string(), json!(summary));
+    }
+    if let Some(description) = operation.description {
+        object.insert("description".to_string(), json!(description));
+    }
+    if !operation.tags.is_empty() {
+        let tags = operation
+            .tags
+            .iter()
+            .map(|name| json!({ "name": name }))
+            .collect();
+        object.insert("tags".to_string(), Value::Array(tags));
+    }
+    object.insert("message".to_string(), message);
+    Value::Object(object)
+}
@@ -0,0 +1,448 @@
+//! Audit log of the mutating operations.
+//!
+//! The audit layer records who made the request (the [`Principal`] of the
+//! security scheme), what was done (the operation id and the path
+//! parameters) and the result (the status code) of the `POST`, `PUT`,
+//! `PATCH` and `DELETE` operations into an [`AuditSink`].
+//!
+//! The JSON request bodies are also recorded, the fields marked with
+//! `#[oai(sensitive)]` in the [`Object`](crate::Object)s of the request body
+//! are redacted automatically, use [`Audit::redact`] to hide the other
+//! fields. Only the bodies with a `Content-Length` of at most
+//! [`Audit::max_body_size`] bytes are recorded, the larger bodies and the
+//! streaming bodies are passed through without being read.
+//!
+//! # Example
+//!
+//! ```
+//! use poem::Request;
+//! use poem_openapi::{
+//!     audit::{Audit, StdoutSink},
+//!     auth::Bearer,
+//!     param::Path,
+//!     payload::Json,
+//!     Object, OpenApi, OpenApiService, SecurityScheme,
+//! };
+//!
+//! async fn check(_req: &Request, bearer: Bearer) -> Option<String> {
+//!     (bearer.token == "secret").then(|| "alice".to_string())
+//! }
+//!
+//! #[derive(SecurityScheme)]
+//! #[oai(type = "bearer", checker = "check")]
+//! struct MyAuth(String);
+//!
+//! #[derive(Object)]
+//! struct UpdateUser {
+//!     name: String,
+//!     #[oai(sensitive)]
+//!     password: String,
+//! }
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/users/:id", method = "delete", operation_id = "delete_user")]
+//!     async fn delete_user(&self, auth: MyAuth, id: Path<i64>) {}
+//!
+//!     #[oai(path = "/users/:id", method = "put")]
+//!     async fn update_user(&self, auth: MyAuth, id: Path<i64>, user: Json<UpdateUser>) {}
+//! }
+//!
+//! let api_service =
+//!     OpenApiService::new(Api, "Demo", "1.0").audit(Audit::new(StdoutSink).redact("token"));
+//! ```
+//!
+//! [`Principal`]: crate::auth::Principal
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use poem::{
+    endpoint::BoxEndpoint,
+    http::{header, Method},
+    Endpoint, Request, Response, Result,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    registry::{MetaOperation, MetaSchema, MetaSchemaRef, Registry},
+    types::Type,
+    OperationInfo,
+};
+
+const REDACTED: &str = "[REDACTED]";
+const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// An audit event.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AuditEvent {
+    /// The Unix timestamp of the request in milliseconds.
+    pub timestamp: u64,
+    /// The principal of the security scheme.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+    /// The id of the operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<&'static str>,
+    /// The HTTP method.
+    pub method: String,
+    /// The path of the request.
+    pub path: String,
+    /// The path parameters.
+    pub path_params: BTreeMap<String, String>,
+    /// The status code of the response.
+    pub status: u16,
+    /// The JSON request body with the sensitive and the configured fields
+    /// redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A sink that receives the audit events, such as a log, Kafka producer or
+/// database.
+///
+/// The events are recorded before the response is returned, so the slow
+/// sinks should send the events to a queue, for example with the
+/// implementation for [`UnboundedSender`].
+#[poem::async_trait]
+pub trait AuditSink: Send + Sync + 'static {
+    /// Records an audit event.
+    async fn record(&self, event: AuditEvent);
+}
+
+/// An [`AuditSink`] that writes the events to the stdout as JSON lines.
+pub struct StdoutSink;
+
+#[poem::async_trait]
+impl AuditSink for StdoutSink {
+    async fn record(&self, event: AuditEvent) {
+        if let Ok(line) = serde_json::to_string(&event) {
+            println!("{}", line);
+        }
+    }
+}
+
+#[poem::async_trait]
+impl AuditSink for UnboundedSender<AuditEvent> {
+    async fn record(&self, event: AuditEvent) {
+        let _ = self.send(event);
+    }
+}
+
+/// The configuration of the audit layer.
+///
+/// See also: [`OpenApiService::audit`](crate::OpenApiService::audit)
+pub struct Audit {
+    sink: Arc<dyn AuditSink>,
+    redact: Vec<String>,
+    max_body_size: usize,
+}
+
+impl Audit {
+    /// Create an audit layer with the sink.
+    pub fn new(sink: impl AuditSink) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            redact: Vec::new(),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    /// Sets the maximum size of the request bodies that are recorded, the
+    /// default is 64KiB.
+    #[must_use]
+    pub fn max_body_size(self, max_body_size: usize) -> Self {
+        Self {
+            max_body_size,
+            ..self
+        }
+    }
+
+    /// Redacts a field of the JSON request body, nested fields are separated
+    /// by `.`, such as `card.number`.
+    #[must_use]
+    pub fn redact(mut self, field: impl Into<String>) -> Self {
+        self.redact.push(field.into());
+        self
+    }
+}
+
+#[derive(Default)]
+struct AuditContext {
+    principal: Mutex<Option<String>>,
+}
+
+#[doc(hidden)]
+pub fn record_principal(req: &Request, principals: impl FnOnce() -> Vec<Option<String>>) {
+    if let Some(ctx) = req.extensions().get::<Arc<AuditContext>>() {
+        *ctx.principal.lock().unwrap() = principals().into_iter().flatten().next();
+    }
+}
+
+fn is_mutating(method: &Method) -> bool {
+    [Method::POST, Method::PUT, Method::PATCH, Method::DELETE].contains(method)
+}
+
+fn redact<S: AsRef<str>>(value: &mut Value, path: &[S]) {
+    match (value, path) {
+        (Value::Array(items), _) => {
+            for item in items {
+                redact(item, path);
+            }
+        }
+        (Value::Object(object), [name]) => {
+            if let Some(value) = object.get_mut(name.as_ref()) {
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+        (Value::Object(object), [name, path @ ..]) => {
+            if let Some(value) = object.get_mut(name.as_ref()) {
+                redact(value, path);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn split_path(path: &str) -> Vec<String> {
+    path.split('.').map(ToString::to_string).collect()
+}
+
+/// Returns the paths of the fields marked with `#[oai(sensitive)]`, the
+/// items of the arrays share the path of the array.
+fn sensitive_fields(registry: &Registry, schema: &MetaSchemaRef) -> Vec<Vec<String>> {
+    fn visit_ref(
+        registry: &Registry,
+        schema: &MetaSchemaRef,
+        path: &[String],
+        visiting: &mut Vec<&'static str>,
+        fields: &mut Vec<Vec<String>>,
+    ) {
+        match schema {
+            MetaSchemaRef::Inline(schema) => visit(registry, schema, path, visiting, fields),
+            MetaSchemaRef::Reference(name) if !visiting.contains(name) => {
+                if let Some(schema) = registry.schemas.get(name) {
+                    visiting.push(name);
+                    visit(registry, schema, path, visiting, fields);
+                    visiting.pop();
+                }
+            }
+            MetaSchemaRef::Reference(_) => {}
+        }
+    }
+
+    fn visit(
+        registry: &Registry,
+        schema: &MetaSchema,
+        path: &[String],
+        visiting: &mut Vec<&'static str>,
+        fields: &mut Vec<Vec<String>>,
+    ) {
+        if schema.sensitive && !path.is_empty() {
+            fields.push(path.to_vec());
+            return;
+        }
+
+        for (name, property) in &schema.properties {
+            let mut path = path.to_vec();
+            path.push(name.to_string());
+            visit_ref(registry, property, &path, visiting, fields);
+        }
+        for schema in schema
+            .items
+            .as_deref()
+            .into_iter()
+            .chain(&schema.prefix_items)
+            .chain(&schema.all_of)
+            .chain(&schema.one_of)
+        {
+            visit_ref(registry, schema, path, visiting, fields);
+        }
+    }
+
+    let mut fields = Vec::new();
+    visit_ref(registry, schema, &[], &mut Vec::new(), &mut fields);
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Redacts the fields marked with `#[oai(sensitive)]` in the JSON value of
+/// the type, so that the value can be written to the logs.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{audit::redact_sensitive, types::ToJSON, Object};
+/// use serde_json::json;
+///
+/// #[derive(Object)]
+/// struct Login {
+///     username: String,
+///     #[oai(sensitive)]
+///     password: String,
+/// }
+///
+/// let mut value = Login {
+///     username: "alice".to_string(),
+///     password: "123456".to_string(),
+/// }
+/// .to_json();
+/// redact_sensitive::<Login>(&mut value);
+/// assert_eq!(
+///     value,
+///     json!({ "username": "alice", "password": "[REDACTED]" })
+/// );
+/// ```
+pub fn redact_sensitive<T: Type>(value: &mut Value) {
+    let mut registry = Registry::new();
+    T::register(&mut registry);
+    for path in sensitive_fields(&registry, &T::schema_ref()) {
+        redact(value, &path);
+    }
+}
+
+struct AuditEndpoint {
+    inner: BoxEndpoint<'static, Response>,
+    sink: Arc<dyn AuditSink>,
+    redact: Vec<Vec<String>>,
+    max_body_size: usize,
+    operation_id: Option<&'static str>,
+    path_params: Vec<&'static str>,
+    sensitive_fields: Vec<Vec<String>>,
+}
+
+#[poem::async_trait]
+impl Endpoint for AuditEndpoint {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let is_json = req
+            .header(header::CONTENT_TYPE)
+            .map(|content_type| content_type.starts_with("application/json"))
+            .unwrap_or_default();
+        let content_length = req
+            .header(header::CONTENT_LENGTH)
+            .and_then(|value| value.parse::<usize>().ok());
+        let body = if is_json && matches!(content_length, Some(len) if len <= self.max_body_size) {
+            let data = req.take_body().into_bytes().await?;
+            let body = serde_json::from_slice::<Value>(&data).ok().map(|mut body| {
+                for path in self.sensitive_fields.iter().chain(&self.redact) {
+                    redact(&mut body, path);
+                }
+                body
+            });
+            req.set_body(data);
+            body
+        } else {
+            None
+        };
+
+        let ctx = Arc::new(AuditContext::default());
+        req.extensions_mut().insert(ctx.clone());
+        let method = req.method().to_string();
+        let path = req.uri().path().to_string();
+        let path_params = self
+            .path_params
+            .iter()
+            .filter_map(|name| Some((name.to_string(), req.raw_path_param(name)?.to_string())))
+            .collect();
+
+        let res = self.inner.call(req).await;
+        let status = match &res {
+            Ok(resp) => resp.status(),
+            Err(err) => err.as_response().status(),
+        };
+        let principal = ctx.principal.lock().unwrap().take();
+
+        self.sink
+            .record(AuditEvent {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or_default(),
+                principal,
+                operation_id: self.operation_id,
+                method,
+                path,
+                path_params,
+                status: status.as_u16(),
+                body,
+            })
+            .await;
+        res
+    }
+}
+
+/// Records the audit events of the operation if it is a mutating operation.
+pub(crate) fn apply(
+    ep: BoxEndpoint<'static, Response>,
+    audit: &Audit,
+    info: &OperationInfo,
+    operation: &MetaOperation,
+    registry: &Registry,
+) -> BoxEndpoint<'static, Response> {
+    if !is_mutating(info.method()) {
+        return ep;
+    }
+
+    let mut sensitive_fields = operation
+        .request
+        .iter()
+        .flat_map(|request| &request.content)
+        .flat_map(|content| sensitive_fields(registry, &content.schema))
+        .collect::<Vec<_>>();
+    sensitive_fields.sort();
+    sensitive_fields.dedup();
+
+    Box::new(AuditEndpoint {
+        inner: ep,
+        sink: audit.sink.clone(),
+        redact: audit.redact.iter().map(|path| split_path(path)).collect(),
+        max_body_size: audit.max_body_size,
+        operation_id: info.operation_id(),
+        path_params: info
+            .path()
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .collect(),
+        sensitive_fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn redact_fields() {
+        let mut value = json!({
+            "name": "a",
+            "password": "123",
+            "card": { "number": "4242", "exp": "12/30" },
+            "items": [{ "secret": 1 }, { "secret": 2, "other": 3 }],
+        });
+        redact(&mut value, &["password"]);
+        redact(&mut value, &["card", "number"]);
+        redact(&mut value, &["items", "secret"]);
+        redact(&mut value, &["missing", "field"]);
+        assert_eq!(
+            value,
+            json!({
+                "name": "a",
+                "password": REDACTED,
+                "card": { "number": REDACTED, "exp": "12/30" },
+                "items": [{ "secret": REDACTED }, { "secret": REDACTED, "other": 3 }],
+            })
+        );
+    }
+}
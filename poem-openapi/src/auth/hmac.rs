@@ -0,0 +1,326 @@
+//! HMAC request signing.
+//!
+//! See also: [`HmacSignature`]
+
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use libhmac::{Hmac, Mac};
+use poem::{
+    http::{Method, Uri},
+    Body, Request, RequestBody, Result,
+};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::{
+    auth::HmacAuthorization,
+    error::AuthorizationError,
+    registry::{MetaOperationParam, MetaParamIn, MetaSchema, MetaSchemaRef},
+};
+
+/// The header that contains the id of the signing key.
+pub const KEY_ID_HEADER: &str = "X-Key-Id";
+
+/// The header that contains the Unix timestamp of the request in seconds.
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+
+/// The header that contains an unique value of the request.
+pub const NONCE_HEADER: &str = "X-Nonce";
+
+/// The header that contains the hex encoded SHA-256 digest of the body.
+pub const CONTENT_SHA256_HEADER: &str = "X-Content-SHA256";
+
+/// The header that contains the hex encoded HMAC-SHA256 signature.
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Returns the canonical form of a request that is signed with HMAC-SHA256.
+///
+/// The canonical form is the following lines joined with `\n`:
+///
+/// - The HTTP method in uppercase
+/// - The path
+/// - The query string with the pairs sorted
+/// - The value of the `X-Timestamp` header
+/// - The value of the `X-Nonce` header
+/// - The value of the `X-Content-SHA256` header, or an empty string
+pub fn canonical_request(
+    method: &Method,
+    uri: &Uri,
+    timestamp: i64,
+    nonce: &str,
+    content_sha256: Option<&str>,
+) -> String {
+    let mut query = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .collect::<Vec<_>>();
+    query.sort_unstable();
+
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method.as_str(),
+        uri.path(),
+        query.join("&"),
+        timestamp,
+        nonce,
+        content_sha256.unwrap_or_default()
+    )
+}
+
+/// Signs the canonical form of a request, returns the hex encoded
+/// HMAC-SHA256 signature.
+pub fn sign(secret: &[u8], canonical_request: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("any key length is valid");
+    mac.update(canonical_request.as_bytes());
+    to_hex(&mac.finalize().into_bytes())
+}
+
+/// Returns the hex encoded SHA-256 digest of the body, it is the value of the
+/// `X-Content-SHA256` header.
+pub fn content_sha256(body: &[u8]) -> String {
+    to_hex(&Sha256::digest(body))
+}
+
+pub(crate) fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn from_hex(s: &str) -> Option<Vec<u8>> {
+    s.as_bytes()
+        .chunks(2)
+        .map(|chunk| match chunk {
+            [a, b] => Some((hex_digit(*a)? << 4) | hex_digit(*b)?),
+            _ => None,
+        })
+        .collect()
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|d| d as u8)
+}
+
+pub(crate) fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// A store of the nonces that have been used, for replay protection.
+#[poem::async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Records a nonce of a key until the Unix timestamp `expires_at`,
+    /// returns `false` if it has already been used.
+    async fn insert(&self, key_id: &str, nonce: &str, expires_at: i64) -> bool;
+}
+
+/// A [`NonceStore`] in memory, it is suitable for a single server.
+#[derive(Default)]
+pub struct MemoryNonceStore {
+    nonces: Mutex<HashMap<(String, String), i64>>,
+}
+
+impl MemoryNonceStore {
+    /// Create a memory nonce store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+#[poem::async_trait]
+impl NonceStore for MemoryNonceStore {
+    async fn insert(&self, key_id: &str, nonce: &str, expires_at: i64) -> bool {
+        let mut nonces = self.nonces.lock().await;
+        let now = now();
+        nonces.retain(|_, expires_at| *expires_at >= now);
+        nonces
+            .insert((key_id.to_string(), nonce.to_string()), expires_at)
+            .is_none()
+    }
+}
+
+/// Used to extract the HMAC signature from the request.
+///
+/// The client signs the [`canonical_request`] with the secret of the key, and
+/// sends the `X-Key-Id`, `X-Timestamp`, `X-Nonce`, `X-Signature` and the
+/// `X-Content-SHA256` headers. Use [`HmacSignature::verify`] in the checker of
+/// the security scheme to verify it.
+///
+/// The `X-Content-SHA256` header is required if the request has a body, the
+/// `hmac` security scheme reads the body and rejects the request if it does
+/// not match the header, before the checker is called. So the security scheme
+/// argument must be declared before the payload argument of the operation.
+#[derive(Debug, Clone)]
+pub struct HmacSignature {
+    /// The id of the signing key.
+    pub key_id: String,
+    /// The Unix timestamp of the request in seconds.
+    pub timestamp: i64,
+    /// The unique value of the request.
+    pub nonce: String,
+    /// The hex encoded SHA-256 digest of the body.
+    pub content_sha256: Option<String>,
+    /// The signature.
+    pub signature: Vec<u8>,
+    /// The canonical form of the request.
+    pub canonical_request: String,
+}
+
+impl HmacSignature {
+    /// Returns `true` if the signature is valid for the secret.
+    pub fn verify_signature(&self, secret: &[u8]) -> bool {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("any key length is valid");
+        mac.update(self.canonical_request.as_bytes());
+        mac.verify_slice(&self.signature).is_ok()
+    }
+
+    /// Returns `true` if the body matches the `X-Content-SHA256` header.
+    ///
+    /// It returns `false` if the request does not have the header. The `hmac`
+    /// security scheme already verifies the body with it.
+    pub fn verify_content(&self, body: &[u8]) -> bool {
+        self.content_sha256.as_deref() == Some(content_sha256(body).as_str())
+    }
+
+    /// Verifies the signature, the timestamp is within `max_skew` of the
+    /// current time, and the nonce has not been used in the `store`.
+    pub async fn verify(&self, secret: &[u8], max_skew: Duration, store: &dyn NonceStore) -> bool {
+        if !self.verify_signature(secret) {
+            return false;
+        }
+        let skew = now().checked_sub(self.timestamp).map(i64::unsigned_abs);
+        if skew.map_or(true, |skew| skew > max_skew.as_secs()) {
+            return false;
+        }
+        let expires_at = self
+            .timestamp
+            .saturating_add(max_skew.as_secs().try_into().unwrap_or(i64::MAX));
+        store.insert(&self.key_id, &self.nonce, expires_at).await
+    }
+}
+
+/// Extracts the signature for the `hmac` security scheme, and verifies the
+/// body against the `X-Content-SHA256` header, which is required if the body
+/// is not empty.
+#[doc(hidden)]
+pub async fn extract_signature(req: &Request, body: &mut RequestBody) -> Result<HmacSignature> {
+    let signature = <HmacSignature as HmacAuthorization>::from_request(req)?;
+    let data = body.take()?.into_bytes().await?;
+    let verified = match &signature.content_sha256 {
+        Some(_) => signature.verify_content(&data),
+        None => data.is_empty(),
+    };
+    *body = RequestBody::new(Body::from(data));
+    if !verified {
+        return Err(AuthorizationError.into());
+    }
+    Ok(signature)
+}
+
+impl HmacAuthorization for HmacSignature {
+    fn from_request(req: &Request) -> Result<Self> {
+        let header = |name| req.header(name).ok_or(AuthorizationError);
+        let timestamp = header(TIMESTAMP_HEADER)?
+            .parse()
+            .map_err(|_| AuthorizationError)?;
+        let nonce = header(NONCE_HEADER)?;
+        let content_sha256 = req.header(CONTENT_SHA256_HEADER);
+        let signature = from_hex(header(SIGNATURE_HEADER)?).ok_or(AuthorizationError)?;
+
+        Ok(Self {
+            key_id: header(KEY_ID_HEADER)?.to_string(),
+            timestamp,
+            nonce: nonce.to_string(),
+            content_sha256: content_sha256.map(ToString::to_string),
+            signature,
+            canonical_request: canonical_request(
+                req.method(),
+                req.uri(),
+                timestamp,
+                nonce,
+                content_sha256,
+            ),
+        })
+    }
+
+    fn params() -> Vec<MetaOperationParam> {
+        let param = |name, description, required| MetaOperationParam {
+            name,
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+            in_type: MetaParamIn::Header,
+            description: Some(description),
+            required,
+            deprecated: false,
+        };
+
+        vec![
+            param(KEY_ID_HEADER, "The id of the signing key.", true),
+            MetaOperationParam {
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    format: Some("int64"),
+                    ..MetaSchema::new("integer")
+                })),
+                ..param(
+                    TIMESTAMP_HEADER,
+                    "The Unix timestamp of the request in seconds.",
+                    true,
+                )
+            },
+            param(NONCE_HEADER, "An unique value of the request.", true),
+            param(
+                CONTENT_SHA256_HEADER,
+                "The hex encoded SHA-256 digest of the body, it is required if the request has a \
+                 body.",
+                false,
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical() {
+        assert_eq!(
+            canonical_request(
+                &Method::POST,
+                &Uri::from_static("/a/b?z=1&a=2"),
+                100,
+                "abc",
+                Some("digest")
+            ),
+            "POST\n/a/b\na=2&z=1\n100\nabc\ndigest"
+        );
+        assert_eq!(
+            canonical_request(&Method::GET, &Uri::from_static("/"), 100, "abc", None),
+            "GET\n/\n\n100\nabc\n"
+        );
+    }
+
+    #[test]
+    fn hex() {
+        assert_eq!(
+            from_hex(&to_hex(&[0, 1, 0xab, 0xff])),
+            Some(vec![0, 1, 0xab, 0xff])
+        );
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[tokio::test]
+    async fn nonce_store() {
+        let store = MemoryNonceStore::new();
+        assert!(store.insert("key", "a", now() + 10).await);
+        assert!(!store.insert("key", "a", now() + 10).await);
+        assert!(store.insert("key2", "a", now() + 10).await);
+        assert!(store.insert("key", "b", now() - 10).await);
+        assert!(store.insert("key", "b", now() + 10).await);
+    }
+}
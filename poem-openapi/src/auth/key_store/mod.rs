@@ -0,0 +1,351 @@
+//! API key management for the `api_key` security scheme.
+//!
+//! A [`KeyStore`] looks up the API keys, their scopes and rate-limit tiers,
+//! revokes them and meters their usage. The stores only keep the SHA-256
+//! digest of the keys.
+//!
+//! Use [`check_api_key`] as the checker of the security scheme, it looks up
+//! the key in the `Arc<dyn KeyStore>` attached to the endpoint, rejects the
+//! unknown and revoked keys, and records the usage of the accepted keys.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use poem::{error::Forbidden, EndpointExt, Result, Route};
+//! use poem_openapi::{
+//!     auth::{
+//!         key_store::{check_api_key, ApiKeyInfo, KeyStore, MemoryKeyStore},
+//!         ApiKey,
+//!     },
+//!     error::AuthorizationError,
+//!     payload::PlainText,
+//!     OpenApi, OpenApiService, SecurityScheme,
+//! };
+//!
+//! #[derive(SecurityScheme)]
+//! #[oai(
+//!     type = "api_key",
+//!     key_name = "X-API-Key",
+//!     in = "header",
+//!     checker = "check_api_key"
+//! )]
+//! struct MyApiKey(ApiKeyInfo);
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/pets", method = "get")]
+//!     async fn pets(&self, auth: MyApiKey) -> Result<PlainText<String>> {
+//!         if !auth.0.has_scope("pets:read") {
+//!             return Err(Forbidden(AuthorizationError));
+//!         }
+//!         Ok(PlainText(auth.0.key_id))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let store = MemoryKeyStore::new();
+//! store
+//!     .insert("abc", ApiKeyInfo::new("key1").scope("pets:read"))
+//!     .await
+//!     .unwrap();
+//!
+//! let app = Route::new()
+//!     .nest("/", OpenApiService::new(Api, "Demo", "1.0"))
+//!     .data(Arc::new(store) as Arc<dyn KeyStore>);
+//! # });
+//! ```
+
+#[cfg(any(
+    feature = "sqlx-mysql-rustls",
+    feature = "sqlx-postgres-rustls",
+    feature = "sqlx-sqlite-rustls"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "sqlx-mysql-rustls",
+        feature = "sqlx-postgres-rustls",
+        feature = "sqlx-sqlite-rustls"
+    )))
+)]
+pub mod sqlx;
+
+use std::{collections::HashMap, sync::Arc};
+
+use poem::{http::StatusCode, Error, Request, Result};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::{
+    auth::{ApiKey, Permissions},
+    error::AuthorizationError,
+};
+
+/// Returns the digest of the API key that is saved in the stores.
+pub fn key_hash(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The information of an API key.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ApiKeyInfo {
+    /// The id of the key, it is not secret and can be used to identify the
+    /// key in logs and metrics.
+    pub key_id: String,
+    /// The scopes granted to the key.
+    pub scopes: Vec<String>,
+    /// The rate-limit tier of the key.
+    pub tier: Option<String>,
+    /// The key has been revoked.
+    pub revoked: bool,
+}
+
+impl ApiKeyInfo {
+    /// Create the information of an API key.
+    pub fn new(key_id: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            scopes: Vec::new(),
+            tier: None,
+            revoked: false,
+        }
+    }
+
+    /// Grants a scope to the key.
+    #[must_use]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// Sets the rate-limit tier of the key.
+    #[must_use]
+    pub fn tier(self, tier: impl Into<String>) -> Self {
+        Self {
+            tier: Some(tier.into()),
+            ..self
+        }
+    }
+
+    /// Returns `true` if the key has been granted the scope.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|item| item == scope)
+    }
+}
+
+impl Permissions for ApiKeyInfo {
+    fn has_permission(&self, permission: &str) -> bool {
+        self.has_scope(permission)
+    }
+}
+
+/// A store of the API keys.
+#[poem::async_trait]
+pub trait KeyStore: Send + Sync {
+    /// Looks up the information of an API key.
+    async fn lookup(&self, key: &str) -> Result<Option<ApiKeyInfo>>;
+
+    /// Adds an API key.
+    async fn insert(&self, key: &str, info: ApiKeyInfo) -> Result<()>;
+
+    /// Revokes the key with the id, returns `false` if it does not exist.
+    async fn revoke(&self, key_id: &str) -> Result<bool>;
+
+    /// Records an use of the key with the id.
+    async fn record_usage(&self, key_id: &str) -> Result<()>;
+
+    /// Returns the information of an API key if it exists and has not been
+    /// revoked, and records the usage of it.
+    async fn check(&self, key: &str) -> Result<Option<ApiKeyInfo>> {
+        match self.lookup(key).await? {
+            Some(info) if !info.revoked => {
+                self.record_usage(&info.key_id).await?;
+                Ok(Some(info))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// A checker of the `api_key` security scheme that validates the key with the
+/// `Arc<dyn KeyStore>` attached to the endpoint.
+///
+/// It returns [`AuthorizationError`] if the key is unknown or revoked, the
+/// errors of the store are returned as is, and `500 Internal Server Error` is
+/// returned if the store is not attached.
+pub async fn check_api_key(req: &Request, api_key: ApiKey) -> Result<ApiKeyInfo> {
+    let store = req.data::<Arc<dyn KeyStore>>().ok_or_else(|| {
+        Error::from_string(
+            "the `Arc<dyn KeyStore>` is not attached to the endpoint",
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )
+    })?;
+    store
+        .check(&api_key.key)
+        .await?
+        .ok_or_else(|| AuthorizationError.into())
+}
+
+#[derive(Default)]
+struct MemoryKeys {
+    keys: HashMap<String, ApiKeyInfo>,
+    usage: HashMap<String, u64>,
+}
+
+/// A [`KeyStore`] in memory, it is suitable for a single server and testing.
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    inner: Mutex<MemoryKeys>,
+}
+
+impl MemoryKeyStore {
+    /// Create a memory key store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the number of the recorded uses of the key with the id.
+    pub async fn usage(&self, key_id: &str) -> u64 {
+        self.inner
+            .lock()
+            .await
+            .usage
+            .get(key_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[poem::async_trait]
+impl KeyStore for MemoryKeyStore {
+    async fn lookup(&self, key: &str) -> Result<Option<ApiKeyInfo>> {
+        Ok(self.inner.lock().await.keys.get(&key_hash(key)).cloned())
+    }
+
+    async fn insert(&self, key: &str, info: ApiKeyInfo) -> Result<()> {
+        self.inner.lock().await.keys.insert(key_hash(key), info);
+        Ok(())
+    }
+
+    async fn revoke(&self, key_id: &str) -> Result<bool> {
+        let mut inner = self.inner.lock().await;
+        let mut found = false;
+        for info in inner.keys.values_mut() {
+            if info.key_id == key_id {
+                info.revoked = true;
+                found = true;
+            }
+        }
+        Ok(found)
+    }
+
+    async fn record_usage(&self, key_id: &str) -> Result<()> {
+        *self
+            .inner
+            .lock()
+            .await
+            .usage
+            .entry(key_id.to_string())
+            .or_default() += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_store() {
+        let store = MemoryKeyStore::new();
+        store
+            .insert(
+                "abc",
+                ApiKeyInfo::new("key1")
+                    .scope("read")
+                    .scope("write")
+                    .tier("free"),
+            )
+            .await
+            .unwrap();
+
+        assert!(store.lookup("abd").await.unwrap().is_none());
+        assert!(store.check("abd").await.unwrap().is_none());
+
+        let info = store.check("abc").await.unwrap().unwrap();
+        assert_eq!(info.key_id, "key1");
+        assert!(info.has_scope("read"));
+        assert!(!info.has_scope("admin"));
+        assert_eq!(info.tier.as_deref(), Some("free"));
+        store.check("abc").await.unwrap();
+        assert_eq!(store.usage("key1").await, 2);
+
+        assert!(store.revoke("key1").await.unwrap());
+        assert!(!store.revoke("key2").await.unwrap());
+        assert!(store.lookup("abc").await.unwrap().unwrap().revoked);
+        assert!(store.check("abc").await.unwrap().is_none());
+        assert_eq!(store.usage("key1").await, 2);
+    }
+
+    #[tokio::test]
+    async fn check_api_key_errors() {
+        struct FailingStore;
+
+        #[poem::async_trait]
+        impl KeyStore for FailingStore {
+            async fn lookup(&self, _key: &str) -> Result<Option<ApiKeyInfo>> {
+                Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE))
+            }
+
+            async fn insert(&self, _key: &str, _info: ApiKeyInfo) -> Result<()> {
+                Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE))
+            }
+
+            async fn revoke(&self, _key_id: &str) -> Result<bool> {
+                Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE))
+            }
+
+            async fn record_usage(&self, _key_id: &str) -> Result<()> {
+                Err(Error::from_status(StatusCode::SERVICE_UNAVAILABLE))
+            }
+        }
+
+        let check = |store: Option<Arc<dyn KeyStore>>| async move {
+            let mut req = Request::builder().finish();
+            if let Some(store) = store {
+                req.extensions_mut().insert(store);
+            }
+            check_api_key(
+                &req,
+                ApiKey {
+                    key: "abc".to_string(),
+                },
+            )
+            .await
+            .map_err(|err| err.as_response().status())
+        };
+
+        let store = MemoryKeyStore::new();
+        store.insert("abc", ApiKeyInfo::new("key1")).await.unwrap();
+        assert_eq!(
+            check(Some(Arc::new(store))).await,
+            Ok(ApiKeyInfo::new("key1"))
+        );
+        assert_eq!(
+            check(Some(Arc::new(MemoryKeyStore::new()))).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            check(Some(Arc::new(FailingStore))).await,
+            Err(StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(check(None).await, Err(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+}
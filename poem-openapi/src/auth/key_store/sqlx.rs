@@ -0,0 +1,236 @@
+//! sqlx-backed key stores.
+//!
+//! The scopes are saved as a string separated by spaces.
+//!
+//! # Create the table for the key store
+//!
+//! ```sql
+//! create table poem_api_keys (
+//!     key_hash varchar(64) primary key not null,
+//!     key_id varchar(255) not null,
+//!     scopes text not null,
+//!     tier varchar(255) null,
+//!     revoked boolean not null default false,
+//!     usage_count bigint not null default 0
+//! );
+//!
+//! create index poem_api_keys_key_id_idx on poem_api_keys (key_id);
+//! ```
+
+use poem::{error::InternalServerError, Result};
+
+use super::{key_hash, ApiKeyInfo, KeyStore};
+
+const DEFAULT_TABLE_NAME: &str = "poem_api_keys";
+
+macro_rules! define_key_store {
+    (
+        $(#[$meta:meta])*
+        $ty:ident, $pool:ty, $placeholder:literal
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        pub struct $ty {
+            pool: $pool,
+            lookup_sql: String,
+            insert_sql: String,
+            revoke_sql: String,
+            usage_sql: String,
+        }
+
+        impl $ty {
+            /// Create a key store with the `poem_api_keys` table.
+            pub fn new(pool: $pool) -> Self {
+                Self::with_table_name(pool, DEFAULT_TABLE_NAME)
+            }
+
+            /// Create a key store with the table.
+            pub fn with_table_name(pool: $pool, table_name: &str) -> Self {
+                let p = |n: usize| $placeholder.replace("{n}", &n.to_string());
+                Self {
+                    pool,
+                    lookup_sql: format!(
+                        "select key_id, scopes, tier, revoked from {} where key_hash = {}",
+                        table_name,
+                        p(1)
+                    ),
+                    insert_sql: format!(
+                        "insert into {} (key_hash, key_id, scopes, tier, revoked, usage_count) \
+                         values ({}, {}, {}, {}, {}, 0)",
+                        table_name,
+                        p(1),
+                        p(2),
+                        p(3),
+                        p(4),
+                        p(5)
+                    ),
+                    revoke_sql: format!(
+                        "update {} set revoked = {} where key_id = {}",
+                        table_name,
+                        p(1),
+                        p(2)
+                    ),
+                    usage_sql: format!(
+                        "update {} set usage_count = usage_count + 1 where key_id = {}",
+                        table_name,
+                        p(1)
+                    ),
+                }
+            }
+        }
+
+        #[poem::async_trait]
+        impl KeyStore for $ty {
+            async fn lookup(&self, key: &str) -> Result<Option<ApiKeyInfo>> {
+                let res: Option<(String, String, Option<String>, bool)> =
+                    ::sqlx::query_as(&self.lookup_sql)
+                        .bind(key_hash(key))
+                        .fetch_optional(&self.pool)
+                        .await
+                        .map_err(InternalServerError)?;
+                Ok(res.map(|(key_id, scopes, tier, revoked)| ApiKeyInfo {
+                    key_id,
+                    scopes: scopes.split_whitespace().map(ToString::to_string).collect(),
+                    tier,
+                    revoked,
+                }))
+            }
+
+            async fn insert(&self, key: &str, info: ApiKeyInfo) -> Result<()> {
+                ::sqlx::query(&self.insert_sql)
+                    .bind(key_hash(key))
+                    .bind(info.key_id)
+                    .bind(info.scopes.join(" "))
+                    .bind(info.tier)
+                    .bind(info.revoked)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(InternalServerError)?;
+                Ok(())
+            }
+
+            async fn revoke(&self, key_id: &str) -> Result<bool> {
+                let res = ::sqlx::query(&self.revoke_sql)
+                    .bind(true)
+                    .bind(key_id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(InternalServerError)?;
+                Ok(res.rows_affected() > 0)
+            }
+
+            async fn record_usage(&self, key_id: &str) -> Result<()> {
+                ::sqlx::query(&self.usage_sql)
+                    .bind(key_id)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(InternalServerError)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlx-mysql-rustls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlx-mysql-rustls")))]
+define_key_store!(
+    /// Key store using MySQL.
+    ///
+    /// # Errors
+    ///
+    /// - [`sqlx::Error`](::sqlx::Error)
+    MysqlKeyStore,
+    ::sqlx::MySqlPool,
+    "?"
+);
+
+#[cfg(feature = "sqlx-postgres-rustls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlx-postgres-rustls")))]
+define_key_store!(
+    /// Key store using Postgres.
+    ///
+    /// # Errors
+    ///
+    /// - [`sqlx::Error`](::sqlx::Error)
+    PgKeyStore,
+    ::sqlx::PgPool,
+    "${n}"
+);
+
+#[cfg(feature = "sqlx-sqlite-rustls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlx-sqlite-rustls")))]
+define_key_store!(
+    /// Key store using Sqlite.
+    ///
+    /// # Errors
+    ///
+    /// - [`sqlx::Error`](::sqlx::Error)
+    SqliteKeyStore,
+    ::sqlx::SqlitePool,
+    "?"
+);
+
+#[cfg(all(test, feature = "sqlx-sqlite-rustls"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sqlite_store() {
+        let pool = ::sqlx::SqlitePool::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        ::sqlx::query(
+            r#"
+        create table poem_api_keys (
+            key_hash varchar(64) primary key not null,
+            key_id varchar(255) not null,
+            scopes text not null,
+            tier varchar(255) null,
+            revoked boolean not null default false,
+            usage_count bigint not null default 0
+        )
+        "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let store = SqliteKeyStore::new(pool.clone());
+        store
+            .insert("abc", ApiKeyInfo::new("key1").scope("read").tier("free"))
+            .await
+            .unwrap();
+
+        assert!(store.check("abd").await.unwrap().is_none());
+        assert_eq!(
+            store.check("abc").await.unwrap(),
+            Some(ApiKeyInfo::new("key1").scope("read").tier("free"))
+        );
+        store.check("abc").await.unwrap();
+
+        let (usage,): (i64,) =
+            ::sqlx::query_as("select usage_count from poem_api_keys where key_id = 'key1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(usage, 2);
+
+        assert!(store.revoke("key1").await.unwrap());
+        assert!(!store.revoke("key2").await.unwrap());
+        assert!(store.check("abc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn sqlite_store_errors() {
+        // The table does not exist, so every query fails.
+        let pool = ::sqlx::SqlitePool::connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let store = SqliteKeyStore::new(pool);
+
+        assert!(store.lookup("abc").await.is_err());
+        assert!(store.check("abc").await.is_err());
+        assert!(store.insert("abc", ApiKeyInfo::new("key1")).await.is_err());
+        assert!(store.revoke("key1").await.is_err());
+    }
+}
@@ -1,13 +1,55 @@
 //! Some certificate types for security scheme.
 
 mod api_key;
+#[cfg(feature = "base64")]
 mod basic;
 mod bearer;
+#[cfg(feature = "hmac")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hmac")))]
+pub mod hmac;
+#[cfg(feature = "key-store")]
+#[cfg_attr(docsrs, doc(cfg(feature = "key-store")))]
+pub mod key_store;
+mod permissions;
+mod principal;
 
 use poem::{Request, Result};
 
-pub use self::{api_key::ApiKey, basic::Basic, bearer::Bearer};
-use crate::{base::UrlQuery, registry::MetaParamIn};
+#[cfg(feature = "base64")]
+#[cfg_attr(docsrs, doc(cfg(feature = "base64")))]
+pub use self::basic::Basic;
+#[cfg(feature = "hmac")]
+pub use self::hmac::HmacSignature;
+pub use self::{api_key::ApiKey, bearer::Bearer, permissions::Permissions, principal::Principal};
+#[doc(hidden)]
+pub use self::{
+    permissions::{
+        check_permissions, AssertSecurityScheme, PermissionsProbe, ViaNoPermissions, ViaPermissions,
+    },
+    principal::{PrincipalProbe, ViaNoPrincipal, ViaPrincipal},
+};
+#[cfg(feature = "hmac")]
+use crate::registry::MetaOperationParam;
+use crate::{base::UrlQuery, error::AuthorizationError, registry::MetaParamIn};
+
+/// The return types of the checkers of the security schemes, `None` is
+/// converted to [`AuthorizationError`].
+#[doc(hidden)]
+pub trait CheckerOutput<T> {
+    fn into_result(self) -> Result<T>;
+}
+
+impl<T> CheckerOutput<T> for Option<T> {
+    fn into_result(self) -> Result<T> {
+        self.ok_or_else(|| AuthorizationError.into())
+    }
+}
+
+impl<T> CheckerOutput<T> for Result<T> {
+    fn into_result(self) -> Result<T> {
+        self
+    }
+}
 
 /// Represents a basic authorization extractor.
 pub trait BasicAuthorization: Sized {
@@ -31,3 +73,15 @@ pub trait ApiKeyAuthorization: Sized {
         in_type: MetaParamIn,
     ) -> Result<Self>;
 }
+
+/// Represents a HMAC signature authorization extractor.
+#[cfg(feature = "hmac")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hmac")))]
+pub trait HmacAuthorization: Sized {
+    /// Extract from the HTTP request.
+    fn from_request(req: &Request) -> Result<Self>;
+
+    /// Returns the headers used by the signature, they are documented as the
+    /// parameters of the operations.
+    fn params() -> Vec<MetaOperationParam>;
+}
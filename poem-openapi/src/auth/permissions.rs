@@ -0,0 +1,149 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    marker::PhantomData,
+};
+
+use poem::Result;
+
+use crate::{error::PermissionDeniedError, ApiExtractor, ApiExtractorType};
+
+/// Represents the output of a security scheme that carries a set of
+/// permissions.
+///
+/// If the output of a security scheme implements this trait, the operations
+/// can use `#[oai(require_permission = "...")]` to check the permissions
+/// before calling the handler.
+pub trait Permissions {
+    /// Returns `true` if it has been granted the permission.
+    fn has_permission(&self, permission: &str) -> bool;
+}
+
+impl Permissions for HashSet<String> {
+    fn has_permission(&self, permission: &str) -> bool {
+        self.contains(permission)
+    }
+}
+
+impl Permissions for BTreeSet<String> {
+    fn has_permission(&self, permission: &str) -> bool {
+        self.contains(permission)
+    }
+}
+
+impl Permissions for Vec<String> {
+    fn has_permission(&self, permission: &str) -> bool {
+        self.iter().any(|item| item == permission)
+    }
+}
+
+#[doc(hidden)]
+pub struct PermissionsProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaPermissions<'a> {
+    fn probe_permissions(&self) -> Option<&'a dyn Permissions>;
+}
+
+impl<'a, T: Permissions> ViaPermissions<'a> for PermissionsProbe<'a, T> {
+    fn probe_permissions(&self) -> Option<&'a dyn Permissions> {
+        Some(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaNoPermissions<'a> {
+    fn probe_permissions(&self) -> Option<&'a dyn Permissions>;
+}
+
+impl<'a, T> ViaNoPermissions<'a> for &PermissionsProbe<'a, T> {
+    fn probe_permissions(&self) -> Option<&'a dyn Permissions> {
+        None
+    }
+}
+
+/// The extractors of an operation, nested as `(A, (B, ()))`.
+#[doc(hidden)]
+pub trait HasSecurityScheme {
+    const VALUE: bool;
+}
+
+impl HasSecurityScheme for () {
+    const VALUE: bool = false;
+}
+
+impl<'a, T: ApiExtractor<'a>, R: HasSecurityScheme> HasSecurityScheme for (T, R) {
+    const VALUE: bool = matches!(T::TYPE, ApiExtractorType::SecurityScheme) || R::VALUE;
+}
+
+#[doc(hidden)]
+pub struct AssertSecurityScheme<T>(PhantomData<T>);
+
+impl<T: HasSecurityScheme> AssertSecurityScheme<T> {
+    /// Fails to compile if an operation requires permissions, but it has no
+    /// security scheme, indexing out of bounds stands in for the `const`
+    /// panics that are not available on the minimum supported Rust version.
+    pub const REQUIRE_PERMISSION_MUST_HAVE_A_SECURITY_SCHEME: () = [()][!T::VALUE as usize];
+}
+
+#[doc(hidden)]
+pub fn check_permissions(
+    permissions: &[Option<&dyn Permissions>],
+    required: &[&'static str],
+) -> Result<()> {
+    let missing = match permissions.iter().flatten().next() {
+        Some(permissions) => required
+            .iter()
+            .find(|permission| !permissions.has_permission(permission)),
+        None => required.first(),
+    };
+    match missing {
+        Some(permission) => Err(PermissionDeniedError { permission }.into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Granted(Vec<String>);
+
+    impl Permissions for Granted {
+        fn has_permission(&self, permission: &str) -> bool {
+            self.0.has_permission(permission)
+        }
+    }
+
+    struct NotGranted;
+
+    #[test]
+    fn probe() {
+        let granted = Granted(vec!["a".to_string()]);
+        assert!(PermissionsProbe(&granted).probe_permissions().is_some());
+        assert!((&PermissionsProbe(&NotGranted))
+            .probe_permissions()
+            .is_none());
+    }
+
+    #[test]
+    fn check() {
+        let granted = vec!["a".to_string(), "b".to_string()];
+        assert!(check_permissions(&[None, Some(&granted)], &["a", "b"]).is_ok());
+
+        let err = check_permissions(&[Some(&granted)], &["a", "c"]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PermissionDeniedError>()
+                .unwrap()
+                .permission,
+            "c"
+        );
+
+        let err = check_permissions(&[None], &["a"]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PermissionDeniedError>()
+                .unwrap()
+                .permission,
+            "a"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(feature = "key-store")]
+use crate::auth::key_store::ApiKeyInfo;
+#[cfg(feature = "base64")]
+use crate::auth::Basic;
+#[cfg(feature = "hmac")]
+use crate::auth::HmacSignature;
+
+/// Represents the output of a security scheme that identifies who made the
+/// request, it is recorded by the [`audit`](crate::audit) layer.
+pub trait Principal {
+    /// Returns the identity, such as the username or the id of the key.
+    ///
+    /// It must not contain the secrets.
+    fn principal(&self) -> String;
+}
+
+impl Principal for String {
+    fn principal(&self) -> String {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "base64")]
+impl Principal for Basic {
+    fn principal(&self) -> String {
+        self.username.clone()
+    }
+}
+
+#[cfg(feature = "hmac")]
+impl Principal for HmacSignature {
+    fn principal(&self) -> String {
+        self.key_id.clone()
+    }
+}
+
+#[cfg(feature = "key-store")]
+impl Principal for ApiKeyInfo {
+    fn principal(&self) -> String {
+        self.key_id.clone()
+    }
+}
+
+#[doc(hidden)]
+pub struct PrincipalProbe<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaPrincipal {
+    fn probe_principal(&self) -> Option<String>;
+}
+
+impl<'a, T: Principal> ViaPrincipal for PrincipalProbe<'a, T> {
+    fn probe_principal(&self) -> Option<String> {
+        Some(self.0.principal())
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaNoPrincipal {
+    fn probe_principal(&self) -> Option<String>;
+}
+
+impl<'a, T> ViaNoPrincipal for &PrincipalProbe<'a, T> {
+    fn probe_principal(&self) -> Option<String> {
+        None
+    }
+}
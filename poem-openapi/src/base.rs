@@ -1,12 +1,15 @@
-use std::ops::Deref;
+use std::{collections::BTreeMap, ops::Deref};
 
 use poem::{Error, FromRequest, Request, RequestBody, Result, Route};
+use serde_json::Value;
 
 use crate::{
+    auth::Permissions,
+    operation_info::OperationWrapper,
     payload::Payload,
     registry::{
-        MetaApi, MetaMediaType, MetaOAuthScope, MetaParamIn, MetaRequest, MetaResponse,
-        MetaResponses, MetaSchemaRef, MetaWebhook, Registry,
+        MetaApi, MetaMediaType, MetaOAuthScope, MetaOperationParam, MetaParamIn, MetaRequest,
+        MetaResponse, MetaResponses, MetaSchemaRef, MetaWebhook, Registry,
     },
 };
 
@@ -104,6 +107,11 @@ impl<T> Default for ExtractParamOptions<T> {
 ///    Extract the parameters in the signed cookie into
 /// [`CookieSigned`](crate::param::CookieSigned).
 ///
+/// - **Locale**
+///
+///    Negotiate the language from the `Accept-Language` header into
+/// [`Locale`](crate::param::Locale). (requires the `i18n` feature)
+///
 /// - **Binary&lt;T>**
 ///
 ///     Extract the request body as binary into
@@ -159,11 +167,36 @@ pub trait ApiExtractor<'a>: Sized {
         None
     }
 
+    /// Returns the parameters documented by this extractor if this extractor is
+    /// security scheme.
+    fn security_params() -> Vec<MetaOperationParam> {
+        Vec::new()
+    }
+
+    /// Returns the permissions granted to this extractor if this extractor is
+    /// security scheme and its output implements
+    /// [`Permissions`](crate::auth::Permissions).
+    fn permissions(&self) -> Option<&dyn Permissions> {
+        None
+    }
+
+    /// Returns the principal of this extractor if this extractor is security
+    /// scheme and its output implements [`Principal`](crate::auth::Principal).
+    fn principal(&self) -> Option<String> {
+        None
+    }
+
     /// Returns the location of the parameter if this extractor is parameter.
     fn param_in() -> Option<MetaParamIn> {
         None
     }
 
+    /// Returns the fixed name of the parameter if this extractor is parameter
+    /// that does not take its name from the argument.
+    fn param_name() -> Option<&'static str> {
+        None
+    }
+
     /// Returns the schema of the parameter if this extractor is parameter.
     fn param_schema_ref() -> Option<MetaSchemaRef> {
         None
@@ -174,6 +207,18 @@ pub trait ApiExtractor<'a>: Sized {
         None
     }
 
+    /// Returns the parameters documented by this extractor if this extractor is
+    /// request object.
+    fn request_params() -> Vec<MetaOperationParam> {
+        Vec::new()
+    }
+
+    /// Returns the responses documented by this extractor if this extractor is
+    /// request object.
+    fn request_responses() -> Vec<MetaResponse> {
+        Vec::new()
+    }
+
     /// Returns a reference to the raw type of this parameter.
     fn param_raw_type(&self) -> Option<&Self::ParamRawType> {
         None
@@ -273,6 +318,12 @@ pub trait ApiResponse: Sized {
     /// Register the schema contained in this response object to the registry.
     fn register(registry: &mut Registry);
 
+    /// Returns the extension fields of the operations that return this
+    /// response object, whose names start with `x-`.
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        BTreeMap::new()
+    }
+
     /// Convert [`poem::Error`] to this response object.
     #[allow(unused_variables)]
     fn from_parse_request_error(err: Error) -> Self {
@@ -306,6 +357,10 @@ impl<T: ApiResponse> ApiResponse for Result<T> {
         T::register(registry);
     }
 
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        T::operation_extensions()
+    }
+
     fn from_parse_request_error(err: Error) -> Self {
         Ok(T::from_parse_request_error(err))
     }
@@ -339,6 +394,15 @@ pub trait OpenApi: Sized {
 
     /// Adds all API endpoints to the routing object.
     fn add_routes(self, route: Route) -> Route;
+
+    /// Adds all API endpoints to the routing object, the endpoint of each
+    /// operation is passed to `wrapper` with the
+    /// [`OperationInfo`](crate::OperationInfo) of the operation.
+    #[doc(hidden)]
+    fn add_routes_with(self, route: Route, wrapper: &OperationWrapper<'_>) -> Route {
+        let _ = wrapper;
+        self.add_routes(route)
+    }
 }
 
 macro_rules! impl_openapi_for_tuple {
@@ -366,6 +430,14 @@ macro_rules! impl_openapi_for_tuple {
                 )*
                 route
             }
+
+            fn add_routes_with(self, route: Route, wrapper: &OperationWrapper<'_>) -> Route {
+                let route = self.$hn.add_routes_with(route, wrapper);
+                $(
+                let route = self.$tn.add_routes_with(route, wrapper);
+                )*
+                route
+            }
         }
     };
 
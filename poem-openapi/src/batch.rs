@@ -0,0 +1,211 @@
+//! Batch requests.
+//!
+//! The batch endpoint is enabled with [`OpenApiService::batch`], it accepts an
+//! array of sub-requests and dispatches them in order through the same routes
+//! as the other operations, so the authentication of every sub-request is
+//! checked as usual. The headers of the batch request are inherited by the
+//! sub-requests, for example the `Authorization` header, and so are the
+//! remote address, the cookies and the data of the batch request, see
+//! [`Request::sub_request`](poem::Request::sub_request).
+//!
+//! The number of sub-requests is limited by
+//! [`OpenApiService::batch_max_requests`], the batch requests with more
+//! sub-requests are rejected with `413 Payload Too Large`.
+//!
+//! [`OpenApiService::batch`]: crate::OpenApiService::batch
+//! [`OpenApiService::batch_max_requests`]: crate::OpenApiService::batch_max_requests
+
+use std::{collections::HashMap, str::FromStr};
+
+use poem::{
+    http::{header, HeaderName, HeaderValue, Method, StatusCode, Uri},
+    Endpoint, Request, Response, Result,
+};
+use serde_json::Value;
+
+use crate::{
+    error::TooManySubRequestsError,
+    payload::{Json, ParsePayload},
+    registry::{MetaApi, MetaOperation, MetaPath, Registry},
+    types::{ToJSON, Type},
+    ApiExtractor, ApiResponse, Object,
+};
+
+/// A sub-request of a batch request.
+#[derive(Object, Debug, Clone, PartialEq)]
+#[oai(internal)]
+pub struct BatchRequest {
+    /// The HTTP method, such as `GET` or `POST`.
+    pub method: String,
+    /// The path of the request, including the query string.
+    pub path: String,
+    /// The headers of the request, they override the headers of the batch
+    /// request.
+    #[oai(default)]
+    pub headers: HashMap<String, String>,
+    /// The JSON body of the request.
+    pub body: Option<Value>,
+}
+
+/// A sub-response of a batch request.
+#[derive(Object, Debug, Clone, PartialEq)]
+#[oai(internal)]
+pub struct BatchResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The headers of the response.
+    pub headers: HashMap<String, String>,
+    /// The body of the response, it is a JSON value if the content type of the
+    /// response is `application/json`, otherwise it is a string.
+    pub body: Option<Value>,
+}
+
+impl BatchResponse {
+    fn error(status: StatusCode, reason: impl Into<String>) -> Self {
+        Self {
+            status: status.as_u16(),
+            headers: HashMap::new(),
+            body: Some(Value::String(reason.into())),
+        }
+    }
+}
+
+pub(crate) fn meta(path: &'static str) -> MetaApi {
+    MetaApi {
+        paths: vec![MetaPath {
+            path,
+            operations: vec![MetaOperation {
+                method: Method::POST,
+                tags: vec![],
+                summary: Some("Batch requests"),
+                description: Some(
+                    "Dispatches the sub-requests in order and returns the sub-responses.",
+                ),
+                external_docs: None,
+                params: vec![],
+                request: <Json<Vec<BatchRequest>> as ApiExtractor>::request_meta(),
+                responses: <Json<Vec<BatchResponse>> as ApiResponse>::meta(),
+                deprecated: false,
+                security: vec![],
+                operation_id: None,
+                priority: None,
+                extensions: Default::default(),
+            }],
+        }],
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    BatchRequest::register(registry);
+    BatchResponse::register(registry);
+}
+
+pub(crate) const DEFAULT_MAX_REQUESTS: usize = 32;
+
+pub(crate) struct BatchEndpoint<E> {
+    pub(crate) path: &'static str,
+    pub(crate) max_requests: usize,
+    pub(crate) inner: E,
+}
+
+impl<E: Endpoint<Output = Response>> BatchEndpoint<E> {
+    async fn call_sub_request(&self, parent: &mut Request, request: BatchRequest) -> BatchResponse {
+        let method = match Method::from_bytes(request.method.to_uppercase().as_bytes()) {
+            Ok(method) => method,
+            Err(_) => return BatchResponse::error(StatusCode::BAD_REQUEST, "invalid method"),
+        };
+        let uri = match Uri::from_str(&request.path) {
+            Ok(uri) => uri,
+            Err(_) => return BatchResponse::error(StatusCode::BAD_REQUEST, "invalid path"),
+        };
+        if uri.path() == self.path {
+            return BatchResponse::error(
+                StatusCode::BAD_REQUEST,
+                "batch requests cannot be nested",
+            );
+        }
+
+        let mut headers = parent.headers().clone();
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+        for (name, value) in &request.headers {
+            match (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => return BatchResponse::error(StatusCode::BAD_REQUEST, "invalid header"),
+            }
+        }
+
+        let builder = parent.sub_request().method(method).uri(uri);
+        let mut sub_request = match request.body {
+            Some(body) => builder
+                .content_type("application/json")
+                .body(body.to_json_string()),
+            None => builder.finish(),
+        };
+        sub_request.headers_mut().extend(headers);
+
+        let resp = match self.inner.call(sub_request).await {
+            Ok(resp) => resp,
+            Err(err) => err.as_response(),
+        };
+
+        let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let is_json = resp
+            .content_type()
+            .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+            .map(|mime| mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+            .unwrap_or_default();
+        let body = match resp.into_body().into_bytes().await {
+            Ok(data) if data.is_empty() => None,
+            Ok(data) if is_json => serde_json::from_slice(&data).ok(),
+            Ok(data) => Some(Value::String(String::from_utf8_lossy(&data).into_owned())),
+            Err(err) => {
+                return BatchResponse::error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+            }
+        };
+
+        BatchResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+#[poem::async_trait]
+impl<E: Endpoint<Output = Response>> Endpoint for BatchEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.uri().path() != self.path || req.method() != Method::POST {
+            return self.inner.call(req).await;
+        }
+
+        let (mut req, mut body) = req.split();
+        let requests = <Json<Vec<BatchRequest>> as ParsePayload>::from_request(&req, &mut body)
+            .await?
+            .0;
+        if requests.len() > self.max_requests {
+            return Err(TooManySubRequestsError {
+                max_requests: self.max_requests,
+            }
+            .into());
+        }
+
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.call_sub_request(&mut req, request).await);
+        }
+        Ok(poem::IntoResponse::into_response(Json(responses)))
+    }
+}
@@ -0,0 +1,76 @@
+use poem::{endpoint::BoxEndpoint, Endpoint, Request, Response, Result};
+
+use crate::{
+    error::{ContentTypeError, UnsupportedMediaTypeError},
+    registry::{MetaApi, MetaOperation, MetaResponse},
+};
+
+/// Replaces the [`ContentTypeError`] of the operation with
+/// `415 Unsupported Media Type` that lists the supported content types.
+struct ContentTypeEndpoint {
+    supported: Vec<&'static str>,
+    inner: BoxEndpoint<'static, Response>,
+}
+
+#[poem::async_trait]
+impl Endpoint for ContentTypeEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let content_type = req.content_type().unwrap_or_default().to_string();
+        match self.inner.call(req).await {
+            Err(err) if err.is::<ContentTypeError>() => Err(UnsupportedMediaTypeError {
+                content_type,
+                supported: self.supported.clone(),
+            }
+            .into()),
+            res => res,
+        }
+    }
+}
+
+pub(crate) fn apply(
+    ep: BoxEndpoint<'static, Response>,
+    operation: &MetaOperation,
+) -> BoxEndpoint<'static, Response> {
+    let request = match &operation.request {
+        Some(request) => request,
+        None => return ep,
+    };
+    let mut supported = request
+        .content
+        .iter()
+        .map(|media| media.content_type)
+        .collect::<Vec<_>>();
+    supported.sort_unstable();
+    supported.dedup();
+    Box::new(ContentTypeEndpoint {
+        supported,
+        inner: ep,
+    })
+}
+
+/// Adds the `415` response to the operations that have the request bodies.
+pub(crate) fn document(apis: &mut [MetaApi]) {
+    for operation in apis
+        .iter_mut()
+        .flat_map(|api| &mut api.paths)
+        .flat_map(|path| &mut path.operations)
+    {
+        if operation.request.is_none()
+            || operation
+                .responses
+                .responses
+                .iter()
+                .any(|resp| resp.status == Some(415))
+        {
+            continue;
+        }
+        operation.responses.responses.push(MetaResponse {
+            description: "The `Content-Type` of the request body is not supported.",
+            status: Some(415),
+            content: vec![],
+            headers: vec![],
+        });
+    }
+}
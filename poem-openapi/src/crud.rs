@@ -0,0 +1,272 @@
+//! Scaffolding for simple CRUD resources.
+//!
+//! Implement [`CrudStorage`] for the storage of a resource, and use the
+//! [`crud_api!`](crate::crud_api) macro to generate an API with the following
+//! operations:
+//!
+//! | Method | Path        | Description                                           |
+//! |--------|-------------|-------------------------------------------------------|
+//! | GET    | `{path}`    | List the objects with the `offset` and `limit` params |
+//! | POST   | `{path}`    | Create an object                                      |
+//! | GET    | `{path}/id` | Get an object                                         |
+//! | PUT    | `{path}/id` | Update an object                                      |
+//! | DELETE | `{path}/id` | Delete an object                                      |
+//!
+//! The operations that get, update and delete an object respond with
+//! `404 Not Found` if it does not exist, and the request bodies are validated
+//! by the validators of the object.
+
+use poem::Result;
+
+use crate::{
+    payload::Json,
+    types::{ParseFromJSON, ParseFromParameter, ToJSON, Type},
+    ApiResponse,
+};
+
+/// The default number of objects returned by the list operation, the
+/// `limit` param cannot exceed 100.
+pub const DEFAULT_LIMIT: u64 = 20;
+
+/// Represents the storage of a CRUD resource.
+#[poem::async_trait]
+pub trait CrudStorage: Send + Sync {
+    /// The type of the object.
+    type Object: Type + ParseFromJSON + ToJSON + Send + Sync;
+
+    /// The type of the object id.
+    type Id: Type + ParseFromParameter + Send + Sync;
+
+    /// Gets an object, returns `None` if it does not exist.
+    async fn get(&self, id: Self::Id) -> Result<Option<Self::Object>>;
+
+    /// Lists the objects, returns the objects and the total number of objects.
+    async fn list(&self, offset: u64, limit: u64) -> Result<(Vec<Self::Object>, u64)>;
+
+    /// Creates an object, returns the created object.
+    async fn create(&self, object: Self::Object) -> Result<Self::Object>;
+
+    /// Updates an object, returns `None` if it does not exist.
+    async fn update(&self, id: Self::Id, object: Self::Object) -> Result<Option<Self::Object>>;
+
+    /// Deletes an object, returns `false` if it does not exist.
+    async fn delete(&self, id: Self::Id) -> Result<bool>;
+}
+
+/// The response of the list operation.
+#[derive(ApiResponse)]
+#[oai(internal)]
+pub enum ListResponse<T: Type + ToJSON + Send> {
+    /// The objects.
+    #[oai(status = 200)]
+    Ok(
+        Json<Vec<T>>,
+        /// The total number of objects.
+        #[oai(header = "X-Total-Count")]
+        u64,
+    ),
+}
+
+/// The response of the create operation.
+#[derive(ApiResponse)]
+#[oai(internal)]
+pub enum CreateResponse<T: Type + ToJSON + Send> {
+    /// The object is created.
+    #[oai(status = 201)]
+    Created(Json<T>),
+}
+
+/// The response of the get and update operations.
+#[derive(ApiResponse)]
+#[oai(internal)]
+pub enum ObjectResponse<T: Type + ToJSON + Send> {
+    /// The object.
+    #[oai(status = 200)]
+    Ok(Json<T>),
+    /// The object does not exist.
+    #[oai(status = 404)]
+    NotFound,
+}
+
+impl<T: Type + ToJSON + Send> From<Option<T>> for ObjectResponse<T> {
+    fn from(object: Option<T>) -> Self {
+        match object {
+            Some(object) => Self::Ok(Json(object)),
+            None => Self::NotFound,
+        }
+    }
+}
+
+/// The response of the delete operation.
+#[derive(ApiResponse)]
+#[oai(internal)]
+pub enum DeleteResponse {
+    /// The object is deleted.
+    #[oai(status = 204)]
+    NoContent,
+    /// The object does not exist.
+    #[oai(status = 404)]
+    NotFound,
+}
+
+/// Generates an API for a [`CrudStorage`].
+///
+/// The generated struct wraps the storage, and the path of the resource is
+/// specified after `at`. The tags of the operations can be specified with
+/// `tag = "..."`, the same as the `tag` attribute of `OpenApi`.
+///
+/// See also: [`crud`](crate::crud)
+///
+/// # Example
+///
+/// ```
+/// use std::{collections::BTreeMap, sync::Mutex};
+///
+/// use poem::Result;
+/// use poem_openapi::{crud::CrudStorage, crud_api, Object, OpenApiService};
+///
+/// #[derive(Object, Clone)]
+/// struct User {
+///     #[oai(read_only)]
+///     id: i64,
+///     #[oai(validator(max_length = 32))]
+///     name: String,
+/// }
+///
+/// #[derive(Default)]
+/// struct UserStorage(Mutex<BTreeMap<i64, User>>);
+///
+/// #[poem::async_trait]
+/// impl CrudStorage for UserStorage {
+///     type Object = User;
+///     type Id = i64;
+///
+///     async fn get(&self, id: i64) -> Result<Option<User>> {
+///         Ok(self.0.lock().unwrap().get(&id).cloned())
+///     }
+///
+///     async fn list(&self, offset: u64, limit: u64) -> Result<(Vec<User>, u64)> {
+///         let users = self.0.lock().unwrap();
+///         let items = users
+///             .values()
+///             .skip(offset as usize)
+///             .take(limit as usize)
+///             .cloned()
+///             .collect();
+///         Ok((items, users.len() as u64))
+///     }
+///
+///     async fn create(&self, mut user: User) -> Result<User> {
+///         let mut users = self.0.lock().unwrap();
+///         user.id = users.keys().last().copied().unwrap_or_default() + 1;
+///         users.insert(user.id, user.clone());
+///         Ok(user)
+///     }
+///
+///     async fn update(&self, id: i64, mut user: User) -> Result<Option<User>> {
+///         let mut users = self.0.lock().unwrap();
+///         Ok(users.get_mut(&id).map(|current| {
+///             user.id = id;
+///             *current = user.clone();
+///             user
+///         }))
+///     }
+///
+///     async fn delete(&self, id: i64) -> Result<bool> {
+///         Ok(self.0.lock().unwrap().remove(&id).is_some())
+///     }
+/// }
+///
+/// crud_api! {
+///     /// User management.
+///     pub struct UserApi(UserStorage) at "/users";
+/// }
+///
+/// let api_service = OpenApiService::new(UserApi(UserStorage::default()), "Users", "1.0");
+/// ```
+#[macro_export]
+macro_rules! crud_api {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($storage:ty) at $path:literal $(, tag = $tag:literal)* ;) => {
+        $(#[$meta])*
+        $vis struct $name(pub $storage);
+
+        #[$crate::OpenApi(prefix_path = $path $(, tag = $tag)*)]
+        impl $name {
+            /// List the objects
+            #[oai(path = "/", method = "get")]
+            async fn list(
+                &self,
+                offset: $crate::param::Query<::std::option::Option<u64>>,
+                #[oai(validator(maximum(value = "100")))]
+                limit: $crate::param::Query<::std::option::Option<u64>>,
+            ) -> $crate::__private::poem::Result<
+                $crate::crud::ListResponse<<$storage as $crate::crud::CrudStorage>::Object>,
+            > {
+                let (items, total) = $crate::crud::CrudStorage::list(
+                    &self.0,
+                    offset.0.unwrap_or_default(),
+                    limit.0.unwrap_or($crate::crud::DEFAULT_LIMIT),
+                )
+                .await?;
+                ::std::result::Result::Ok($crate::crud::ListResponse::Ok(
+                    $crate::payload::Json(items),
+                    total,
+                ))
+            }
+
+            /// Create an object
+            #[oai(path = "/", method = "post")]
+            async fn create(
+                &self,
+                object: $crate::payload::Json<<$storage as $crate::crud::CrudStorage>::Object>,
+            ) -> $crate::__private::poem::Result<
+                $crate::crud::CreateResponse<<$storage as $crate::crud::CrudStorage>::Object>,
+            > {
+                let object = $crate::crud::CrudStorage::create(&self.0, object.0).await?;
+                ::std::result::Result::Ok($crate::crud::CreateResponse::Created(
+                    $crate::payload::Json(object),
+                ))
+            }
+
+            /// Get an object
+            #[oai(path = "/:id", method = "get")]
+            async fn get(
+                &self,
+                id: $crate::param::Path<<$storage as $crate::crud::CrudStorage>::Id>,
+            ) -> $crate::__private::poem::Result<
+                $crate::crud::ObjectResponse<<$storage as $crate::crud::CrudStorage>::Object>,
+            > {
+                ::std::result::Result::Ok(::std::convert::Into::into(
+                    $crate::crud::CrudStorage::get(&self.0, id.0).await?,
+                ))
+            }
+
+            /// Update an object
+            #[oai(path = "/:id", method = "put")]
+            async fn update(
+                &self,
+                id: $crate::param::Path<<$storage as $crate::crud::CrudStorage>::Id>,
+                object: $crate::payload::Json<<$storage as $crate::crud::CrudStorage>::Object>,
+            ) -> $crate::__private::poem::Result<
+                $crate::crud::ObjectResponse<<$storage as $crate::crud::CrudStorage>::Object>,
+            > {
+                ::std::result::Result::Ok(::std::convert::Into::into(
+                    $crate::crud::CrudStorage::update(&self.0, id.0, object.0).await?,
+                ))
+            }
+
+            /// Delete an object
+            #[oai(path = "/:id", method = "delete")]
+            async fn delete(
+                &self,
+                id: $crate::param::Path<<$storage as $crate::crud::CrudStorage>::Id>,
+            ) -> $crate::__private::poem::Result<$crate::crud::DeleteResponse> {
+                if $crate::crud::CrudStorage::delete(&self.0, id.0).await? {
+                    ::std::result::Result::Ok($crate::crud::DeleteResponse::NoContent)
+                } else {
+                    ::std::result::Result::Ok($crate::crud::DeleteResponse::NotFound)
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,112 @@
+use std::{borrow::Cow, sync::Arc};
+
+use poem::Request;
+use serde_json::Value;
+
+use crate::{
+    payload::ContentTypeTable,
+    registry::{MetaApi, MetaMediaType, MetaSchemaRef},
+};
+
+type DeserializeFn = dyn Fn(&[u8]) -> Result<Value, String> + Send + Sync;
+
+/// A function that deserializes the request bodies of the content type to
+/// JSON values, they are parsed by the [`Json`](crate::payload::Json)
+/// payloads.
+pub(crate) struct Deserializer {
+    content_type: &'static str,
+    ty: Option<(Cow<'static, str>, MetaSchemaRef)>,
+    f: Box<DeserializeFn>,
+}
+
+impl Deserializer {
+    pub(crate) fn new<F, E>(
+        content_type: &'static str,
+        ty: Option<(Cow<'static, str>, MetaSchemaRef)>,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(&[u8]) -> Result<Value, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        Self {
+            content_type,
+            ty,
+            f: Box::new(move |data| f(data).map_err(|err| err.to_string())),
+        }
+    }
+
+    pub(crate) fn deserialize(&self, data: &[u8]) -> Result<Value, String> {
+        (self.f)(data)
+    }
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct Deserializers(pub(crate) Arc<Vec<Deserializer>>);
+
+/// Returns the deserializer of the type for the `Content-Type` of the
+/// request, the deserializers of the type are preferred to the deserializers
+/// of all the types.
+pub(crate) fn find<'a>(request: &'a Request, type_name: &str) -> Option<&'a Deserializer> {
+    let content_type = request.content_type()?;
+    let Deserializers(deserializers) = request.data::<Deserializers>()?;
+    let matches = |deserializer: &&Deserializer| {
+        ContentTypeTable::new(&[deserializer.content_type])
+            .matches(content_type)
+            .is_some()
+    };
+    deserializers
+        .iter()
+        .filter(matches)
+        .find(|deserializer| {
+            deserializer
+                .ty
+                .as_ref()
+                .map(|(name, _)| name == type_name)
+                .unwrap_or_default()
+        })
+        .or_else(|| {
+            deserializers
+                .iter()
+                .filter(matches)
+                .find(|deserializer| deserializer.ty.is_none())
+        })
+}
+
+/// Adds the content types of the deserializers to the request bodies of the
+/// operations that accept `application/json`.
+pub(crate) fn document(deserializers: &[Deserializer], apis: &mut [MetaApi]) {
+    for request in apis
+        .iter_mut()
+        .flat_map(|api| &mut api.paths)
+        .flat_map(|path| &mut path.operations)
+        .filter_map(|operation| operation.request.as_mut())
+    {
+        let schemas = request
+            .content
+            .iter()
+            .filter(|media| media.content_type == "application/json")
+            .map(|media| media.schema.clone())
+            .collect::<Vec<_>>();
+        for schema in schemas {
+            for deserializer in deserializers {
+                let matches = deserializer
+                    .ty
+                    .as_ref()
+                    .map(|(_, schema_ref)| *schema_ref == schema)
+                    .unwrap_or(true);
+                if matches
+                    && !request
+                        .content
+                        .iter()
+                        .any(|media| media.content_type == deserializer.content_type)
+                {
+                    request.content.push(MetaMediaType {
+                        content_type: deserializer.content_type,
+                        schema: schema.clone(),
+                    });
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Some common error types.
 
-use poem::{error::ResponseError, http::StatusCode};
+use std::sync::Arc;
+
+use poem::{error::ResponseError, http::StatusCode, Error as PoemError, Request};
 use thiserror::Error;
 
 /// Parameter error.
@@ -34,6 +36,23 @@ impl ResponseError for ParseJsonError {
     }
 }
 
+/// Parse Protobuf error.
+#[cfg(feature = "prost")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+#[derive(Debug, Error)]
+#[error("parse Protobuf error: {reason}")]
+pub struct ParseProtobufError {
+    /// The reason for the error.
+    pub reason: String,
+}
+
+#[cfg(feature = "prost")]
+impl ResponseError for ParseProtobufError {
+    fn status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
 /// Parse multipart error.
 #[derive(Debug, Error)]
 #[error("parse multipart error: {reason}")]
@@ -48,6 +67,23 @@ impl ResponseError for ParseMultipartError {
     }
 }
 
+/// The content type of a multipart field is not allowed.
+#[derive(Debug, Error)]
+#[error("the content type of field `{field}` is not supported: {content_type}")]
+pub struct MultipartContentTypeError {
+    /// The name of the field.
+    pub field: &'static str,
+
+    /// The content type of the field.
+    pub content_type: String,
+}
+
+impl ResponseError for MultipartContentTypeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+}
+
 /// Content type error.
 #[derive(Debug, Error)]
 pub enum ContentTypeError {
@@ -69,6 +105,76 @@ impl ResponseError for ContentTypeError {
     }
 }
 
+/// The content type of the request body is not supported by the operation,
+/// it is returned by the services with
+/// [`OpenApiService::strict_content_type`](crate::OpenApiService::strict_content_type).
+#[derive(Debug, Error)]
+#[error(
+    "the `Content-Type` requested by the client is not supported: {content_type}, the supported content types are: {}",
+    .supported.join(", ")
+)]
+pub struct UnsupportedMediaTypeError {
+    /// The `Content-Type` header requested by the client, it is empty if the
+    /// header is missing.
+    pub content_type: String,
+
+    /// The content types supported by the operation.
+    pub supported: Vec<&'static str>,
+}
+
+impl ResponseError for UnsupportedMediaTypeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+}
+
+/// The operation cannot produce any content type that is accepted by the
+/// client, it is returned by the services with
+/// [`OpenApiService::strict_accept`](crate::OpenApiService::strict_accept).
+#[derive(Debug, Error)]
+#[error("the `Accept` header requested by the client is not supported: {accept}")]
+pub struct NotAcceptableError {
+    /// The `Accept` header requested by the client.
+    pub accept: String,
+}
+
+impl ResponseError for NotAcceptableError {
+    fn status(&self) -> StatusCode {
+        StatusCode::NOT_ACCEPTABLE
+    }
+}
+
+/// The request to an operation cannot be built by the request builder that
+/// is generated by `#[OpenApi(request_builders)]`.
+#[derive(Debug, Clone, Error)]
+pub enum BuildRequestError {
+    /// The required parameter is not set.
+    #[error("the required parameter `{name}` is missing")]
+    MissingParam {
+        /// The name of the parameter.
+        name: &'static str,
+    },
+
+    /// The parameter cannot be sent by the request builder, such as the
+    /// private and signed cookies, whose keys are not known.
+    #[error("the parameter `{name}` cannot be sent by the request builder")]
+    UnsupportedParam {
+        /// The name of the parameter.
+        name: &'static str,
+    },
+
+    /// The required body is not set.
+    #[error("the required body is missing")]
+    MissingBody,
+
+    /// The URI or the headers of the request are invalid.
+    #[error("invalid request: {reason}")]
+    InvalidRequest {
+        /// The reason for the error.
+        reason: String,
+    },
+}
+
 /// Authorization error.
 #[derive(Debug, Error)]
 #[error("authorization error")]
@@ -79,3 +185,139 @@ impl ResponseError for AuthorizationError {
         StatusCode::UNAUTHORIZED
     }
 }
+
+/// The security scheme has not been granted the permission required by the
+/// operation.
+#[derive(Debug, Error)]
+#[error("permission denied: requires the `{permission}` permission")]
+pub struct PermissionDeniedError {
+    /// The missing permission.
+    pub permission: &'static str,
+}
+
+impl ResponseError for PermissionDeniedError {
+    fn status(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+}
+
+/// The `If-Match` header does not match the current version of the resource.
+#[derive(Debug, Error)]
+#[error("precondition failed: the resource has been modified")]
+pub struct PreconditionFailedError;
+
+impl ResponseError for PreconditionFailedError {
+    fn status(&self) -> StatusCode {
+        StatusCode::PRECONDITION_FAILED
+    }
+}
+
+/// The request body exceeds the maximum size.
+#[derive(Debug, Clone, Error)]
+#[error("payload too large: the maximum size is {max_size} bytes")]
+pub struct PayloadTooLargeError {
+    /// The maximum size of the body.
+    pub max_size: usize,
+}
+
+impl ResponseError for PayloadTooLargeError {
+    fn status(&self) -> StatusCode {
+        StatusCode::PAYLOAD_TOO_LARGE
+    }
+}
+
+/// The batch request contains more sub-requests than the maximum.
+#[derive(Debug, Clone, Error)]
+#[error("payload too large: the maximum number of sub-requests is {max_requests}")]
+pub struct TooManySubRequestsError {
+    /// The maximum number of sub-requests.
+    pub max_requests: usize,
+}
+
+impl ResponseError for TooManySubRequestsError {
+    fn status(&self) -> StatusCode {
+        StatusCode::PAYLOAD_TOO_LARGE
+    }
+}
+
+/// A hook to translate or rewrite the messages of the errors that occur while
+/// parsing the request, for example to render them in the language of the
+/// caller.
+///
+/// It is called with [`ParseParamError`], [`ParseJsonError`],
+/// [`ParseMultipartError`], [`MultipartContentTypeError`] and
+/// [`ContentTypeError`], use [`poem::Error::downcast_ref`] to get the concrete
+/// error. Only the message of the error is replaced, so the rewritten error can
+/// still be downcast to the concrete error and keeps its status code.
+///
+/// # Example
+///
+/// ```
+/// use poem::{Error, Request};
+/// use poem_openapi::{
+///     error::{ErrorMessageProvider, ParseParamError},
+///     OpenApi, OpenApiService,
+/// };
+///
+/// struct LocalizedErrors;
+///
+/// impl ErrorMessageProvider for LocalizedErrors {
+///     fn message(&self, request: &Request, err: &Error) -> Option<String> {
+///         let err = err.downcast_ref::<ParseParamError>()?;
+///         let language = request.header("accept-language")?;
+///         if language.starts_with("zh") {
+///             Some(format!("参数 `{}` 解析失败", err.name))
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {}
+///
+/// let api_service =
+///     OpenApiService::new(Api, "Demo", "1.0").error_message_provider(LocalizedErrors);
+/// ```
+pub trait ErrorMessageProvider: Send + Sync + 'static {
+    /// Returns the new message of the error, or `None` to keep the original
+    /// message.
+    fn message(&self, request: &Request, err: &PoemError) -> Option<String>;
+}
+
+impl<F> ErrorMessageProvider for F
+where
+    F: Fn(&Request, &PoemError) -> Option<String> + Send + Sync + 'static,
+{
+    fn message(&self, request: &Request, err: &PoemError) -> Option<String> {
+        (self)(request, err)
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ErrorMessageProviderData(pub(crate) Option<Arc<dyn ErrorMessageProvider>>);
+
+#[doc(hidden)]
+pub fn rewrite_error_message(request: &Request, err: PoemError) -> PoemError {
+    let provider = match request.data::<ErrorMessageProviderData>() {
+        Some(ErrorMessageProviderData(Some(provider))) => provider,
+        _ => return err,
+    };
+
+    if !(err.is::<ParseParamError>()
+        || err.is::<ParseJsonError>()
+        || err.is::<ParseMultipartError>()
+        || err.is::<MultipartContentTypeError>()
+        || err.is::<ContentTypeError>())
+    {
+        return err;
+    }
+
+    let mut err = err;
+    if let Some(message) = provider.message(request, &err) {
+        err.set_error_message(message);
+    }
+    err
+}
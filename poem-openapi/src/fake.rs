@@ -0,0 +1,494 @@
+//! Fake data generated from the schemas of the types.
+//!
+//! The [`Faker`] generates the values that honor the validators of the
+//! types, such as the ranges, the lengths, the patterns and the enums, for
+//! the examples, the mock responses and the tests.
+//!
+//! # Example
+//!
+//! ```
+//! use poem_openapi::{fake::Faker, Enum, Object};
+//!
+//! #[derive(Debug, Enum)]
+//! enum Role {
+//!     Admin,
+//!     Member,
+//! }
+//!
+//! #[derive(Debug, Object)]
+//! struct User {
+//!     #[oai(validator(pattern = r"^[a-z]{3,8}$"))]
+//!     name: String,
+//!     #[oai(validator(minimum(value = "18"), maximum(value = "99")))]
+//!     age: u8,
+//!     role: Role,
+//! }
+//!
+//! let mut faker = Faker::new().seed(1);
+//! let user = faker.fake::<User>().unwrap();
+//! assert!((3..=8).contains(&user.name.len()));
+//! assert!((18..=99).contains(&user.age));
+//!
+//! // The same seed generates the same values.
+//! assert_eq!(
+//!     Faker::new().seed(1).value::<User>(),
+//!     Faker::new().seed(1).value::<User>()
+//! );
+//! ```
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use regex::Regex;
+use regex_syntax::hir::{
+    Class, ClassBytes, ClassBytesRange, ClassUnicode, ClassUnicodeRange, Hir, HirKind,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, Type},
+};
+
+const MAX_DEPTH: usize = 8;
+const MAX_ATTEMPTS: usize = 16;
+
+/// A generator of the fake values of the types.
+pub struct Faker {
+    registry: Registry,
+    rng: StdRng,
+}
+
+impl Default for Faker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Faker {
+    /// Create a fake value generator with a random seed.
+    pub fn new() -> Self {
+        Self {
+            registry: Registry::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Sets the seed of the random generator, the same seed generates the
+    /// same values.
+    #[must_use]
+    pub fn seed(self, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            ..self
+        }
+    }
+
+    /// Generates a JSON value of the schema of `T`.
+    pub fn value<T: Type>(&mut self) -> Value {
+        T::register(&mut self.registry);
+        let schema = T::schema_ref();
+        let mut gen = ValueGenerator::new(&self.registry, self.rng.clone());
+        let value = gen.value(&schema, 0);
+        self.rng = gen.rng;
+        value
+    }
+
+    /// Generates a value of `T`.
+    ///
+    /// The value is generated again if it cannot be parsed, for example the
+    /// validators that cannot be described by the schema are not satisfied,
+    /// and the error of the last attempt is returned.
+    pub fn fake<T: Type + ParseFromJSON>(&mut self) -> Result<T, ParseError<T>> {
+        let mut res = T::parse_from_json(self.value::<T>());
+        for _ in 1..MAX_ATTEMPTS {
+            if res.is_ok() {
+                break;
+            }
+            res = T::parse_from_json(self.value::<T>());
+        }
+        res
+    }
+}
+
+/// Generates the values of the schemas.
+pub(crate) struct ValueGenerator<'a> {
+    registry: &'a Registry,
+    pub(crate) rng: StdRng,
+    /// `false` if the generated values may be invalid, such as the numbers of
+    /// the `multiple_of` validators.
+    pub(crate) certain: bool,
+    /// Generates the unusual values, such as the edge values and the special
+    /// characters, instead of the realistic values.
+    pub(crate) unusual: bool,
+    /// Generates the visible ASCII characters only.
+    pub(crate) ascii: bool,
+}
+
+impl<'a> ValueGenerator<'a> {
+    pub(crate) fn new(registry: &'a Registry, rng: StdRng) -> Self {
+        Self {
+            registry,
+            rng,
+            certain: true,
+            unusual: false,
+            ascii: false,
+        }
+    }
+
+    pub(crate) fn resolve(&self, schema: &'a MetaSchemaRef) -> Option<&'a MetaSchema> {
+        match schema {
+            MetaSchemaRef::Inline(schema) => Some(schema),
+            MetaSchemaRef::Reference(name) => self.registry.schemas.get(name),
+        }
+    }
+
+    /// Generates a valid value of the schema.
+    pub(crate) fn value(&mut self, schema: &'a MetaSchemaRef, depth: usize) -> Value {
+        match self.resolve(schema) {
+            Some(schema) if depth < MAX_DEPTH => self.schema_value(schema, depth),
+            _ => {
+                self.certain = false;
+                Value::Null
+            }
+        }
+    }
+
+    pub(crate) fn schema_value(&mut self, schema: &'a MetaSchema, depth: usize) -> Value {
+        if let Some(example) = &schema.example {
+            if self.rng.gen_bool(0.2) {
+                return example.clone();
+            }
+        }
+        if let Some(default) = &schema.default {
+            if self.rng.gen_bool(0.2) {
+                return default.clone();
+            }
+        }
+        if let Some(value) = schema.enum_items.choose(&mut self.rng) {
+            return value.clone();
+        }
+
+        if !schema.one_of.is_empty() {
+            let idx = self.rng.gen_range(0..schema.one_of.len());
+            let variant = &schema.one_of[idx];
+            let mut value = self.value(variant, depth + 1);
+            if let (Some(discriminator), MetaSchemaRef::Reference(name), Value::Object(obj)) =
+                (&schema.discriminator, variant, &mut value)
+            {
+                let reference = format!("#/components/schemas/{}", name);
+                let mapping = discriminator
+                    .mapping
+                    .iter()
+                    .find(|(_, item)| *item == reference)
+                    .map(|(key, _)| *key)
+                    .unwrap_or(name);
+                obj.insert(
+                    discriminator.property_name.to_string(),
+                    Value::String(mapping.to_string()),
+                );
+            }
+            return value;
+        }
+
+        if !schema.all_of.is_empty() {
+            let mut merged = Value::Null;
+            for item in &schema.all_of {
+                match (&mut merged, self.value(item, depth + 1)) {
+                    (Value::Object(merged), Value::Object(obj)) => merged.extend(obj),
+                    (merged @ Value::Null, value) => *merged = value,
+                    _ => {}
+                }
+            }
+            if merged.is_null() {
+                self.certain = false;
+            }
+            return merged;
+        }
+
+        match schema.ty {
+            "boolean" => Value::Bool(self.rng.gen()),
+            "integer" => {
+                let (format_min, format_max) = match schema.format {
+                    Some("int8") => (i8::MIN as i64, i8::MAX as i64),
+                    Some("int16") => (i16::MIN as i64, i16::MAX as i64),
+                    Some("int32") => (i32::MIN as i64, i32::MAX as i64),
+                    Some("uint8") => (0, u8::MAX as i64),
+                    Some("uint16") => (0, u16::MAX as i64),
+                    Some("uint32") => (0, u32::MAX as i64),
+                    Some(format) if format.starts_with('u') => (0, i64::MAX),
+                    _ => (i64::MIN, i64::MAX),
+                };
+                let min = match (schema.minimum, schema.exclusive_minimum) {
+                    (Some(min), Some(true)) => min.floor() as i64 + 1,
+                    (Some(min), _) => min.ceil() as i64,
+                    (None, _) if self.unusual => -1000,
+                    (None, _) => 0,
+                }
+                .max(format_min);
+                let max = match (schema.maximum, schema.exclusive_maximum) {
+                    (Some(max), Some(true)) => max.ceil() as i64 - 1,
+                    (Some(max), _) => max.floor() as i64,
+                    (None, _) => min.max(0) + 1000,
+                }
+                .min(format_max);
+                if min > max {
+                    self.certain = false;
+                    return Value::from(min);
+                }
+                // the edge values find the most bugs
+                let mut value = match self.rng.gen_range(0..8) {
+                    0 if self.unusual => min,
+                    1 if self.unusual => max,
+                    2 if self.unusual => 0.clamp(min, max),
+                    _ => self.rng.gen_range(min..=max),
+                };
+                if let Some(multiple_of) = schema.multiple_of {
+                    let multiple_of = multiple_of as i64;
+                    if multiple_of > 0 {
+                        value = value / multiple_of * multiple_of;
+                        if value < min {
+                            value += multiple_of;
+                        }
+                    }
+                }
+                Value::from(value)
+            }
+            "number" => {
+                let min = schema
+                    .minimum
+                    .unwrap_or(if self.unusual { -1000.0 } else { 0.0 });
+                let max = schema.maximum.unwrap_or(min.max(0.0) + 1000.0);
+                if schema.multiple_of.is_some() || min >= max {
+                    self.certain = false;
+                }
+                if min >= max {
+                    return Value::from(min);
+                }
+                let value = self.rng.gen_range(min..max);
+                if schema.exclusive_minimum == Some(true) && value <= min {
+                    return Value::from((min + max) / 2.0);
+                }
+                Value::from(value)
+            }
+            "string" => Value::String(self.string(schema)),
+            "array" => {
+                let min = schema.min_items.unwrap_or(0);
+                let max = schema.max_items.unwrap_or(min + 3).max(min);
+                let len = self.rng.gen_range(min..=max);
+                let mut items = Vec::with_capacity(len);
+                for item in schema.prefix_items.iter().take(len) {
+                    items.push(self.value(item, depth + 1));
+                }
+                if let Some(item) = &schema.items {
+                    for _ in items.len()..len {
+                        items.push(self.value(item, depth + 1));
+                    }
+                }
+                if schema.unique_items == Some(true) {
+                    let mut unique = Vec::with_capacity(items.len());
+                    for item in items {
+                        if !unique.contains(&item) {
+                            unique.push(item);
+                        }
+                    }
+                    if unique.len() < min {
+                        self.certain = false;
+                    }
+                    items = unique;
+                }
+                Value::Array(items)
+            }
+            "object" => {
+                let mut obj = Map::new();
+                for (name, property) in &schema.properties {
+                    let read_only =
+                        matches!(property, MetaSchemaRef::Inline(schema) if schema.read_only);
+                    if read_only {
+                        continue;
+                    }
+                    if schema.required.contains(name) || self.rng.gen_bool(0.5) {
+                        let value = self.value(property, depth + 1);
+                        obj.insert(name.to_string(), value);
+                    }
+                }
+                if let Some(additional_properties) = &schema.additional_properties {
+                    for _ in 0..self.rng.gen_range(0..3) {
+                        let key = self.words(1, 8);
+                        let value = self.value(additional_properties, depth + 1);
+                        obj.insert(key, value);
+                    }
+                }
+                if schema.max_properties.is_some() || schema.min_properties.is_some() {
+                    self.certain = false;
+                }
+                Value::Object(obj)
+            }
+            _ => {
+                self.certain = false;
+                Value::Null
+            }
+        }
+    }
+
+    fn string(&mut self, schema: &MetaSchema) -> String {
+        let n = self.rng.gen_range(0..10000);
+        let value = match schema.format {
+            Some("date-time") => Some(format!(
+                "2022-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                n % 12 + 1,
+                n % 28 + 1,
+                n % 24,
+                n % 60,
+                n / 60 % 60
+            )),
+            Some("date") => Some(format!("2022-{:02}-{:02}", n % 12 + 1, n % 28 + 1)),
+            Some("time") => Some(format!("{:02}:{:02}:{:02}", n % 24, n % 60, n / 60 % 60)),
+            Some("uuid") => Some(format!("00000000-0000-4000-8000-{:012}", n)),
+            Some("email") => Some(format!("user{}@example.com", n)),
+            Some("uri") | Some("url") => Some(format!("https://example.com/{}", n)),
+            Some("hostname") => Some(format!("host{}.example.com", n)),
+            Some("ipv4") => Some(format!("10.0.{}.{}", n / 256 % 256, n % 256)),
+            Some("ipv6") => Some(format!("2001:db8::{:x}", n)),
+            Some("duration") => Some(format!("PT{}S", n)),
+            Some("bytes") => Some(base64::encode(n.to_string())),
+            Some("binary") => None,
+            Some("password") | None => None,
+            Some(_) => {
+                self.certain = false;
+                None
+            }
+        };
+        if let Some(value) = value {
+            return value;
+        }
+
+        let min = schema.min_length.unwrap_or(0);
+        let max = schema.max_length.unwrap_or(min + 16).max(min);
+        if let Some(pattern) = &schema.pattern {
+            return match self.pattern(pattern, min, max) {
+                Some(value) => value,
+                None => {
+                    self.certain = false;
+                    self.words(min, max)
+                }
+            };
+        }
+        if self.unusual {
+            // the length validators count the bytes
+            self.unusual_string(min, max, schema.max_length.is_some())
+        } else {
+            self.words(min, max)
+        }
+    }
+
+    /// Generates a string of the lowercase words.
+    fn words(&mut self, min: usize, max: usize) -> String {
+        const WORDS: &[&str] = &[
+            "lorem",
+            "ipsum",
+            "dolor",
+            "sit",
+            "amet",
+            "consectetur",
+            "adipiscing",
+            "elit",
+            "sed",
+            "do",
+            "eiusmod",
+            "tempor",
+        ];
+
+        let len = self.rng.gen_range(min..=max.min(min + 24));
+        let mut s = String::with_capacity(len + 12);
+        while s.len() < len {
+            if !s.is_empty() {
+                s.push(' ');
+            }
+            s.push_str(WORDS.choose(&mut self.rng).unwrap());
+        }
+        s.truncate(len);
+        if s.ends_with(' ') {
+            s.pop();
+            s.push('a');
+        }
+        s
+    }
+
+    fn unusual_string(&mut self, min: usize, max: usize, ascii: bool) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', '9', '-', '_', '.', ' ', '/', '%', '&', '='];
+        const UNICODE: &[char] = &['é', 'ß', '日', '本', '😀'];
+
+        let len = self.rng.gen_range(min..=max);
+        (0..len)
+            .map(|_| match self.rng.gen_range(0..10) {
+                0 | 1 => *CHARS.choose(&mut self.rng).unwrap(),
+                2 if !ascii && !self.ascii => *UNICODE.choose(&mut self.rng).unwrap(),
+                _ => self.rng.gen_range(b'a'..=b'z') as char,
+            })
+            .collect()
+    }
+
+    /// Generates a string that matches the pattern and whose length in bytes
+    /// is between `min` and `max`, or returns `None` if it is not found.
+    fn pattern(&mut self, pattern: &str, min: usize, max: usize) -> Option<String> {
+        let hir = regex_syntax::Parser::new().parse(pattern).ok()?;
+        let regex = Regex::new(pattern).ok()?;
+        for _ in 0..MAX_ATTEMPTS {
+            let mut s = String::new();
+            self.hir(&hir, &mut s);
+            if (min..=max).contains(&s.len()) && regex.is_match(&s) {
+                return Some(s);
+            }
+        }
+        None
+    }
+
+    fn hir(&mut self, hir: &Hir, s: &mut String) {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {}
+            HirKind::Literal(literal) => s.push_str(&String::from_utf8_lossy(&literal.0)),
+            HirKind::Class(Class::Unicode(class)) => {
+                let mut ascii = class.clone();
+                ascii.intersect(&ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]));
+                // the realistic values prefer the ASCII characters
+                let class = if ascii.ranges().is_empty() {
+                    if self.ascii {
+                        return;
+                    }
+                    class
+                } else if self.unusual && !self.ascii {
+                    class
+                } else {
+                    &ascii
+                };
+                if let Some(range) = class.ranges().choose(&mut self.rng) {
+                    let c = self.rng.gen_range(range.start()..=range.end());
+                    s.push(c);
+                }
+            }
+            HirKind::Class(Class::Bytes(class)) => {
+                let mut ascii = class.clone();
+                ascii.intersect(&ClassBytes::new([ClassBytesRange::new(b' ', b'~')]));
+                if let Some(range) = ascii.ranges().choose(&mut self.rng) {
+                    s.push(self.rng.gen_range(range.start()..=range.end()) as char);
+                }
+            }
+            HirKind::Repetition(repetition) => {
+                let max = repetition.max.unwrap_or(repetition.min + 4);
+                for _ in 0..self.rng.gen_range(repetition.min..=max) {
+                    self.hir(&repetition.sub, s);
+                }
+            }
+            HirKind::Capture(capture) => self.hir(&capture.sub, s),
+            HirKind::Concat(hirs) => {
+                for hir in hirs {
+                    self.hir(hir, s);
+                }
+            }
+            HirKind::Alternation(hirs) => {
+                if let Some(hir) = hirs.choose(&mut self.rng) {
+                    self.hir(hir, s);
+                }
+            }
+        }
+    }
+}
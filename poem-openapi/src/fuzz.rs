@@ -0,0 +1,513 @@
+//! Spec-driven fuzz testing of the APIs.
+//!
+//! The [`Fuzzer`] generates requests for every operation from the
+//! specification, the valid requests and the requests with one invalid
+//! parameter or body, calls the service in-process, and reports the
+//! responses that are server errors, undocumented or unexpected.
+//!
+//! # Example
+//!
+//! ```
+//! use poem_openapi::{fuzz::Fuzzer, param::Query, payload::PlainText, OpenApi, OpenApiService};
+//!
+//! struct Api;
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/hello", method = "get")]
+//!     async fn hello(
+//!         &self,
+//!         #[oai(validator(max_length = 8))] name: Query<String>,
+//!     ) -> PlainText<String> {
+//!         PlainText(format!("hello, {}!", name.0))
+//!     }
+//! }
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let report = Fuzzer::new(OpenApiService::new(Api, "Demo", "1.0"))
+//!     .iterations(20)
+//!     .run()
+//!     .await;
+//! report.assert_ok();
+//! # });
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use poem::{
+    endpoint::BoxEndpoint,
+    http::{header, Method, StatusCode},
+    Endpoint, IntoEndpoint, Request, Response,
+};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde_json::Value;
+
+use crate::{
+    fake::ValueGenerator,
+    registry::{MetaApi, MetaOperation, MetaParamIn, MetaSchemaRef, Registry},
+    request_builder::{param_string, percent_encode},
+    OpenApi, OpenApiService, Webhook,
+};
+
+/// A fuzz tester that generates the requests from the specification.
+pub struct Fuzzer {
+    ep: BoxEndpoint<'static, Response>,
+    apis: Vec<MetaApi>,
+    registry: Registry,
+    iterations: usize,
+    seed: u64,
+}
+
+impl Fuzzer {
+    /// Create a fuzz tester for the service.
+    pub fn new<T: OpenApi + 'static, W: Webhook + 'static>(service: OpenApiService<T, W>) -> Self {
+        let (apis, registry) = service.document_meta();
+        Self {
+            ep: service.into_endpoint(),
+            apis,
+            registry,
+            iterations: 50,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of the valid and the invalid requests that are
+    /// generated for each operation, the default is `50`.
+    #[must_use]
+    pub fn iterations(self, iterations: usize) -> Self {
+        Self { iterations, ..self }
+    }
+
+    /// Sets the seed of the random generator, the default is `0`.
+    #[must_use]
+    pub fn seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    /// Calls the service with the generated requests.
+    pub async fn run(&self) -> FuzzReport {
+        let mut values = ValueGenerator::new(&self.registry, StdRng::seed_from_u64(self.seed));
+        values.unusual = true;
+        let mut gen = Generator { values };
+        let mut report = FuzzReport::default();
+
+        for path in self.apis.iter().flat_map(|api| &api.paths) {
+            for operation in &path.operations {
+                if let Some(reason) = unsupported(operation) {
+                    report
+                        .skipped
+                        .push(format!("{} {}: {}", operation.method, path.path, reason));
+                    continue;
+                }
+
+                for i in 0..self.iterations * 2 {
+                    let generated = match gen.request(path.path, operation, i % 2 == 1) {
+                        Some(generated) => generated,
+                        None => continue,
+                    };
+                    report.requests += 1;
+
+                    let resp = match self.ep.call(generated.request).await {
+                        Ok(resp) => resp,
+                        Err(err) => err.as_response(),
+                    };
+                    let status = resp.status();
+                    if let Some(reason) =
+                        check(operation, status, generated.invalid, generated.certain)
+                    {
+                        report.failures.push(FuzzFailure {
+                            method: operation.method.clone(),
+                            uri: generated.uri,
+                            body: generated.body,
+                            status,
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+
+        report
+    }
+}
+
+/// The result of the fuzz testing.
+#[derive(Debug, Default)]
+pub struct FuzzReport {
+    /// The number of the requests.
+    pub requests: usize,
+    /// The operations that are not tested, and the reasons.
+    pub skipped: Vec<String>,
+    /// The unexpected responses.
+    pub failures: Vec<FuzzFailure>,
+}
+
+impl FuzzReport {
+    /// Returns `true` if there are no unexpected responses.
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Panics if there are unexpected responses.
+    pub fn assert_ok(&self) {
+        if !self.is_ok() {
+            let failures = self
+                .failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            panic!("fuzz testing failed:\n{}", failures.join("\n"));
+        }
+    }
+}
+
+/// An unexpected response.
+#[derive(Debug)]
+pub struct FuzzFailure {
+    /// The method of the request.
+    pub method: Method,
+    /// The URI of the request.
+    pub uri: String,
+    /// The body of the request.
+    pub body: Option<String>,
+    /// The status of the response.
+    pub status: StatusCode,
+    /// Why the response is unexpected.
+    pub reason: &'static str,
+}
+
+impl Display for FuzzFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} => {}: {}",
+            self.method, self.uri, self.status, self.reason
+        )?;
+        if let Some(body) = &self.body {
+            write!(f, "\n  body: {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+fn unsupported(operation: &MetaOperation) -> Option<&'static str> {
+    if operation.params.iter().any(|param| {
+        matches!(
+            param.in_type,
+            MetaParamIn::CookiePrivate | MetaParamIn::CookieSigned
+        )
+    }) {
+        return Some("the private and signed cookies are not supported");
+    }
+    if let Some(request) = &operation.request {
+        if !request.content.is_empty()
+            && !request
+                .content
+                .iter()
+                .any(|media| body_kind(media.content_type).is_some())
+        {
+            return Some("the content types of the request body are not supported");
+        }
+    }
+    None
+}
+
+/// Returns the reason if the status of the response is unexpected.
+fn check(
+    operation: &MetaOperation,
+    status: StatusCode,
+    invalid: bool,
+    certain: bool,
+) -> Option<&'static str> {
+    if status == StatusCode::INTERNAL_SERVER_ERROR
+        || (status.is_server_error() && !is_documented(operation, status))
+    {
+        return Some("server error");
+    }
+    if invalid {
+        return (!status.is_client_error()).then(|| "the invalid request is not rejected");
+    }
+    if is_documented(operation, status)
+        || (status == StatusCode::BAD_REQUEST && !certain)
+        || (matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+            && !operation.security.is_empty())
+    {
+        return None;
+    }
+    Some("the status is not documented")
+}
+
+fn is_documented(operation: &MetaOperation, status: StatusCode) -> bool {
+    operation
+        .responses
+        .responses
+        .iter()
+        .any(|resp| resp.status.map(|s| s == status.as_u16()).unwrap_or(true))
+}
+
+#[derive(Copy, Clone)]
+enum BodyKind {
+    Json,
+    Text,
+    Binary,
+}
+
+fn body_kind(content_type: &str) -> Option<BodyKind> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    if essence == "application/json" || essence.ends_with("+json") {
+        Some(BodyKind::Json)
+    } else if essence == "text/plain" {
+        Some(BodyKind::Text)
+    } else if essence == "application/octet-stream" {
+        Some(BodyKind::Binary)
+    } else {
+        None
+    }
+}
+
+struct GeneratedRequest {
+    request: Request,
+    uri: String,
+    body: Option<String>,
+    /// `true` if a parameter or the body is invalid.
+    invalid: bool,
+    /// `false` if the generated values may be invalid, such as the strings of
+    /// the patterns.
+    certain: bool,
+}
+
+/// Which part of the request is invalid.
+#[derive(Copy, Clone)]
+enum Target {
+    Param(usize),
+    Body,
+}
+
+struct Generator<'a> {
+    values: ValueGenerator<'a>,
+}
+
+impl<'a> Generator<'a> {
+    fn request(
+        &mut self,
+        path: &str,
+        operation: &'a MetaOperation,
+        invalid: bool,
+    ) -> Option<GeneratedRequest> {
+        self.values.certain = true;
+
+        let json_media = operation.request.as_ref().and_then(|request| {
+            request
+                .content
+                .iter()
+                .find(|media| matches!(body_kind(media.content_type), Some(BodyKind::Json)))
+        });
+        let mut target = None;
+        if invalid {
+            let mut targets = (0..operation.params.len())
+                .map(Target::Param)
+                .collect::<Vec<_>>();
+            if json_media.is_some() {
+                targets.push(Target::Body);
+            }
+            target = Some(*targets.choose(&mut self.values.rng)?);
+        }
+        let mut invalid = false;
+
+        let mut uri = path.to_string();
+        let mut query = Vec::new();
+        let mut cookies = Vec::new();
+        let mut builder = Request::builder().method(operation.method.clone());
+
+        for (idx, param) in operation.params.iter().enumerate() {
+            // the header values must be visible ASCII characters
+            self.values.ascii = param.in_type == MetaParamIn::Header;
+            let is_path = param.in_type == MetaParamIn::Path;
+            let value = match target {
+                Some(Target::Param(i)) if i == idx => {
+                    // the path parameters cannot be missing
+                    match self.invalid_value(&param.schema, param.required && !is_path) {
+                        Some(value) => {
+                            invalid = true;
+                            value
+                        }
+                        None => Some(self.values.value(&param.schema, 0)),
+                    }
+                }
+                _ if param.required || self.values.rng.gen_bool(0.5) => {
+                    let value = self.values.value(&param.schema, 0);
+                    (!value.is_null()).then(|| value)
+                }
+                _ => None,
+            };
+            self.values.ascii = false;
+
+            match (param.in_type, value) {
+                (MetaParamIn::Path, Some(value)) => {
+                    let value = param_string(&value);
+                    if value.is_empty() {
+                        return None;
+                    }
+                    uri = uri.replace(&format!("{{{}}}", param.name), &percent_encode(&value));
+                }
+                (MetaParamIn::Path, None) => return None,
+                (MetaParamIn::Query, Some(Value::Array(values))) => {
+                    for value in values {
+                        query.push((param.name, param_string(&value)));
+                    }
+                }
+                (MetaParamIn::Query, Some(value)) => query.push((param.name, param_string(&value))),
+                (MetaParamIn::Header, Some(value)) => {
+                    builder = builder.header(param.name, param_string(&value));
+                }
+                (MetaParamIn::Cookie, Some(value)) => {
+                    cookies.push(format!(
+                        "{}={}",
+                        param.name,
+                        percent_encode(&param_string(&value))
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if !query.is_empty() {
+            let query = query
+                .iter()
+                .map(|(name, value)| format!("{}={}", percent_encode(name), percent_encode(value)))
+                .collect::<Vec<_>>();
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+        if !cookies.is_empty() {
+            builder = builder.header(header::COOKIE, cookies.join("; "));
+        }
+        builder = builder.uri_str(&uri);
+
+        let mut body = None;
+        if let Some(request) = &operation.request {
+            let value = match (target, json_media) {
+                (Some(Target::Body), Some(media)) => {
+                    match self.invalid_value(&media.schema, request.required) {
+                        Some(value) => {
+                            invalid = true;
+                            value.map(|value| (media, value.to_string()))
+                        }
+                        None => Some((media, self.values.value(&media.schema, 0).to_string())),
+                    }
+                }
+                _ => {
+                    let media = request
+                        .content
+                        .iter()
+                        .filter(|media| body_kind(media.content_type).is_some())
+                        .collect::<Vec<_>>();
+                    match media.choose(&mut self.values.rng) {
+                        Some(media) if request.required || self.values.rng.gen_bool(0.5) => {
+                            let value = self.values.value(&media.schema, 0);
+                            let data = match body_kind(media.content_type) {
+                                Some(BodyKind::Json) => value.to_string(),
+                                _ => param_string(&value),
+                            };
+                            Some((*media, data))
+                        }
+                        _ => None,
+                    }
+                }
+            };
+
+            if let Some((media, data)) = value {
+                builder = builder.content_type(media.content_type);
+                body = Some(data);
+            }
+        }
+
+        let request = match &body {
+            Some(body) => builder.body(body.clone()),
+            None => builder.finish(),
+        };
+        Some(GeneratedRequest {
+            request,
+            uri,
+            body,
+            invalid,
+            certain: self.values.certain,
+        })
+    }
+
+    /// Generates an invalid value of the schema, the inner `None` means that
+    /// the value is missing, or returns `None` if there is no invalid value.
+    fn invalid_value(
+        &mut self,
+        schema: &'a MetaSchemaRef,
+        required: bool,
+    ) -> Option<Option<Value>> {
+        let schema = self.values.resolve(schema)?;
+
+        let mut candidates = Vec::new();
+        if required {
+            candidates.push(None);
+        }
+        match schema.ty {
+            "integer" | "number" => {
+                candidates.push(Some(Value::String("not-a-number".to_string())));
+                if let Some(max) = schema.maximum {
+                    let value = if schema.exclusive_maximum == Some(true) {
+                        max
+                    } else {
+                        max + 1.0
+                    };
+                    candidates.push(Some(number(value, schema.ty)));
+                }
+                if let Some(min) = schema.minimum {
+                    let value = if schema.exclusive_minimum == Some(true) {
+                        min
+                    } else {
+                        min - 1.0
+                    };
+                    candidates.push(Some(number(value, schema.ty)));
+                }
+            }
+            "boolean" => candidates.push(Some(Value::String("not-a-boolean".to_string()))),
+            "string" => {
+                if let Some(max) = schema.max_length {
+                    candidates.push(Some(Value::String("x".repeat(max + 1))));
+                }
+                if let Some(min) = schema.min_length.filter(|min| *min > 0) {
+                    candidates.push(Some(Value::String("x".repeat(min - 1))));
+                }
+                if !schema.enum_items.is_empty() {
+                    candidates.push(Some(Value::String("__invalid__".to_string())));
+                }
+            }
+            "object" => {
+                candidates.push(Some(Value::Array(vec![])));
+                let value = self.values.schema_value(schema, 0);
+                if let (Value::Object(mut obj), Some(name)) =
+                    (value, schema.required.choose(&mut self.values.rng))
+                {
+                    obj.remove(*name);
+                    candidates.push(Some(Value::Object(obj)));
+                }
+                if schema.deny_additional_properties {
+                    if let Value::Object(mut obj) = self.values.schema_value(schema, 0) {
+                        obj.insert("__unknown__".to_string(), Value::Bool(true));
+                        candidates.push(Some(Value::Object(obj)));
+                    }
+                }
+            }
+            "array" => candidates.push(Some(Value::Bool(true))),
+            _ => {}
+        }
+
+        candidates.choose(&mut self.values.rng).cloned()
+    }
+}
+
+fn number(value: f64, ty: &str) -> Value {
+    if ty == "integer" {
+        Value::from(value as i64)
+    } else {
+        Value::from(value)
+    }
+}
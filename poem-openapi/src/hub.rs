@@ -0,0 +1,427 @@
+//! A broadcast hub for the server-sent events.
+//!
+//! A [`Hub`] fans out the published events to all the subscribers, every
+//! subscription is converted to an event stream payload. The recent events
+//! are kept in a history buffer, so a client that reconnects with the
+//! `Last-Event-ID` header receives the events it has missed, and a slow
+//! subscriber that falls behind the history is handled with its
+//! [`LagPolicy`].
+//!
+//! # Examples
+//!
+//! ```rust
+//! use poem::Request;
+//! use poem_openapi::{
+//!     hub::{Hub, HubEventStream},
+//!     param::Query,
+//!     Object, OpenApi,
+//! };
+//!
+//! #[derive(Object, Clone)]
+//! struct OrderEvent {
+//!     customer: String,
+//!     amount: i64,
+//! }
+//!
+//! struct Api {
+//!     orders: Hub<OrderEvent>,
+//! }
+//!
+//! #[OpenApi]
+//! impl Api {
+//!     #[oai(path = "/orders/events", method = "get")]
+//!     async fn events(
+//!         &self,
+//!         req: &Request,
+//!         customer: Query<String>,
+//!     ) -> HubEventStream<OrderEvent> {
+//!         self.orders
+//!             .resume(req)
+//!             .filter(move |event| event.customer == customer.0)
+//!             .into_event_stream()
+//!     }
+//! }
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+use futures_util::{stream::BoxStream, StreamExt};
+use poem::{web::sse::Event, Request};
+use serde_json::Value;
+use tokio::sync::watch;
+
+use crate::{
+    payload::{CustomEventStream, EventStream},
+    registry::{MetaSchemaRef, Registry},
+    types::{ToJSON, Type},
+};
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+type FilterFn<T> = Box<dyn Fn(&T) -> bool + Send + Sync + 'static>;
+
+/// The event stream payload of a [`Subscription`].
+pub type HubEventStream<T> =
+    CustomEventStream<BoxStream<'static, HubEvent<T>>, fn(HubEvent<T>) -> Event>;
+
+/// What a subscriber does when it falls behind the history of the hub, so
+/// some events have been dropped before it received them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LagPolicy {
+    /// Skip the dropped events and continue with the oldest event in the
+    /// history.
+    Skip,
+    /// Close the stream, the client can reconnect and reload its state.
+    Close,
+}
+
+impl Default for LagPolicy {
+    fn default() -> Self {
+        Self::Skip
+    }
+}
+
+/// An event published to a [`Hub`], with its id.
+///
+/// It has the same schema as the data, the id is sent as the id of the
+/// server-sent event.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HubEvent<T> {
+    /// The id of the event, the ids are increased from `1`.
+    pub id: u64,
+    /// The data of the event.
+    pub data: T,
+}
+
+impl<T> Deref for HubEvent<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<T: Type> Type for HubEvent<T> {
+    const IS_REQUIRED: bool = T::IS_REQUIRED;
+
+    type RawValueType = T::RawValueType;
+
+    type RawElementValueType = T::RawElementValueType;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        T::schema_ref()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        self.data.as_raw_value()
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        self.data.raw_element_iter()
+    }
+}
+
+impl<T: ToJSON> ToJSON for HubEvent<T> {
+    fn to_json(&self) -> Value {
+        self.data.to_json()
+    }
+}
+
+struct History<T> {
+    latest_id: u64,
+    events: VecDeque<HubEvent<T>>,
+}
+
+struct Inner<T> {
+    capacity: usize,
+    history: Mutex<History<T>>,
+    notify: watch::Sender<()>,
+}
+
+/// A broadcast hub for the events of type `T`.
+///
+/// The hub is cheap to clone, the clones publish to the same subscribers.
+pub struct Hub<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for Hub<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Hub<T> {
+    /// Create a hub that keeps the `capacity` most recent events for the
+    /// subscribers that are behind and for the clients that resume.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "the capacity of the hub must be greater than 0"
+        );
+        Self {
+            inner: Arc::new(Inner {
+                capacity,
+                history: Mutex::new(History {
+                    latest_id: 0,
+                    events: VecDeque::with_capacity(capacity),
+                }),
+                notify: watch::channel(()).0,
+            }),
+        }
+    }
+
+    /// Publish an event to all the subscribers, returns the id of the event.
+    pub fn publish(&self, data: T) -> u64 {
+        let mut history = self.inner.history.lock().unwrap();
+        history.latest_id += 1;
+        let id = history.latest_id;
+        if history.events.len() == self.inner.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(HubEvent { id, data });
+        // It fails if there are no subscribers, which is fine.
+        let _ = self.inner.notify.send(());
+        id
+    }
+
+    /// Returns the id of the latest event, or `0` if no event has been
+    /// published.
+    pub fn latest_id(&self) -> u64 {
+        self.inner.history.lock().unwrap().latest_id
+    }
+
+    /// Subscribe to the events that are published from now.
+    pub fn subscribe(&self) -> Subscription<T> {
+        self.subscribe_after(self.latest_id())
+    }
+
+    /// Subscribe to the events after the `Last-Event-ID` header of the
+    /// request, or to the events that are published from now if the header
+    /// is absent or invalid.
+    pub fn resume(&self, req: &Request) -> Subscription<T> {
+        let last_event_id = req
+            .headers()
+            .get(LAST_EVENT_ID)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        match last_event_id {
+            Some(id) => self.subscribe_after(id),
+            None => self.subscribe(),
+        }
+    }
+
+    /// Subscribe to the events after the event with the id.
+    ///
+    /// The ids that are greater than the latest id, for example the ids
+    /// received before the server is restarted, are treated as the latest id.
+    /// The ids of the events that have been dropped from the history are
+    /// treated as the id before the oldest event in the history, so the
+    /// subscription starts with the oldest event instead of lagging, even
+    /// with [`LagPolicy::Close`].
+    pub fn subscribe_after(&self, id: u64) -> Subscription<T> {
+        let history = self.inner.history.lock().unwrap();
+        let dropped_id = history.latest_id - history.events.len() as u64;
+        Subscription {
+            inner: self.inner.clone(),
+            last_id: id.clamp(dropped_id, history.latest_id),
+            filter: None,
+            lag: LagPolicy::default(),
+        }
+    }
+}
+
+/// A subscription to a [`Hub`].
+pub struct Subscription<T> {
+    inner: Arc<Inner<T>>,
+    last_id: u64,
+    filter: Option<FilterFn<T>>,
+    lag: LagPolicy,
+}
+
+impl<T: Clone + Send + Sync + 'static> Subscription<T> {
+    /// Only receive the events that match the predicate.
+    #[must_use]
+    pub fn filter(self, f: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            filter: Some(Box::new(f)),
+            ..self
+        }
+    }
+
+    /// Specify what the subscriber does when it falls behind the history, the
+    /// default is [`LagPolicy::Skip`].
+    #[must_use]
+    pub fn on_lag(self, lag: LagPolicy) -> Self {
+        Self { lag, ..self }
+    }
+
+    /// Convert the subscription to a stream of the events.
+    pub fn into_stream(self) -> BoxStream<'static, HubEvent<T>> {
+        let Subscription {
+            inner,
+            last_id,
+            filter,
+            lag,
+        } = self;
+        let receiver = inner.notify.subscribe();
+        let state = (inner, receiver, last_id, filter, VecDeque::new());
+
+        futures_util::stream::unfold(
+            state,
+            move |(inner, mut receiver, mut last_id, filter, mut pending)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((event, (inner, receiver, last_id, filter, pending)));
+                    }
+
+                    // Mark the notification as seen before reading the history,
+                    // so the events published after that wake up the subscriber.
+                    receiver.borrow_and_update();
+                    let events = {
+                        let history = inner.history.lock().unwrap();
+                        // The ids of the events in the history are consecutive.
+                        let dropped_id = history.latest_id - history.events.len() as u64;
+                        if dropped_id > last_id && lag == LagPolicy::Close {
+                            return None;
+                        }
+                        let start = last_id.saturating_sub(dropped_id) as usize;
+                        history.events.range(start..).cloned().collect::<Vec<_>>()
+                    };
+                    if let Some(event) = events.last() {
+                        last_id = event.id;
+                    }
+                    pending.extend(
+                        events.into_iter().filter(|event| {
+                            filter.as_ref().map(|f| f(&event.data)).unwrap_or(true)
+                        }),
+                    );
+
+                    if pending.is_empty() && receiver.changed().await.is_err() {
+                        return None;
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+}
+
+impl<T: Type + ToJSON + Clone + 'static> Subscription<T> {
+    /// Convert the subscription to an event stream payload, the ids of the
+    /// events are sent so that the clients can resume with the
+    /// `Last-Event-ID` header.
+    pub fn into_event_stream(self) -> HubEventStream<T> {
+        EventStream::new(self.into_stream()).to_event(|event| {
+            Event::message(serde_json::to_string(&event.data.to_json()).unwrap_or_default())
+                .id(event.id.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use poem::IntoResponse;
+
+    use super::*;
+
+    async fn next<T>(stream: &mut BoxStream<'static, HubEvent<T>>) -> Option<(u64, T)> {
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .ok()
+            .flatten()
+            .map(|event| (event.id, event.data))
+    }
+
+    #[tokio::test]
+    async fn publish() {
+        let hub = Hub::new(8);
+        hub.publish(1);
+        let mut a = hub.subscribe().into_stream();
+        let mut b = hub.subscribe().filter(|n| n % 2 == 0).into_stream();
+
+        hub.publish(2);
+        hub.publish(3);
+        hub.publish(4);
+        assert_eq!(next(&mut a).await, Some((2, 2)));
+        assert_eq!(next(&mut a).await, Some((3, 3)));
+        assert_eq!(next(&mut a).await, Some((4, 4)));
+        assert_eq!(next(&mut a).await, None);
+        assert_eq!(next(&mut b).await, Some((2, 2)));
+        assert_eq!(next(&mut b).await, Some((4, 4)));
+        assert_eq!(next(&mut b).await, None);
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let hub = Hub::new(8);
+        for n in 1..=3 {
+            hub.publish(n);
+        }
+
+        let req = Request::builder().header("Last-Event-ID", "1").finish();
+        let mut stream = hub.resume(&req).into_stream();
+        assert_eq!(next(&mut stream).await, Some((2, 2)));
+        assert_eq!(next(&mut stream).await, Some((3, 3)));
+
+        let req = Request::builder().header("Last-Event-ID", "100").finish();
+        let mut stream = hub.resume(&req).into_stream();
+        hub.publish(4);
+        assert_eq!(next(&mut stream).await, Some((4, 4)));
+        assert_eq!(next(&mut stream).await, None);
+    }
+
+    #[tokio::test]
+    async fn lag() {
+        let hub = Hub::new(2);
+        let mut skip = hub.subscribe().into_stream();
+        let mut close = hub.subscribe().on_lag(LagPolicy::Close).into_stream();
+        for n in 1..=4 {
+            hub.publish(n);
+        }
+
+        assert_eq!(next(&mut skip).await, Some((3, 3)));
+        assert_eq!(next(&mut skip).await, Some((4, 4)));
+        assert_eq!(close.next().await, None);
+
+        // Resuming from a dropped event starts with the oldest event.
+        let req = Request::builder().header("Last-Event-ID", "1").finish();
+        let mut close = hub.resume(&req).on_lag(LagPolicy::Close).into_stream();
+        assert_eq!(next(&mut close).await, Some((3, 3)));
+        assert_eq!(next(&mut close).await, Some((4, 4)));
+    }
+
+    #[tokio::test]
+    async fn event_stream() {
+        let hub = Hub::new(8);
+        let stream = hub.subscribe_after(0).into_event_stream();
+        hub.publish("a".to_string());
+
+        let mut body = stream.into_response().into_body().into_bytes_stream();
+        let data = body.next().await.unwrap().unwrap();
+        assert_eq!(&data[..], b"id: 1\ndata: \"a\"\n\n");
+    }
+}
@@ -38,34 +38,7 @@
 //! tokio = { version = "1", features = ["macros", "rt-multi-thread"] }
 //! ```
 //!
-//! main.rs
-//!
-//! ```no_run
-//! use poem::{listener::TcpListener, Route, Server};
-//! use poem_openapi::{payload::PlainText, OpenApi, OpenApiService};
-//!
-//! struct Api;
-//!
-//! #[OpenApi]
-//! impl Api {
-//!     /// Hello world
-//!     #[oai(path = "/", method = "get")]
-//!     async fn index(&self) -> PlainText<&'static str> {
-//!         PlainText("Hello World")
-//!     }
-//! }
-//!
-//! let api_service =
-//!     OpenApiService::new(Api, "Hello World", "1.0").server("http://localhost:3000");
-//! let docs = api_service.swagger_ui();
-//! let app = Route::new().nest("/", api_service).nest("/docs", docs);
-//!
-//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
-//! Server::new(TcpListener::bind("127.0.0.1:3000"))
-//!     .run(app)
-//!     .await;
-//! # });
-//! ```
+#![cfg_attr(feature = "swagger-ui", doc = include_str!("docs/quickstart.md"))]
 //!
 //! ## Check it
 //!
@@ -89,17 +62,47 @@
 //! ## Crate features
 //!
 //! To avoid compiling unused dependencies, Poem gates certain features, some of
-//! which are disabled by default:
+//! which are disabled by default. Disable the default features for a minimal
+//! JSON API, which does not compile the dependencies of the multipart and the
+//! server-sent events payloads, the base64 and the regex types:
 //!
 //! | Feature    | Description |
 //! |------------|-----------------------------------------------------------------------|
+//! | multipart  | Add the multipart payloads and the [`Multipart`](derive@Multipart) macro, it is enabled by default |
+//! | sse        | Add the [`EventStream`](payload::EventStream) payload and the [`hub`] module, it is enabled by default |
+//! | base64     | Add the [`Base64`](types::Base64) type and the [`Basic`](auth::Basic) authorization of the `basic` security scheme, it is enabled by default |
+//! | regex      | Add the `pattern` validator, the [`LanguageTag`](types::LanguageTag) type and the `Regex` type of the [`regex` crate](https://crates.io/crates/regex), it is enabled by default |
+//! | websocket  | Add the [`TypedWebSocket`](payload::TypedWebSocket) response for the typed WebSocket protocols |
 //! | chrono     | Integrate with the [`chrono` crate](https://crates.io/crates/chrono). |
 //! | swagger-ui | Add swagger UI support |
 //! | rapidoc    | Add RapiDoc UI support |
 //! | redoc      | Add Redoc UI support |
 //! | email      | Support for email address string |
 //! | hostname   | Support for hostname string |
+//! | phone-number | Support for E.164 phone number string |
+//! | country-code | Support for ISO 3166-1 alpha-2 country code string |
+//! | geojson    | Add the [GeoJSON](https://datatracker.ietf.org/doc/html/rfc7946) types |
+//! | encrypted  | Add the [`Encrypted`](types::Encrypted) type that encrypts the values with AES-256-GCM |
+//! | hmac       | Add the `hmac` security scheme and the [`webhook`] signing with HMAC-SHA256 |
+//! | key-store  | Add the [`KeyStore`](auth::key_store::KeyStore) for the `api_key` security scheme |
 //! | uuid       | Integrate with the [`uuid` crate](https://crates.io/crates/uuid)|
+//! | semver     | Integrate with the [`semver` crate](https://crates.io/crates/semver)|
+//! | indexmap   | Integrate with the [`indexmap` crate](https://crates.io/crates/indexmap), enable the `preserve_order` feature of `serde_json` to keep the insertion order of the maps in JSON |
+//! | smallvec   | Integrate with the [`smallvec` crate](https://crates.io/crates/smallvec)|
+//! | arrow      | Add the [`ArrowStream`](payload::ArrowStream) payload that streams the Arrow record batches in the IPC format |
+//! | parquet    | Add the [`Parquet`](payload::Parquet) payload that streams the Arrow record batches in the Parquet format |
+//! | archive    | Add the [`Archive`](payload::Archive) response that streams the zip or tar.gz archives |
+//! | image      | Add the [`Image`](payload::Image) response that resizes and encodes the images with the [`image` crate](https://crates.io/crates/image), the format is negotiated with the `Accept` header |
+//! | image-avif | Support the AVIF format of the [`Image`](payload::Image) response |
+//! | askama     | Render the [`askama` crate](https://crates.io/crates/askama) templates with the [`Html`](payload::Html) payload |
+//! | tera       | Add the [`TeraTemplate`](payload::TeraTemplate) that renders the [`tera` crate](https://crates.io/crates/tera) templates with the [`Html`](payload::Html) payload |
+//! | prost      | Add the [`Protobuf`](payload::Protobuf) payload that is encoded with the [`prost` crate](https://crates.io/crates/prost) |
+//! | i18n       | Add the `Locale` extractor that negotiates `Accept-Language` |
+//! | fake       | Add the [`Faker`](fake::Faker) that generates the fake values of the types from the schemas |
+//! | fuzz       | Add the [`Fuzzer`](fuzz::Fuzzer) that tests the APIs with the requests generated from the specification |
+//! | sqlx-mysql-rustls | Add the MySQL [`KeyStore`](auth::key_store::KeyStore) implementation |
+//! | sqlx-postgres-rustls | Add the Postgres [`KeyStore`](auth::key_store::KeyStore) implementation |
+//! | sqlx-sqlite-rustls | Add the Sqlite [`KeyStore`](auth::key_store::KeyStore) implementation |
 
 #![doc(html_favicon_url = "https://raw.githubusercontent.com/poem-web/poem/master/favicon.ico")]
 #![doc(html_logo_url = "https://raw.githubusercontent.com/poem-web/poem/master/logo.png")]
@@ -111,18 +114,44 @@
 #[macro_use]
 mod macros;
 
+pub mod audit;
 pub mod auth;
+pub mod batch;
+pub mod crud;
 pub mod error;
+#[cfg(feature = "fake")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fake")))]
+pub mod fake;
+#[cfg(feature = "fuzz")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fuzz")))]
+pub mod fuzz;
+#[cfg(feature = "sse")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+pub mod hub;
 pub mod param;
 pub mod payload;
 #[doc(hidden)]
 pub mod registry;
+pub mod transform;
 pub mod types;
 #[doc(hidden)]
 pub mod validation;
+#[cfg(feature = "hmac")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hmac")))]
+pub mod webhook;
 
+mod accept;
+mod asyncapi;
 mod base;
+mod content_type;
+mod deserializer;
+mod lint;
+mod maintenance;
 mod openapi;
+mod operation_info;
+mod request_builder;
+mod scheduler;
+mod scope;
 #[cfg(any(feature = "swagger-ui", feature = "rapidoc", feature = "redoc"))]
 mod ui;
 
@@ -130,13 +159,17 @@ pub use base::{
     ApiExtractor, ApiExtractorType, ApiResponse, ExtractParamOptions, OAuthScopes, OpenApi,
     ResponseContent, Tags, Webhook,
 };
+pub use lint::LintWarning;
 pub use openapi::{ExternalDocumentObject, LicenseObject, OpenApiService, ServerObject};
+pub use operation_info::OperationInfo;
 #[doc = include_str!("docs/request.md")]
 pub use poem_openapi_derive::ApiRequest;
 #[doc = include_str!("docs/response.md")]
 pub use poem_openapi_derive::ApiResponse;
 #[doc = include_str!("docs/enum.md")]
 pub use poem_openapi_derive::Enum;
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 #[doc = include_str!("docs/multipart.md")]
 pub use poem_openapi_derive::Multipart;
 pub use poem_openapi_derive::NewType;
@@ -155,7 +188,10 @@ pub use poem_openapi_derive::SecurityScheme;
 #[doc = include_str!("docs/tags.md")]
 pub use poem_openapi_derive::Tags;
 #[doc = include_str!("docs/webhook.md")]
+#[cfg_attr(feature = "hmac", doc = include_str!("docs/webhook_signature.md"))]
 pub use poem_openapi_derive::Webhook;
+pub use registry::OpenApiDocument;
+pub use scope::MiddlewareScope;
 pub use validation::Validator;
 
 #[doc(hidden)]
@@ -166,5 +202,20 @@ pub mod __private {
     pub use serde;
     pub use serde_json;
 
-    pub use crate::{base::UrlQuery, payload::ContentTypeTable};
+    pub use crate::{
+        audit::record_principal,
+        auth::{
+            check_permissions, AssertSecurityScheme, CheckerOutput, PermissionsProbe,
+            PrincipalProbe, ViaNoPermissions, ViaNoPrincipal, ViaPermissions, ViaPrincipal,
+        },
+        base::UrlQuery,
+        error::rewrite_error_message,
+        operation_info::{operation_endpoint, OperationWrapper},
+        payload::ContentTypeTable,
+        request_builder::{RequestBody, RequestBuilder, RequestParam},
+        types::{
+            computed_is_required, computed_schema_ref, flatten_schema, parse_without_validation,
+            register_computed, remove_aliased_field, validation_skipped, DurationSeconds,
+        },
+    };
 }
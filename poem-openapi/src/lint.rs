@@ -0,0 +1,223 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{self, Display, Formatter},
+};
+
+use poem::http::Method;
+
+use crate::registry::{
+    MetaApi, MetaOperation, MetaParamIn, MetaSchema, MetaSchemaRef, MetaWebhook, Registry,
+};
+
+/// The header parameters that are ignored by the OpenAPI specification, they
+/// are described by the request body and the security schemes.
+const SHADOWED_HEADERS: &[&str] = &["accept", "content-type", "authorization"];
+
+/// A warning about the quality of the specification, returned by
+/// [`OpenApiService::lint`](crate::OpenApiService::lint).
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LintWarning {
+    /// The operation has neither a summary nor a description.
+    MissingDescription {
+        /// The method of the operation.
+        method: Method,
+        /// The path of the operation.
+        path: &'static str,
+    },
+    /// The operation accepts the parameters, the request body or the
+    /// credentials, but does not document a `4xx` or default response.
+    MissingClientErrorResponse {
+        /// The method of the operation.
+        method: Method,
+        /// The path of the operation.
+        path: &'static str,
+    },
+    /// The schema is not referenced by the operations or the webhooks.
+    UnusedSchema {
+        /// The name of the schema.
+        name: &'static str,
+    },
+    /// The operation id is used by more than one operation.
+    DuplicateOperationId {
+        /// The operation id.
+        operation_id: &'static str,
+    },
+    /// The header parameter is one of `Accept`, `Content-Type` and
+    /// `Authorization`, that are ignored by the OpenAPI specification.
+    ShadowedHeader {
+        /// The method of the operation.
+        method: Method,
+        /// The path of the operation.
+        path: &'static str,
+        /// The name of the parameter.
+        name: &'static str,
+    },
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LintWarning::MissingDescription { method, path } => {
+                write!(f, "{} {}: the operation has no description", method, path)
+            }
+            LintWarning::MissingClientErrorResponse { method, path } => write!(
+                f,
+                "{} {}: the operation does not document a 4xx response",
+                method, path
+            ),
+            LintWarning::UnusedSchema { name } => {
+                write!(f, "the schema `{}` is not used", name)
+            }
+            LintWarning::DuplicateOperationId { operation_id } => {
+                write!(f, "duplicate operation id: {}", operation_id)
+            }
+            LintWarning::ShadowedHeader { method, path, name } => write!(
+                f,
+                "{} {}: the header parameter `{}` is ignored by the specification",
+                method, path, name
+            ),
+        }
+    }
+}
+
+pub(crate) fn lint(
+    apis: &[MetaApi],
+    webhooks: &[MetaWebhook],
+    registry: &Registry,
+) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut operation_ids = HashMap::new();
+    let mut used = BTreeSet::new();
+
+    for path in apis.iter().flat_map(|api| &api.paths) {
+        for operation in &path.operations {
+            let method = operation.method.clone();
+            if operation.summary.is_none() && operation.description.is_none() {
+                warnings.push(LintWarning::MissingDescription {
+                    method: method.clone(),
+                    path: path.path,
+                });
+            }
+
+            let accepts_input = !operation.params.is_empty()
+                || operation.request.is_some()
+                || !operation.security.is_empty();
+            let client_error = operation
+                .responses
+                .responses
+                .iter()
+                .any(|resp| match resp.status {
+                    Some(status) => (400..500).contains(&status),
+                    None => true,
+                });
+            if accepts_input && !client_error {
+                warnings.push(LintWarning::MissingClientErrorResponse {
+                    method: method.clone(),
+                    path: path.path,
+                });
+            }
+
+            for param in &operation.params {
+                if param.in_type == MetaParamIn::Header
+                    && SHADOWED_HEADERS.contains(&param.name.to_ascii_lowercase().as_str())
+                {
+                    warnings.push(LintWarning::ShadowedHeader {
+                        method: method.clone(),
+                        path: path.path,
+                        name: param.name,
+                    });
+                }
+            }
+
+            if let Some(operation_id) = operation.operation_id {
+                *operation_ids.entry(operation_id).or_insert(0) += 1;
+            }
+            use_operation(registry, operation, &mut used);
+        }
+    }
+
+    let mut duplicates = operation_ids
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(operation_id, _)| operation_id)
+        .collect::<Vec<_>>();
+    duplicates.sort_unstable();
+    warnings.extend(
+        duplicates
+            .into_iter()
+            .map(|operation_id| LintWarning::DuplicateOperationId { operation_id }),
+    );
+
+    for webhook in webhooks {
+        use_operation(registry, &webhook.operation, &mut used);
+    }
+    // the schemas are used by their writable variants
+    for (name, writable_name) in registry.writable_variants() {
+        if used.contains(writable_name) {
+            used.insert(name);
+        }
+    }
+    warnings.extend(
+        registry
+            .schemas
+            .keys()
+            .filter(|name| !used.contains(*name))
+            .map(|name| LintWarning::UnusedSchema { name }),
+    );
+
+    warnings
+}
+
+fn use_operation(
+    registry: &Registry,
+    operation: &MetaOperation,
+    used: &mut BTreeSet<&'static str>,
+) {
+    for param in &operation.params {
+        use_schema_ref(registry, &param.schema, used);
+    }
+    for media in operation
+        .request
+        .iter()
+        .flat_map(|request| &request.content)
+    {
+        use_schema_ref(registry, &media.schema, used);
+    }
+    for resp in &operation.responses.responses {
+        for media in &resp.content {
+            use_schema_ref(registry, &media.schema, used);
+        }
+        for header in &resp.headers {
+            use_schema_ref(registry, &header.schema, used);
+        }
+    }
+}
+
+fn use_schema_ref(registry: &Registry, schema: &MetaSchemaRef, used: &mut BTreeSet<&'static str>) {
+    match schema {
+        MetaSchemaRef::Inline(schema) => use_schema(registry, schema, used),
+        MetaSchemaRef::Reference(name) => {
+            if used.insert(name) {
+                if let Some(schema) = registry.schemas.get(name) {
+                    use_schema(registry, schema, used);
+                }
+            }
+        }
+    }
+}
+
+fn use_schema(registry: &Registry, schema: &MetaSchema, used: &mut BTreeSet<&'static str>) {
+    let schemas = schema
+        .properties
+        .iter()
+        .map(|(_, schema)| schema)
+        .chain(schema.items.as_deref())
+        .chain(&schema.prefix_items)
+        .chain(schema.additional_properties.as_deref())
+        .chain(&schema.one_of)
+        .chain(&schema.all_of);
+    for schema in schemas {
+        use_schema_ref(registry, schema, used);
+    }
+}
@@ -26,6 +26,14 @@ macro_rules! impl_apirequest_for_payload {
                 })
             }
 
+            fn request_params() -> Vec<$crate::registry::MetaOperationParam> {
+                <Self as $crate::payload::ParsePayload>::params()
+            }
+
+            fn request_responses() -> Vec<$crate::registry::MetaResponse> {
+                <Self as $crate::payload::ParsePayload>::responses()
+            }
+
             async fn from_request(
                 request: &'a poem::Request,
                 body: &mut poem::RequestBody,
@@ -33,16 +41,8 @@ macro_rules! impl_apirequest_for_payload {
             ) -> poem::Result<Self> {
                 match request.content_type() {
                     Some(content_type) => {
-                        let mime: mime::Mime = match content_type.parse() {
-                            Ok(mime) => mime,
-                            Err(_) => {
-                                return Err($crate::error::ContentTypeError::NotSupported {
-                                    content_type: content_type.to_string(),
-                                }.into());
-                            }
-                        };
-
-                        if mime.essence_str() != <Self as $crate::payload::Payload>::CONTENT_TYPE {
+                        let table = $crate::payload::ContentTypeTable::new(&[<Self as $crate::payload::Payload>::CONTENT_TYPE]);
+                        if table.matches(content_type).is_none() {
                             return Err($crate::error::ContentTypeError::NotSupported {
                                 content_type: content_type.to_string(),
                             }.into());
@@ -56,3 +56,21 @@ macro_rules! impl_apirequest_for_payload {
         }
     };
 }
+
+/// Expands the items if the `multipart` feature is enabled, it is used by the
+/// derive macros to implement `ParseFromMultipartField`.
+#[cfg(feature = "multipart")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multipart_items {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "multipart"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __multipart_items {
+    ($($item:item)*) => {};
+}
@@ -0,0 +1,58 @@
+use poem::middleware::Maintenance;
+
+use crate::{
+    registry::{
+        MetaApi, MetaHeader, MetaMediaType, MetaResponse, MetaSchema, MetaSchemaRef, Registry,
+    },
+    types::Type,
+    Object,
+};
+
+/// The body of the `503 Service Unavailable` response during the
+/// maintenance.
+#[derive(Object)]
+#[oai(internal, rename = "MaintenanceError")]
+struct MaintenanceBody {
+    /// Always `maintenance`.
+    code: String,
+    /// The message of the response.
+    message: String,
+    /// The number of seconds after which the client can retry.
+    retry_after: Option<u64>,
+}
+
+/// Adds the `503` response to the operations that are not allowed during the
+/// maintenance.
+pub(crate) fn document(maintenance: &Maintenance, apis: &mut [MetaApi], registry: &mut Registry) {
+    MaintenanceBody::register(registry);
+
+    for path in apis.iter_mut().flat_map(|api| &mut api.paths) {
+        if maintenance.is_allowed(path.path) {
+            continue;
+        }
+
+        for operation in &mut path.operations {
+            let responses = &mut operation.responses.responses;
+            if responses.iter().any(|resp| resp.status == Some(503)) {
+                continue;
+            }
+            responses.push(MetaResponse {
+                description: "The service is under maintenance.",
+                status: Some(503),
+                content: vec![MetaMediaType {
+                    content_type: "application/json",
+                    schema: MaintenanceBody::schema_ref(),
+                }],
+                headers: vec![MetaHeader {
+                    name: "Retry-After",
+                    description: Some("The number of seconds after which the client can retry."),
+                    required: false,
+                    deprecated: false,
+                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
+                        "integer", "uint64",
+                    ))),
+                }],
+            });
+        }
+    }
+}
@@ -1,18 +1,41 @@
-use std::{collections::HashSet, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    marker::PhantomData,
+    sync::Arc,
+};
 
 use poem::{
     endpoint::{make_sync, BoxEndpoint},
-    middleware::CookieJarManager,
+    middleware::{CookieJarManager, Maintenance, Middleware, Scheduler},
     web::cookie::CookieKey,
     Endpoint, EndpointExt, IntoEndpoint, Request, Response, Result, Route,
 };
+use serde_json::Value;
 
+#[cfg(feature = "encrypted")]
+use crate::types::KeyProvider;
 use crate::{
+    accept, asyncapi,
+    audit::{self, Audit},
     base::UrlQuery,
-    registry::{Document, MetaExternalDocument, MetaInfo, MetaLicense, MetaServer, Registry},
-    OpenApi, Webhook,
+    batch::{self, BatchEndpoint},
+    content_type,
+    deserializer::{self, Deserializer, Deserializers},
+    error::{ErrorMessageProvider, ErrorMessageProviderData},
+    lint::{self, LintWarning},
+    maintenance,
+    registry::{
+        MetaApi, MetaExternalDocument, MetaInfo, MetaLicense, MetaServer, OpenApiDocument, Registry,
+    },
+    scheduler,
+    scope::{MiddlewareScope, ScopedMiddleware},
+    types::Type,
+    OpenApi, OperationInfo, Webhook,
 };
 
+type SpecHook = Arc<dyn Fn(&mut OpenApiDocument) + Send + Sync>;
+
 /// An object representing a Server.
 #[derive(Debug, Clone)]
 pub struct ServerObject {
@@ -126,7 +149,21 @@ pub struct OpenApiService<T, W: ?Sized> {
     info: MetaInfo,
     external_document: Option<MetaExternalDocument>,
     servers: Vec<MetaServer>,
+    extensions: BTreeMap<String, Value>,
+    spec_hooks: Vec<SpecHook>,
     cookie_key: Option<CookieKey>,
+    error_message_provider: Option<Arc<dyn ErrorMessageProvider>>,
+    batch_path: Option<&'static str>,
+    batch_max_requests: usize,
+    scoped_middlewares: Vec<(MiddlewareScope, ScopedMiddleware)>,
+    audit: Option<Audit>,
+    maintenance: Option<Maintenance>,
+    scheduler: Option<Scheduler>,
+    strict_accept: bool,
+    strict_content_type: bool,
+    deserializers: Vec<Deserializer>,
+    #[cfg(feature = "encrypted")]
+    key_provider: Option<Arc<dyn KeyProvider>>,
 }
 
 impl<T> OpenApiService<T, ()> {
@@ -143,10 +180,25 @@ impl<T> OpenApiService<T, ()> {
                 version: version.into(),
                 terms_of_service: None,
                 license: None,
+                extensions: BTreeMap::new(),
             },
             external_document: None,
             servers: Vec::new(),
+            extensions: BTreeMap::new(),
+            spec_hooks: Vec::new(),
             cookie_key: None,
+            error_message_provider: None,
+            batch_path: None,
+            batch_max_requests: batch::DEFAULT_MAX_REQUESTS,
+            scoped_middlewares: Vec::new(),
+            audit: None,
+            maintenance: None,
+            scheduler: None,
+            strict_accept: false,
+            strict_content_type: false,
+            deserializers: Vec::new(),
+            #[cfg(feature = "encrypted")]
+            key_provider: None,
         }
     }
 }
@@ -160,7 +212,21 @@ impl<T, W: ?Sized> OpenApiService<T, W> {
             info: self.info,
             external_document: self.external_document,
             servers: self.servers,
+            extensions: self.extensions,
+            spec_hooks: self.spec_hooks,
             cookie_key: self.cookie_key,
+            error_message_provider: self.error_message_provider,
+            batch_path: self.batch_path,
+            batch_max_requests: self.batch_max_requests,
+            scoped_middlewares: self.scoped_middlewares,
+            audit: self.audit,
+            maintenance: self.maintenance,
+            scheduler: self.scheduler,
+            strict_accept: self.strict_accept,
+            strict_content_type: self.strict_content_type,
+            deserializers: self.deserializers,
+            #[cfg(feature = "encrypted")]
+            key_provider: self.key_provider,
         }
     }
 
@@ -178,6 +244,14 @@ impl<T, W: ?Sized> OpenApiService<T, W> {
         self
     }
 
+    /// Sets the version of the API container, it replaces the version passed
+    /// to [`OpenApiService::new`], such as with the version of the deployment.
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.info.version = version.into();
+        self
+    }
+
     /// Sets a URL to the Terms of Service for the API.
     #[must_use]
     pub fn terms_of_service(mut self, url: impl Into<String>) -> Self {
@@ -198,6 +272,107 @@ impl<T, W: ?Sized> OpenApiService<T, W> {
         self
     }
 
+    /// Replaces the servers of the API container, such as with the servers
+    /// read from the configuration of the environment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{OpenApiService, ServerObject};
+    /// # struct Api;
+    ///
+    /// let servers = "https://api.example.com,https://backup.example.com";
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0")
+    ///     .server("http://localhost:3000")
+    ///     .servers(servers.split(',').map(ServerObject::new))
+    ///     .version("1.0.3")
+    ///     .extension("x-environment", "production");
+    /// ```
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#serverObject>
+    #[must_use]
+    pub fn servers(mut self, servers: impl IntoIterator<Item = impl Into<ServerObject>>) -> Self {
+        self.servers.clear();
+        servers
+            .into_iter()
+            .fold(self, |service, server| service.server(server))
+    }
+
+    /// Sets an extension field of the specification.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name does not start with `x-`.
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#specification-extensions>
+    #[must_use]
+    pub fn extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        assert!(
+            name.starts_with("x-"),
+            "the name of the extension `{}` must start with `x-`",
+            name
+        );
+        self.extensions.insert(name, value.into());
+        self
+    }
+
+    /// Sets an extension field of the info object.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name does not start with `x-`.
+    ///
+    /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#specification-extensions>
+    #[must_use]
+    pub fn info_extension(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        let name = name.into();
+        assert!(
+            name.starts_with("x-"),
+            "the name of the extension `{}` must start with `x-`",
+            name
+        );
+        self.info.extensions.insert(name, value.into());
+        self
+    }
+
+    /// Adds a hook that changes the document before it is serialized, for the
+    /// adjustments that the macros cannot express.
+    ///
+    /// The hooks are called in the order they are added, and they also apply
+    /// to the document checked by [`OpenApiService::lint`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     #[oai(path = "/internal/health", method = "get")]
+    ///     async fn health(&self) {}
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(Api, "Demo", "1.0").map_spec(|doc| {
+    ///     // hide the internal operations from the public specification
+    ///     for api in &mut doc.apis {
+    ///         api.paths.retain(|path| !path.path.starts_with("/internal/"));
+    ///     }
+    /// });
+    /// let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    /// assert_eq!(spec["paths"], serde_json::json!({}));
+    /// ```
+    #[must_use]
+    pub fn map_spec<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut OpenApiDocument) + Send + Sync + 'static,
+    {
+        self.spec_hooks.push(Arc::new(f));
+        self
+    }
+
     /// Sets the license information for the exposed API.
     ///
     /// Reference: <https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md#license-object>
@@ -237,7 +412,349 @@ impl<T, W: ?Sized> OpenApiService<T, W> {
         }
     }
 
+    /// Sets the provider of the keys used by the
+    /// [`Encrypted`](crate::types::Encrypted) values of the requests and
+    /// responses.
+    #[cfg(feature = "encrypted")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "encrypted")))]
+    #[must_use]
+    pub fn key_provider(self, provider: impl KeyProvider) -> Self {
+        Self {
+            key_provider: Some(Arc::new(provider)),
+            ..self
+        }
+    }
+
+    /// Sets the provider that translates or rewrites the messages of the
+    /// errors that occur while parsing the request.
+    ///
+    /// See also: [`ErrorMessageProvider`]
+    #[must_use]
+    pub fn error_message_provider(self, provider: impl ErrorMessageProvider) -> Self {
+        Self {
+            error_message_provider: Some(Arc::new(provider)),
+            ..self
+        }
+    }
+
+    /// Adds a deserializer of the request bodies with the content type, the
+    /// body is deserialized to a JSON value that is parsed by the
+    /// [`Json`](crate::payload::Json) payloads.
+    ///
+    /// The content type is added to the documents of the request bodies that
+    /// accept `application/json`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::Json, OpenApi, OpenApiService};
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/echo", method = "post")]
+    ///     async fn echo(&self, text: Json<String>) -> Json<String> {
+    ///         text
+    ///     }
+    /// }
+    ///
+    /// let api_service =
+    ///     OpenApiService::new(MyApi, "Demo", "1.0").deserializer("text/plain", |data| {
+    ///         std::str::from_utf8(data).map(serde_json::Value::from)
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn deserializer<F, E>(mut self, content_type: &'static str, f: F) -> Self
+    where
+        F: Fn(&[u8]) -> Result<Value, E> + Send + Sync + 'static,
+        E: Display,
+    {
+        self.deserializers
+            .push(Deserializer::new(content_type, None, f));
+        self
+    }
+
+    /// Adds a deserializer of the request bodies with the content type, that
+    /// is only used by the [`Json`](crate::payload::Json) payloads of the
+    /// type, such as the `application/x-protobuf` messages.
+    ///
+    /// The deserializers of a type are preferred to the deserializers that
+    /// are added with [`OpenApiService::deserializer`].
+    #[must_use]
+    pub fn deserializer_for<P, F, E>(mut self, content_type: &'static str, f: F) -> Self
+    where
+        P: Type,
+        F: Fn(&[u8]) -> Result<Value, E> + Send + Sync + 'static,
+        E: Display,
+    {
+        self.deserializers.push(Deserializer::new(
+            content_type,
+            Some((P::name(), P::schema_ref())),
+            f,
+        ));
+        self
+    }
+
+    /// Enables the batch endpoint at the specified path, it accepts an array
+    /// of sub-requests and returns an array of sub-responses.
+    ///
+    /// See also: [`batch`](crate::batch)
+    #[must_use]
+    pub fn batch(self, path: &'static str) -> Self {
+        Self {
+            batch_path: Some(path),
+            ..self
+        }
+    }
+
+    /// Sets the maximum number of sub-requests of a batch request, the batch
+    /// requests with more sub-requests are rejected with `413 Payload Too
+    /// Large`. The default is `32`.
+    #[must_use]
+    pub fn batch_max_requests(self, max_requests: usize) -> Self {
+        Self {
+            batch_max_requests: max_requests,
+            ..self
+        }
+    }
+
+    /// Applies a middleware to the operations in the scope, the scope is a tag
+    /// or a path prefix.
+    ///
+    /// The middleware is applied to the endpoint of each operation in the
+    /// scope after the request is routed, so the
+    /// [`OperationInfo`](crate::OperationInfo) of the operation is available
+    /// to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::middleware::SetHeader;
+    /// use poem_openapi::{MiddlewareScope, OpenApi, OpenApiService, Tags};
+    ///
+    /// #[derive(Tags)]
+    /// enum ApiTags {
+    ///     Admin,
+    /// }
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/admin/users", method = "get", tag = "ApiTags::Admin")]
+    ///     async fn users(&self) {}
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(MyApi, "Demo", "1.0")
+    ///     .with_on(ApiTags::Admin, SetHeader::new().appending("X-Admin", "1"))
+    ///     .with_on(
+    ///         MiddlewareScope::path_prefix("/admin"),
+    ///         SetHeader::new().appending("Cache-Control", "no-store"),
+    ///     );
+    /// ```
+    #[must_use]
+    pub fn with_on<M>(mut self, scope: impl Into<MiddlewareScope>, middleware: M) -> Self
+    where
+        M: Middleware<BoxEndpoint<'static, Response>> + Send + Sync + 'static,
+        M::Output: 'static,
+    {
+        self.scoped_middlewares.push((
+            scope.into(),
+            Box::new(move |ep| middleware.transform(ep).map_to_response().boxed()),
+        ));
+        self
+    }
+
+    /// Records the mutating operations into the audit sink.
+    ///
+    /// See also: [`audit`](crate::audit)
+    #[must_use]
+    pub fn audit(self, audit: Audit) -> Self {
+        Self {
+            audit: Some(audit),
+            ..self
+        }
+    }
+
+    /// Rejects the operations with `503 Service Unavailable` when the
+    /// maintenance mode is on, except for the allowed paths.
+    ///
+    /// The `503` response is added to the documents of the operations that
+    /// are not allowed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::middleware::{Maintenance, MaintenanceSwitch};
+    /// use poem_openapi::{OpenApi, OpenApiService};
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/health", method = "get")]
+    ///     async fn health(&self) {}
+    ///
+    ///     #[oai(path = "/users", method = "post")]
+    ///     async fn create_user(&self) {}
+    /// }
+    ///
+    /// let switch = MaintenanceSwitch::new();
+    /// let api_service = OpenApiService::new(MyApi, "Demo", "1.0")
+    ///     .maintenance(Maintenance::new(switch.clone()).allow_path("/health"));
+    /// ```
+    #[must_use]
+    pub fn maintenance(self, maintenance: Maintenance) -> Self {
+        Self {
+            maintenance: Some(maintenance),
+            ..self
+        }
+    }
+
+    /// Schedules the operations by their priorities when the service is
+    /// saturated, the priority of an operation is specified with
+    /// `#[oai(priority = "...")]`.
+    ///
+    /// See also: [`Scheduler`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::middleware::{Priority, Scheduler};
+    /// use poem_openapi::{OpenApi, OpenApiService};
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/checkout", method = "post", priority = "critical")]
+    ///     async fn checkout(&self) {}
+    ///
+    ///     #[oai(path = "/recommendations", method = "get", priority = "low")]
+    ///     async fn recommendations(&self) {}
+    /// }
+    ///
+    /// let api_service = OpenApiService::new(MyApi, "Demo", "1.0")
+    ///     .scheduler(Scheduler::new(64).shed(Priority::Low));
+    /// ```
+    #[must_use]
+    pub fn scheduler(self, scheduler: Scheduler) -> Self {
+        Self {
+            scheduler: Some(scheduler),
+            ..self
+        }
+    }
+
+    /// Rejects the requests with `406 Not Acceptable` if the `Accept` header
+    /// excludes all the content types produced by the operation, such as
+    /// `Accept: text/html` for an operation that only produces
+    /// `application/json`.
+    ///
+    /// The `Accept` header is ignored by default. The requests without the
+    /// `Accept` header and the operations without the response bodies are
+    /// always allowed, the wildcards such as `*/*` and `application/*` are
+    /// supported and the media ranges with `q=0` are excluded.
+    ///
+    /// The `406` response is added to the documents of the operations that
+    /// produce the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{http::StatusCode, Endpoint, IntoEndpoint, Request};
+    /// use poem_openapi::{payload::Json, OpenApi, OpenApiService};
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/value", method = "get")]
+    ///     async fn value(&self) -> Json<i32> {
+    ///         Json(1)
+    ///     }
+    /// }
+    ///
+    /// let ep = OpenApiService::new(MyApi, "Demo", "1.0")
+    ///     .strict_accept()
+    ///     .into_endpoint();
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = ep
+    ///     .get_response(
+    ///         Request::builder()
+    ///             .uri_str("/value")
+    ///             .header("accept", "text/html")
+    ///             .finish(),
+    ///     )
+    ///     .await;
+    /// assert_eq!(resp.status(), StatusCode::NOT_ACCEPTABLE);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn strict_accept(self) -> Self {
+        Self {
+            strict_accept: true,
+            ..self
+        }
+    }
+
+    /// Rejects the request bodies whose `Content-Type` is not supported by
+    /// the operation with `415 Unsupported Media Type`, the body of the
+    /// response lists the supported content types.
+    ///
+    /// The unsupported content types are rejected with `405 Method Not
+    /// Allowed` by default.
+    ///
+    /// The `415` response is added to the documents of the operations that
+    /// have the request bodies.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::{http::StatusCode, Endpoint, IntoEndpoint, Request};
+    /// use poem_openapi::{payload::Json, OpenApi, OpenApiService};
+    ///
+    /// struct MyApi;
+    ///
+    /// #[OpenApi]
+    /// impl MyApi {
+    ///     #[oai(path = "/value", method = "post")]
+    ///     async fn value(&self, value: Json<i32>) -> Json<i32> {
+    ///         value
+    ///     }
+    /// }
+    ///
+    /// let ep = OpenApiService::new(MyApi, "Demo", "1.0")
+    ///     .strict_content_type()
+    ///     .into_endpoint();
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let resp = ep
+    ///     .get_response(
+    ///         Request::builder()
+    ///             .method(poem::http::Method::POST)
+    ///             .uri_str("/value")
+    ///             .content_type("text/plain")
+    ///             .body("1"),
+    ///     )
+    ///     .await;
+    /// assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn strict_content_type(self) -> Self {
+        Self {
+            strict_content_type: true,
+            ..self
+        }
+    }
+
     /// Create the Swagger UI endpoint.
+    ///
+    /// The scripts and the styles are served compressed from the
+    /// `swagger-ui-bundle.js` and `swagger-ui.css` paths next to the page,
+    /// with the `Cache-Control` and `ETag` headers. The page is redirected to
+    /// the path that ends with `/`, such as `/docs` to `/docs/`, because the
+    /// assets are relative to it.
     #[must_use]
     #[cfg(feature = "swagger-ui")]
     pub fn swagger_ui(&self) -> impl Endpoint
@@ -284,28 +801,136 @@ impl<T, W: ?Sized> OpenApiService<T, W> {
         })
     }
 
-    /// Returns the OAS specification file.
-    pub fn spec(&self) -> String
+    /// Create an endpoint to serve the AsyncAPI document, see
+    /// [`OpenApiService::asyncapi_spec`].
+    pub fn asyncapi_endpoint(&self) -> impl Endpoint
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let spec = self.asyncapi_spec();
+        make_sync(move |_| {
+            Response::builder()
+                .content_type("application/json")
+                .body(spec.clone())
+        })
+    }
+
+    /// Returns the documented operations and the registry of the schemas.
+    pub(crate) fn document_meta(&self) -> (Vec<MetaApi>, Registry)
     where
         T: OpenApi,
         W: Webhook,
     {
         let mut registry = Registry::new();
-        let metadata = T::meta();
+        let mut metadata = T::meta();
         T::register(&mut registry);
         W::register(&mut registry);
 
-        let webhooks = W::meta();
+        if let Some(path) = self.batch_path {
+            metadata.push(batch::meta(path));
+            batch::register(&mut registry);
+        }
+
+        if let Some(maintenance) = &self.maintenance {
+            maintenance::document(maintenance, &mut metadata, &mut registry);
+        }
+        if self.strict_accept {
+            accept::document(&mut metadata);
+        }
+        deserializer::document(&self.deserializers, &mut metadata);
+        if self.strict_content_type {
+            content_type::document(&mut metadata);
+        }
+        registry.use_writable_schemas(&mut metadata);
+        (metadata, registry)
+    }
 
-        let doc = Document {
-            info: &self.info,
-            servers: &self.servers,
-            apis: &metadata,
-            webhooks: &webhooks,
-            registry: &registry,
-            external_document: self.external_document.as_ref(),
+    /// Returns the document with the hooks applied.
+    fn document(&self) -> OpenApiDocument
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let (apis, registry) = self.document_meta();
+        let mut doc = OpenApiDocument {
+            info: self.info.clone(),
+            servers: self.servers.clone(),
+            apis,
+            webhooks: W::meta(),
+            registry,
+            external_document: self.external_document.clone(),
+            extensions: self.extensions.clone(),
         };
-        serde_json::to_string_pretty(&doc).unwrap()
+        for hook in &self.spec_hooks {
+            hook(&mut doc);
+        }
+        doc
+    }
+
+    /// Returns the OAS specification file.
+    pub fn spec(&self) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        serde_json::to_string_pretty(&self.document()).unwrap()
+    }
+
+    /// Returns the AsyncAPI 2.x document of the event-driven interfaces, they
+    /// are the endpoints that return [`EventStream`](crate::payload::EventStream)
+    /// or the typed WebSocket responses, and the webhooks.
+    ///
+    /// The channels are named after the paths and the names of the webhooks,
+    /// and the messages reference the same schemas as the OAS specification.
+    pub fn asyncapi_spec(&self) -> String
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        serde_json::to_string_pretty(&asyncapi::document(&self.document())).unwrap()
+    }
+
+    /// Checks the quality of the specification, such as the operations
+    /// without descriptions and the unused schemas.
+    ///
+    /// Use it in the tests to enforce the rules of the specification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{payload::PlainText, LintWarning, OpenApi, OpenApiService};
+    ///
+    /// struct Api;
+    ///
+    /// #[OpenApi]
+    /// impl Api {
+    ///     /// Say hello
+    ///     #[oai(path = "/hello", method = "get")]
+    ///     async fn hello(&self) -> PlainText<&'static str> {
+    ///         PlainText("hello")
+    ///     }
+    ///
+    ///     #[oai(path = "/ping", method = "get")]
+    ///     async fn ping(&self) {}
+    /// }
+    ///
+    /// let warnings = OpenApiService::new(Api, "Demo", "1.0").lint();
+    /// assert_eq!(
+    ///     warnings,
+    ///     vec![LintWarning::MissingDescription {
+    ///         method: poem::http::Method::GET,
+    ///         path: "/ping",
+    ///     }]
+    /// );
+    /// ```
+    pub fn lint(&self) -> Vec<LintWarning>
+    where
+        T: OpenApi,
+        W: Webhook,
+    {
+        let doc = self.document();
+        lint::lint(&doc.apis, &doc.webhooks, &doc.registry)
     }
 }
 
@@ -325,11 +950,12 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
         };
 
         // check duplicate operation id
+        let mut apis = T::meta();
         let mut operation_ids = HashSet::new();
-        for operation in T::meta()
-            .into_iter()
-            .flat_map(|api| api.paths.into_iter())
-            .flat_map(|path| path.operations.into_iter())
+        for operation in apis
+            .iter()
+            .flat_map(|api| &api.paths)
+            .flat_map(|path| &path.operations)
         {
             if let Some(operation_id) = operation.operation_id {
                 if !operation_ids.insert(operation_id) {
@@ -338,11 +964,82 @@ impl<T: OpenApi, W: Webhook> IntoEndpoint for OpenApiService<T, W> {
             }
         }
 
-        self.api
-            .add_routes(Route::new())
+        deserializer::document(&self.deserializers, &mut apis);
+
+        #[cfg(feature = "encrypted")]
+        if self.key_provider.is_none() {
+            let mut registry = Registry::new();
+            T::register(&mut registry);
+            if registry.uses_encrypted {
+                panic!("the API uses `Encrypted`, but no key provider is set");
+            }
+        }
+
+        let audit = self.audit.map(|audit| {
+            let mut registry = Registry::new();
+            T::register(&mut registry);
+            (audit, registry)
+        });
+        let scoped_middlewares = self.scoped_middlewares;
+        let strict_accept = self.strict_accept;
+        let strict_content_type = self.strict_content_type;
+        let scheduler = self.scheduler;
+        let wrapper = |info: &OperationInfo, mut ep: BoxEndpoint<'static, Response>| {
+            for (scope, middleware) in &scoped_middlewares {
+                if scope.contains(info) {
+                    ep = middleware(ep);
+                }
+            }
+
+            let operation = apis
+                .iter()
+                .flat_map(|api| &api.paths)
+                .filter(|path| path.path == info.path())
+                .flat_map(|path| &path.operations)
+                .find(|operation| operation.method == info.method());
+            let operation = match operation {
+                Some(operation) => operation,
+                None => return ep,
+            };
+            if let Some((audit, registry)) = &audit {
+                ep = audit::apply(ep, audit, info, operation, registry);
+            }
+            if strict_accept {
+                ep = accept::apply(ep, operation);
+            }
+            if strict_content_type {
+                ep = content_type::apply(ep, operation);
+            }
+            if let Some(scheduler) = &scheduler {
+                ep = scheduler::apply(ep, scheduler, operation);
+            }
+            ep
+        };
+
+        let mut ep = self.api.add_routes_with(Route::new(), &wrapper).boxed();
+        if let Some(maintenance) = self.maintenance {
+            ep = ep.with(maintenance).boxed();
+        }
+        #[cfg(feature = "encrypted")]
+        if let Some(provider) = self.key_provider {
+            ep = crate::types::encrypted::apply(ep, provider);
+        }
+
+        let ep = ep
             .with(cookie_jar_manager)
             .before(extract_query)
-            .map_to_response()
-            .boxed()
+            .data(ErrorMessageProviderData(self.error_message_provider))
+            .data(Deserializers(Arc::new(self.deserializers)))
+            .map_to_response();
+
+        match self.batch_path {
+            Some(path) => BatchEndpoint {
+                path,
+                max_requests: self.batch_max_requests,
+                inner: ep,
+            }
+            .boxed(),
+            None => ep.boxed(),
+        }
     }
 }
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use poem::{
+    endpoint::BoxEndpoint, error::GetDataError, http::Method, Endpoint, EndpointExt, FromRequest,
+    IntoResponse, Request, RequestBody, Response, Result,
+};
+
+#[derive(Debug)]
+struct Inner {
+    method: Method,
+    path: &'static str,
+    operation_id: Option<&'static str>,
+    tags: Vec<&'static str>,
+}
+
+/// The metadata of the operation that the request is routed to.
+///
+/// It is available to the handlers and the middlewares applied with
+/// [`OpenApiService::with_on`](crate::OpenApiService::with_on) as an
+/// extractor, and to the middlewares applied outside the service in the
+/// extensions of the response, unless an error is returned.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     http::{StatusCode, Uri},
+///     Endpoint, IntoEndpoint, Request,
+/// };
+/// use poem_openapi::{param::Path, OpenApi, OpenApiService, OperationInfo};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users/:id", method = "get", operation_id = "getUser")]
+///     async fn user(&self, info: OperationInfo, id: Path<i64>) {
+///         assert_eq!(info.operation_id(), Some("getUser"));
+///         assert_eq!(info.path(), "/users/{id}");
+///     }
+/// }
+///
+/// let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = ep
+///     .call(Request::builder().uri(Uri::from_static("/users/1")).finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::OK);
+/// let info = resp.extensions().get::<OperationInfo>().unwrap();
+/// assert_eq!(info.operation_id(), Some("getUser"));
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct OperationInfo(Arc<Inner>);
+
+impl OperationInfo {
+    /// Returns the method of the operation.
+    pub fn method(&self) -> &Method {
+        &self.0.method
+    }
+
+    /// Returns the path template of the operation, such as `/users/{id}`.
+    pub fn path(&self) -> &'static str {
+        self.0.path
+    }
+
+    /// Returns the id of the operation.
+    pub fn operation_id(&self) -> Option<&'static str> {
+        self.0.operation_id
+    }
+
+    /// Returns the tags of the operation.
+    pub fn tags(&self) -> &[&'static str] {
+        &self.0.tags
+    }
+}
+
+#[poem::async_trait]
+impl<'a> FromRequest<'a> for OperationInfo {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        req.extensions()
+            .get::<OperationInfo>()
+            .cloned()
+            .ok_or_else(|| GetDataError(std::any::type_name::<OperationInfo>()).into())
+    }
+}
+
+/// Inserts the [`OperationInfo`] of the operation into the extensions of
+/// the request and the response.
+struct OperationInfoEndpoint<E> {
+    info: OperationInfo,
+    inner: E,
+}
+
+#[poem::async_trait]
+impl<E: Endpoint> Endpoint for OperationInfoEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        req.extensions_mut().insert(self.info.clone());
+        let mut resp = self.inner.call(req).await?.into_response();
+        resp.extensions_mut().insert(self.info.clone());
+        Ok(resp)
+    }
+}
+
+/// Wraps the endpoint of an operation, such as the middlewares applied with
+/// [`OpenApiService::with_on`](crate::OpenApiService::with_on).
+#[doc(hidden)]
+pub type OperationWrapper<'a> =
+    dyn Fn(&OperationInfo, BoxEndpoint<'static, Response>) -> BoxEndpoint<'static, Response> + 'a;
+
+#[doc(hidden)]
+pub fn operation_endpoint(
+    ep: impl Endpoint + 'static,
+    method: Method,
+    path: &'static str,
+    operation_id: Option<&'static str>,
+    tags: Vec<&'static str>,
+    wrapper: &OperationWrapper<'_>,
+) -> impl Endpoint<Output = Response> {
+    let info = OperationInfo(Arc::new(Inner {
+        method,
+        path,
+        operation_id,
+        tags,
+    }));
+    OperationInfoEndpoint {
+        inner: wrapper(&info, ep.map_to_response().boxed()),
+        info,
+    }
+}
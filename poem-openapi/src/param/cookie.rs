@@ -108,7 +108,7 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookiePrivate<T> {
     }
 
     fn param_in() -> Option<MetaParamIn> {
-        Some(MetaParamIn::Cookie)
+        Some(MetaParamIn::CookiePrivate)
     }
 
     fn param_schema_ref() -> Option<MetaSchemaRef> {
@@ -178,7 +178,7 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for CookieSigned<T> {
     }
 
     fn param_in() -> Option<MetaParamIn> {
-        Some(MetaParamIn::Cookie)
+        Some(MetaParamIn::CookieSigned)
     }
 
     fn param_schema_ref() -> Option<MetaSchemaRef> {
@@ -1,6 +1,6 @@
 use std::ops::{Deref, DerefMut};
 
-use poem::{Request, RequestBody, Result};
+use poem::{http::header::HeaderName, web::VaryHeaders, Request, RequestBody, Result};
 
 use crate::{
     error::ParseParamError,
@@ -55,6 +55,10 @@ impl<'a, T: ParseFromParameter> ApiExtractor<'a> for Header<T> {
         _body: &mut RequestBody,
         param_opts: ExtractParamOptions<Self::ParamType>,
     ) -> Result<Self> {
+        if let Ok(name) = HeaderName::try_from(param_opts.name) {
+            VaryHeaders::record(request, name);
+        }
+
         let mut values = request
             .headers()
             .get_all(param_opts.name)
@@ -0,0 +1,88 @@
+use std::ops::Deref;
+
+use poem::{i18n::Locale as PoemLocale, FromRequest, Request, RequestBody, Result};
+
+use crate::{
+    registry::{MetaParamIn, MetaSchema, MetaSchemaRef},
+    ApiExtractor, ApiExtractorType, ExtractParamOptions,
+};
+
+/// Represents the locale negotiated from the `Accept-Language` header.
+///
+/// The quality values of the header are respected, and the languages are
+/// negotiated against the languages contained in the
+/// [`I18NResources`](poem::i18n::I18NResources) data attached to the endpoint.
+///
+/// Unlike [`poem::i18n::Locale`], this extractor documents the
+/// `Accept-Language` header as an optional parameter of the operation.
+///
+/// # Example
+///
+/// ```
+/// use poem::{i18n::I18NResources, EndpointExt, Route};
+/// use poem_openapi::{param::Locale, payload::PlainText, OpenApi, OpenApiService};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/hello", method = "get")]
+///     async fn hello(&self, locale: Locale) -> PlainText<String> {
+///         PlainText(
+///             locale
+///                 .text("hello-world")
+///                 .unwrap_or_else(|_| "error".to_string()),
+///         )
+///     }
+/// }
+///
+/// let resources = I18NResources::builder()
+///     .add_ftl("en-US", "hello-world = hello world!")
+///     .add_ftl("zh-CN", "hello-world = 你好世界！")
+///     .build()
+///     .unwrap();
+///
+/// let api_service = OpenApiService::new(Api, "Demo", "1.0");
+/// let app = Route::new().nest("/", api_service).data(resources);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "i18n")))]
+pub struct Locale(pub PoemLocale);
+
+impl Deref for Locale {
+    type Target = PoemLocale;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[poem::async_trait]
+impl<'a> ApiExtractor<'a> for Locale {
+    const TYPE: ApiExtractorType = ApiExtractorType::Parameter;
+    const PARAM_IS_REQUIRED: bool = false;
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn param_in() -> Option<MetaParamIn> {
+        Some(MetaParamIn::Header)
+    }
+
+    fn param_name() -> Option<&'static str> {
+        Some("Accept-Language")
+    }
+
+    fn param_schema_ref() -> Option<MetaSchemaRef> {
+        Some(MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))))
+    }
+
+    async fn from_request(
+        request: &'a Request,
+        body: &mut RequestBody,
+        _param_opts: ExtractParamOptions<Self::ParamType>,
+    ) -> Result<Self> {
+        <PoemLocale as FromRequest>::from_request(request, body)
+            .await
+            .map(Self)
+    }
+}
@@ -1,10 +1,16 @@
 //! Parameter types for the API operation.
 mod cookie;
 mod header;
+#[cfg(feature = "i18n")]
+mod locale;
 mod path;
 mod query;
+mod tenant;
 
 pub use cookie::{Cookie, CookiePrivate, CookieSigned};
 pub use header::Header;
+#[cfg(feature = "i18n")]
+pub use locale::Locale;
 pub use path::Path;
 pub use query::Query;
+pub use tenant::Tenant;
@@ -0,0 +1,83 @@
+use std::ops::Deref;
+
+use poem::{web::Tenant as PoemTenant, Request, RequestBody, Result};
+
+use crate::{
+    error::ParseParamError,
+    registry::{MetaParamIn, MetaSchema, MetaSchemaRef},
+    ApiExtractor, ApiExtractorType, ExtractParamOptions,
+};
+
+/// Represents the tenant supplied by the client in the `X-Tenant-Id` header.
+///
+/// Unlike [`poem::web::Tenant`], this extractor documents the `X-Tenant-Id`
+/// header as a required parameter of the operation. The tenant resolved by
+/// the [`Tenancy`](poem::middleware::Tenancy) middleware takes precedence
+/// over the header, and if the tenant is resolved from the subdomain or a
+/// token claim, use [`poem::web::Tenant`] instead because it is not supplied
+/// in a parameter.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{param::Tenant, payload::PlainText, OpenApi};
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "get")]
+///     async fn users(&self, tenant: Tenant) -> PlainText<String> {
+///         PlainText(tenant.scoped_key("users"))
+///     }
+/// }
+/// ```
+pub struct Tenant(pub PoemTenant);
+
+impl Deref for Tenant {
+    type Target = PoemTenant;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[poem::async_trait]
+impl<'a> ApiExtractor<'a> for Tenant {
+    const TYPE: ApiExtractorType = ApiExtractorType::Parameter;
+    const PARAM_IS_REQUIRED: bool = true;
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn param_in() -> Option<MetaParamIn> {
+        Some(MetaParamIn::Header)
+    }
+
+    fn param_name() -> Option<&'static str> {
+        Some(PoemTenant::HEADER)
+    }
+
+    fn param_schema_ref() -> Option<MetaSchemaRef> {
+        Some(MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))))
+    }
+
+    async fn from_request(
+        request: &'a Request,
+        _body: &mut RequestBody,
+        _param_opts: ExtractParamOptions<Self::ParamType>,
+    ) -> Result<Self> {
+        if let Some(tenant) = request.data::<PoemTenant>() {
+            return Ok(Self(tenant.clone()));
+        }
+
+        match request.header(PoemTenant::HEADER).map(str::trim) {
+            Some(tenant) if !tenant.is_empty() => Ok(Self(PoemTenant::new(tenant))),
+            _ => Err(ParseParamError {
+                name: PoemTenant::HEADER,
+                reason: "Type \"Tenant\" expects an input value.".to_string(),
+            }
+            .into()),
+        }
+    }
+}
@@ -0,0 +1,724 @@
+use std::{
+    io::{Error as IoError, ErrorKind, Write},
+    pin::Pin,
+};
+
+use bytes::Bytes;
+use flate2::{
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use futures_util::{Stream, StreamExt};
+use poem::{http::header, Body, IntoResponse, Response};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{
+    payload::Attachment,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse, ResponseContent,
+};
+
+/// The size of the chunks that are read from the entries.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The format of an [`Archive`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ArchiveFormat {
+    /// A zip archive, the entries are compressed with deflate.
+    Zip,
+    /// A tar archive compressed with gzip.
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Returns the content type of the archive.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "application/zip",
+            ArchiveFormat::TarGz => "application/gzip",
+        }
+    }
+
+    fn writer(&self) -> Box<dyn ArchiveWriter> {
+        match self {
+            ArchiveFormat::Zip => Box::new(ZipWriter::default()),
+            ArchiveFormat::TarGz => Box::new(TarGzWriter::default()),
+        }
+    }
+}
+
+/// An entry of an [`Archive`], the content is read from the reader when the
+/// archive is written.
+pub struct ArchiveEntry {
+    name: String,
+    size: Option<u64>,
+    reader: Pin<Box<dyn AsyncRead + Send>>,
+}
+
+impl ArchiveEntry {
+    /// Create an entry with the path in the archive and the reader of the
+    /// content.
+    pub fn new(name: impl Into<String>, reader: impl AsyncRead + Send + 'static) -> Self {
+        Self {
+            name: name.into(),
+            size: None,
+            reader: Box::pin(reader),
+        }
+    }
+
+    /// Specify the size of the content.
+    ///
+    /// The tar archives require the size before the content, the content of
+    /// the entries without it is buffered in memory.
+    #[must_use]
+    pub fn size(self, size: u64) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+}
+
+impl<N, R> From<(N, R)> for ArchiveEntry
+where
+    N: Into<String>,
+    R: AsyncRead + Send + 'static,
+{
+    fn from((name, reader): (N, R)) -> Self {
+        ArchiveEntry::new(name, reader)
+    }
+}
+
+/// An archive response that is built on the fly from a stream of entries,
+/// the content of the entries is read as the response body is sent, so
+/// nothing is written to the temporary files.
+///
+/// The zip archives use the data descriptors and do not support ZIP64, so
+/// the archive must be smaller than 4GiB and have less than 65536 entries.
+/// The body is aborted if an entry cannot be read or the limits are
+/// exceeded.
+///
+/// # Examples
+///
+/// ```rust
+/// use futures_util::stream::{self, BoxStream, StreamExt};
+/// use poem_openapi::{
+///     payload::{Archive, ArchiveEntry},
+///     OpenApi,
+/// };
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/attachments", method = "get")]
+///     async fn attachments(&self) -> Archive<BoxStream<'static, ArchiveEntry>> {
+///         let entries = vec![
+///             ArchiveEntry::new("a.txt", &b"hello"[..]),
+///             ArchiveEntry::new("b.txt", &b"world"[..]),
+///         ];
+///         Archive::zip(stream::iter(entries).boxed()).filename("attachments.zip")
+///     }
+/// }
+/// ```
+pub struct Archive<S> {
+    format: ArchiveFormat,
+    entries: S,
+    filename: Option<String>,
+}
+
+impl<S> Archive<S> {
+    /// Create an archive with the format and the entries.
+    pub fn new(format: ArchiveFormat, entries: S) -> Self {
+        Self {
+            format,
+            entries,
+            filename: None,
+        }
+    }
+
+    /// Create a zip archive with the entries.
+    pub fn zip(entries: S) -> Self {
+        Self::new(ArchiveFormat::Zip, entries)
+    }
+
+    /// Create a gzip compressed tar archive with the entries.
+    pub fn tar_gz(entries: S) -> Self {
+        Self::new(ArchiveFormat::TarGz, entries)
+    }
+
+    /// Specify the file name.
+    #[must_use]
+    pub fn filename(self, filename: impl Into<String>) -> Self {
+        Self {
+            filename: Some(filename.into()),
+            ..self
+        }
+    }
+}
+
+impl<S, T> IntoResponse for Archive<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<ArchiveEntry>,
+{
+    fn into_response(self) -> Response {
+        let body = archive_body(self.format, self.entries);
+        let mut attachment = Attachment::new(body);
+        if let Some(filename) = self.filename {
+            attachment = attachment.filename(filename);
+        }
+        let mut resp = attachment.into_response();
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            self.format.content_type().parse().unwrap(),
+        );
+        resp
+    }
+}
+
+impl<S, T> ResponseContent for Archive<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<ArchiveEntry>,
+{
+    fn media_types() -> Vec<MetaMediaType> {
+        [ArchiveFormat::Zip, ArchiveFormat::TarGz]
+            .iter()
+            .map(|format| MetaMediaType {
+                content_type: format.content_type(),
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    format: Some("binary"),
+                    ..MetaSchema::new("string")
+                })),
+            })
+            .collect()
+    }
+}
+
+impl<S, T> ApiResponse for Archive<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<ArchiveEntry>,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: Self::media_types(),
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+/// Writes the entries to an in-memory buffer, that is taken after each
+/// chunk.
+trait ArchiveWriter: Send {
+    /// Returns `true` if the size of the entries must be known before the
+    /// content.
+    fn requires_size(&self) -> bool;
+
+    fn start_entry(&mut self, name: &str, size: Option<u64>) -> Result<(), IoError>;
+
+    fn write(&mut self, data: &[u8]) -> Result<(), IoError>;
+
+    fn finish_entry(&mut self) -> Result<(), IoError>;
+
+    fn finish(&mut self) -> Result<(), IoError>;
+
+    /// Takes the bytes that are written so far.
+    fn take(&mut self) -> Bytes;
+}
+
+struct ArchiveState<S> {
+    writer: Box<dyn ArchiveWriter>,
+    entries: Pin<Box<S>>,
+    current: Option<Pin<Box<dyn AsyncRead + Send>>>,
+    buf: Vec<u8>,
+}
+
+impl<S, T> ArchiveState<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<ArchiveEntry>,
+{
+    /// Writes the next part of the archive, returns `false` if the archive is
+    /// finished.
+    async fn advance(&mut self) -> Result<bool, IoError> {
+        if let Some(reader) = &mut self.current {
+            let n = reader.read(&mut self.buf).await?;
+            if n == 0 {
+                self.writer.finish_entry()?;
+                self.current = None;
+            } else {
+                self.writer.write(&self.buf[..n])?;
+            }
+            return Ok(true);
+        }
+
+        let mut entry = match self.entries.next().await {
+            Some(entry) => entry.into(),
+            None => {
+                self.writer.finish()?;
+                return Ok(false);
+            }
+        };
+        if entry.size.is_none() && self.writer.requires_size() {
+            let mut content = Vec::new();
+            entry.reader.read_to_end(&mut content).await?;
+            entry.size = Some(content.len() as u64);
+            entry.reader = Box::pin(std::io::Cursor::new(content));
+        }
+        self.writer.start_entry(&entry.name, entry.size)?;
+        self.current = Some(entry.reader);
+        Ok(true)
+    }
+}
+
+/// Creates a streaming body that writes the entries to the archive, the body
+/// is aborted if an entry fails.
+fn archive_body<S, T>(format: ArchiveFormat, entries: S) -> Body
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Into<ArchiveEntry>,
+{
+    let state = ArchiveState {
+        writer: format.writer(),
+        entries: Box::pin(entries),
+        current: None,
+        buf: vec![0; CHUNK_SIZE],
+    };
+    let stream = futures_util::stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        loop {
+            match state.advance().await {
+                Ok(more) => {
+                    let chunk = state.writer.take();
+                    if !more {
+                        return Some((Ok(chunk), None));
+                    }
+                    if !chunk.is_empty() {
+                        return Some((Ok(chunk), Some(state)));
+                    }
+                }
+                Err(err) => return Some((Err(err), None)),
+            }
+        }
+    })
+    .filter(|chunk| futures_util::future::ready(!matches!(chunk, Ok(chunk) if chunk.is_empty())));
+    Body::from_bytes_stream(stream)
+}
+
+fn too_large() -> IoError {
+    IoError::new(ErrorKind::InvalidData, "the archive is too large")
+}
+
+struct ZipEntry {
+    name: String,
+    offset: u32,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+}
+
+/// The MS-DOS date of 1980-01-01, the modification time of the entries.
+const ZIP_DOS_DATE: u16 = (1 << 5) | 1;
+
+/// The entries use the data descriptors and UTF-8 names.
+const ZIP_FLAGS: u16 = (1 << 3) | (1 << 11);
+
+#[derive(Default)]
+struct ZipWriter {
+    output: Vec<u8>,
+    offset: u64,
+    entries: Vec<ZipEntry>,
+    current: Option<(DeflateEncoder<Vec<u8>>, crc32fast::Hasher, u64)>,
+}
+
+impl ZipWriter {
+    fn offset(&self) -> Result<u32, IoError> {
+        u32::try_from(self.offset + self.output.len() as u64).map_err(|_| too_large())
+    }
+}
+
+impl ArchiveWriter for ZipWriter {
+    fn requires_size(&self) -> bool {
+        false
+    }
+
+    fn start_entry(&mut self, name: &str, _size: Option<u64>) -> Result<(), IoError> {
+        if self.entries.len() >= u16::MAX as usize {
+            return Err(too_large());
+        }
+        let offset = self.offset()?;
+        let out = &mut self.output;
+        out.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        out.extend_from_slice(&20u16.to_le_bytes());
+        out.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+        out.extend_from_slice(&8u16.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(&ZIP_DOS_DATE.to_le_bytes());
+        // the crc and the sizes are written in the data descriptor
+        out.extend_from_slice(&[0; 12]);
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        self.entries.push(ZipEntry {
+            name: name.to_string(),
+            offset,
+            crc: 0,
+            compressed_size: 0,
+            size: 0,
+        });
+        self.current = Some((
+            DeflateEncoder::new(Vec::new(), Compression::default()),
+            crc32fast::Hasher::new(),
+            0,
+        ));
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), IoError> {
+        if let Some((encoder, hasher, size)) = &mut self.current {
+            encoder.write_all(data)?;
+            hasher.update(data);
+            *size += data.len() as u64;
+            self.output.append(encoder.get_mut());
+        }
+        Ok(())
+    }
+
+    fn finish_entry(&mut self) -> Result<(), IoError> {
+        let (encoder, hasher, size) = match self.current.take() {
+            Some(current) => current,
+            None => return Ok(()),
+        };
+        self.output.extend(encoder.finish()?);
+        let entry = self.entries.last_mut().unwrap();
+        let compressed_size = self.offset + self.output.len() as u64
+            - entry.offset as u64
+            - 30
+            - entry.name.len() as u64;
+        entry.crc = hasher.finalize();
+        entry.compressed_size = u32::try_from(compressed_size).map_err(|_| too_large())?;
+        entry.size = u32::try_from(size).map_err(|_| too_large())?;
+        let (crc, compressed_size, size) = (entry.crc, entry.compressed_size, entry.size);
+        self.output.extend_from_slice(&0x08074b50u32.to_le_bytes());
+        self.output.extend_from_slice(&crc.to_le_bytes());
+        self.output
+            .extend_from_slice(&compressed_size.to_le_bytes());
+        self.output.extend_from_slice(&size.to_le_bytes());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), IoError> {
+        let directory_offset = self.offset()?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes());
+            directory.extend_from_slice(&20u16.to_le_bytes());
+            directory.extend_from_slice(&ZIP_FLAGS.to_le_bytes());
+            directory.extend_from_slice(&8u16.to_le_bytes());
+            directory.extend_from_slice(&0u16.to_le_bytes());
+            directory.extend_from_slice(&ZIP_DOS_DATE.to_le_bytes());
+            directory.extend_from_slice(&entry.crc.to_le_bytes());
+            directory.extend_from_slice(&entry.compressed_size.to_le_bytes());
+            directory.extend_from_slice(&entry.size.to_le_bytes());
+            directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            // the extra field, the comment, the disk and the attributes
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&entry.offset.to_le_bytes());
+            directory.extend_from_slice(entry.name.as_bytes());
+        }
+        let count = self.entries.len() as u16;
+        let directory_size = u32::try_from(directory.len()).map_err(|_| too_large())?;
+        self.output.extend(directory);
+        self.output.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        self.output.extend_from_slice(&[0; 4]);
+        self.output.extend_from_slice(&count.to_le_bytes());
+        self.output.extend_from_slice(&count.to_le_bytes());
+        self.output.extend_from_slice(&directory_size.to_le_bytes());
+        self.output
+            .extend_from_slice(&directory_offset.to_le_bytes());
+        self.output.extend_from_slice(&0u16.to_le_bytes());
+        Ok(())
+    }
+
+    fn take(&mut self) -> Bytes {
+        self.offset += self.output.len() as u64;
+        std::mem::take(&mut self.output).into()
+    }
+}
+
+const TAR_BLOCK_SIZE: u64 = 512;
+
+struct TarGzWriter {
+    encoder: GzEncoder<Vec<u8>>,
+    /// The declared size and the written size of the current entry.
+    current: Option<(u64, u64)>,
+}
+
+impl Default for TarGzWriter {
+    fn default() -> Self {
+        Self {
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            current: None,
+        }
+    }
+}
+
+/// Writes the number as a NUL terminated octal string.
+fn write_octal(field: &mut [u8], value: u64) -> Result<(), IoError> {
+    let s = format!("{:0width$o}", value, width = field.len() - 1);
+    if s.len() >= field.len() {
+        return Err(too_large());
+    }
+    field[..s.len()].copy_from_slice(s.as_bytes());
+    Ok(())
+}
+
+/// Splits the name into the `prefix` and `name` fields of the ustar header.
+fn split_tar_name(name: &str) -> Result<(&str, &str), IoError> {
+    if name.len() <= 100 {
+        return Ok(("", name));
+    }
+    name.char_indices()
+        .filter(|(_, c)| *c == '/')
+        .map(|(idx, _)| (&name[..idx], &name[idx + 1..]))
+        .find(|(prefix, name)| prefix.len() <= 155 && !name.is_empty() && name.len() <= 100)
+        .ok_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                format!("the name of the entry `{}` is too long", name),
+            )
+        })
+}
+
+impl ArchiveWriter for TarGzWriter {
+    fn requires_size(&self) -> bool {
+        true
+    }
+
+    fn start_entry(&mut self, name: &str, size: Option<u64>) -> Result<(), IoError> {
+        let size = size.unwrap_or_default();
+        let (prefix, name) = split_tar_name(name)?;
+        let mut header = [0u8; TAR_BLOCK_SIZE as usize];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        write_octal(&mut header[100..108], 0o644)?;
+        write_octal(&mut header[108..116], 0)?;
+        write_octal(&mut header[116..124], 0)?;
+        write_octal(&mut header[124..136], size)?;
+        write_octal(&mut header[136..148], 0)?;
+        header[156] = b'0';
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        // the checksum is computed with the checksum field filled with spaces
+        header[148..156].fill(b' ');
+        let checksum = header.iter().map(|b| *b as u64).sum::<u64>();
+        write_octal(&mut header[148..155], checksum)?;
+        self.encoder.write_all(&header)?;
+        self.current = Some((size, 0));
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), IoError> {
+        if let Some((size, written)) = &mut self.current {
+            *written += data.len() as u64;
+            if *written > *size {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    "the content of the entry is larger than its size",
+                ));
+            }
+            self.encoder.write_all(data)?;
+        }
+        Ok(())
+    }
+
+    fn finish_entry(&mut self) -> Result<(), IoError> {
+        if let Some((size, written)) = self.current.take() {
+            if written != size {
+                return Err(IoError::new(
+                    ErrorKind::InvalidData,
+                    "the content of the entry is smaller than its size",
+                ));
+            }
+            let padding = (TAR_BLOCK_SIZE - size % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+            self.encoder.write_all(&vec![0; padding as usize])?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), IoError> {
+        self.encoder.write_all(&[0; 2 * TAR_BLOCK_SIZE as usize])?;
+        self.encoder.try_finish()
+    }
+
+    fn take(&mut self) -> Bytes {
+        std::mem::take(self.encoder.get_mut()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::{DeflateDecoder, GzDecoder};
+    use futures_util::stream;
+
+    use super::*;
+
+    fn u16_at(data: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    /// Reads the entries of the zip archive from the central directory.
+    fn read_zip(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = data.len() - 22;
+        assert_eq!(u32_at(data, end), 0x06054b50);
+        let count = u16_at(data, end + 10) as usize;
+        let mut offset = u32_at(data, end + 16) as usize;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            assert_eq!(u32_at(data, offset), 0x02014b50);
+            let crc = u32_at(data, offset + 16);
+            let compressed_size = u32_at(data, offset + 20) as usize;
+            let name_len = u16_at(data, offset + 28) as usize;
+            let local_offset = u32_at(data, offset + 42) as usize;
+            let name =
+                String::from_utf8(data[offset + 46..offset + 46 + name_len].to_vec()).unwrap();
+
+            assert_eq!(u32_at(data, local_offset), 0x04034b50);
+            let start = local_offset + 30 + name_len;
+            let mut content = Vec::new();
+            DeflateDecoder::new(&data[start..start + compressed_size])
+                .read_to_end(&mut content)
+                .unwrap();
+            assert_eq!(crc32fast::hash(&content), crc);
+            assert_eq!(u32_at(data, start + compressed_size), 0x08074b50);
+
+            entries.push((name, content));
+            offset += 46 + name_len;
+        }
+        entries
+    }
+
+    /// Reads the entries of the gzip compressed tar archive.
+    fn read_tar_gz(data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut data_tar = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut data_tar).unwrap();
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while data_tar[offset..offset + 512].iter().any(|b| *b != 0) {
+            let header = &data_tar[offset..offset + 512];
+            let field = |range: std::ops::Range<usize>| {
+                let s = std::str::from_utf8(&header[range]).unwrap();
+                s.trim_end_matches('\0').to_string()
+            };
+            let checksum = u64::from_str_radix(field(148..154).as_str(), 8).unwrap();
+            let mut blank = header.to_vec();
+            blank[148..156].fill(b' ');
+            assert_eq!(blank.iter().map(|b| *b as u64).sum::<u64>(), checksum);
+
+            let (prefix, name) = (field(345..500), field(0..100));
+            let name = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            let size = u64::from_str_radix(field(124..135).as_str(), 8).unwrap() as usize;
+            let content = data_tar[offset + 512..offset + 512 + size].to_vec();
+            entries.push((name, content));
+            offset += 512 + size + (512 - size % 512) % 512;
+        }
+        assert_eq!(data_tar.len(), offset + 1024);
+        entries
+    }
+
+    fn entries() -> Vec<ArchiveEntry> {
+        let long_name = format!("{}/{}.txt", "a".repeat(120), "b".repeat(60));
+        vec![
+            ArchiveEntry::new("a.txt", &b"hello"[..]),
+            ArchiveEntry::new("dir/b.bin", std::io::Cursor::new(vec![7; 200_000])).size(200_000),
+            ArchiveEntry::new("empty.txt", &b""[..]),
+            ArchiveEntry::new(long_name, &b"long"[..]),
+        ]
+    }
+
+    fn expected() -> Vec<(String, Vec<u8>)> {
+        entries()
+            .into_iter()
+            .map(|entry| entry.name)
+            .zip([
+                b"hello".to_vec(),
+                vec![7; 200_000],
+                Vec::new(),
+                b"long".to_vec(),
+            ])
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn zip() {
+        let resp = Archive::zip(stream::iter(entries()))
+            .filename("files.zip")
+            .into_response();
+        assert_eq!(resp.content_type(), Some("application/zip"));
+        assert_eq!(
+            resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
+            "attachment; filename=\"files.zip\""
+        );
+        let data = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(read_zip(&data), expected());
+    }
+
+    #[tokio::test]
+    async fn tar_gz() {
+        let resp = Archive::tar_gz(stream::iter(entries())).into_response();
+        assert_eq!(resp.content_type(), Some("application/gzip"));
+        let data = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(read_tar_gz(&data), expected());
+    }
+
+    #[tokio::test]
+    async fn tuple_entries() {
+        let resp = Archive::zip(stream::iter(vec![("a.txt", &b"hello"[..])])).into_response();
+        let data = resp.into_body().into_vec().await.unwrap();
+        assert_eq!(
+            read_zip(&data),
+            vec![("a.txt".to_string(), b"hello".to_vec())]
+        );
+    }
+
+    #[tokio::test]
+    async fn tar_gz_wrong_size() {
+        let resp = Archive::tar_gz(stream::iter(vec![ArchiveEntry::new(
+            "a.txt",
+            &b"hello"[..],
+        )
+        .size(3)]))
+        .into_response();
+        assert!(resp.into_body().into_vec().await.is_err());
+    }
+
+    #[test]
+    fn meta() {
+        let meta = Archive::<stream::Iter<std::vec::IntoIter<ArchiveEntry>>>::meta();
+        let content_types = meta.responses[0]
+            .content
+            .iter()
+            .map(|media| media.content_type)
+            .collect::<Vec<_>>();
+        assert_eq!(content_types, vec!["application/zip", "application/gzip"]);
+    }
+}
@@ -0,0 +1,223 @@
+use std::io::Error as IoError;
+
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::SchemaRef;
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use poem::{Body, IntoResponse, Response};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// Encodes the record batches to the chunks of a columnar format.
+pub(crate) trait BatchWriter: Send + 'static {
+    /// Writes the batch and returns the bytes that are encoded so far.
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, IoError>;
+
+    /// Returns the remaining bytes after the last batch.
+    fn finish(self) -> Result<Bytes, IoError>;
+}
+
+/// Creates a streaming body that encodes the batches with the writer, the
+/// body is aborted if either the stream or the writer fails.
+pub(crate) fn batch_body<W, S, E>(writer: Result<W, IoError>, batches: S) -> Body
+where
+    W: BatchWriter,
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let stream = futures_util::stream::unfold(
+        (Some(writer), Box::pin(batches)),
+        |(writer, mut batches)| async move {
+            let mut writer = match writer? {
+                Ok(writer) => writer,
+                Err(err) => return Some((Err(err), (None, batches))),
+            };
+            match batches.next().await {
+                Some(Ok(batch)) => {
+                    let chunk = writer.write(&batch);
+                    let writer = chunk.is_ok().then(|| Ok(writer));
+                    Some((chunk, (writer, batches)))
+                }
+                Some(Err(err)) => Some((Err(IoError::other(err)), (None, batches))),
+                None => Some((writer.finish(), (None, batches))),
+            }
+        },
+    )
+    .filter(|chunk| futures_util::future::ready(!matches!(chunk, Ok(chunk) if chunk.is_empty())));
+    Body::from_bytes_stream(stream)
+}
+
+impl BatchWriter for StreamWriter<Vec<u8>> {
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, IoError> {
+        StreamWriter::write(self, batch).map_err(IoError::other)?;
+        Ok(std::mem::take(self.get_mut()).into())
+    }
+
+    fn finish(mut self) -> Result<Bytes, IoError> {
+        StreamWriter::finish(&mut self).map_err(IoError::other)?;
+        Ok(std::mem::take(self.get_mut()).into())
+    }
+}
+
+/// An [Arrow IPC stream](https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format)
+/// payload, the record batches are encoded as they are produced by the
+/// stream.
+///
+/// The stream is described as a binary string in the specification.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use arrow_array::{Int32Array, RecordBatch};
+/// use arrow_schema::{ArrowError, DataType, Field, Schema};
+/// use futures_util::stream::{self, BoxStream};
+/// use poem_openapi::{payload::ArrowStream, OpenApi};
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/values", method = "get")]
+///     async fn values(&self) -> ArrowStream<BoxStream<'static, Result<RecordBatch, ArrowError>>> {
+///         let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+///         let batch = RecordBatch::try_new(
+///             schema.clone(),
+///             vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+///         );
+///         ArrowStream::new(schema, Box::pin(stream::iter(vec![batch])))
+///     }
+/// }
+/// ```
+pub struct ArrowStream<S> {
+    schema: SchemaRef,
+    batches: S,
+}
+
+impl<S> ArrowStream<S> {
+    /// Create an Arrow IPC stream payload with the schema of the batches.
+    pub fn new(schema: SchemaRef, batches: S) -> Self {
+        Self { schema, batches }
+    }
+}
+
+impl<S, E> Payload for ArrowStream<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    const CONTENT_TYPE: &'static str = "application/vnd.apache.arrow.stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+impl<S, E> IntoResponse for ArrowStream<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        let writer = StreamWriter::try_new(Vec::new(), &self.schema).map_err(IoError::other);
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(batch_body(writer, self.batches))
+    }
+}
+
+impl<S, E> ApiResponse for ArrowStream<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use arrow_array::{Int32Array, StringArray};
+    use arrow_ipc::reader::StreamReader;
+    use arrow_schema::{ArrowError, DataType, Field, Schema};
+    use futures_util::stream;
+
+    use super::*;
+
+    fn batches() -> (SchemaRef, Vec<RecordBatch>) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let batches = [(vec![1, 2], vec!["a", "b"]), (vec![3], vec!["c"])]
+            .into_iter()
+            .map(|(ids, names)| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![
+                        Arc::new(Int32Array::from(ids)),
+                        Arc::new(StringArray::from(names)),
+                    ],
+                )
+                .unwrap()
+            })
+            .collect();
+        (schema, batches)
+    }
+
+    #[tokio::test]
+    async fn arrow_stream() {
+        let (schema, batches) = batches();
+        let resp = ArrowStream::new(
+            schema,
+            stream::iter(batches.clone().into_iter().map(Ok::<_, ArrowError>)),
+        )
+        .into_response();
+        assert_eq!(
+            resp.content_type(),
+            Some("application/vnd.apache.arrow.stream")
+        );
+
+        let data = resp.into_body().into_vec().await.unwrap();
+        let reader = StreamReader::try_new(Cursor::new(data), None).unwrap();
+        assert_eq!(reader.collect::<Result<Vec<_>, _>>().unwrap(), batches);
+    }
+
+    #[tokio::test]
+    async fn arrow_stream_error() {
+        let (schema, batches) = batches();
+        let resp = ArrowStream::new(
+            schema,
+            stream::iter(vec![
+                Ok(batches[0].clone()),
+                Err(ArrowError::ComputeError("failed".to_string())),
+            ]),
+        )
+        .into_response();
+        assert!(resp.into_body().into_vec().await.is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    io::{Error as IoError, ErrorKind},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use poem::{error::BadRequest, http::header, Body, Request, RequestBody, Result};
+
+use crate::{
+    error::PayloadTooLargeError,
+    payload::{ParsePayload, Payload},
+    registry::{MetaSchema, MetaSchemaRef},
+};
+
+/// A streaming binary request payload.
+///
+/// Unlike [`Binary`](crate::payload::Binary), it does not buffer the whole
+/// body, the chunks are read from the connection on demand, so it is suitable
+/// for large uploads and proxies. It is documented as
+/// `application/octet-stream`.
+///
+/// Use [`BinaryStream::max_size`] to limit the size of the body, the stream
+/// yields [`PayloadTooLargeError`] when it is exceeded.
+///
+/// # Examples
+///
+/// ```rust
+/// use poem::{
+///     http::{Method, StatusCode, Uri},
+///     IntoEndpoint, Request, Result,
+/// };
+/// use poem_openapi::{
+///     payload::{BinaryStream, Json},
+///     OpenApi, OpenApiService,
+/// };
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/upload", method = "post")]
+///     async fn upload(&self, data: BinaryStream) -> Result<Json<usize>> {
+///         let mut data = data.max_size(1024);
+///         let mut size = 0;
+///         while let Some(chunk) = data.next_chunk().await? {
+///             size += chunk.len();
+///         }
+///         Ok(Json(size))
+///     }
+/// }
+///
+/// let api = OpenApiService::new(MyApi, "Demo", "0.1.0").into_endpoint();
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = api
+///     .call(
+///         Request::builder()
+///             .method(Method::POST)
+///             .content_type("application/octet-stream")
+///             .uri(Uri::from_static("/upload"))
+///             .body("abcdef"),
+///     )
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::OK);
+/// assert_eq!(resp.into_body().into_string().await.unwrap(), "6");
+/// # });
+/// ```
+pub struct BinaryStream {
+    stream: BoxStream<'static, Result<Bytes, IoError>>,
+    content_length: Option<u64>,
+    max_size: Option<usize>,
+    size: usize,
+    finished: bool,
+}
+
+impl BinaryStream {
+    /// Create a streaming binary payload from a body.
+    pub fn new(body: Body) -> Self {
+        Self {
+            stream: body.into_bytes_stream().boxed(),
+            content_length: None,
+            max_size: None,
+            size: 0,
+            finished: false,
+        }
+    }
+
+    /// Sets the maximum size of the body.
+    ///
+    /// If the `Content-Length` header of the request is larger than it, the
+    /// stream fails immediately without reading the body.
+    #[must_use]
+    pub fn max_size(self, max_size: usize) -> Self {
+        Self {
+            max_size: Some(max_size),
+            ..self
+        }
+    }
+
+    /// Returns the value of the `Content-Length` header of the request.
+    #[inline]
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Reads the next chunk, returns `None` if the body is finished.
+    ///
+    /// # Errors
+    ///
+    /// - [`PayloadTooLargeError`]
+    /// - [`poem::error::BadRequest`] if failed to read the body
+    pub async fn next_chunk(&mut self) -> Result<Option<Bytes>> {
+        match self.next().await {
+            Some(Ok(data)) => Ok(Some(data)),
+            Some(Err(err)) => Err(
+                match err
+                    .get_ref()
+                    .and_then(|err| err.downcast_ref::<PayloadTooLargeError>())
+                {
+                    Some(err) => err.clone().into(),
+                    None => BadRequest(err),
+                },
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Consumes this payload to return a body, for example to forward it to
+    /// another service.
+    pub fn into_body(self) -> Body {
+        Body::from_bytes_stream(self)
+    }
+
+    fn too_large(&mut self, max_size: usize) -> Poll<Option<Result<Bytes, IoError>>> {
+        self.finished = true;
+        Poll::Ready(Some(Err(IoError::new(
+            ErrorKind::Other,
+            PayloadTooLargeError { max_size },
+        ))))
+    }
+}
+
+impl Stream for BinaryStream {
+    type Item = Result<Bytes, IoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        if let (Some(max_size), Some(content_length)) = (self.max_size, self.content_length) {
+            if content_length > max_size as u64 {
+                return self.too_large(max_size);
+            }
+        }
+
+        match self.stream.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(data))) => {
+                self.size += data.len();
+                match self.max_size {
+                    Some(max_size) if self.size > max_size => self.too_large(max_size),
+                    _ => Poll::Ready(Some(Ok(data))),
+                }
+            }
+            Poll::Ready(Some(Err(err))) => {
+                self.finished = true;
+                Poll::Ready(Some(Err(err)))
+            }
+            Poll::Ready(None) => {
+                self.finished = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Payload for BinaryStream {
+    const CONTENT_TYPE: &'static str = "application/octet-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+#[poem::async_trait]
+impl ParsePayload for BinaryStream {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        Ok(Self {
+            content_length: request
+                .header(header::CONTENT_LENGTH)
+                .and_then(|value| value.parse().ok()),
+            ..Self::new(body.take()?)
+        })
+    }
+}
+
+impl_apirequest_for_payload!(BinaryStream);
+
+#[cfg(test)]
+mod tests {
+    use poem::http::StatusCode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn stream() {
+        let mut data =
+            BinaryStream::new(Body::from_bytes_stream(futures_util::stream::iter(vec![
+                Ok::<_, IoError>(Bytes::from_static(b"abc")),
+                Ok(Bytes::from_static(b"def")),
+            ])));
+        assert_eq!(data.next_chunk().await.unwrap().unwrap(), "abc");
+        assert_eq!(data.next_chunk().await.unwrap().unwrap(), "def");
+        assert!(data.next_chunk().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn max_size() {
+        let mut data =
+            BinaryStream::new(Body::from_bytes_stream(futures_util::stream::iter(vec![
+                Ok::<_, IoError>(Bytes::from_static(b"abc")),
+                Ok(Bytes::from_static(b"def")),
+            ])))
+            .max_size(4);
+        assert_eq!(data.next_chunk().await.unwrap().unwrap(), "abc");
+        let err = data.next_chunk().await.unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(data.next_chunk().await.unwrap().is_none());
+
+        let req = Request::builder()
+            .header("content-length", 6)
+            .body("abcdef");
+        let (req, mut body) = req.split();
+        let mut data = BinaryStream::from_request(&req, &mut body)
+            .await
+            .unwrap()
+            .max_size(4);
+        assert_eq!(data.content_length(), Some(6));
+        assert!(data
+            .next_chunk()
+            .await
+            .unwrap_err()
+            .is::<PayloadTooLargeError>());
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use poem::{
+    http::{header, HeaderValue},
+    web::cookie::{Cookie, CookieJar, CookieKey},
+    Error, IntoResponse,
+};
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaHeader, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// A response type wrapper that sets the cookies, the `Set-Cookie` header is
+/// documented on the responses of `T`.
+///
+/// The attributes of the cookies, such as `SameSite`, `Max-Age` and `Secure`,
+/// are set on the [`Cookie`].
+///
+/// # Example
+///
+/// ```
+/// use poem::web::cookie::{Cookie, CookieKey, SameSite};
+/// use poem_openapi::{
+///     payload::{PlainText, WithCookie},
+///     OpenApi,
+/// };
+///
+/// struct Api {
+///     key: CookieKey,
+/// }
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/login", method = "post")]
+///     async fn login(&self) -> WithCookie<PlainText<&'static str>> {
+///         let mut cookie = Cookie::new_with_str("session", "alice");
+///         cookie.set_same_site(SameSite::Strict);
+///         cookie.set_secure(true);
+///         cookie.set_http_only(true);
+///         WithCookie::new(PlainText("hello")).signed_cookie(cookie, &self.key)
+///     }
+/// }
+/// ```
+pub struct WithCookie<T> {
+    inner: T,
+    cookies: Vec<Cookie>,
+}
+
+impl<T> WithCookie<T> {
+    /// Create a response object without cookies.
+    #[must_use]
+    pub fn new(resp: T) -> Self {
+        Self {
+            inner: resp,
+            cookies: Vec::new(),
+        }
+    }
+
+    /// Sets a cookie.
+    #[must_use]
+    pub fn cookie(mut self, cookie: Cookie) -> Self {
+        self.cookies.push(cookie);
+        self
+    }
+
+    /// Sets a cookie, the value is signed with the key.
+    #[must_use]
+    pub fn signed_cookie(self, cookie: Cookie, key: &CookieKey) -> Self {
+        let name = cookie.name().to_string();
+        let jar = CookieJar::default();
+        jar.signed_with_key(key).add(cookie);
+        match jar.get(&name) {
+            Some(cookie) => self.cookie(cookie),
+            None => self,
+        }
+    }
+
+    /// Sets a cookie, the value is encrypted with the key.
+    #[must_use]
+    pub fn private_cookie(self, cookie: Cookie, key: &CookieKey) -> Self {
+        let name = cookie.name().to_string();
+        let jar = CookieJar::default();
+        jar.private_with_key(key).add(cookie);
+        match jar.get(&name) {
+            Some(cookie) => self.cookie(cookie),
+            None => self,
+        }
+    }
+
+    /// Removes a cookie from the client.
+    #[must_use]
+    pub fn remove_cookie(self, name: impl Into<String>) -> Self {
+        let mut cookie = Cookie::named(name);
+        cookie.make_removal();
+        self.cookie(cookie)
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for WithCookie<T> {
+    fn into_response(self) -> poem::Response {
+        let mut resp = self.inner.into_response();
+        for cookie in self.cookies {
+            if let Ok(value) = HeaderValue::from_str(&cookie.to_string()) {
+                resp.headers_mut().append(header::SET_COOKIE, value);
+            }
+        }
+        resp
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for WithCookie<T> {
+    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER;
+
+    fn meta() -> MetaResponses {
+        let mut meta = T::meta();
+        for resp in &mut meta.responses {
+            resp.headers.push(MetaHeader {
+                name: "Set-Cookie",
+                description: Some("The cookies set by the response."),
+                required: false,
+                deprecated: false,
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+            });
+        }
+        meta
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        T::operation_extensions()
+    }
+
+    fn from_parse_request_error(err: Error) -> Self {
+        Self::new(T::from_parse_request_error(err))
+    }
+}
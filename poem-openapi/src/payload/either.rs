@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use poem::{Error, IntoResponse};
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// A response that is one of two response types.
+///
+/// The responses of both types are documented, the responses with the same
+/// status are merged, and the schemas with the same content type are
+/// combined with `oneOf`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::{Either, Json, PlainText},
+///     ApiResponse, OpenApi,
+/// };
+///
+/// #[derive(ApiResponse)]
+/// enum NotFound {
+///     #[oai(status = 404)]
+///     NotFound,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users/:id", method = "get")]
+///     async fn user(
+///         &self,
+///         id: poem_openapi::param::Path<i64>,
+///     ) -> Either<PlainText<String>, NotFound> {
+///         if id.0 == 1 {
+///             Either::A(PlainText("alice".to_string()))
+///         } else {
+///             Either::B(NotFound::NotFound)
+///         }
+///     }
+/// }
+/// ```
+pub enum Either<A, B> {
+    /// The first response type.
+    A(A),
+    /// The second response type.
+    B(B),
+}
+
+impl<A: IntoResponse, B: IntoResponse> IntoResponse for Either<A, B> {
+    fn into_response(self) -> poem::Response {
+        match self {
+            Either::A(a) => a.into_response(),
+            Either::B(b) => b.into_response(),
+        }
+    }
+}
+
+fn merge_schema(a: MetaSchemaRef, b: MetaSchemaRef) -> MetaSchemaRef {
+    if a == b {
+        return a;
+    }
+
+    let mut one_of = Vec::new();
+    for schema in [a, b] {
+        match schema {
+            MetaSchemaRef::Inline(schema) if schema.ty.is_empty() && !schema.one_of.is_empty() => {
+                one_of.extend(schema.one_of);
+            }
+            schema => one_of.push(schema),
+        }
+    }
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        one_of,
+        ..MetaSchema::ANY
+    }))
+}
+
+impl<A: ApiResponse, B: ApiResponse> ApiResponse for Either<A, B> {
+    const BAD_REQUEST_HANDLER: bool = A::BAD_REQUEST_HANDLER || B::BAD_REQUEST_HANDLER;
+
+    fn meta() -> MetaResponses {
+        let mut responses = A::meta().responses;
+
+        for resp in B::meta().responses {
+            let existing = match responses.iter_mut().find(|item| item.status == resp.status) {
+                Some(existing) => existing,
+                None => {
+                    responses.push(resp);
+                    continue;
+                }
+            };
+
+            if existing.description.is_empty() {
+                existing.description = resp.description;
+            }
+            for media in resp.content {
+                match existing
+                    .content
+                    .iter_mut()
+                    .find(|item| item.content_type == media.content_type)
+                {
+                    Some(item) => {
+                        let schema = std::mem::replace(
+                            &mut item.schema,
+                            MetaSchemaRef::Inline(Box::new(MetaSchema::ANY)),
+                        );
+                        item.schema = merge_schema(schema, media.schema);
+                    }
+                    None => existing.content.push(media),
+                }
+            }
+            // a header is only required if both responses require it
+            for item in &mut existing.headers {
+                item.required = item.required
+                    && resp
+                        .headers
+                        .iter()
+                        .any(|header| header.name == item.name && header.required);
+            }
+            for mut header in resp.headers {
+                if !existing.headers.iter().any(|item| item.name == header.name) {
+                    header.required = false;
+                    existing.headers.push(header);
+                }
+            }
+        }
+
+        MetaResponses { responses }
+    }
+
+    fn register(registry: &mut Registry) {
+        A::register(registry);
+        B::register(registry);
+    }
+
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        let mut extensions = A::operation_extensions();
+        extensions.extend(B::operation_extensions());
+        extensions
+    }
+
+    fn from_parse_request_error(err: Error) -> Self {
+        if A::BAD_REQUEST_HANDLER {
+            Either::A(A::from_parse_request_error(err))
+        } else {
+            Either::B(B::from_parse_request_error(err))
+        }
+    }
+}
@@ -39,6 +39,17 @@ impl<T> EventStream<T> {
             ..self
         }
     }
+
+    /// Converts the items to the events with the function, instead of sending
+    /// them as the JSON data of the message events.
+    ///
+    /// It can be used to set the id or the type of the events.
+    pub fn to_event<F>(self, f: F) -> CustomEventStream<T, F> {
+        CustomEventStream {
+            inner: self,
+            to_event: f,
+        }
+    }
 }
 
 impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON> Payload for EventStream<T> {
@@ -88,3 +99,79 @@ impl<T: Stream<Item = E> + Send + 'static, E: Type + ToJSON> ApiResponse for Eve
         E::register(registry);
     }
 }
+
+/// An event stream payload whose items are converted to the events with a
+/// function, it is created with [`EventStream::to_event`].
+pub struct CustomEventStream<T, F> {
+    inner: EventStream<T>,
+    to_event: F,
+}
+
+impl<T, F> CustomEventStream<T, F> {
+    /// Set the keep alive interval.
+    #[must_use]
+    pub fn keep_alive(self, duration: Duration) -> Self {
+        Self {
+            inner: self.inner.keep_alive(duration),
+            ..self
+        }
+    }
+}
+
+impl<T, E, F> Payload for CustomEventStream<T, F>
+where
+    T: Stream<Item = E> + Send + 'static,
+    E: Type,
+    F: FnMut(E) -> Event + Send + 'static,
+{
+    const CONTENT_TYPE: &'static str = "text/event-stream";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(E::schema_ref())),
+            ..MetaSchema::new_with_format("array", "event-stream")
+        }))
+    }
+}
+
+impl<T, E, F> IntoResponse for CustomEventStream<T, F>
+where
+    T: Stream<Item = E> + Send + 'static,
+    E: Type,
+    F: FnMut(E) -> Event + Send + 'static,
+{
+    fn into_response(self) -> Response {
+        let mut sse = SSE::new(self.inner.stream.map(self.to_event));
+
+        if let Some(keep_alive) = self.inner.keep_alive {
+            sse = sse.keep_alive(keep_alive);
+        }
+
+        sse.into_response()
+    }
+}
+
+impl<T, E, F> ApiResponse for CustomEventStream<T, F>
+where
+    T: Stream<Item = E> + Send + 'static,
+    E: Type,
+    F: FnMut(E) -> Event + Send + 'static,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        E::register(registry);
+    }
+}
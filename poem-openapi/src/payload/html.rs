@@ -0,0 +1,227 @@
+use std::ops::{Deref, DerefMut};
+#[cfg(feature = "tera")]
+use std::sync::Arc;
+
+#[cfg(any(feature = "askama", feature = "tera"))]
+use poem::error::InternalServerError;
+use poem::{IntoResponse, Response, Result};
+
+use crate::{
+    payload::Payload,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
+    types::Type,
+    ApiResponse,
+};
+
+/// A template that is rendered to the body of an [`Html`] response.
+///
+/// It is implemented for the [`askama`](https://crates.io/crates/askama)
+/// templates with the `askama` feature, and by [`TeraTemplate`] with the
+/// `tera` feature.
+pub trait HtmlTemplate: Send {
+    /// Renders the template to HTML.
+    fn render_html(&self) -> Result<String>;
+}
+
+#[cfg(feature = "askama")]
+#[cfg_attr(docsrs, doc(cfg(feature = "askama")))]
+impl<T: askama::Template + Send> HtmlTemplate for T {
+    fn render_html(&self) -> Result<String> {
+        askama::Template::render(self).map_err(InternalServerError)
+    }
+}
+
+/// A [`tera`](https://crates.io/crates/tera) template with its context.
+#[cfg(feature = "tera")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub struct TeraTemplate {
+    tera: Arc<tera::Tera>,
+    name: String,
+    context: tera::Context,
+}
+
+#[cfg(feature = "tera")]
+impl TeraTemplate {
+    /// Create a template with the name of the template in the `Tera`
+    /// instance and the context.
+    pub fn new(tera: Arc<tera::Tera>, name: impl Into<String>, context: tera::Context) -> Self {
+        Self {
+            tera,
+            name: name.into(),
+            context,
+        }
+    }
+}
+
+#[cfg(feature = "tera")]
+impl HtmlTemplate for TeraTemplate {
+    fn render_html(&self) -> Result<String> {
+        self.tera
+            .render(&self.name, &self.context)
+            .map_err(InternalServerError)
+    }
+}
+
+/// A server-rendered HTML payload, the template is rendered when the
+/// response is created.
+///
+/// It is documented as a `text/html` string, since the structure of the
+/// pages is not described by a schema. The response is `500 Internal Server
+/// Error` if the template cannot be rendered.
+///
+/// # Examples
+///
+/// ```rust
+/// use poem::Result;
+/// use poem_openapi::{
+///     payload::{Html, HtmlTemplate},
+///     OpenApi,
+/// };
+///
+/// struct Hello {
+///     name: String,
+/// }
+///
+/// impl HtmlTemplate for Hello {
+///     fn render_html(&self) -> Result<String> {
+///         Ok(format!("<p>Hello, {}!</p>", self.name))
+///     }
+/// }
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/hello", method = "get")]
+///     async fn hello(&self) -> Html<Hello> {
+///         Html(Hello {
+///             name: "world".to_string(),
+///         })
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Html<T>(pub T);
+
+impl<T> Deref for Html<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Html<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: HtmlTemplate> Payload for Html<T> {
+    const CONTENT_TYPE: &'static str = "text/html; charset=utf-8";
+
+    fn schema_ref() -> MetaSchemaRef {
+        String::schema_ref()
+    }
+}
+
+impl<T: HtmlTemplate> IntoResponse for Html<T> {
+    fn into_response(self) -> Response {
+        match self.0.render_html() {
+            Ok(html) => Response::builder()
+                .content_type(Self::CONTENT_TYPE)
+                .body(html),
+            Err(err) => err.as_response(),
+        }
+    }
+}
+
+impl<T: HtmlTemplate> ApiResponse for Html<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{error::InternalServerError, http::StatusCode};
+
+    use super::*;
+
+    struct Page(Option<&'static str>);
+
+    impl HtmlTemplate for Page {
+        fn render_html(&self) -> Result<String> {
+            self.0
+                .map(ToString::to_string)
+                .ok_or_else(|| InternalServerError(std::fmt::Error))
+        }
+    }
+
+    #[tokio::test]
+    async fn render() {
+        let resp = Html(Page(Some("<p>hello</p>"))).into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.content_type(), Some("text/html; charset=utf-8"));
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "<p>hello</p>"
+        );
+
+        let resp = Html(Page(None)).into_response();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[cfg(feature = "askama")]
+    #[tokio::test]
+    async fn askama() {
+        #[derive(askama::Template)]
+        #[template(source = "<p>Hello, {{ name }}!</p>", ext = "html")]
+        struct Hello {
+            name: &'static str,
+        }
+
+        let resp = Html(Hello { name: "<world>" }).into_response();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "<p>Hello, &#60;world&#62;!</p>"
+        );
+    }
+
+    #[cfg(feature = "tera")]
+    #[tokio::test]
+    async fn tera() {
+        let mut tera = tera::Tera::default();
+        tera.add_raw_template("hello.html", "<p>Hello, {{ name }}!</p>")
+            .unwrap();
+        let tera = Arc::new(tera);
+        let mut context = tera::Context::new();
+        context.insert("name", "world");
+
+        let resp = Html(TeraTemplate::new(tera.clone(), "hello.html", context)).into_response();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "<p>Hello, world!</p>"
+        );
+
+        let resp = Html(TeraTemplate::new(
+            tera,
+            "missing.html",
+            tera::Context::new(),
+        ))
+        .into_response();
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
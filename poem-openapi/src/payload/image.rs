@@ -0,0 +1,356 @@
+use std::io::{Cursor, Error as IoError, ErrorKind};
+
+use libimage::{codecs, imageops::FilterType, DynamicImage, ImageError, ImageReader, Limits};
+use poem::{
+    error::BadRequest,
+    http::{header, HeaderValue},
+    web::VaryHeaders,
+    Body, IntoResponse, Request, Response, Result,
+};
+
+use crate::{
+    accept::accept_quality,
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse, ResponseContent,
+};
+
+const DEFAULT_MAX_DIMENSION: u32 = 8192;
+const DEFAULT_MAX_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// The output format of an [`Image`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFormat {
+    /// JPEG, the alpha channel is removed.
+    Jpeg,
+    /// PNG.
+    Png,
+    /// Lossless WebP.
+    WebP,
+    /// AVIF, it requires the `image-avif` feature.
+    #[cfg(feature = "image-avif")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "image-avif")))]
+    Avif,
+}
+
+impl ImageFormat {
+    /// The formats in the order of preference when the `Accept` header
+    /// accepts them with the same quality.
+    const ALL: &'static [ImageFormat] = &[
+        #[cfg(feature = "image-avif")]
+        ImageFormat::Avif,
+        ImageFormat::WebP,
+        ImageFormat::Jpeg,
+        ImageFormat::Png,
+    ];
+
+    /// Returns the content type of the format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::WebP => "image/webp",
+            #[cfg(feature = "image-avif")]
+            ImageFormat::Avif => "image/avif",
+        }
+    }
+
+    /// Returns the preferred format that is accepted by the `Accept` header,
+    /// or `None` if none of them is accepted.
+    ///
+    /// The wildcards, such as `image/*`, do not select the formats that are
+    /// not widely supported, WebP and AVIF must be listed explicitly.
+    pub fn negotiate(accept: &str) -> Option<ImageFormat> {
+        let mut selected: Option<(ImageFormat, f32)> = None;
+        for format in ImageFormat::ALL {
+            let quality = match accept_quality(accept, format.content_type()) {
+                Some(quality) => quality,
+                None => continue,
+            };
+            let explicit = accept.split(',').any(|item| {
+                item.split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .eq_ignore_ascii_case(format.content_type())
+            });
+            if quality <= 0.0 || (format.is_modern() && !explicit) {
+                continue;
+            }
+            if selected.map(|(_, q)| quality > q).unwrap_or(true) {
+                selected = Some((*format, quality));
+            }
+        }
+        selected.map(|(format, _)| format)
+    }
+
+    fn is_modern(&self) -> bool {
+        !matches!(self, ImageFormat::Jpeg | ImageFormat::Png)
+    }
+}
+
+/// An image response, the image is resized and encoded to the format that is
+/// negotiated with the `Accept` header.
+///
+/// The image is encoded in a blocking thread when the body is sent, the body
+/// is aborted if the encoding fails. The response is documented with the
+/// content types of all the formats.
+///
+/// # Examples
+///
+/// ```rust
+/// use poem::{Request, Result};
+/// use poem_openapi::{payload::Image, OpenApi};
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/thumbnail", method = "get")]
+///     async fn thumbnail(&self, req: &Request) -> Result<Image> {
+///         let data = std::fs::read("photo.png").unwrap_or_default();
+///         Ok(Image::from_bytes(&data)?.thumbnail(128, 128).negotiate(req))
+///     }
+/// }
+/// ```
+pub struct Image {
+    image: DynamicImage,
+    format: ImageFormat,
+    size: Option<(u32, u32, FilterType)>,
+    quality: u8,
+}
+
+impl Image {
+    /// Create a JPEG image response.
+    pub fn new(image: DynamicImage) -> Self {
+        Self {
+            image,
+            format: ImageFormat::Jpeg,
+            size: None,
+            quality: 80,
+        }
+    }
+
+    /// Decodes the image, the format is guessed from the data.
+    ///
+    /// The width and the height of the image are limited to 8192 pixels and
+    /// the decoder allocates at most 256MiB, use
+    /// [`Image::from_bytes_with_limits`] to change the limits. Returns `400
+    /// Bad Request` if the data is not a supported image or exceeds the
+    /// limits.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(DEFAULT_MAX_DIMENSION);
+        limits.max_image_height = Some(DEFAULT_MAX_DIMENSION);
+        limits.max_alloc = Some(DEFAULT_MAX_ALLOC);
+        Self::from_bytes_with_limits(data, limits)
+    }
+
+    /// Decodes the image with the limits of the decoder, the format is guessed
+    /// from the data.
+    pub fn from_bytes_with_limits(data: &[u8], limits: Limits) -> Result<Self> {
+        let mut reader = ImageReader::new(Cursor::new(data))
+            .with_guessed_format()
+            .map_err(BadRequest)?;
+        reader.limits(limits);
+        Ok(Self::new(reader.decode().map_err(BadRequest)?))
+    }
+
+    /// Specify the output format.
+    #[must_use]
+    pub fn format(self, format: ImageFormat) -> Self {
+        Self { format, ..self }
+    }
+
+    /// Selects the output format with the `Accept` header of the request, the
+    /// format is not changed if none of the formats is accepted.
+    ///
+    /// The `Accept` header is recorded in the
+    /// [`VaryHeaders`](poem::web::VaryHeaders).
+    #[must_use]
+    pub fn negotiate(self, req: &Request) -> Self {
+        VaryHeaders::record(req, header::ACCEPT);
+        let accept = req
+            .headers()
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        match ImageFormat::negotiate(&accept) {
+            Some(format) => self.format(format),
+            None => self,
+        }
+    }
+
+    /// Resizes the image to fit in the bounds with a fast filter, the aspect
+    /// ratio is preserved.
+    #[must_use]
+    pub fn thumbnail(self, max_width: u32, max_height: u32) -> Self {
+        Self {
+            size: Some((max_width, max_height, FilterType::Triangle)),
+            ..self
+        }
+    }
+
+    /// Resizes the image to fit in the bounds with the filter, the aspect
+    /// ratio is preserved.
+    #[must_use]
+    pub fn resize(self, max_width: u32, max_height: u32, filter: FilterType) -> Self {
+        Self {
+            size: Some((max_width, max_height, filter)),
+            ..self
+        }
+    }
+
+    /// Specify the quality of the lossy formats, from 1 to 100, the default is
+    /// 80.
+    #[must_use]
+    pub fn quality(self, quality: u8) -> Self {
+        Self {
+            quality: quality.clamp(1, 100),
+            ..self
+        }
+    }
+
+    fn encode(self) -> Result<Vec<u8>, ImageError> {
+        let image = match self.size {
+            Some((width, height, filter)) => self.image.resize(width, height, filter),
+            None => self.image,
+        };
+        let mut data = Cursor::new(Vec::new());
+        match self.format {
+            ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.into_rgb8()).write_with_encoder(
+                codecs::jpeg::JpegEncoder::new_with_quality(&mut data, self.quality),
+            ),
+            ImageFormat::Png => image.write_with_encoder(codecs::png::PngEncoder::new(&mut data)),
+            ImageFormat::WebP => {
+                image.write_with_encoder(codecs::webp::WebPEncoder::new_lossless(&mut data))
+            }
+            #[cfg(feature = "image-avif")]
+            ImageFormat::Avif => image.write_with_encoder(
+                codecs::avif::AvifEncoder::new_with_speed_quality(&mut data, 8, self.quality),
+            ),
+        }?;
+        Ok(data.into_inner())
+    }
+}
+
+impl IntoResponse for Image {
+    fn into_response(self) -> Response {
+        let content_type = self.format.content_type();
+        let stream = futures_util::stream::once(async move {
+            match tokio::task::spawn_blocking(move || self.encode()).await {
+                Ok(Ok(data)) => Ok(bytes::Bytes::from(data)),
+                Ok(Err(err)) => Err(IoError::new(ErrorKind::InvalidData, err)),
+                Err(err) => Err(IoError::from(err)),
+            }
+        });
+        Response::builder()
+            .header(header::CONTENT_TYPE, HeaderValue::from_static(content_type))
+            .body(Body::from_bytes_stream(stream))
+    }
+}
+
+impl ResponseContent for Image {
+    fn media_types() -> Vec<MetaMediaType> {
+        ImageFormat::ALL
+            .iter()
+            .map(|format| MetaMediaType {
+                content_type: format.content_type(),
+                schema: MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    format: Some("binary"),
+                    ..MetaSchema::new("string")
+                })),
+            })
+            .collect()
+    }
+}
+
+impl ApiResponse for Image {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: Self::media_types(),
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use libimage::{GenericImageView, Rgba, RgbaImage};
+    use poem::http::StatusCode;
+
+    use super::*;
+
+    fn image() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 32, Rgba([255, 0, 0, 255])))
+    }
+
+    #[test]
+    fn negotiate() {
+        assert_eq!(ImageFormat::negotiate(""), None);
+        assert_eq!(ImageFormat::negotiate("text/html"), None);
+        assert_eq!(
+            ImageFormat::negotiate("image/*, */*;q=0.8"),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(
+            ImageFormat::negotiate("image/webp, image/*;q=0.8"),
+            Some(ImageFormat::WebP)
+        );
+        assert_eq!(
+            ImageFormat::negotiate("image/webp;q=0.5, image/png"),
+            Some(ImageFormat::Png)
+        );
+        assert_eq!(
+            ImageFormat::negotiate("image/*, image/jpeg;q=0"),
+            Some(ImageFormat::Png)
+        );
+    }
+
+    #[tokio::test]
+    async fn encode() {
+        let req = Request::builder()
+            .header(header::ACCEPT, "image/webp,image/*")
+            .finish();
+        let resp = Image::new(image())
+            .thumbnail(16, 16)
+            .negotiate(&req)
+            .into_response();
+        assert_eq!(resp.content_type(), Some("image/webp"));
+        let data = resp.into_body().into_vec().await.unwrap();
+        let decoded =
+            libimage::load_from_memory_with_format(&data, libimage::ImageFormat::WebP).unwrap();
+        assert_eq!(decoded.dimensions(), (16, 8));
+
+        let resp = Image::new(image()).into_response();
+        assert_eq!(resp.content_type(), Some("image/jpeg"));
+        let data = resp.into_body().into_vec().await.unwrap();
+        let decoded =
+            libimage::load_from_memory_with_format(&data, libimage::ImageFormat::Jpeg).unwrap();
+        assert_eq!(decoded.dimensions(), (64, 32));
+    }
+
+    #[test]
+    fn from_bytes() {
+        let mut data = Vec::new();
+        image()
+            .write_to(&mut Cursor::new(&mut data), libimage::ImageFormat::Png)
+            .unwrap();
+        assert!(Image::from_bytes(&data).is_ok());
+
+        let err = Image::from_bytes(b"not an image").err().unwrap();
+        assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(32);
+        let err = Image::from_bytes_with_limits(&data, limits).err().unwrap();
+        assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+    }
+}
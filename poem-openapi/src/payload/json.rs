@@ -4,11 +4,12 @@ use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result};
 use serde_json::Value;
 
 use crate::{
-    error::ParseJsonError,
-    payload::{ParsePayload, Payload},
-    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
+    deserializer,
+    error::{ContentTypeError, ParseJsonError},
+    payload::{ContentTypeTable, ParsePayload, Payload},
+    registry::{MetaMediaType, MetaRequest, MetaResponse, MetaResponses, MetaSchemaRef, Registry},
     types::{ParseFromJSON, ToJSON, Type},
-    ApiResponse,
+    ApiExtractor, ApiExtractorType, ApiResponse, ExtractParamOptions,
 };
 
 /// A JSON payload.
@@ -50,6 +51,10 @@ impl<T: ParseFromJSON> ParsePayload for Json<T> {
         let data: Vec<u8> = FromRequest::from_request(request, body).await?;
         let value = if data.is_empty() {
             Value::Null
+        } else if let Some(deserializer) = deserializer::find(request, &T::name()) {
+            deserializer
+                .deserialize(&data)
+                .map_err(|reason| ParseJsonError { reason })?
         } else {
             serde_json::from_slice(&data).map_err(|err| ParseJsonError {
                 reason: err.to_string(),
@@ -89,4 +94,51 @@ impl<T: ToJSON> ApiResponse for Json<T> {
     }
 }
 
-impl_apirequest_for_payload!(Json<T>, T: ParseFromJSON);
+// The `Content-Type` of the request can also be one of the deserializers of
+// the service.
+#[poem::async_trait]
+impl<'a, T: ParseFromJSON> ApiExtractor<'a> for Json<T> {
+    const TYPE: ApiExtractorType = ApiExtractorType::RequestObject;
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn register(registry: &mut Registry) {
+        <Self as Payload>::register(registry);
+    }
+
+    fn request_meta() -> Option<MetaRequest> {
+        Some(MetaRequest {
+            description: None,
+            content: vec![MetaMediaType {
+                content_type: Self::CONTENT_TYPE,
+                schema: Self::schema_ref(),
+            }],
+            required: <Self as ParsePayload>::IS_REQUIRED,
+        })
+    }
+
+    async fn from_request(
+        request: &'a Request,
+        body: &mut RequestBody,
+        _param_opts: ExtractParamOptions<Self::ParamType>,
+    ) -> Result<Self> {
+        match request.content_type() {
+            Some(content_type) => {
+                if ContentTypeTable::new(&[Self::CONTENT_TYPE])
+                    .matches(content_type)
+                    .is_none()
+                    && deserializer::find(request, &T::name()).is_none()
+                {
+                    return Err(ContentTypeError::NotSupported {
+                        content_type: content_type.to_string(),
+                    }
+                    .into());
+                }
+
+                <Self as ParsePayload>::from_request(request, body).await
+            }
+            None => Err(ContentTypeError::ExpectContentType.into()),
+        }
+    }
+}
@@ -1,22 +1,86 @@
 //! Commonly used payload types.
 
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "arrow")]
+mod arrow;
 mod attachment;
 mod binary;
+mod binary_stream;
+mod cookie;
+mod either;
+#[cfg(feature = "sse")]
 mod event_stream;
+mod html;
+#[cfg(feature = "image")]
+mod image;
 mod json;
+mod modify_response;
+#[cfg(feature = "multipart")]
+mod multipart_mixed;
+#[cfg(feature = "parquet")]
+mod parquet;
 mod plain_text;
+#[cfg(feature = "prost")]
+mod protobuf;
+mod redirect;
 mod response;
+mod status;
+mod versioned;
+#[cfg(feature = "websocket")]
+mod websocket;
 
 use std::str::FromStr;
 
 use mime::Mime;
 use poem::{Request, RequestBody, Result};
 
+#[cfg(feature = "archive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "archive")))]
+pub use self::archive::{Archive, ArchiveEntry, ArchiveFormat};
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub use self::arrow::ArrowStream;
+#[cfg(feature = "sse")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sse")))]
+pub use self::event_stream::{CustomEventStream, EventStream};
+#[cfg(feature = "tera")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tera")))]
+pub use self::html::TeraTemplate;
+#[cfg(feature = "image")]
+#[cfg_attr(docsrs, doc(cfg(feature = "image")))]
+pub use self::image::{Image, ImageFormat};
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
+pub use self::multipart_mixed::{MixedPart, MultipartMixed};
+#[cfg(feature = "parquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+pub use self::parquet::Parquet;
+#[cfg(feature = "prost")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prost")))]
+pub use self::protobuf::Protobuf;
+#[cfg(feature = "websocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
+pub use self::websocket::{TypedWebSocket, WebSocketConnection};
 pub use self::{
-    attachment::Attachment, binary::Binary, event_stream::EventStream, json::Json,
-    plain_text::PlainText, response::Response,
+    attachment::Attachment,
+    binary::Binary,
+    binary_stream::BinaryStream,
+    cookie::WithCookie,
+    either::Either,
+    html::{Html, HtmlTemplate},
+    json::Json,
+    modify_response::ModifyResponse,
+    plain_text::PlainText,
+    redirect::{
+        Found, LocationTemplate, MovedPermanently, PermanentRedirect, Redirect, SeeOther,
+        TemporaryRedirect,
+    },
+    response::Response,
+    status::{Accepted, Created, NoContent},
+    versioned::{HasVersion, Versioned},
 };
-use crate::registry::{MetaSchemaRef, Registry};
+use crate::registry::{MetaOperationParam, MetaResponse, MetaSchemaRef, Registry};
 
 /// Represents a payload type.
 pub trait Payload: Send {
@@ -37,6 +101,18 @@ pub trait ParsePayload: Sized {
     /// If it is `true`, it means that this payload is required.
     const IS_REQUIRED: bool;
 
+    /// Returns the parameters that are read by this payload besides the body,
+    /// for example the headers of a conditional request.
+    fn params() -> Vec<MetaOperationParam> {
+        Vec::new()
+    }
+
+    /// Returns the responses of the errors that are reported by this payload
+    /// besides the parse errors.
+    fn responses() -> Vec<MetaResponse> {
+        Vec::new()
+    }
+
     /// Parse the payload object from the HTTP request.
     async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self>;
 }
@@ -69,16 +145,56 @@ impl ContentTypeTable {
         ContentTypeTable { items }
     }
 
+    /// Returns the index of the content type that matches, the structured
+    /// suffixes such as `application/problem+json` match `application/json`
+    /// if no other content type matches.
     pub fn matches(&self, content_type: &str) -> Option<usize> {
-        for (mime, idx) in &self.items {
-            if let Ok(x) = Mime::from_str(content_type) {
-                if (x.type_() == mime.type_() || mime.type_() == mime::STAR)
-                    && (x.subtype() == mime.subtype() || mime.subtype() == mime::STAR)
-                {
-                    return Some(*idx);
-                }
-            }
+        let x = Mime::from_str(content_type).ok()?;
+        let matches = |mime: &Mime, subtype_matches: bool| {
+            (x.type_() == mime.type_() || mime.type_() == mime::STAR)
+                && subtype_matches
+                && charset_matches(mime, &x)
+        };
+        self.items
+            .iter()
+            .find(|(mime, _)| {
+                matches(
+                    mime,
+                    x.subtype() == mime.subtype() || mime.subtype() == mime::STAR,
+                )
+            })
+            .or_else(|| {
+                self.items.iter().find(|(mime, _)| {
+                    matches(
+                        mime,
+                        x.suffix()
+                            .map(|suffix| suffix == mime.subtype())
+                            .unwrap_or_default(),
+                    )
+                })
+            })
+            .map(|(_, idx)| *idx)
+    }
+}
+
+/// The `charset` of the request must be the `charset` of the content type,
+/// the textual content types are decoded as `utf-8` if it is not specified.
+fn charset_matches(mime: &Mime, x: &Mime) -> bool {
+    let charset = match x.get_param(mime::CHARSET) {
+        Some(charset) => charset,
+        None => return true,
+    };
+    match mime.get_param(mime::CHARSET) {
+        Some(expected) => charset == expected.as_str(),
+        None => {
+            let textual = mime.type_() == mime::TEXT
+                || [mime.subtype(), mime.suffix().unwrap_or(mime.subtype())]
+                    .iter()
+                    .any(|name| *name == mime::JSON || *name == mime::XML);
+            !textual
+                || [mime::UTF_8.as_str(), "utf8", "us-ascii"]
+                    .iter()
+                    .any(|name| charset == *name)
         }
-        None
     }
 }
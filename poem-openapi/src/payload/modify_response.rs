@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use poem::{Error, IntoResponse};
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaResponses, Registry},
+    ApiResponse,
+};
+
+/// A response type wrapper that modifies the [`poem::Response`] created by
+/// `T` with a function, the responses are documented as those of `T`.
+///
+/// Use it for the rare cases that the typed responses cannot express,
+/// without giving up the `OpenApi` macro for the operation.
+///
+/// # Example
+///
+/// ```
+/// use poem::{http::header, Request};
+/// use poem_openapi::{
+///     param::Query,
+///     payload::{ModifyResponse, PlainText},
+///     OpenApi,
+/// };
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/hello", method = "get")]
+///     async fn hello(
+///         &self,
+///         req: &Request,
+///         name: Query<String>,
+///     ) -> ModifyResponse<PlainText<String>> {
+///         let user_agent = req
+///             .header(header::USER_AGENT)
+///             .unwrap_or_default()
+///             .to_string();
+///         ModifyResponse::new(PlainText(format!("hello, {}", name.0)), move |resp| {
+///             resp.headers_mut()
+///                 .insert("X-User-Agent", user_agent.parse().unwrap());
+///         })
+///     }
+/// }
+/// ```
+pub struct ModifyResponse<T> {
+    inner: T,
+    f: Box<dyn FnOnce(&mut poem::Response) + Send + Sync>,
+}
+
+impl<T> ModifyResponse<T> {
+    /// Create a response object that is modified by the function.
+    #[must_use]
+    pub fn new<F>(resp: T, f: F) -> Self
+    where
+        F: FnOnce(&mut poem::Response) + Send + Sync + 'static,
+    {
+        Self {
+            inner: resp,
+            f: Box::new(f),
+        }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for ModifyResponse<T> {
+    fn into_response(self) -> poem::Response {
+        let mut resp = self.inner.into_response();
+        (self.f)(&mut resp);
+        resp
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for ModifyResponse<T> {
+    const BAD_REQUEST_HANDLER: bool = T::BAD_REQUEST_HANDLER;
+
+    fn meta() -> MetaResponses {
+        T::meta()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        T::operation_extensions()
+    }
+
+    fn from_parse_request_error(err: Error) -> Self {
+        Self::new(T::from_parse_request_error(err), |_| {})
+    }
+}
@@ -0,0 +1,449 @@
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    io::{Error as IoError, ErrorKind},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use poem::{
+    http::{header, HeaderMap, HeaderValue},
+    Body, IntoResponse, Request, RequestBody, Response, Result,
+};
+
+use crate::{
+    error::{ParseJsonError, ParseMultipartError},
+    payload::{ParsePayload, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    types::ParseFromJSON,
+    ApiResponse,
+};
+
+/// A part of a [`MultipartMixed`] payload.
+#[derive(Debug, Clone, Default)]
+pub struct MixedPart {
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl MixedPart {
+    /// Create a part with the specified content type and body.
+    pub fn new(content_type: &str, body: impl Into<Bytes>) -> Self {
+        Self::default()
+            .header(header::CONTENT_TYPE.as_str(), content_type)
+            .body(body)
+    }
+
+    /// Create a part with a JSON body.
+    pub fn json(value: &impl crate::types::ToJSON) -> Self {
+        Self::new(
+            "application/json",
+            serde_json::to_vec(&value.to_json()).unwrap_or_default(),
+        )
+    }
+
+    /// Sets a header of this part, invalid header names or values are
+    /// ignored.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            header::HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Sets the body of this part.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Returns the headers of this part.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the content type of this part.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns the value of the `Content-ID` header of this part.
+    pub fn content_id(&self) -> Option<&str> {
+        self.headers
+            .get("content-id")
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns the body of this part.
+    #[inline]
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Consumes this part to return the body.
+    #[inline]
+    pub fn into_bytes(self) -> Bytes {
+        self.body
+    }
+
+    /// Parses the body of this part as JSON.
+    pub fn parse_json<T: ParseFromJSON>(&self) -> Result<T, ParseJsonError> {
+        let value = serde_json::from_slice(&self.body).map_err(|err| ParseJsonError {
+            reason: err.to_string(),
+        })?;
+        T::parse_from_json(value).map_err(|err| ParseJsonError {
+            reason: err.into_message(),
+        })
+    }
+
+    /// Parses the body of this part as a nested multipart payload, the content
+    /// type of this part must contain the boundary.
+    pub fn into_multipart(self) -> Result<MultipartMixed, ParseMultipartError> {
+        let boundary = self
+            .content_type()
+            .and_then(parse_boundary)
+            .ok_or_else(|| ParseMultipartError {
+                reason: "the part is not a multipart body".to_string(),
+            })?;
+        let body = self.body;
+        Ok(MultipartMixed::from_stream(
+            futures_util::stream::once(async move { Ok::<_, Infallible>(body) }),
+            boundary,
+        ))
+    }
+}
+
+enum Source {
+    Stream(multer::Multipart<'static>),
+    Parts(VecDeque<MixedPart>),
+}
+
+/// A `multipart/mixed` payload, the parts are read one by one with
+/// [`MultipartMixed::next_part`].
+///
+/// It can also be used as a response, the parts are written in order.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::{MixedPart, MultipartMixed},
+///     OpenApi,
+/// };
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/echo", method = "post")]
+///     async fn echo(&self, mut body: MultipartMixed) -> poem::Result<MultipartMixed> {
+///         let mut resp = MultipartMixed::new();
+///         while let Some(part) = body.next_part().await? {
+///             resp = resp.part(MixedPart::new("text/plain", part.into_bytes()));
+///         }
+///         Ok(resp)
+///     }
+/// }
+/// ```
+pub struct MultipartMixed {
+    boundary: String,
+    source: Source,
+}
+
+impl Default for MultipartMixed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultipartMixed {
+    /// Create an empty multipart payload with a generated boundary.
+    pub fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        Self::with_boundary(format!(
+            "poem-{:x}-{:x}",
+            nanos,
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ))
+    }
+
+    /// Create an empty multipart payload with the specified boundary.
+    pub fn with_boundary(boundary: impl Into<String>) -> Self {
+        Self {
+            boundary: boundary.into(),
+            source: Source::Parts(VecDeque::new()),
+        }
+    }
+
+    fn from_stream<S, E>(stream: S, boundary: String) -> Self
+    where
+        S: futures_util::Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        Self {
+            source: Source::Stream(multer::Multipart::new(stream, boundary.clone())),
+            boundary,
+        }
+    }
+
+    /// Appends a part, the parts that have not been read from a request are
+    /// discarded.
+    #[must_use]
+    pub fn part(mut self, part: MixedPart) -> Self {
+        match &mut self.source {
+            Source::Parts(parts) => parts.push_back(part),
+            Source::Stream(_) => {
+                let mut parts = VecDeque::new();
+                parts.push_back(part);
+                self.source = Source::Parts(parts);
+            }
+        }
+        self
+    }
+
+    /// Returns the boundary of this payload.
+    #[inline]
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// Returns the content type of this payload, including the boundary.
+    pub fn content_type(&self) -> String {
+        format!("{}; boundary={}", Self::CONTENT_TYPE, self.boundary)
+    }
+
+    /// Yields the next part if available.
+    pub async fn next_part(&mut self) -> Result<Option<MixedPart>, ParseMultipartError> {
+        match &mut self.source {
+            Source::Stream(multipart) => {
+                let field = match multipart.next_field().await {
+                    Ok(Some(field)) => field,
+                    Ok(None) => return Ok(None),
+                    Err(err) => {
+                        return Err(ParseMultipartError {
+                            reason: err.to_string(),
+                        })
+                    }
+                };
+                let headers = field.headers().clone();
+                let body = field.bytes().await.map_err(|err| ParseMultipartError {
+                    reason: err.to_string(),
+                })?;
+                Ok(Some(MixedPart { headers, body }))
+            }
+            Source::Parts(parts) => Ok(parts.pop_front()),
+        }
+    }
+
+    /// Consumes this payload to return a part that contains it, this is used
+    /// to create nested multipart bodies.
+    pub async fn into_part(mut self) -> Result<MixedPart, ParseMultipartError> {
+        let content_type = self.content_type();
+        let mut data = BytesMut::new();
+        while let Some(part) = self.next_part().await? {
+            write_part(&mut data, &self.boundary, &part);
+        }
+        write_end(&mut data, &self.boundary);
+        Ok(MixedPart::new(&content_type, data.freeze()))
+    }
+}
+
+fn parse_boundary(content_type: &str) -> Option<String> {
+    let mime: mime::Mime = content_type.parse().ok()?;
+    if mime.type_() != mime::MULTIPART {
+        return None;
+    }
+    mime.get_param(mime::BOUNDARY)
+        .map(|boundary| boundary.as_str().to_string())
+}
+
+fn write_part(data: &mut BytesMut, boundary: &str, part: &MixedPart) {
+    data.put_slice(b"--");
+    data.put_slice(boundary.as_bytes());
+    data.put_slice(b"\r\n");
+    for (name, value) in &part.headers {
+        data.put_slice(name.as_str().as_bytes());
+        data.put_slice(b": ");
+        data.put_slice(value.as_bytes());
+        data.put_slice(b"\r\n");
+    }
+    data.put_slice(b"\r\n");
+    data.put_slice(&part.body);
+    data.put_slice(b"\r\n");
+}
+
+fn write_end(data: &mut BytesMut, boundary: &str) {
+    data.put_slice(b"--");
+    data.put_slice(boundary.as_bytes());
+    data.put_slice(b"--\r\n");
+}
+
+impl Payload for MultipartMixed {
+    const CONTENT_TYPE: &'static str = "multipart/mixed";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(MetaSchemaRef::Inline(Box::new(
+                MetaSchema::new_with_format("string", "binary"),
+            )))),
+            ..MetaSchema::new("array")
+        }))
+    }
+}
+
+#[poem::async_trait]
+impl ParsePayload for MultipartMixed {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let boundary = request
+            .content_type()
+            .and_then(parse_boundary)
+            .ok_or_else(|| ParseMultipartError {
+                reason: "the boundary is required".to_string(),
+            })?;
+        Ok(Self::from_stream(
+            body.take()?.into_bytes_stream(),
+            boundary,
+        ))
+    }
+}
+
+impl IntoResponse for MultipartMixed {
+    fn into_response(self) -> Response {
+        let content_type = self.content_type();
+        let body = futures_util::stream::unfold(Some(self), |payload| async move {
+            let mut payload = payload?;
+            let mut data = BytesMut::new();
+            match payload.next_part().await {
+                Ok(Some(part)) => {
+                    write_part(&mut data, &payload.boundary, &part);
+                    Some((Ok(data.freeze()), Some(payload)))
+                }
+                Ok(None) => {
+                    write_end(&mut data, &payload.boundary);
+                    Some((Ok(data.freeze()), None))
+                }
+                Err(err) => Some((Err(IoError::new(ErrorKind::Other, err.reason)), None)),
+            }
+        });
+
+        Response::builder()
+            .content_type(&content_type)
+            .body(Body::from_bytes_stream(body))
+    }
+}
+
+impl ApiResponse for MultipartMixed {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+impl_apirequest_for_payload!(MultipartMixed);
+
+#[cfg(test)]
+mod tests {
+    use poem::http::StatusCode;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn parse() {
+        let data = "--X-BOUNDARY\r\nContent-Type: text/plain\r\nContent-ID: <a>\r\n\r\nabc\r\n--X-BOUNDARY\r\nContent-Type: application/json\r\n\r\n[1,2]\r\n--X-BOUNDARY--\r\n";
+        let mut payload = MultipartMixed::from_request(
+            &Request::builder()
+                .header("content-type", "multipart/mixed; boundary=X-BOUNDARY")
+                .finish(),
+            &mut RequestBody::new(data.into()),
+        )
+        .await
+        .unwrap();
+
+        let part = payload.next_part().await.unwrap().unwrap();
+        assert_eq!(part.content_type(), Some("text/plain"));
+        assert_eq!(part.content_id(), Some("<a>"));
+        assert_eq!(part.bytes(), &Bytes::from_static(b"abc"));
+
+        let part = payload.next_part().await.unwrap().unwrap();
+        assert_eq!(part.parse_json::<Vec<i32>>().unwrap(), vec![1, 2]);
+
+        assert!(payload.next_part().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn response() {
+        let resp = MultipartMixed::with_boundary("X-BOUNDARY")
+            .part(MixedPart::new("text/plain", "abc"))
+            .part(MixedPart::json(&100))
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "multipart/mixed; boundary=X-BOUNDARY"
+        );
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            "--X-BOUNDARY\r\ncontent-type: text/plain\r\n\r\nabc\r\n--X-BOUNDARY\r\ncontent-type: application/json\r\n\r\n100\r\n--X-BOUNDARY--\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn nested() {
+        let inner = MultipartMixed::with_boundary("INNER")
+            .part(MixedPart::new("text/plain", "abc"))
+            .into_part()
+            .await
+            .unwrap();
+        let data = MultipartMixed::with_boundary("OUTER")
+            .part(inner)
+            .into_response()
+            .into_body()
+            .into_bytes()
+            .await
+            .unwrap();
+
+        let mut outer = MultipartMixed::from_stream(
+            futures_util::stream::once(async move { Ok::<_, Infallible>(data) }),
+            "OUTER".to_string(),
+        );
+        let mut inner = outer
+            .next_part()
+            .await
+            .unwrap()
+            .unwrap()
+            .into_multipart()
+            .unwrap();
+        assert_eq!(
+            inner.next_part().await.unwrap().unwrap().into_bytes(),
+            Bytes::from_static(b"abc")
+        );
+        assert!(inner.next_part().await.unwrap().is_none());
+    }
+}
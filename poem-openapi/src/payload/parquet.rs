@@ -0,0 +1,188 @@
+use std::io::Error as IoError;
+
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use bytes::Bytes;
+use futures_util::Stream;
+use libparquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+use poem::{IntoResponse, Response};
+
+use crate::{
+    payload::{
+        arrow::{batch_body, BatchWriter},
+        Payload,
+    },
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+impl BatchWriter for ArrowWriter<Vec<u8>> {
+    fn write(&mut self, batch: &RecordBatch) -> Result<Bytes, IoError> {
+        ArrowWriter::write(self, batch).map_err(IoError::other)?;
+        Ok(std::mem::take(self.inner_mut()).into())
+    }
+
+    fn finish(mut self) -> Result<Bytes, IoError> {
+        ArrowWriter::finish(&mut self).map_err(IoError::other)?;
+        Ok(std::mem::take(self.inner_mut()).into())
+    }
+}
+
+/// A [Parquet](https://parquet.apache.org/) payload, the row groups are sent
+/// as they are filled by the record batches of the stream, and the metadata is
+/// sent after the last batch.
+///
+/// The file is described as a binary string in the specification.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use arrow_array::{Int32Array, RecordBatch};
+/// use arrow_schema::{ArrowError, DataType, Field, Schema};
+/// use futures_util::stream::{self, BoxStream};
+/// use poem_openapi::{payload::Parquet, OpenApi};
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/values", method = "get")]
+///     async fn values(&self) -> Parquet<BoxStream<'static, Result<RecordBatch, ArrowError>>> {
+///         let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Int32, false)]));
+///         let batch = RecordBatch::try_new(
+///             schema.clone(),
+///             vec![Arc::new(Int32Array::from(vec![1, 2, 3]))],
+///         );
+///         Parquet::new(schema, Box::pin(stream::iter(vec![batch])))
+///     }
+/// }
+/// ```
+pub struct Parquet<S> {
+    schema: SchemaRef,
+    batches: S,
+    properties: Option<WriterProperties>,
+}
+
+impl<S> Parquet<S> {
+    /// Create a Parquet payload with the schema of the batches.
+    pub fn new(schema: SchemaRef, batches: S) -> Self {
+        Self {
+            schema,
+            batches,
+            properties: None,
+        }
+    }
+
+    /// Sets the properties of the writer, such as the compression and the
+    /// size of the row groups.
+    #[must_use]
+    pub fn properties(self, properties: WriterProperties) -> Self {
+        Self {
+            properties: Some(properties),
+            ..self
+        }
+    }
+}
+
+impl<S, E> Payload for Parquet<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    const CONTENT_TYPE: &'static str = "application/vnd.apache.parquet";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+impl<S, E> IntoResponse for Parquet<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn into_response(self) -> Response {
+        let writer =
+            ArrowWriter::try_new(Vec::new(), self.schema, self.properties).map_err(IoError::other);
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(batch_body(writer, self.batches))
+    }
+}
+
+impl<S, E> ApiResponse for Parquet<S>
+where
+    S: Stream<Item = Result<RecordBatch, E>> + Send + 'static,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::Int32Array;
+    use arrow_schema::{ArrowError, DataType, Field, Schema};
+    use futures_util::stream;
+    use libparquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn parquet() {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        let batches = (0..3)
+            .map(|n| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(vec![n * 2, n * 2 + 1]))],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let resp = Parquet::new(
+            schema,
+            stream::iter(batches.clone().into_iter().map(Ok::<_, ArrowError>)),
+        )
+        .properties(
+            WriterProperties::builder()
+                .set_max_row_group_size(2)
+                .build(),
+        )
+        .into_response();
+        assert_eq!(resp.content_type(), Some("application/vnd.apache.parquet"));
+
+        let data = resp.into_body().into_bytes().await.unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(data).unwrap();
+        assert_eq!(reader.metadata().num_row_groups(), 3);
+        assert_eq!(
+            reader
+                .with_batch_size(2)
+                .build()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            batches
+        );
+    }
+}
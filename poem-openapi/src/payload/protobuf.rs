@@ -0,0 +1,136 @@
+use std::ops::{Deref, DerefMut};
+
+use bytes::Bytes;
+use poem::{FromRequest, IntoResponse, Request, RequestBody, Response, Result};
+use prost::Message;
+
+use crate::{
+    error::ParseProtobufError,
+    payload::{ParsePayload, Payload},
+    registry::{MetaMediaType, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// A Protobuf payload, the messages are encoded and decoded with the
+/// [`prost` crate](https://crates.io/crates/prost).
+///
+/// The messages are described as binary strings in the specification, since
+/// their schemas are defined by the `.proto` files.
+///
+/// # Examples
+///
+/// ```rust
+/// use poem::{
+///     http::{Method, StatusCode},
+///     Endpoint, IntoEndpoint, Request,
+/// };
+/// use poem_openapi::{payload::Protobuf, OpenApi, OpenApiService};
+/// use prost::Message;
+///
+/// #[derive(Clone, PartialEq, Message)]
+/// struct Point {
+///     #[prost(int32, tag = "1")]
+///     x: i32,
+///     #[prost(int32, tag = "2")]
+///     y: i32,
+/// }
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/move", method = "post")]
+///     async fn move_point(&self, point: Protobuf<Point>) -> Protobuf<Point> {
+///         Protobuf(Point {
+///             x: point.x + 1,
+///             y: point.y + 1,
+///         })
+///     }
+/// }
+///
+/// let api = OpenApiService::new(MyApi, "Demo", "0.1.0").into_endpoint();
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = api
+///     .call(
+///         Request::builder()
+///             .method(Method::POST)
+///             .content_type("application/x-protobuf")
+///             .uri_str("/move")
+///             .body(Point { x: 1, y: 2 }.encode_to_vec()),
+///     )
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::OK);
+/// let data = resp.into_body().into_bytes().await.unwrap();
+/// assert_eq!(Point::decode(data).unwrap(), Point { x: 2, y: 3 });
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Protobuf<T>(pub T);
+
+impl<T> Deref for Protobuf<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Protobuf<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Send> Payload for Protobuf<T> {
+    const CONTENT_TYPE: &'static str = "application/x-protobuf";
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            format: Some("binary"),
+            ..MetaSchema::new("string")
+        }))
+    }
+}
+
+#[poem::async_trait]
+impl<T: Message + Default> ParsePayload for Protobuf<T> {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let data = Bytes::from_request(request, body).await?;
+        let value = T::decode(data).map_err(|err| ParseProtobufError {
+            reason: err.to_string(),
+        })?;
+        Ok(Self(value))
+    }
+}
+
+impl<T: Message> IntoResponse for Protobuf<T> {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .content_type(Self::CONTENT_TYPE)
+            .body(self.0.encode_to_vec())
+    }
+}
+
+impl<T: Message> ApiResponse for Protobuf<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
+
+impl_apirequest_for_payload!(Protobuf<T>, T: Message + Default);
@@ -0,0 +1,151 @@
+use std::marker::PhantomData;
+
+use poem::{
+    http::{header, StatusCode, Uri},
+    IntoResponse, Response,
+};
+
+use crate::{
+    registry::{MetaHeader, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+/// The URI template of the `Location` header of a [`Redirect`], it is
+/// documented as the description of the header.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::payload::{LocationTemplate, SeeOther};
+///
+/// struct Callback;
+///
+/// impl LocationTemplate for Callback {
+///     const TEMPLATE: &'static str = "{redirect_uri}?code={code}&state={state}";
+/// }
+///
+/// type CallbackRedirect = SeeOther<Callback>;
+/// ```
+pub trait LocationTemplate: Send {
+    /// The URI template, such as `/users/{id}`.
+    const TEMPLATE: &'static str;
+}
+
+impl LocationTemplate for () {
+    const TEMPLATE: &'static str = "The URI to redirect to.";
+}
+
+/// A redirect response, the status is one of the redirect status codes and
+/// it is documented with the `Location` header.
+///
+/// Use the type aliases, such as [`SeeOther`], instead of specifying the
+/// status, the other statuses must be `3xx`, or it fails to compile.
+///
+/// # Example
+///
+/// ```
+/// use poem::http::Uri;
+/// use poem_openapi::{
+///     param::Path,
+///     payload::{Found, LocationTemplate},
+///     OpenApi,
+/// };
+///
+/// struct ShortLink;
+///
+/// impl LocationTemplate for ShortLink {
+///     const TEMPLATE: &'static str = "https://example.com/articles/{slug}";
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/s/:code", method = "get")]
+///     async fn short_link(&self, code: Path<String>) -> Found<ShortLink> {
+///         Found::new(Uri::from_static("https://example.com/articles/hello"))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Redirect<L, const STATUS: u16> {
+    uri: Uri,
+    _mark: PhantomData<L>,
+}
+
+/// A `301 Moved Permanently` redirect response.
+pub type MovedPermanently<L = ()> = Redirect<L, 301>;
+
+/// A `302 Found` redirect response.
+pub type Found<L = ()> = Redirect<L, 302>;
+
+/// A `303 See Other` redirect response.
+pub type SeeOther<L = ()> = Redirect<L, 303>;
+
+/// A `307 Temporary Redirect` redirect response.
+pub type TemporaryRedirect<L = ()> = Redirect<L, 307>;
+
+/// A `308 Permanent Redirect` redirect response.
+pub type PermanentRedirect<L = ()> = Redirect<L, 308>;
+
+impl<L, const STATUS: u16> Redirect<L, STATUS> {
+    /// Create a redirect response to the URI.
+    pub fn new(uri: Uri) -> Self {
+        Self::assert_status();
+        Self {
+            uri,
+            _mark: PhantomData,
+        }
+    }
+
+    /// Returns the URI to redirect to.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Fails to compile if the status is not a redirection status, indexing
+    /// out of bounds stands in for the `const` panics that are not available
+    /// on the minimum supported Rust version.
+    const STATUS_MUST_BE_A_REDIRECTION: () = [()][!(STATUS >= 300 && STATUS < 400) as usize];
+
+    fn assert_status() {
+        let () = Self::STATUS_MUST_BE_A_REDIRECTION;
+    }
+
+    fn status() -> StatusCode {
+        Self::assert_status();
+        StatusCode::from_u16(STATUS).expect("redirection status")
+    }
+}
+
+impl<L: LocationTemplate, const STATUS: u16> IntoResponse for Redirect<L, STATUS> {
+    fn into_response(self) -> Response {
+        Self::status()
+            .with_header(header::LOCATION, self.uri.to_string())
+            .into_response()
+    }
+}
+
+impl<L: LocationTemplate, const STATUS: u16> ApiResponse for Redirect<L, STATUS> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: Self::status().canonical_reason().unwrap_or_default(),
+                status: Some(STATUS),
+                content: vec![],
+                headers: vec![MetaHeader {
+                    name: "Location",
+                    description: Some(L::TEMPLATE),
+                    required: true,
+                    deprecated: false,
+                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
+                        "string",
+                        "uri-reference",
+                    ))),
+                }],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
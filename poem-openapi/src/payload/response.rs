@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
+
 use poem::{
     http::{header::HeaderName, HeaderMap, HeaderValue, StatusCode},
     Error, IntoResponse,
 };
+use serde_json::Value;
 
 use crate::{
     registry::{MetaResponses, Registry},
@@ -75,6 +78,10 @@ impl<T: ApiResponse> ApiResponse for Response<T> {
         T::register(registry);
     }
 
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        T::operation_extensions()
+    }
+
     fn from_parse_request_error(err: Error) -> Self {
         Self::new(T::from_parse_request_error(err))
     }
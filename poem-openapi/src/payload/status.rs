@@ -0,0 +1,184 @@
+use poem::{
+    http::{header, HeaderValue, StatusCode, Uri},
+    IntoResponse, Response,
+};
+
+use crate::{
+    registry::{MetaHeader, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef, Registry},
+    ApiResponse,
+};
+
+fn location_header(description: &'static str, required: bool) -> MetaHeader {
+    MetaHeader {
+        name: "Location",
+        description: Some(description),
+        required,
+        deprecated: false,
+        schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "uri"))),
+    }
+}
+
+/// Changes the `200` responses of `T` to the status, and adds the `Location`
+/// header to them.
+fn with_status<T: ApiResponse>(
+    status: StatusCode,
+    location_description: &'static str,
+    location_required: bool,
+) -> MetaResponses {
+    let mut meta = T::meta();
+    for resp in &mut meta.responses {
+        if resp.status == Some(200) {
+            resp.status = Some(status.as_u16());
+            if resp.description.is_empty() {
+                resp.description = status.canonical_reason().unwrap_or_default();
+            }
+            resp.headers
+                .push(location_header(location_description, location_required));
+        }
+    }
+    meta
+}
+
+fn into_response(resp: impl IntoResponse, status: StatusCode, location: Option<Uri>) -> Response {
+    let mut resp = resp.into_response();
+    if resp.status() == StatusCode::OK {
+        resp.set_status(status);
+        if let Some(value) = location.and_then(|uri| HeaderValue::from_str(&uri.to_string()).ok()) {
+            resp.headers_mut().insert(header::LOCATION, value);
+        }
+    }
+    resp
+}
+
+/// A `201 Created` response with the `Location` header of the created
+/// resource, the body is `T`, such as [`Json`](crate::payload::Json).
+///
+/// # Example
+///
+/// ```
+/// use poem::http::Uri;
+/// use poem_openapi::{
+///     payload::{Created, Json},
+///     Object, OpenApi,
+/// };
+///
+/// #[derive(Object)]
+/// struct User {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/users", method = "post")]
+///     async fn create_user(&self, name: Json<String>) -> Created<Json<User>> {
+///         let user = User { id: 1, name: name.0 };
+///         let location = format!("/users/{}", user.id).parse::<Uri>().unwrap();
+///         Created::new(Json(user), location)
+///     }
+/// }
+/// ```
+pub struct Created<T = ()> {
+    body: T,
+    location: Uri,
+}
+
+impl<T> Created<T> {
+    /// Create a `201 Created` response.
+    pub fn new(body: T, location: Uri) -> Self {
+        Self { body, location }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Created<T> {
+    fn into_response(self) -> Response {
+        into_response(self.body, StatusCode::CREATED, Some(self.location))
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for Created<T> {
+    fn meta() -> MetaResponses {
+        with_status::<T>(
+            StatusCode::CREATED,
+            "The URI of the created resource.",
+            true,
+        )
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+/// A `202 Accepted` response, the body is `T`, and the `Location` header
+/// can point to the status of the processing.
+pub struct Accepted<T = ()> {
+    body: T,
+    location: Option<Uri>,
+}
+
+impl<T> Accepted<T> {
+    /// Create a `202 Accepted` response.
+    pub fn new(body: T) -> Self {
+        Self {
+            body,
+            location: None,
+        }
+    }
+
+    /// Sets the `Location` header.
+    #[must_use]
+    pub fn location(self, location: Uri) -> Self {
+        Self {
+            location: Some(location),
+            ..self
+        }
+    }
+}
+
+impl<T: IntoResponse> IntoResponse for Accepted<T> {
+    fn into_response(self) -> Response {
+        into_response(self.body, StatusCode::ACCEPTED, self.location)
+    }
+}
+
+impl<T: ApiResponse> ApiResponse for Accepted<T> {
+    fn meta() -> MetaResponses {
+        with_status::<T>(
+            StatusCode::ACCEPTED,
+            "The URI of the status of the processing.",
+            false,
+        )
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+/// A `204 No Content` response.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct NoContent;
+
+impl IntoResponse for NoContent {
+    fn into_response(self) -> Response {
+        StatusCode::NO_CONTENT.into_response()
+    }
+}
+
+impl ApiResponse for NoContent {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "No Content",
+                status: Some(204),
+                content: vec![],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(_registry: &mut Registry) {}
+}
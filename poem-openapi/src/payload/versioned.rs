@@ -0,0 +1,216 @@
+use std::ops::{Deref, DerefMut};
+
+use poem::{http::header, IntoResponse, Request, RequestBody, Response, Result};
+
+use crate::{
+    error::PreconditionFailedError,
+    payload::{Json, ParsePayload, Payload},
+    registry::{
+        MetaHeader, MetaMediaType, MetaOperationParam, MetaParamIn, MetaResponse, MetaResponses,
+        MetaSchema, MetaSchemaRef, Registry,
+    },
+    types::{ParseFromJSON, ToJSON, Type},
+    ApiResponse,
+};
+
+/// Represents an object that has a version, it is used as the entity tag of
+/// the [`Versioned`] payload.
+///
+/// It can be derived by the `Object` macro with the `version` attribute of a
+/// field.
+pub trait HasVersion {
+    /// Returns the version of the object.
+    fn version(&self) -> String;
+}
+
+/// A JSON payload with optimistic concurrency control.
+///
+/// When used as a response, the version of the object is sent as the `ETag`
+/// header. When used as a request, the `If-Match` header is extracted, and
+/// [`Versioned::check`] validates it against the current version of the
+/// object, the header parameter and a `412 Precondition Failed` response are
+/// documented in the operation.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::{HasVersion, Versioned},
+///     Object, OpenApi,
+/// };
+///
+/// #[derive(Object, Clone)]
+/// struct Document {
+///     #[oai(read_only, version)]
+///     revision: i32,
+///     content: String,
+/// }
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/document", method = "put")]
+///     async fn update(&self, doc: Versioned<Document>) -> poem::Result<Versioned<Document>> {
+///         let mut current = Document {
+///             revision: 1,
+///             content: String::new(),
+///         };
+///         doc.check(&current)?;
+///         current.content = doc.into_inner().content;
+///         current.revision += 1;
+///         Ok(Versioned::new(current))
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Versioned<T> {
+    value: T,
+    if_match: Option<String>,
+}
+
+impl<T> Deref for Versioned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Versioned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T> Versioned<T> {
+    /// Create a versioned payload.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            if_match: None,
+        }
+    }
+
+    /// Consumes this payload to return the inner value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns the value of the `If-Match` header of the request.
+    #[inline]
+    pub fn if_match(&self) -> Option<&str> {
+        self.if_match.as_deref()
+    }
+
+    /// Checks the `If-Match` header of the request against the current version
+    /// of the object, returns [`PreconditionFailedError`] if it does not match.
+    ///
+    /// It always succeeds if the request does not include the `If-Match`
+    /// header.
+    pub fn check(&self, current: &impl HasVersion) -> Result<(), PreconditionFailedError> {
+        let if_match = match &self.if_match {
+            Some(if_match) => if_match,
+            None => return Ok(()),
+        };
+        let version = current.version();
+        let matched = if_match.split(',').map(str::trim).any(|tag| {
+            tag == "*"
+                || tag
+                    .strip_prefix('"')
+                    .and_then(|tag| tag.strip_suffix('"'))
+                    .map(|tag| tag == version)
+                    .unwrap_or_default()
+        });
+        if matched {
+            Ok(())
+        } else {
+            Err(PreconditionFailedError)
+        }
+    }
+}
+
+impl<T: Type> Payload for Versioned<T> {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    fn schema_ref() -> MetaSchemaRef {
+        T::schema_ref()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+#[poem::async_trait]
+impl<T: ParseFromJSON> ParsePayload for Versioned<T> {
+    const IS_REQUIRED: bool = T::IS_REQUIRED;
+
+    fn params() -> Vec<MetaOperationParam> {
+        vec![MetaOperationParam {
+            name: "If-Match",
+            schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+            in_type: MetaParamIn::Header,
+            description: Some("The versions of the object that the request applies to."),
+            required: false,
+            deprecated: false,
+        }]
+    }
+
+    fn responses() -> Vec<MetaResponse> {
+        vec![MetaResponse {
+            description: "The `If-Match` header does not match the current version.",
+            status: Some(412),
+            content: vec![],
+            headers: vec![],
+        }]
+    }
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let value = <Json<T> as ParsePayload>::from_request(request, body)
+            .await?
+            .0;
+        Ok(Self {
+            value,
+            if_match: request.header(header::IF_MATCH).map(ToString::to_string),
+        })
+    }
+}
+
+impl<T: ToJSON + HasVersion> IntoResponse for Versioned<T> {
+    fn into_response(self) -> Response {
+        let etag = format!("\"{}\"", self.value.version());
+        Json(self.value)
+            .with_header(header::ETAG, etag)
+            .into_response()
+    }
+}
+
+impl<T: ToJSON + HasVersion> ApiResponse for Versioned<T> {
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "",
+                status: Some(200),
+                content: vec![MetaMediaType {
+                    content_type: Self::CONTENT_TYPE,
+                    schema: Self::schema_ref(),
+                }],
+                headers: vec![MetaHeader {
+                    name: "ETag",
+                    description: Some("The version of the object."),
+                    required: true,
+                    deprecated: false,
+                    schema: MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))),
+                }],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+impl_apirequest_for_payload!(Versioned<T>, T: ParseFromJSON);
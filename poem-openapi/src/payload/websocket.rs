@@ -0,0 +1,433 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use futures_util::{
+    future::{BoxFuture, Either},
+    FutureExt, Sink, SinkExt, Stream, StreamExt,
+};
+use poem::{
+    web::websocket::{Message, WebSocket, WebSocketStream},
+    IntoResponse, Response,
+};
+use serde_json::{json, Value};
+use tokio::time::{Instant, Interval};
+
+use crate::{
+    registry::{MetaResponse, MetaResponses, Registry},
+    types::{ParseError, ParseFromJSON, ParseResult, ToJSON},
+    ApiResponse,
+};
+
+type Callback<C, S> =
+    Box<dyn FnOnce(WebSocketConnection<C, S>) -> BoxFuture<'static, ()> + Send + Sync + 'static>;
+
+/// A WebSocket endpoint that exchanges typed JSON messages, `C` is the type of
+/// the messages sent by the client and `S` is the type of the messages sent by
+/// the server.
+///
+/// The messages are usually enums that derive [`OneOf`](crate::OneOf). The
+/// protocol is documented with the `x-websocket` extension of the operation,
+/// which describes the messages as the AsyncAPI `publish` (sent by the
+/// client) and `subscribe` (sent by the server) operations.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use poem::web::websocket::WebSocket;
+/// use poem_openapi::{payload::TypedWebSocket, Object, OneOf, OpenApi};
+///
+/// #[derive(Object)]
+/// struct Subscribe {
+///     topic: String,
+/// }
+///
+/// #[derive(OneOf)]
+/// #[oai(property_name = "type")]
+/// enum ClientMessage {
+///     Subscribe(Subscribe),
+/// }
+///
+/// #[derive(Object)]
+/// struct Subscribed {
+///     topic: String,
+/// }
+///
+/// #[derive(OneOf)]
+/// #[oai(property_name = "type")]
+/// enum ServerMessage {
+///     Subscribed(Subscribed),
+/// }
+///
+/// struct MyApi;
+///
+/// #[OpenApi]
+/// impl MyApi {
+///     #[oai(path = "/ws", method = "get")]
+///     async fn ws(&self, ws: WebSocket) -> TypedWebSocket<ClientMessage, ServerMessage> {
+///         TypedWebSocket::new(ws, |mut conn| async move {
+///             while let Some(msg) = conn.recv().await {
+///                 if let Ok(ClientMessage::Subscribe(Subscribe { topic })) = msg {
+///                     let reply = ServerMessage::Subscribed(Subscribed { topic });
+///                     if conn.send(&reply).await.is_err() {
+///                         break;
+///                     }
+///                 }
+///             }
+///         })
+///         .heartbeat(Duration::from_secs(30))
+///     }
+/// }
+/// ```
+pub struct TypedWebSocket<C, S> {
+    websocket: WebSocket,
+    heartbeat: Option<Duration>,
+    callback: Callback<C, S>,
+}
+
+impl<C, S> TypedWebSocket<C, S>
+where
+    C: ParseFromJSON + Send + 'static,
+    S: ToJSON + Send + 'static,
+{
+    /// Create a typed WebSocket response, the `callback` is called with the
+    /// connection after the connection is upgraded.
+    pub fn new<F, Fut>(websocket: WebSocket, callback: F) -> Self
+    where
+        F: FnOnce(WebSocketConnection<C, S>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        Self {
+            websocket,
+            heartbeat: None,
+            callback: Box::new(move |conn| callback(conn).boxed()),
+        }
+    }
+
+    /// Send a ping to the client at every `interval`, the connection is
+    /// closed if nothing is received from the client for two intervals.
+    #[must_use]
+    pub fn heartbeat(self, interval: Duration) -> Self {
+        Self {
+            heartbeat: Some(interval),
+            ..self
+        }
+    }
+}
+
+impl<C, S> IntoResponse for TypedWebSocket<C, S>
+where
+    C: ParseFromJSON + Send + 'static,
+    S: ToJSON + Send + 'static,
+{
+    fn into_response(self) -> Response {
+        let heartbeat = self.heartbeat;
+        let callback = self.callback;
+        self.websocket
+            .on_upgrade(move |stream| {
+                let mut conn = WebSocketConnection::new(stream);
+                if let Some(interval) = heartbeat {
+                    conn = conn.heartbeat(interval);
+                }
+                callback(conn)
+            })
+            .into_response()
+    }
+}
+
+impl<C, S> ApiResponse for TypedWebSocket<C, S>
+where
+    C: ParseFromJSON + Send + 'static,
+    S: ToJSON + Send + 'static,
+{
+    fn meta() -> MetaResponses {
+        MetaResponses {
+            responses: vec![MetaResponse {
+                description: "Switching Protocols",
+                status: Some(101),
+                content: vec![],
+                headers: vec![],
+            }],
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        C::register(registry);
+        S::register(registry);
+    }
+
+    fn operation_extensions() -> BTreeMap<String, Value> {
+        let mut extensions = BTreeMap::new();
+        extensions.insert(
+            "x-websocket".to_string(),
+            json!({
+                "publish": { "message": { "payload": C::schema_ref() } },
+                "subscribe": { "message": { "payload": S::schema_ref() } },
+            }),
+        );
+        extensions
+    }
+}
+
+struct Heartbeat {
+    interval: Interval,
+    timeout: Duration,
+    last_seen: Instant,
+}
+
+/// A WebSocket connection that exchanges typed JSON messages.
+///
+/// The text and the binary messages are parsed as JSON, the ping and pong
+/// messages are handled by the connection.
+pub struct WebSocketConnection<C, S, T = WebSocketStream> {
+    stream: T,
+    heartbeat: Option<Heartbeat>,
+    _mark: PhantomData<fn() -> (C, S)>,
+}
+
+impl<C, S, T> WebSocketConnection<C, S, T>
+where
+    C: ParseFromJSON,
+    S: ToJSON,
+    T: Stream<Item = IoResult<Message>> + Sink<Message, Error = IoError> + Unpin,
+{
+    /// Create a connection from the WebSocket stream.
+    pub fn new(stream: T) -> Self {
+        Self {
+            stream,
+            heartbeat: None,
+            _mark: PhantomData,
+        }
+    }
+
+    /// Send a ping at every `interval` while the connection is waiting in
+    /// [`recv`](Self::recv), the connection is closed if nothing is received
+    /// for two intervals.
+    #[must_use]
+    pub fn heartbeat(self, interval: Duration) -> Self {
+        Self {
+            heartbeat: Some(Heartbeat {
+                interval: tokio::time::interval_at(Instant::now() + interval, interval),
+                timeout: interval * 2,
+                last_seen: Instant::now(),
+            }),
+            ..self
+        }
+    }
+
+    /// Receive the next message, returns `None` if the connection is closed.
+    ///
+    /// The messages that cannot be parsed are returned as errors, and the
+    /// connection can still be used.
+    pub async fn recv(&mut self) -> Option<ParseResult<C>> {
+        loop {
+            // Wait for the next message or the next heartbeat.
+            let msg = {
+                let Self {
+                    stream, heartbeat, ..
+                } = self;
+                let tick = async {
+                    match heartbeat {
+                        Some(heartbeat) => {
+                            heartbeat.interval.tick().await;
+                        }
+                        None => futures_util::future::pending().await,
+                    }
+                };
+                futures_util::pin_mut!(tick);
+                match futures_util::future::select(stream.next(), tick).await {
+                    Either::Left((msg, _)) => Some(msg),
+                    Either::Right(_) => None,
+                }
+            };
+
+            match msg {
+                Some(msg) => {
+                    if let Some(heartbeat) = &mut self.heartbeat {
+                        heartbeat.last_seen = Instant::now();
+                    }
+                    match msg {
+                        Some(Ok(Message::Text(text))) => {
+                            return Some(parse_message(text.as_bytes()))
+                        }
+                        Some(Ok(Message::Binary(data))) => return Some(parse_message(&data)),
+                        Some(Ok(Message::Ping(_) | Message::Pong(_))) => {}
+                        Some(Ok(Message::Close(_)) | Err(_)) | None => return None,
+                    }
+                }
+                None => {
+                    let timeout = matches!(&self.heartbeat, Some(heartbeat) if heartbeat.last_seen.elapsed() >= heartbeat.timeout);
+                    if timeout || self.stream.send(Message::ping(Vec::new())).await.is_err() {
+                        let _ = self.stream.close().await;
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Send a message.
+    pub async fn send(&mut self, msg: &S) -> IoResult<()> {
+        let text = serde_json::to_string(&msg.to_json())
+            .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+        self.stream.send(Message::text(text)).await
+    }
+
+    /// Close the connection.
+    pub async fn close(mut self) -> IoResult<()> {
+        self.stream.send(Message::close()).await?;
+        self.stream.close().await
+    }
+}
+
+fn parse_message<C: ParseFromJSON>(data: &[u8]) -> ParseResult<C> {
+    let value = serde_json::from_slice(data).map_err(|err| ParseError::custom(err.to_string()))?;
+    C::parse_from_json(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{Object, OpenApi, OpenApiService};
+
+    struct MockStream {
+        incoming: mpsc::UnboundedReceiver<IoResult<Message>>,
+        outgoing: Option<mpsc::UnboundedSender<Message>>,
+    }
+
+    impl Stream for MockStream {
+        type Item = IoResult<Message>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.incoming.poll_recv(cx)
+        }
+    }
+
+    impl Sink<Message> for MockStream {
+        type Error = IoError;
+
+        fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Message) -> IoResult<()> {
+            match &self.outgoing {
+                Some(outgoing) => outgoing
+                    .send(item)
+                    .map_err(|_| IoError::from(ErrorKind::BrokenPipe)),
+                None => Err(IoError::from(ErrorKind::BrokenPipe)),
+            }
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+            self.outgoing = None;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[derive(Object, Debug, PartialEq)]
+    #[oai(internal)]
+    struct Msg {
+        value: i32,
+    }
+
+    type Connection = WebSocketConnection<Msg, Msg, MockStream>;
+
+    fn connection() -> (
+        Connection,
+        mpsc::UnboundedSender<IoResult<Message>>,
+        mpsc::UnboundedReceiver<Message>,
+    ) {
+        let (incoming_tx, incoming) = mpsc::unbounded_channel();
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let conn = WebSocketConnection::new(MockStream {
+            incoming,
+            outgoing: Some(outgoing),
+        });
+        (conn, incoming_tx, outgoing_rx)
+    }
+
+    #[tokio::test]
+    async fn messages() {
+        let (mut conn, incoming, mut outgoing) = connection();
+        incoming.send(Ok(Message::text(r#"{"value": 1}"#))).unwrap();
+        incoming.send(Ok(Message::ping(vec![]))).unwrap();
+        incoming.send(Ok(Message::text("1"))).unwrap();
+        incoming
+            .send(Ok(Message::binary(br#"{"value": 2}"#.to_vec())))
+            .unwrap();
+        incoming.send(Ok(Message::close())).unwrap();
+
+        assert_eq!(conn.recv().await.unwrap().unwrap(), Msg { value: 1 });
+        assert!(conn.recv().await.unwrap().is_err());
+        assert_eq!(conn.recv().await.unwrap().unwrap(), Msg { value: 2 });
+        assert!(conn.recv().await.is_none());
+
+        conn.send(&Msg { value: 3 }).await.unwrap();
+        assert_eq!(
+            outgoing.recv().await.unwrap(),
+            Message::text(r#"{"value":3}"#)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn heartbeat() {
+        let (conn, incoming, mut outgoing) = connection();
+        let mut conn = conn.heartbeat(Duration::from_secs(10));
+
+        let recv = tokio::spawn(async move { conn.recv().await.is_none() });
+        tokio::time::sleep(Duration::from_secs(11)).await;
+        assert_eq!(outgoing.recv().await.unwrap(), Message::ping(vec![]));
+        assert!(!recv.is_finished());
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert!(recv.await.unwrap());
+        assert!(outgoing.recv().await.is_none());
+        drop(incoming);
+    }
+
+    #[test]
+    fn operation_extensions() {
+        struct Api;
+
+        #[OpenApi(internal)]
+        impl Api {
+            #[oai(path = "/ws", method = "get")]
+            async fn ws(&self, ws: WebSocket) -> TypedWebSocket<Msg, Msg> {
+                TypedWebSocket::new(ws, |_| async move {})
+            }
+        }
+
+        let spec: Value =
+            serde_json::from_str(&OpenApiService::new(Api, "test", "1.0").spec()).unwrap();
+        let operation = &spec["paths"]["/ws"]["get"];
+        assert_eq!(
+            operation["responses"]["101"]["description"],
+            json!("Switching Protocols")
+        );
+        assert_eq!(
+            operation["x-websocket"],
+            json!({
+                "publish": { "message": { "payload": { "$ref": "#/components/schemas/Msg" } } },
+                "subscribe": { "message": { "payload": { "$ref": "#/components/schemas/Msg" } } },
+            })
+        );
+        assert!(spec["components"]["schemas"]["Msg"].is_object());
+    }
+}
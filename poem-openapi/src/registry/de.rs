@@ -0,0 +1,296 @@
+//! The documents are deserialized with owned strings, such as
+//! `OpenApiDocument<String>`.
+
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use poem::{http::Method, middleware::Priority};
+use serde::{
+    de::{Error, IntoDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::Value;
+
+use super::{
+    MetaApi, MetaExternalDocument, MetaHeader, MetaInfo, MetaMediaType, MetaOAuthScope,
+    MetaOperation, MetaPath, MetaResponse, MetaResponses, MetaSchema, MetaSchemaRef,
+    MetaSecurityScheme, MetaServer, MetaStr, MetaTag, MetaWebhook, OpenApiDocument, Registry,
+};
+
+/// The entries of a map, in the order of the document.
+struct Entries<K, V>(Vec<(K, V)>);
+
+impl<K, V> Default for Entries<K, V> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for Entries<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<K, V>(PhantomData<(K, V)>);
+
+        impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for EntriesVisitor<K, V> {
+            type Value = Entries<K, V>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Entries(entries))
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+pub(super) fn properties<'de, T, D>(deserializer: D) -> Result<Vec<(T, MetaSchemaRef<T>)>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Entries::deserialize(deserializer)?.0)
+}
+
+/// The unknown fields are collected by the flattened extensions, only the
+/// fields whose names start with `x-` are kept.
+pub(super) fn extensions<'de, D>(deserializer: D) -> Result<Vec<(String, Value)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut extensions = Entries::<String, Value>::deserialize(deserializer)?.0;
+    extensions.retain(|(name, _)| name.starts_with("x-"));
+    Ok(extensions)
+}
+
+pub(super) fn mapping<'de, T, D>(deserializer: D) -> Result<Vec<(T, String)>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Entries::deserialize(deserializer)?.0)
+}
+
+pub(super) fn content<'de, T, D>(deserializer: D) -> Result<Vec<MetaMediaType<T>>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Entries::<T, MetaMediaType<T>>::deserialize(deserializer)?
+        .0
+        .into_iter()
+        .map(|(content_type, media)| MetaMediaType {
+            content_type,
+            ..media
+        })
+        .collect())
+}
+
+pub(super) fn headers<'de, T, D>(deserializer: D) -> Result<Vec<MetaHeader<T>>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Entries::<T, MetaHeader<T>>::deserialize(deserializer)?
+        .0
+        .into_iter()
+        .map(|(name, header)| MetaHeader { name, ..header })
+        .collect())
+}
+
+pub(super) fn oauth_flow_scopes<'de, T, D>(
+    deserializer: D,
+) -> Result<Vec<MetaOAuthScope<T>>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Entries::<T, T>::deserialize(deserializer)?
+        .0
+        .into_iter()
+        .map(|(name, description)| MetaOAuthScope {
+            name,
+            // the scopes without descriptions are serialized with empty descriptions
+            description: (!description.as_ref().is_empty()).then(|| description),
+        })
+        .collect())
+}
+
+pub(super) fn security<'de, T, D>(deserializer: D) -> Result<Vec<HashMap<T, Vec<T>>>, D::Error>
+where
+    T: MetaStr + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Ok(Vec::<Entries<T, Vec<T>>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|requirement| requirement.0.into_iter().collect())
+        .collect())
+}
+
+pub(super) fn priority<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Priority>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|priority| match priority.as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            _ => Err(D::Error::custom(format!("unknown priority `{}`", priority))),
+        })
+        .transpose()
+}
+
+impl<'de, T: MetaStr + Deserialize<'de>> Deserialize<'de> for MetaSchema<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        let mut deny_additional_properties = false;
+        if let Some(obj) = value.as_object_mut() {
+            if let Some(Value::Bool(allowed)) = obj.get("additionalProperties") {
+                deny_additional_properties = !allowed;
+                obj.remove("additionalProperties");
+            }
+        }
+        let mut schema = MetaSchema::deserialize(value).map_err(D::Error::custom)?;
+        schema.deny_additional_properties = deny_additional_properties;
+        Ok(schema)
+    }
+}
+
+impl<'de, T: MetaStr + Deserialize<'de>> Deserialize<'de> for MetaSchemaRef<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(reference) => match reference.strip_prefix("#/components/schemas/") {
+                Some(name) => T::deserialize(name.to_string().into_deserializer())
+                    .map(MetaSchemaRef::Reference),
+                None => Err(D::Error::custom(format!(
+                    "unsupported reference `{}`",
+                    reference
+                ))),
+            },
+            None => <MetaSchema<T> as Deserialize>::deserialize(value)
+                .map(|schema| MetaSchemaRef::Inline(Box::new(schema)))
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+impl<'de, T: MetaStr + Deserialize<'de>> Deserialize<'de> for MetaResponses<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let responses = Entries::<String, MetaResponse<T>>::deserialize(deserializer)?
+            .0
+            .into_iter()
+            .map(|(status, resp)| {
+                let status = match status.as_str() {
+                    "default" => None,
+                    _ => Some(status.parse().map_err(|_| {
+                        D::Error::custom(format!("invalid status code `{}`", status))
+                    })?),
+                };
+                Ok(MetaResponse { status, ..resp })
+            })
+            .collect::<Result<_, D::Error>>()?;
+        Ok(MetaResponses { responses })
+    }
+}
+
+fn operation_method(name: &str) -> Option<Method> {
+    Some(match name {
+        "get" => Method::GET,
+        "put" => Method::PUT,
+        "post" => Method::POST,
+        "delete" => Method::DELETE,
+        "options" => Method::OPTIONS,
+        "head" => Method::HEAD,
+        "patch" => Method::PATCH,
+        "trace" => Method::TRACE,
+        _ => return None,
+    })
+}
+
+impl<'de, T: MetaStr + Deserialize<'de>> Deserialize<'de> for OpenApiDocument<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize, Default)]
+        #[serde(rename_all = "camelCase")]
+        struct Components<T: MetaStr> {
+            #[serde(default)]
+            schemas: Entries<T, MetaSchema<T>>,
+            #[serde(default)]
+            security_schemes: Entries<T, MetaSecurityScheme<T>>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Document<T: MetaStr> {
+            info: MetaInfo,
+            #[serde(default)]
+            servers: Vec<MetaServer>,
+            #[serde(default)]
+            tags: Vec<MetaTag<T>>,
+            #[serde(default)]
+            webhooks: Entries<T, MetaOperation<T>>,
+            #[serde(default)]
+            paths: Entries<T, Entries<String, Value>>,
+            #[serde(default)]
+            components: Components<T>,
+            #[serde(default)]
+            external_docs: Option<MetaExternalDocument>,
+            #[serde(flatten)]
+            fields: HashMap<String, Value>,
+        }
+
+        let doc = Document::<T>::deserialize(deserializer)?;
+
+        let mut paths = Vec::new();
+        for (path, item) in doc.paths.0 {
+            let mut operations = Vec::new();
+            // the other fields of the path item are not supported
+            for (name, operation) in item.0 {
+                if let Some(method) = operation_method(&name) {
+                    let mut operation =
+                        MetaOperation::<T>::deserialize(operation).map_err(D::Error::custom)?;
+                    operation
+                        .extensions
+                        .retain(|name, _| name.starts_with("x-"));
+                    operations.push(MetaOperation {
+                        method,
+                        ..operation
+                    });
+                }
+            }
+            paths.push(MetaPath { path, operations });
+        }
+
+        let mut registry = Registry::default();
+        registry.tags.extend(doc.tags);
+        registry.schemas.extend(doc.components.schemas.0);
+        registry
+            .security_schemes
+            .extend(doc.components.security_schemes.0);
+
+        Ok(OpenApiDocument {
+            info: doc.info,
+            servers: doc.servers,
+            apis: vec![MetaApi { paths }],
+            webhooks: doc
+                .webhooks
+                .0
+                .into_iter()
+                .map(|(name, operation)| MetaWebhook { name, operation })
+                .collect(),
+            registry,
+            external_document: doc.external_docs,
+            extensions: doc
+                .fields
+                .into_iter()
+                .filter(|(name, _)| name.starts_with("x-"))
+                .collect(),
+        })
+    }
+}
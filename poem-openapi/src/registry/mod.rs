@@ -1,35 +1,63 @@
+mod de;
 mod ser;
 
 use std::{
+    borrow::Cow,
     cmp::Ordering,
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
     hash::{Hash, Hasher},
+    sync::Mutex,
 };
 
-use poem::http::Method;
-pub(crate) use ser::Document;
-use serde::{ser::SerializeMap, Serialize, Serializer};
+use once_cell::sync::Lazy;
+use poem::{http::Method, middleware::Priority};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
 use serde_json::Value;
 
+/// The string type of the metadata.
+///
+/// The metadata of the APIs uses `&'static str`, and the documents that are
+/// deserialized from the specifications use `String`.
+pub trait MetaStr: AsRef<str> + Clone + Default + Ord + Hash + fmt::Debug + Serialize {}
+
+impl MetaStr for &'static str {}
+
+impl MetaStr for String {}
+
 #[allow(clippy::trivially_copy_pass_by_ref)]
 #[inline]
 const fn is_false(value: &bool) -> bool {
     !*value
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_deny_additional_properties<S: Serializer>(
+    _value: &bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bool(false)
+}
+
+fn is_empty_str<T: MetaStr>(value: &T) -> bool {
+    value.as_ref().is_empty()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaDiscriminatorObject {
-    pub property_name: &'static str,
+pub struct MetaDiscriminatorObject<T: MetaStr = &'static str> {
+    pub property_name: T,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_mapping"
+        serialize_with = "serialize_mapping",
+        default,
+        deserialize_with = "de::mapping"
     )]
-    pub mapping: Vec<(&'static str, String)>,
+    pub mapping: Vec<(T, String)>,
 }
 
-fn serialize_mapping<S: Serializer>(
-    mapping: &[(&'static str, String)],
+fn serialize_mapping<T: MetaStr, S: Serializer>(
+    mapping: &[(T, String)],
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     let mut s = serializer.serialize_map(None)?;
@@ -39,87 +67,160 @@ fn serialize_mapping<S: Serializer>(
     s.end()
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct MetaSchema {
+// The `additionalProperties: false` of the denied properties is handled by the
+// implementations of `Serialize` and `Deserialize`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", rename_all = "camelCase")]
+pub struct MetaSchema<T: MetaStr = &'static str> {
     #[serde(skip)]
     pub rust_typename: Option<&'static str>,
 
-    #[serde(rename = "type", skip_serializing_if = "str::is_empty")]
-    pub ty: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub format: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub title: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, rename = "type", skip_serializing_if = "is_empty_str")]
+    pub ty: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<MetaExternalDocument>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xml: Option<MetaXml<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default: Option<Value>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub required: Vec<&'static str>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<T>,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_properties"
+        serialize_with = "serialize_properties",
+        default,
+        deserialize_with = "de::properties"
+    )]
+    pub properties: Vec<(T, MetaSchemaRef<T>)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<MetaSchemaRef<T>>>,
+    /// The schemas of the items at the positions of a fixed-length array.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<MetaSchemaRef<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<Box<MetaSchemaRef<T>>>,
+    /// `true` if the properties that are not declared are not allowed, it is
+    /// serialized as `additionalProperties: false`.
+    #[serde(
+        rename = "additionalProperties",
+        skip_serializing_if = "is_false",
+        serialize_with = "serialize_deny_additional_properties",
+        skip_deserializing
     )]
-    pub properties: Vec<(&'static str, MetaSchemaRef)>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub items: Option<Box<MetaSchemaRef>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub additional_properties: Option<Box<MetaSchemaRef>>,
-    #[serde(rename = "enum", skip_serializing_if = "Vec::is_empty")]
+    pub deny_additional_properties: bool,
+    #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_items: Vec<Value>,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(
+        default,
+        rename = "x-extensible-enum",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub extensible_enum_items: Vec<Value>,
+    #[serde(
+        default,
+        rename = "x-enum-varnames",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub enum_varnames: Vec<T>,
+    #[serde(
+        default,
+        rename = "x-enum-descriptions",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub enum_descriptions: Vec<T>,
+    #[serde(default, skip_serializing_if = "is_false")]
     pub deprecated: bool,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub one_of: Vec<MetaSchemaRef>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub all_of: Vec<MetaSchemaRef>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub discriminator: Option<MetaDiscriminatorObject>,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(
+        default,
+        rename = "x-deprecated-note",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub deprecation_note: Option<T>,
+    #[serde(
+        default,
+        rename = "x-removed-in",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub removed_in: Option<T>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<MetaSchemaRef<T>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub all_of: Vec<MetaSchemaRef<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<MetaDiscriminatorObject<T>>,
+    /// `true` if the value can be `null`.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub nullable: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
     pub read_only: bool,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub write_only: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, rename = "x-sensitive", skip_serializing_if = "is_false")]
+    pub sensitive: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub example: Option<Value>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub multiple_of: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub maximum: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub exclusive_maximum: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub minimum: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub exclusive_minimum: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_length: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min_length: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_media_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_items: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min_items: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub unique_items: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_properties: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min_properties: Option<usize>,
+    /// The extension fields of the schema, whose names start with `x-`.
+    #[serde(
+        flatten,
+        serialize_with = "serialize_extensions",
+        deserialize_with = "de::extensions"
+    )]
+    pub extensions: Vec<(String, Value)>,
 }
 
-fn serialize_properties<S: Serializer>(
-    properties: &[(&'static str, MetaSchemaRef)],
+fn serialize_properties<T: MetaStr, S: Serializer>(
+    properties: &[(T, MetaSchemaRef<T>)],
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     let mut s = serializer.serialize_map(None)?;
     for item in properties {
-        s.serialize_entry(item.0, &item.1)?;
+        s.serialize_entry(&item.0, &item.1)?;
+    }
+    s.end()
+}
+
+fn serialize_extensions<S: Serializer>(
+    extensions: &[(String, Value)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_map(None)?;
+    for (name, value) in extensions {
+        s.serialize_entry(name, value)?;
     }
     s.end()
 }
@@ -132,18 +233,28 @@ impl MetaSchema {
         title: None,
         description: None,
         external_docs: None,
+        xml: None,
         default: None,
         required: vec![],
         properties: vec![],
         items: None,
+        prefix_items: vec![],
         additional_properties: None,
+        deny_additional_properties: false,
         enum_items: vec![],
+        extensible_enum_items: vec![],
+        enum_varnames: vec![],
+        enum_descriptions: vec![],
         deprecated: false,
+        deprecation_note: None,
+        removed_in: None,
         one_of: vec![],
         all_of: vec![],
         discriminator: None,
+        nullable: false,
         read_only: false,
         write_only: false,
+        sensitive: false,
         example: None,
         multiple_of: None,
         maximum: None,
@@ -153,11 +264,13 @@ impl MetaSchema {
         max_length: None,
         min_length: None,
         pattern: None,
+        content_media_type: None,
         max_items: None,
         min_items: None,
         unique_items: None,
         max_properties: None,
         min_properties: None,
+        extensions: vec![],
     };
 
     pub fn new(ty: &'static str) -> Self {
@@ -181,13 +294,21 @@ impl MetaSchema {
         mut self,
         MetaSchema {
             default,
+            example,
+            nullable,
             read_only,
             write_only,
+            sensitive,
+            deprecated,
+            deprecation_note,
+            removed_in,
             title,
             description,
             external_docs,
+            xml,
             items,
             additional_properties,
+            enum_items,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -196,16 +317,22 @@ impl MetaSchema {
             max_length,
             min_length,
             pattern,
+            content_media_type,
             max_items,
             min_items,
             unique_items,
             max_properties,
             min_properties,
+            one_of,
+            extensions,
             ..
         }: MetaSchema,
     ) -> Self {
+        self.nullable |= nullable;
         self.read_only |= read_only;
         self.write_only |= write_only;
+        self.sensitive |= sensitive;
+        self.deprecated |= deprecated;
 
         macro_rules! merge_optional {
             ($($name:ident),*) => {
@@ -219,9 +346,13 @@ impl MetaSchema {
 
         merge_optional!(
             default,
+            example,
+            deprecation_note,
+            removed_in,
             title,
             description,
             external_docs,
+            xml,
             multiple_of,
             maximum,
             exclusive_maximum,
@@ -230,6 +361,7 @@ impl MetaSchema {
             max_length,
             min_length,
             pattern,
+            content_media_type,
             max_items,
             min_items,
             unique_items,
@@ -237,6 +369,19 @@ impl MetaSchema {
             min_properties
         );
 
+        if !enum_items.is_empty() {
+            self.enum_items = enum_items;
+        }
+
+        if !one_of.is_empty() {
+            self.one_of = one_of;
+        }
+
+        for (name, value) in extensions {
+            self.extensions.retain(|(self_name, _)| *self_name != name);
+            self.extensions.push((name, value));
+        }
+
         if let Some(items) = items {
             if let Some(self_items) = self.items {
                 let items = *items;
@@ -285,9 +430,9 @@ impl MetaSchema {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum MetaSchemaRef {
-    Inline(Box<MetaSchema>),
-    Reference(&'static str),
+pub enum MetaSchemaRef<T: MetaStr = &'static str> {
+    Inline(Box<MetaSchema<T>>),
+    Reference(T),
 }
 
 impl MetaSchemaRef {
@@ -318,8 +463,9 @@ impl MetaSchemaRef {
         match self {
             MetaSchemaRef::Inline(schema) => MetaSchemaRef::Inline(Box::new(schema.merge(other))),
             MetaSchemaRef::Reference(name) => {
-                let other = MetaSchema::ANY.merge(other);
-                if other.is_empty() {
+                let mut other = MetaSchema::ANY.merge(other);
+                let nullable = std::mem::take(&mut other.nullable);
+                let schema = if other.is_empty() {
                     MetaSchemaRef::Reference(name)
                 } else {
                     MetaSchemaRef::Inline(Box::new(MetaSchema {
@@ -329,104 +475,128 @@ impl MetaSchemaRef {
                         ],
                         ..MetaSchema::ANY
                     }))
+                };
+
+                // `nullable` has no effect next to a `$ref`, so the nullable
+                // references are one of the referenced schema or `null`.
+                if nullable {
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        one_of: vec![
+                            schema,
+                            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                                nullable: true,
+                                enum_items: vec![Value::Null],
+                                ..MetaSchema::ANY
+                            })),
+                        ],
+                        ..MetaSchema::ANY
+                    }))
+                } else {
+                    schema
                 }
             }
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MetaParamIn {
     Query,
     Header,
     Path,
     Cookie,
-    #[serde(rename = "cookie")]
+    #[serde(rename = "cookie", skip_deserializing)]
     CookiePrivate,
-    #[serde(rename = "cookie")]
+    #[serde(rename = "cookie", skip_deserializing)]
     CookieSigned,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct MetaOperationParam {
-    pub name: &'static str,
-    pub schema: MetaSchemaRef,
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaOperationParam<T: MetaStr = &'static str> {
+    pub name: T,
+    pub schema: MetaSchemaRef<T>,
     #[serde(rename = "in")]
     pub in_type: MetaParamIn,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
     pub required: bool,
     pub deprecated: bool,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct MetaMediaType {
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaMediaType<T: MetaStr = &'static str> {
     #[serde(skip)]
-    pub content_type: &'static str,
-    pub schema: MetaSchemaRef,
+    pub content_type: T,
+    pub schema: MetaSchemaRef<T>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct MetaRequest {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaRequest<T: MetaStr = &'static str> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_content"
+        serialize_with = "serialize_content",
+        default,
+        deserialize_with = "de::content"
     )]
-    pub content: Vec<MetaMediaType>,
+    pub content: Vec<MetaMediaType<T>>,
     pub required: bool,
 }
 
-fn serialize_content<S: Serializer>(
-    content: &[MetaMediaType],
+fn serialize_content<T: MetaStr, S: Serializer>(
+    content: &[MetaMediaType<T>],
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     let mut s = serializer.serialize_map(None)?;
     for item in content {
-        s.serialize_entry(item.content_type, item)?;
+        s.serialize_entry(&item.content_type, item)?;
     }
     s.end()
 }
 
 #[derive(Debug, PartialEq)]
-pub struct MetaResponses {
-    pub responses: Vec<MetaResponse>,
+pub struct MetaResponses<T: MetaStr = &'static str> {
+    pub responses: Vec<MetaResponse<T>>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaHeader {
+pub struct MetaHeader<T: MetaStr = &'static str> {
     #[serde(skip)]
-    pub name: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
-    #[serde(skip_serializing_if = "is_false")]
+    pub name: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
+    #[serde(default, skip_serializing_if = "is_false")]
     pub required: bool,
     pub deprecated: bool,
-    pub schema: MetaSchemaRef,
+    pub schema: MetaSchemaRef<T>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct MetaResponse {
-    pub description: &'static str,
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaResponse<T: MetaStr = &'static str> {
+    pub description: T,
     #[serde(skip)]
     pub status: Option<u16>,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_content"
+        serialize_with = "serialize_content",
+        default,
+        deserialize_with = "de::content"
     )]
-    pub content: Vec<MetaMediaType>,
+    pub content: Vec<MetaMediaType<T>>,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_headers"
+        serialize_with = "serialize_headers",
+        default,
+        deserialize_with = "de::headers"
     )]
-    pub headers: Vec<MetaHeader>,
+    pub headers: Vec<MetaHeader<T>>,
 }
 
-fn serialize_headers<S: Serializer>(
-    properties: &[MetaHeader],
+fn serialize_headers<T: MetaStr, S: Serializer>(
+    properties: &[MetaHeader<T>],
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     let mut s = serializer.serialize_map(None)?;
@@ -436,196 +606,266 @@ fn serialize_headers<S: Serializer>(
     s.end()
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaWebhook {
-    pub name: &'static str,
-    pub operation: MetaOperation,
+pub struct MetaWebhook<T: MetaStr = &'static str> {
+    pub name: T,
+    pub operation: MetaOperation<T>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaOperation {
+pub struct MetaOperation<T: MetaStr = &'static str> {
     #[serde(skip)]
     pub method: Method,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub tags: Vec<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub summary: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<MetaExternalDocument>,
-    #[serde(rename = "parameters", skip_serializing_if = "Vec::is_empty")]
-    pub params: Vec<MetaOperationParam>,
-    #[serde(rename = "requestBody", skip_serializing_if = "Option::is_none")]
-    pub request: Option<MetaRequest>,
-    pub responses: MetaResponses,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, rename = "parameters", skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<MetaOperationParam<T>>,
+    #[serde(
+        default,
+        rename = "requestBody",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub request: Option<MetaRequest<T>>,
+    pub responses: MetaResponses<T>,
+    #[serde(default, skip_serializing_if = "is_false")]
     pub deprecated: bool,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub security: Vec<HashMap<&'static str, Vec<&'static str>>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub operation_id: Option<&'static str>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "de::security"
+    )]
+    pub security: Vec<HashMap<T, Vec<T>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<T>,
+    #[serde(
+        default,
+        rename = "x-priority",
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "de::priority"
+    )]
+    pub priority: Option<Priority>,
+    /// The extension fields of the operation, whose names start with `x-`.
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Debug, PartialEq)]
-pub struct MetaPath {
-    pub path: &'static str,
-    pub operations: Vec<MetaOperation>,
+pub struct MetaPath<T: MetaStr = &'static str> {
+    pub path: T,
+    pub operations: Vec<MetaOperation<T>>,
 }
 
-#[derive(Debug, Default, PartialEq, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct MetaLicense {
     pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub identifier: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 }
 
-#[derive(Debug, Default, PartialEq, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetaInfo {
     pub title: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub version: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub terms_of_service: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub license: Option<MetaLicense>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MetaServer {
     pub url: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MetaExternalDocument {
     pub url: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetaXml<T: MetaStr = &'static str> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<T>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub attribute: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub wrapped: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaTag {
-    pub name: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+pub struct MetaTag<T: MetaStr = &'static str> {
+    pub name: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub external_docs: Option<MetaExternalDocument>,
 }
 
-impl PartialEq for MetaTag {
+impl<T: MetaStr> PartialEq for MetaTag<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.name.eq(other.name)
+        self.name.eq(&other.name)
     }
 }
 
-impl Eq for MetaTag {}
+impl<T: MetaStr> Eq for MetaTag<T> {}
 
-impl PartialOrd for MetaTag {
+impl<T: MetaStr> PartialOrd for MetaTag<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.name.partial_cmp(other.name)
+        self.name.partial_cmp(&other.name)
     }
 }
 
-impl Ord for MetaTag {
+impl<T: MetaStr> Ord for MetaTag<T> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.name.cmp(other.name)
+        self.name.cmp(&other.name)
     }
 }
 
-impl Hash for MetaTag {
+impl<T: MetaStr> Hash for MetaTag<T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.name.hash(state);
     }
 }
 
-#[derive(Debug, PartialEq, Serialize)]
-pub struct MetaOAuthScope {
-    pub name: &'static str,
-    pub description: Option<&'static str>,
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaOAuthScope<T: MetaStr = &'static str> {
+    pub name: T,
+    pub description: Option<T>,
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaOAuthFlow {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub authorization_url: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token_url: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub refresh_url: Option<&'static str>,
+pub struct MetaOAuthFlow<T: MetaStr = &'static str> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_url: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_url: Option<T>,
     #[serde(
         skip_serializing_if = "Vec::is_empty",
-        serialize_with = "serialize_oauth_flow_scopes"
+        serialize_with = "serialize_oauth_flow_scopes",
+        default,
+        deserialize_with = "de::oauth_flow_scopes"
     )]
-    pub scopes: Vec<MetaOAuthScope>,
+    pub scopes: Vec<MetaOAuthScope<T>>,
 }
 
-fn serialize_oauth_flow_scopes<S: Serializer>(
-    properties: &[MetaOAuthScope],
+fn serialize_oauth_flow_scopes<T: MetaStr, S: Serializer>(
+    properties: &[MetaOAuthScope<T>],
     serializer: S,
 ) -> Result<S::Ok, S::Error> {
     let mut s = serializer.serialize_map(None)?;
     for item in properties {
-        s.serialize_entry(item.name, item.description.unwrap_or_default())?;
+        let description = item.description.as_ref().map(AsRef::as_ref);
+        s.serialize_entry(&item.name, description.unwrap_or_default())?;
     }
     s.end()
 }
 
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaOAuthFlows {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub implicit: Option<MetaOAuthFlow>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub password: Option<MetaOAuthFlow>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub client_credentials: Option<MetaOAuthFlow>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub authorization_code: Option<MetaOAuthFlow>,
-}
-
-#[derive(Debug, Serialize, PartialEq)]
+pub struct MetaOAuthFlows<T: MetaStr = &'static str> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub implicit: Option<MetaOAuthFlow<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<MetaOAuthFlow<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_credentials: Option<MetaOAuthFlow<T>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_code: Option<MetaOAuthFlow<T>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-pub struct MetaSecurityScheme {
+pub struct MetaSecurityScheme<T: MetaStr = &'static str> {
     #[serde(rename = "type")]
-    pub ty: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<&'static str>,
-    #[serde(rename = "in", skip_serializing_if = "Option::is_none")]
-    pub key_in: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub scheme: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub bearer_format: Option<&'static str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub flows: Option<MetaOAuthFlows>,
-    #[serde(rename = "openIdConnectUrl", skip_serializing_if = "Option::is_none")]
-    pub openid_connect_url: Option<&'static str>,
+    pub ty: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<T>,
+    #[serde(default, rename = "in", skip_serializing_if = "Option::is_none")]
+    pub key_in: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_format: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flows: Option<MetaOAuthFlows<T>>,
+    #[serde(
+        default,
+        rename = "openIdConnectUrl",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub openid_connect_url: Option<T>,
 }
 
 #[derive(Debug, PartialEq)]
-pub struct MetaApi {
-    pub paths: Vec<MetaPath>,
+pub struct MetaApi<T: MetaStr = &'static str> {
+    pub paths: Vec<MetaPath<T>>,
+}
+
+/// The OpenAPI document, that can be changed by the hooks added with
+/// [`OpenApiService::map_spec`](crate::OpenApiService::map_spec) before it is
+/// serialized.
+///
+/// The documents with `String` can also be deserialized from the
+/// specifications generated by this crate, such as
+/// `OpenApiDocument<String>`, the path item fields other than the operations
+/// are ignored.
+pub struct OpenApiDocument<T: MetaStr = &'static str> {
+    /// The metadata of the API.
+    pub info: MetaInfo,
+    /// The servers of the API.
+    pub servers: Vec<MetaServer>,
+    /// The operations, grouped by the APIs and the paths.
+    pub apis: Vec<MetaApi<T>>,
+    /// The webhooks.
+    pub webhooks: Vec<MetaWebhook<T>>,
+    /// The schemas, the tags and the security schemes.
+    pub registry: Registry<T>,
+    /// The external documentation of the API.
+    pub external_document: Option<MetaExternalDocument>,
+    /// The extension fields of the document, whose names start with `x-`.
+    pub extensions: BTreeMap<String, Value>,
 }
 
 #[derive(Default)]
-pub struct Registry {
-    pub schemas: BTreeMap<&'static str, MetaSchema>,
-    pub tags: BTreeSet<MetaTag>,
-    pub security_schemes: BTreeMap<&'static str, MetaSecurityScheme>,
+pub struct Registry<T: MetaStr = &'static str> {
+    pub schemas: BTreeMap<T, MetaSchema<T>>,
+    pub tags: BTreeSet<MetaTag<T>>,
+    pub security_schemes: BTreeMap<T, MetaSecurityScheme<T>>,
+    /// The writable variants of the schemas, `None` if it is the schema
+    /// itself.
+    writable_schemas: BTreeMap<T, Option<T>>,
+    /// Whether the [`Encrypted`](crate::types::Encrypted) type is used.
+    #[cfg(feature = "encrypted")]
+    pub(crate) uses_encrypted: bool,
 }
 
 impl Registry {
@@ -674,4 +914,194 @@ impl Registry {
             self.security_schemes.insert(name, security_scheme);
         }
     }
+
+    /// Uses the writable variants of the schemas in the request bodies, the
+    /// read-only properties are removed from them, such as `UserWritable` for
+    /// `User`.
+    pub(crate) fn use_writable_schemas(&mut self, apis: &mut [MetaApi]) {
+        for request in apis
+            .iter_mut()
+            .flat_map(|api| &mut api.paths)
+            .flat_map(|path| &mut path.operations)
+            .filter_map(|operation| operation.request.as_mut())
+        {
+            for media in &mut request.content {
+                self.writable_ref(&mut media.schema);
+            }
+        }
+    }
+
+    /// Returns the schemas and their writable variants.
+    pub(crate) fn writable_variants(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.writable_schemas
+            .iter()
+            .filter_map(|(name, writable_name)| Some((*name, (*writable_name)?)))
+    }
+
+    /// Returns the name of the writable variant of the schema, or `None` if
+    /// the schema has no read-only properties.
+    fn writable_name(&mut self, name: &'static str) -> Option<&'static str> {
+        if let Some(writable_name) = self.writable_schemas.get(name) {
+            return *writable_name;
+        }
+
+        let mut schema = match self.schemas.get(name) {
+            Some(schema) if self.has_read_only(schema, &mut HashSet::from([name])) => {
+                schema.clone()
+            }
+            _ => {
+                self.writable_schemas.insert(name, None);
+                return None;
+            }
+        };
+
+        // Inserting the name before creating the variant allows the recursive
+        // types to reference the variant.
+        let writable_name = intern(format!("{}Writable", name));
+        self.writable_schemas.insert(name, Some(writable_name));
+        self.writable_schema(&mut schema);
+        self.schemas.insert(writable_name, schema);
+        Some(writable_name)
+    }
+
+    /// Returns `true` if the schema or the schemas it references have
+    /// read-only properties.
+    fn has_read_only(&self, schema: &MetaSchema, visited: &mut HashSet<&'static str>) -> bool {
+        if schema
+            .properties
+            .iter()
+            .any(|(_, schema)| matches!(schema, MetaSchemaRef::Inline(schema) if schema.read_only))
+        {
+            return true;
+        }
+
+        schema
+            .properties
+            .iter()
+            .map(|(_, schema)| schema)
+            .chain(schema.items.as_deref())
+            .chain(schema.additional_properties.as_deref())
+            .chain(&schema.prefix_items)
+            .chain(&schema.all_of)
+            .chain(&schema.one_of)
+            .any(|schema| match schema {
+                MetaSchemaRef::Inline(schema) => self.has_read_only(schema, visited),
+                MetaSchemaRef::Reference(name) => {
+                    visited.insert(name)
+                        && self
+                            .schemas
+                            .get(name)
+                            .map(|schema| self.has_read_only(schema, visited))
+                            .unwrap_or_default()
+                }
+            })
+    }
+
+    fn writable_ref(&mut self, schema: &mut MetaSchemaRef) -> bool {
+        match schema {
+            MetaSchemaRef::Inline(schema) => self.writable_schema(schema),
+            MetaSchemaRef::Reference(name) => match self.writable_name(name) {
+                Some(writable_name) => {
+                    *name = writable_name;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    fn writable_schema(&mut self, schema: &mut MetaSchema) -> bool {
+        let read_only = schema
+            .properties
+            .iter()
+            .filter(
+                |(_, schema)| matches!(schema, MetaSchemaRef::Inline(schema) if schema.read_only),
+            )
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        let mut changed = !read_only.is_empty();
+        schema
+            .properties
+            .retain(|(name, _)| !read_only.contains(name));
+        schema.required.retain(|name| !read_only.contains(name));
+
+        for (_, schema) in &mut schema.properties {
+            changed |= self.writable_ref(schema);
+        }
+        for schema in schema
+            .items
+            .iter_mut()
+            .chain(&mut schema.additional_properties)
+        {
+            changed |= self.writable_ref(schema);
+        }
+        for schema in schema
+            .prefix_items
+            .iter_mut()
+            .chain(&mut schema.all_of)
+            .chain(&mut schema.one_of)
+        {
+            changed |= self.writable_ref(schema);
+        }
+        changed
+    }
+}
+
+/// Leaks the name once, because the references to the schemas are
+/// `&'static str`.
+fn intern(name: String) -> &'static str {
+    static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+    let mut names = NAMES.lock().unwrap();
+    match names.get(name.as_str()) {
+        Some(name) => name,
+        None => {
+            let name = Box::leak(name.into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// Returns the schema name of an instantiation of a generic object, such as
+/// `Page_User` for `Page<User>`.
+pub fn generic_type_name(name: &str, params: &[Cow<'static, str>]) -> &'static str {
+    let mut type_name = name.to_string();
+    for param in params {
+        let part = type_name_part(param);
+        if !part.is_empty() {
+            type_name.push('_');
+            type_name.push_str(&part);
+        }
+    }
+    intern(type_name)
+}
+
+/// Returns the schema name of an instantiation of a generic object from the
+/// template, the `{T}` placeholders are replaced with the names of the type
+/// parameters, such as `UserPage` for `{T}Page` and `Page<User>`.
+pub fn format_type_name(template: &str, params: &[(&str, Cow<'static, str>)]) -> &'static str {
+    let mut type_name = template.to_string();
+    for (name, param) in params {
+        type_name = type_name.replace(&format!("{{{}}}", name), &type_name_part(param));
+    }
+    intern(type_name)
+}
+
+/// Returns the schema name that is created by the `rename_via` function of
+/// an object.
+pub fn intern_type_name(name: String) -> &'static str {
+    intern(name)
+}
+
+/// Converts the name of a type to the characters that are allowed in the
+/// schema names, such as `Array_integer_int32` for `[integer(int32)]`.
+fn type_name_part(name: &str) -> String {
+    name.replace('[', "Array_")
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '.' && c != '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
 }
@@ -3,40 +3,46 @@ use std::collections::BTreeMap;
 use serde::{ser::SerializeMap, Serialize, Serializer};
 
 use crate::registry::{
-    MetaApi, MetaExternalDocument, MetaInfo, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef,
-    MetaSecurityScheme, MetaServer, MetaWebhook, Registry,
+    MetaApi, MetaPath, MetaResponses, MetaSchema, MetaSchemaRef, MetaSecurityScheme, MetaStr,
+    MetaWebhook, OpenApiDocument,
 };
 
 const OPENAPI_VERSION: &str = "3.0.0";
 
-impl<'a> Serialize for MetaSchemaRef {
+impl<T: MetaStr> Serialize for MetaSchema<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MetaSchema::serialize(self, serializer)
+    }
+}
+
+impl<T: MetaStr> Serialize for MetaSchemaRef<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
             MetaSchemaRef::Inline(schema) => schema.serialize(serializer),
             MetaSchemaRef::Reference(name) => {
                 let mut s = serializer.serialize_map(None)?;
-                s.serialize_entry("$ref", &format!("#/components/schemas/{}", name))?;
+                s.serialize_entry("$ref", &format!("#/components/schemas/{}", name.as_ref()))?;
                 s.end()
             }
         }
     }
 }
 
-struct PathMap<'a>(&'a [MetaApi]);
+struct PathMap<'a, T: MetaStr>(&'a [MetaApi<T>]);
 
-impl<'a> Serialize for PathMap<'a> {
+impl<'a, T: MetaStr> Serialize for PathMap<'a, T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(Some(self.0.len()))?;
         for api in self.0 {
             for path in &api.paths {
-                s.serialize_entry(path.path, path)?;
+                s.serialize_entry(&path.path, path)?;
             }
         }
         s.end()
     }
 }
 
-impl Serialize for MetaPath {
+impl<T: MetaStr> Serialize for MetaPath<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
 
@@ -48,7 +54,7 @@ impl Serialize for MetaPath {
     }
 }
 
-impl Serialize for MetaResponses {
+impl<T: MetaStr> Serialize for MetaResponses<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(None)?;
         for resp in &self.responses {
@@ -61,9 +67,9 @@ impl Serialize for MetaResponses {
     }
 }
 
-struct WebhookMap<'a>(&'a [MetaWebhook]);
+struct WebhookMap<'a, T: MetaStr>(&'a [MetaWebhook<T>]);
 
-impl<'a> Serialize for WebhookMap<'a> {
+impl<'a, T: MetaStr> Serialize for WebhookMap<'a, T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         let mut s = serializer.serialize_map(Some(self.0.len()))?;
         for webhook in self.0 {
@@ -73,35 +79,26 @@ impl<'a> Serialize for WebhookMap<'a> {
     }
 }
 
-pub(crate) struct Document<'a> {
-    pub(crate) info: &'a MetaInfo,
-    pub(crate) servers: &'a [MetaServer],
-    pub(crate) apis: &'a [MetaApi],
-    pub(crate) webhooks: &'a [MetaWebhook],
-    pub(crate) registry: &'a Registry,
-    pub(crate) external_document: Option<&'a MetaExternalDocument>,
-}
-
-impl<'a> Serialize for Document<'a> {
+impl<T: MetaStr> Serialize for OpenApiDocument<T> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         #[derive(Serialize)]
-        struct Components<'a> {
-            schemas: &'a BTreeMap<&'static str, MetaSchema>,
+        struct Components<'a, T: MetaStr> {
+            schemas: &'a BTreeMap<T, MetaSchema<T>>,
             #[serde(rename = "securitySchemes")]
             #[serde(skip_serializing_if = "BTreeMap::is_empty")]
-            security_schemes: &'a BTreeMap<&'static str, MetaSecurityScheme>,
+            security_schemes: &'a BTreeMap<T, MetaSecurityScheme<T>>,
         }
 
         let mut s = serializer.serialize_map(None)?;
 
         s.serialize_entry("openapi", OPENAPI_VERSION)?;
         s.serialize_entry("info", &self.info)?;
-        s.serialize_entry("servers", self.servers)?;
+        s.serialize_entry("servers", &self.servers)?;
         s.serialize_entry("tags", &self.registry.tags)?;
         if !self.webhooks.is_empty() {
-            s.serialize_entry("webhooks", &WebhookMap(self.webhooks))?;
+            s.serialize_entry("webhooks", &WebhookMap(&self.webhooks))?;
         }
-        s.serialize_entry("paths", &PathMap(self.apis))?;
+        s.serialize_entry("paths", &PathMap(&self.apis))?;
         s.serialize_entry(
             "components",
             &Components {
@@ -110,6 +107,9 @@ impl<'a> Serialize for Document<'a> {
             },
         )?;
         s.serialize_entry("externalDocs", &self.external_document)?;
+        for (name, value) in &self.extensions {
+            s.serialize_entry(name, value)?;
+        }
 
         s.end()
     }
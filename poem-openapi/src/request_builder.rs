@@ -0,0 +1,204 @@
+//! The runtime of the request builders that are generated by
+//! `#[OpenApi(request_builders)]`.
+
+use poem::http::{header, Method, Request};
+use serde_json::Value;
+
+use crate::{
+    error::BuildRequestError,
+    payload::{Binary, Json, Payload, PlainText},
+    registry::MetaParamIn,
+    types::ToJSON,
+};
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct RequestParam {
+    pub name: &'static str,
+    pub in_type: Option<MetaParamIn>,
+    pub required: bool,
+}
+
+/// The body of a request that is sent by a request builder.
+#[doc(hidden)]
+pub trait RequestBody {
+    fn into_content(self) -> (&'static str, Vec<u8>);
+}
+
+impl<T: ToJSON> RequestBody for Json<T> {
+    fn into_content(self) -> (&'static str, Vec<u8>) {
+        (Self::CONTENT_TYPE, self.0.to_json_string().into_bytes())
+    }
+}
+
+impl<T: Into<String> + Send> RequestBody for PlainText<T> {
+    fn into_content(self) -> (&'static str, Vec<u8>) {
+        (Self::CONTENT_TYPE, self.0.into().into_bytes())
+    }
+}
+
+impl<T: Into<Vec<u8>> + Send> RequestBody for Binary<T> {
+    fn into_content(self) -> (&'static str, Vec<u8>) {
+        (Self::CONTENT_TYPE, self.0.into())
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct RequestBuilder {
+    method: Method,
+    path: &'static str,
+    params: Vec<RequestParam>,
+    values: Vec<Option<Value>>,
+    body_required: bool,
+    server: String,
+    body: Option<(&'static str, Vec<u8>)>,
+}
+
+impl RequestBuilder {
+    pub fn new(
+        method: Method,
+        path: &'static str,
+        params: Vec<RequestParam>,
+        body_required: bool,
+    ) -> Self {
+        Self {
+            method,
+            path,
+            values: vec![None; params.len()],
+            params,
+            body_required,
+            server: String::new(),
+            body: None,
+        }
+    }
+
+    #[must_use]
+    pub fn server(self, url: impl Into<String>) -> Self {
+        let mut server = url.into();
+        while server.ends_with('/') {
+            server.pop();
+        }
+        Self { server, ..self }
+    }
+
+    #[must_use]
+    pub fn param(mut self, idx: usize, value: impl ToJSON) -> Self {
+        let value = value.to_json();
+        self.values[idx] = (!value.is_null()).then(|| value);
+        self
+    }
+
+    #[must_use]
+    pub fn body(self, body: impl RequestBody) -> Self {
+        Self {
+            body: Some(body.into_content()),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> Result<Request<Vec<u8>>, BuildRequestError> {
+        let mut uri = format!("{}{}", self.server, self.path);
+        let mut query = Vec::new();
+        let mut cookies = Vec::new();
+        let mut builder = Request::builder().method(self.method);
+
+        for (param, value) in self.params.iter().zip(&self.values) {
+            let in_type = match param.in_type {
+                Some(in_type) => in_type,
+                None => continue,
+            };
+            let value = match value {
+                Some(value) => value,
+                None if param.required || in_type == MetaParamIn::Path => {
+                    return Err(BuildRequestError::MissingParam { name: param.name });
+                }
+                None => continue,
+            };
+            match (in_type, value) {
+                (MetaParamIn::Path, value) => {
+                    uri = uri.replace(
+                        &format!("{{{}}}", param.name),
+                        &percent_encode(&param_string(value)),
+                    );
+                }
+                (MetaParamIn::Query, Value::Array(values)) => {
+                    for value in values {
+                        query.push((param.name, param_string(value)));
+                    }
+                }
+                (MetaParamIn::Query, value) => query.push((param.name, param_string(value))),
+                (MetaParamIn::Header, value) => {
+                    builder = builder.header(param.name, param_string(value));
+                }
+                (MetaParamIn::Cookie, value) => {
+                    cookies.push(format!(
+                        "{}={}",
+                        param.name,
+                        percent_encode(&param_string(value))
+                    ));
+                }
+                // the keys of the private and signed cookies are not known
+                (MetaParamIn::CookiePrivate | MetaParamIn::CookieSigned, _) => {
+                    return Err(BuildRequestError::UnsupportedParam { name: param.name });
+                }
+            }
+        }
+        if self.body_required && self.body.is_none() {
+            return Err(BuildRequestError::MissingBody);
+        }
+
+        if !query.is_empty() {
+            let query = query
+                .iter()
+                .map(|(name, value)| format!("{}={}", percent_encode(name), percent_encode(value)))
+                .collect::<Vec<_>>();
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+        if !cookies.is_empty() {
+            builder = builder.header(header::COOKIE, cookies.join("; "));
+        }
+
+        let data = match self.body {
+            Some((content_type, data)) => {
+                builder = builder.header(header::CONTENT_TYPE, content_type);
+                data
+            }
+            None => Vec::new(),
+        };
+        builder
+            .uri(uri)
+            .body(data)
+            .map_err(|err| BuildRequestError::InvalidRequest {
+                reason: err.to_string(),
+            })
+    }
+}
+
+/// Formats the value of a parameter, the items of the arrays are joined with
+/// `,`.
+pub(crate) fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(values) => values
+            .iter()
+            .map(param_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+pub(crate) fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,40 @@
+use poem::{
+    endpoint::BoxEndpoint,
+    middleware::{Priority, Scheduler},
+    Endpoint, EndpointExt, Request, Response, Result,
+};
+
+use crate::registry::MetaOperation;
+
+/// Inserts the priority of the operation into the extensions of the request.
+struct PriorityEndpoint {
+    priority: Priority,
+    inner: BoxEndpoint<'static, Response>,
+}
+
+#[poem::async_trait]
+impl Endpoint for PriorityEndpoint {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        req.extensions_mut().insert(self.priority);
+        self.inner.call(req).await
+    }
+}
+
+/// Applies the scheduler to the operation, the priorities of the requests are
+/// the priority of the operation.
+pub(crate) fn apply(
+    ep: BoxEndpoint<'static, Response>,
+    scheduler: &Scheduler,
+    operation: &MetaOperation,
+) -> BoxEndpoint<'static, Response> {
+    let ep = ep.with(scheduler.clone()).boxed();
+    match operation.priority {
+        Some(priority) => Box::new(PriorityEndpoint {
+            priority,
+            inner: ep,
+        }),
+        None => ep,
+    }
+}
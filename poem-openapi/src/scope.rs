@@ -0,0 +1,47 @@
+use poem::{endpoint::BoxEndpoint, Response};
+
+use crate::{OperationInfo, Tags};
+
+/// A subset of the operations that a middleware is applied to.
+///
+/// See also: [`OpenApiService::with_on`](crate::OpenApiService::with_on)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MiddlewareScope {
+    /// The operations that have the tag.
+    Tag(&'static str),
+    /// The operations whose paths start with the prefix.
+    PathPrefix(String),
+}
+
+impl MiddlewareScope {
+    /// Create a scope that contains the operations that have the tag.
+    pub fn tag(tag: impl Tags) -> Self {
+        Self::Tag(tag.name())
+    }
+
+    /// Create a scope that contains the operations whose paths start with the
+    /// prefix.
+    pub fn path_prefix(prefix: impl Into<String>) -> Self {
+        Self::PathPrefix(prefix.into())
+    }
+}
+
+impl<T: Tags> From<T> for MiddlewareScope {
+    fn from(tag: T) -> Self {
+        Self::tag(tag)
+    }
+}
+
+pub(crate) type ScopedMiddleware =
+    Box<dyn Fn(BoxEndpoint<'static, Response>) -> BoxEndpoint<'static, Response> + Send + Sync>;
+
+impl MiddlewareScope {
+    /// Returns `true` if the operation is in the scope, the prefix is
+    /// compared with the path template of the operation.
+    pub(crate) fn contains(&self, info: &OperationInfo) -> bool {
+        match self {
+            MiddlewareScope::Tag(tag) => info.tags().contains(tag),
+            MiddlewareScope::PathPrefix(prefix) => info.path().starts_with(prefix.as_str()),
+        }
+    }
+}
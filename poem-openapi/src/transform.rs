@@ -0,0 +1,135 @@
+//! Transformations applied to the input after parsing.
+//!
+//! The `transform` attribute of the object fields and the operation
+//! parameters applies these functions, in the order in which they are
+//! declared, to every string contained in the parsed value before it is
+//! validated.
+//!
+//! The string types that check their format while parsing, such as
+//! [`LanguageTag`](crate::types::LanguageTag) and
+//! [`MediaType`](crate::types::MediaType), do not implement [`Transform`],
+//! because a transformation applied after parsing could produce a value that
+//! no longer has the documented format.
+//!
+//! ```compile_fail
+//! use poem_openapi::{types::LanguageTag, Object};
+//!
+//! #[derive(Object)]
+//! struct User {
+//!     #[oai(transform(lowercase))]
+//!     language: LanguageTag,
+//! }
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use poem_openapi::Object;
+//!
+//! fn remove_dashes(s: &mut String) {
+//!     s.retain(|c| c != '-');
+//! }
+//!
+//! #[derive(Object)]
+//! struct User {
+//!     #[oai(transform(trim, lowercase))]
+//!     email: String,
+//!     #[oai(transform(trim, custom = "remove_dashes"))]
+//!     phone: Option<String>,
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    param::{Cookie, CookiePrivate, CookieSigned, Header, Path, Query},
+    types::Password,
+};
+
+/// Represents a type whose strings can be transformed after parsing.
+pub trait Transform {
+    /// Applies the function to every string contained in this value.
+    fn transform(&mut self, f: fn(&mut String));
+}
+
+/// Removes the leading and trailing whitespace.
+pub fn trim(s: &mut String) {
+    let trimmed = s.trim();
+    if trimmed.len() != s.len() {
+        *s = trimmed.to_string();
+    }
+}
+
+/// Converts the string to lowercase.
+pub fn lowercase(s: &mut String) {
+    *s = s.to_lowercase();
+}
+
+/// Converts the string to uppercase.
+pub fn uppercase(s: &mut String) {
+    *s = s.to_uppercase();
+}
+
+impl Transform for String {
+    fn transform(&mut self, f: fn(&mut String)) {
+        f(self);
+    }
+}
+
+impl<T: Transform> Transform for Option<T> {
+    fn transform(&mut self, f: fn(&mut String)) {
+        if let Some(value) = self {
+            value.transform(f);
+        }
+    }
+}
+
+impl<T: Transform> Transform for Box<T> {
+    fn transform(&mut self, f: fn(&mut String)) {
+        self.as_mut().transform(f);
+    }
+}
+
+impl<T: Transform> Transform for Vec<T> {
+    fn transform(&mut self, f: fn(&mut String)) {
+        for value in self {
+            value.transform(f);
+        }
+    }
+}
+
+impl<K, V: Transform> Transform for HashMap<K, V> {
+    fn transform(&mut self, f: fn(&mut String)) {
+        for value in self.values_mut() {
+            value.transform(f);
+        }
+    }
+}
+
+impl<K, V: Transform> Transform for BTreeMap<K, V> {
+    fn transform(&mut self, f: fn(&mut String)) {
+        for value in self.values_mut() {
+            value.transform(f);
+        }
+    }
+}
+
+impl Transform for Password {
+    fn transform(&mut self, f: fn(&mut String)) {
+        f(&mut self.0);
+    }
+}
+
+macro_rules! impl_transform_for_wrappers {
+    ($($ty:ident),*) => {
+        $(
+        impl<T: Transform> Transform for $ty<T> {
+            fn transform(&mut self, f: fn(&mut String)) {
+                self.0.transform(f);
+            }
+        }
+        )*
+    };
+}
+
+impl_transform_for_wrappers!(Query, Header, Path, Cookie, CookiePrivate, CookieSigned);
@@ -3,12 +3,16 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+#[cfg(feature = "multipart")]
 use bytes::Bytes;
+#[cfg(feature = "multipart")]
 use poem::web::Field;
 
+#[cfg(feature = "multipart")]
+use crate::types::{ParseError, ParseFromMultipartField, ParseResult};
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{ParseError, ParseFromMultipartField, ParseResult, Type},
+    types::Type,
 };
 
 /// Represents a binary data.
@@ -55,6 +59,7 @@ impl<T: Send + Sync> Type for Binary<T> {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for Binary<Vec<u8>> {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -65,6 +70,7 @@ impl ParseFromMultipartField for Binary<Vec<u8>> {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for Binary<Bytes> {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -0,0 +1,281 @@
+use std::{borrow::Cow, ops::Deref};
+
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+macro_rules! impl_bounded_integers {
+    ($($(#[$docs:meta])* ($ty:ident, $inner:ty, $format:literal)),*) => {
+        $(
+        $(#[$docs])*
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $ty<const MIN: $inner, const MAX: $inner>($inner);
+
+        impl<const MIN: $inner, const MAX: $inner> $ty<MIN, MAX> {
+            /// Create a new value, returns `None` if the value is out of range.
+            pub fn new(value: $inner) -> Option<Self> {
+                (MIN..=MAX).contains(&value).then(|| Self(value))
+            }
+
+            /// Returns the inner value.
+            #[inline]
+            pub fn get(self) -> $inner {
+                self.0
+            }
+
+            fn check(value: $inner) -> ParseResult<Self> {
+                Self::new(value).ok_or_else(|| {
+                    ParseError::custom(format!(
+                        "Only integers from {} to {} are accepted.",
+                        MIN, MAX
+                    ))
+                })
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> Deref for $ty<MIN, MAX> {
+            type Target = $inner;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> From<$ty<MIN, MAX>> for $inner {
+            fn from(value: $ty<MIN, MAX>) -> Self {
+                value.0
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> Type for $ty<MIN, MAX> {
+            const IS_REQUIRED: bool = true;
+
+            type RawValueType = Self;
+
+            type RawElementValueType = Self;
+
+            fn name() -> Cow<'static, str> {
+                format!("integer({}, {}..={})", $format, MIN, MAX).into()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    minimum: Some(MIN as f64),
+                    maximum: Some(MAX as f64),
+                    ..MetaSchema::new_with_format("integer", $format)
+                }))
+            }
+
+            fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                Some(self)
+            }
+
+            fn raw_element_iter<'a>(
+                &'a self,
+            ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                Box::new(self.as_raw_value().into_iter())
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> ParseFromJSON for $ty<MIN, MAX> {
+            fn parse_from_json(value: Value) -> ParseResult<Self> {
+                let value = <$inner>::parse_from_json(value).map_err(ParseError::propagate)?;
+                Self::check(value)
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> ParseFromParameter for $ty<MIN, MAX> {
+            fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+                let value = <$inner>::parse_from_parameter(value).map_err(ParseError::propagate)?;
+                Self::check(value)
+            }
+        }
+
+        impl<const MIN: $inner, const MAX: $inner> ToJSON for $ty<MIN, MAX> {
+            fn to_json(&self) -> Value {
+                self.0.to_json()
+            }
+        }
+        )*
+    };
+}
+
+impl_bounded_integers!(
+    /// A 32-bit integer in the range `MIN..=MAX`.
+    (BoundedI32, i32, "int32"),
+    /// A 64-bit integer in the range `MIN..=MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem_openapi::{types::BoundedI64, Object};
+    ///
+    /// #[derive(Object)]
+    /// struct Page {
+    ///     page_size: BoundedI64<1, 100>,
+    /// }
+    /// ```
+    (BoundedI64, i64, "int64"),
+    /// A 32-bit unsigned integer in the range `MIN..=MAX`.
+    (BoundedU32, u32, "uint32"),
+    /// A 64-bit unsigned integer in the range `MIN..=MAX`.
+    (BoundedU64, u64, "uint64")
+);
+
+/// A string that is not empty, and its length does not exceeds `MAX`.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{types::NonEmptyString, Object};
+///
+/// #[derive(Object)]
+/// struct User {
+///     name: NonEmptyString<32>,
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct NonEmptyString<const MAX: usize>(String);
+
+impl<const MAX: usize> NonEmptyString<MAX> {
+    /// Create a new string, returns `None` if the string is empty or too long.
+    pub fn new(value: impl Into<String>) -> Option<Self> {
+        let value = value.into();
+        (!value.is_empty() && value.len() <= MAX).then(|| Self(value))
+    }
+
+    /// Returns the inner string.
+    #[inline]
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+
+    fn check(value: String) -> ParseResult<Self> {
+        Self::new(value).ok_or_else(|| {
+            ParseError::custom(format!(
+                "Only strings with a length from 1 to {} are accepted.",
+                MAX
+            ))
+        })
+    }
+}
+
+impl<const MAX: usize> Deref for NonEmptyString<MAX> {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const MAX: usize> AsRef<str> for NonEmptyString<MAX> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const MAX: usize> From<NonEmptyString<MAX>> for String {
+    fn from(value: NonEmptyString<MAX>) -> Self {
+        value.0
+    }
+}
+
+impl<const MAX: usize> Type for NonEmptyString<MAX> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        format!("string(1..={})", MAX).into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            min_length: Some(1),
+            max_length: Some(MAX),
+            ..MetaSchema::new("string")
+        }))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl<const MAX: usize> ParseFromJSON for NonEmptyString<MAX> {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Self::check(value)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl<const MAX: usize> ParseFromParameter for NonEmptyString<MAX> {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::check(value.to_string())
+    }
+}
+
+impl<const MAX: usize> ToJSON for NonEmptyString<MAX> {
+    fn to_json(&self) -> Value {
+        Value::String(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn bounded_integer() {
+        assert_eq!(
+            BoundedI64::<1, 100>::parse_from_json(json!(100)).unwrap(),
+            BoundedI64::new(100).unwrap()
+        );
+        assert!(BoundedI64::<1, 100>::parse_from_json(json!(0)).is_err());
+        assert!(BoundedU32::<1, 100>::parse_from_parameter("101").is_err());
+        assert_eq!(
+            BoundedI32::<-10, 10>::parse_from_parameter("-10")
+                .unwrap()
+                .get(),
+            -10
+        );
+
+        let schema = BoundedI64::<1, 100>::schema_ref();
+        let schema = schema.unwrap_inline();
+        assert_eq!(schema.minimum, Some(1.0));
+        assert_eq!(schema.maximum, Some(100.0));
+    }
+
+    #[test]
+    fn non_empty_string() {
+        assert_eq!(
+            NonEmptyString::<3>::parse_from_json(json!("abc"))
+                .unwrap()
+                .as_str(),
+            "abc"
+        );
+        assert!(NonEmptyString::<3>::parse_from_json(json!("")).is_err());
+        assert!(NonEmptyString::<3>::parse_from_parameter("abcd").is_err());
+
+        let schema = NonEmptyString::<3>::schema_ref();
+        let schema = schema.unwrap_inline();
+        assert_eq!(schema.min_length, Some(1));
+        assert_eq!(schema.max_length, Some(3));
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    ops::{Deref, DerefMut},
+    str::FromStr,
+};
+
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+/// A number of bytes that can be parsed from a human readable string like
+/// `10MB` or `1.5GiB`, or a JSON integer.
+///
+/// The units `KB`, `MB`, `GB`, `TB` and `PB` are powers of 1000, and the
+/// units `KiB`, `MiB`, `GiB`, `TiB` and `PiB` are powers of 1024.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl Deref for ByteSize {
+    type Target = u64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ByteSize {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(size: u64) -> Self {
+        Self(size)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+const UNITS: &[(&str, u64)] = &[
+    ("PiB", 1 << 50),
+    ("PB", 1_000_000_000_000_000),
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+];
+
+impl FromStr for ByteSize {
+    type Err = ParseError<Self>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let len = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (value, unit) = (&s[..len], s[len..].trim_start());
+
+        let unit = match unit {
+            "" | "B" => 1,
+            unit => UNITS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(unit))
+                .map(|(_, size)| *size)
+                .ok_or_else(|| ParseError::custom("invalid byte size unit"))?,
+        };
+
+        let size = match value.split_once('.') {
+            Some(_) => {
+                let value: f64 = value
+                    .parse()
+                    .map_err(|_| ParseError::custom("invalid byte size"))?;
+                (value * unit as f64).round() as u64
+            }
+            None => value
+                .parse::<u64>()
+                .ok()
+                .and_then(|value| value.checked_mul(unit))
+                .ok_or_else(|| ParseError::custom("invalid byte size"))?,
+        };
+
+        Ok(Self(size))
+    }
+}
+
+impl Display for ByteSize {
+    #[allow(clippy::manual_is_multiple_of)]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match UNITS
+            .iter()
+            .find(|(_, size)| self.0 != 0 && self.0 % size == 0)
+        {
+            Some((name, size)) => write!(f, "{}{}", self.0 / size, name),
+            None => write!(f, "{}B", self.0),
+        }
+    }
+}
+
+impl Type for ByteSize {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string(byte-size)".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            pattern: Some(r"^\d+(\.\d+)?\s*(B|[KMGTP]i?B)?$".to_string()),
+            ..MetaSchema::new_with_format("string", "byte-size")
+        }))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl ParseFromJSON for ByteSize {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value {
+            Value::String(value) => value.parse(),
+            Value::Number(n) => n
+                .as_u64()
+                .map(Self)
+                .ok_or_else(|| ParseError::custom("invalid byte size")),
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl ParseFromParameter for ByteSize {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        value.parse()
+    }
+}
+
+impl ToJSON for ByteSize {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for (s, size) in [
+            ("512", 512),
+            ("512B", 512),
+            ("10MB", 10_000_000),
+            ("10 mb", 10_000_000),
+            ("1KiB", 1024),
+            ("1.5GiB", 1536 << 20),
+        ] {
+            assert_eq!(s.parse::<ByteSize>().unwrap().0, size, "{}", s);
+        }
+
+        for s in ["", "MB", "10XB", "1.2.3KB"] {
+            assert!(s.parse::<ByteSize>().is_err(), "{}", s);
+        }
+
+        assert_eq!(ByteSize::parse_from_json(json!(100)).unwrap().0, 100);
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(ByteSize(10_000_000).to_json(), json!("10MB"));
+        assert_eq!(ByteSize(1 << 20).to_json(), json!("1MiB"));
+        assert_eq!(ByteSize(1500).to_json(), json!("1500B"));
+        assert_eq!(ByteSize(0).to_json(), json!("0B"));
+    }
+}
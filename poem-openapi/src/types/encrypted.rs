@@ -0,0 +1,411 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+
+use aes_gcm::{
+    aead::{Aead, NewAead, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use poem::{endpoint::BoxEndpoint, Endpoint, Request, Response, Result};
+use rand::RngCore;
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+};
+
+const NONCE_SIZE: usize = 12;
+
+tokio::task_local! {
+    static KEY_PROVIDER: Arc<dyn KeyProvider>;
+}
+
+/// A provider of the 256-bit keys used by [`Encrypted`].
+///
+/// The keys are requested when the values are serialized or parsed, so a
+/// provider backed by a KMS should cache the data keys it has decrypted
+/// instead of calling the KMS every time.
+pub trait KeyProvider: Send + Sync + 'static {
+    /// Returns the id of the key used to encrypt the new values.
+    fn current_key_id(&self) -> Cow<'_, str>;
+
+    /// Returns the key with the id, or `None` if the key is unknown.
+    ///
+    /// The old keys should be kept after the key rotation, so that the values
+    /// encrypted with them can still be decrypted.
+    fn key(&self, key_id: &str) -> Option<[u8; 32]>;
+}
+
+/// A [`KeyProvider`] that holds the keys in memory.
+pub struct LocalKeyProvider {
+    current_key_id: String,
+    keys: HashMap<String, [u8; 32]>,
+}
+
+impl LocalKeyProvider {
+    /// Create a provider that encrypts the new values with the key.
+    pub fn new(key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        let key_id = key_id.into();
+        Self {
+            keys: [(key_id.clone(), key)].into_iter().collect(),
+            current_key_id: key_id,
+        }
+    }
+
+    /// Adds a key that is only used to decrypt the existing values.
+    #[must_use]
+    pub fn with_key(mut self, key_id: impl Into<String>, key: [u8; 32]) -> Self {
+        self.keys.entry(key_id.into()).or_insert(key);
+        self
+    }
+}
+
+impl KeyProvider for LocalKeyProvider {
+    fn current_key_id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.current_key_id)
+    }
+
+    fn key(&self, key_id: &str) -> Option<[u8; 32]> {
+        self.keys.get(key_id).copied()
+    }
+}
+
+/// An error that occurs when encrypting a value of [`Encrypted`].
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct EncryptError(String);
+
+fn current_key_provider() -> Option<Arc<dyn KeyProvider>> {
+    KEY_PROVIDER.try_with(Arc::clone).ok()
+}
+
+fn encrypt(provider: &dyn KeyProvider, plaintext: &[u8]) -> Result<String, EncryptError> {
+    let key_id = provider.current_key_id();
+    let key = provider
+        .key(&key_id)
+        .ok_or_else(|| EncryptError(format!("unknown key `{}`", key_id)))?;
+
+    let mut nonce = [0; NONCE_SIZE];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = Aes256Gcm::new(Key::from_slice(&key))
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: key_id.as_bytes(),
+            },
+        )
+        .map_err(|_| EncryptError("failed to encrypt the value".to_string()))?;
+
+    let mut data = nonce.to_vec();
+    data.extend(ciphertext);
+    Ok(format!(
+        "{}:{}",
+        key_id,
+        base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+    ))
+}
+
+fn decrypt(provider: &dyn KeyProvider, value: &str) -> Result<Vec<u8>, String> {
+    let (key_id, data) = value
+        .rsplit_once(':')
+        .ok_or_else(|| "invalid encrypted value".to_string())?;
+    let key = provider
+        .key(key_id)
+        .ok_or_else(|| format!("unknown key `{}`", key_id))?;
+    let data = base64::decode_config(data, base64::URL_SAFE_NO_PAD)
+        .map_err(|_| "invalid encrypted value".to_string())?;
+    if data.len() < NONCE_SIZE {
+        return Err("invalid encrypted value".to_string());
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    Aes256Gcm::new(Key::from_slice(&key))
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: key_id.as_bytes(),
+            },
+        )
+        .map_err(|_| "failed to decrypt the value".to_string())
+}
+
+pub(crate) fn apply(
+    ep: BoxEndpoint<'static, Response>,
+    provider: Arc<dyn KeyProvider>,
+) -> BoxEndpoint<'static, Response> {
+    Box::new(KeyProviderEndpoint {
+        inner: ep,
+        provider,
+    })
+}
+
+struct KeyProviderEndpoint {
+    inner: BoxEndpoint<'static, Response>,
+    provider: Arc<dyn KeyProvider>,
+}
+
+#[poem::async_trait]
+impl Endpoint for KeyProviderEndpoint {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        KEY_PROVIDER
+            .scope(self.provider.clone(), self.inner.call(req))
+            .await
+    }
+}
+
+/// A value that is encrypted with AES-256-GCM when it is serialized, and
+/// decrypted when it is parsed.
+///
+/// It is used for the fields that must be encrypted at the application layer
+/// before they are stored, and is documented as an opaque string. The
+/// encrypted value is `<key id>:<base64url(nonce + ciphertext)>`, so the
+/// values remain readable after the key is rotated.
+///
+/// The keys are provided by the [`KeyProvider`] passed to
+/// [`OpenApiService::key_provider`](crate::OpenApiService::key_provider),
+/// it is used while the requests are handled, and building the service
+/// panics if the API uses `Encrypted` without a provider. Outside the
+/// service, use [`Encrypted::encrypt_with`] and [`Encrypted::decrypt_with`]
+/// with the provider. [`ToJSON::to_json`] returns `null` and the
+/// [`Serialize`] implementation returns an error if there is no provider.
+///
+/// The [`Debug`] implementation does not print the value.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{
+///     payload::Json,
+///     types::{Encrypted, LocalKeyProvider},
+///     Object, OpenApi, OpenApiService,
+/// };
+///
+/// #[derive(Debug, Object)]
+/// struct Customer {
+///     name: String,
+///     tax_id: Encrypted<String>,
+/// }
+///
+/// struct Api;
+///
+/// #[OpenApi]
+/// impl Api {
+///     #[oai(path = "/customers", method = "post")]
+///     async fn create(&self, customer: Json<Customer>) -> Json<Customer> {
+///         customer
+///     }
+/// }
+///
+/// let provider = LocalKeyProvider::new("2022-01", [7; 32]);
+/// let encrypted = Encrypted("123-45-6789".to_string())
+///     .encrypt_with(&provider)
+///     .unwrap();
+/// assert!(encrypted.starts_with("2022-01:"));
+/// assert_eq!(
+///     Encrypted::<String>::decrypt_with(&encrypted, &provider)
+///         .unwrap()
+///         .as_str(),
+///     "123-45-6789"
+/// );
+///
+/// let api_service = OpenApiService::new(Api, "Demo", "1.0").key_provider(provider);
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Default)]
+pub struct Encrypted<T>(pub T);
+
+impl<T> Deref for Encrypted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Encrypted<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> Debug for Encrypted<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Encrypted(..)")
+    }
+}
+
+impl<T: ToJSON> Encrypted<T> {
+    /// Encrypts the value with the key provider.
+    pub fn encrypt_with(&self, provider: &dyn KeyProvider) -> Result<String, EncryptError> {
+        encrypt(provider, self.0.to_json().to_string().as_bytes())
+    }
+
+    fn encrypt(&self) -> Result<String, EncryptError> {
+        let provider = current_key_provider()
+            .ok_or_else(|| EncryptError("no key provider for `Encrypted`".to_string()))?;
+        self.encrypt_with(&*provider)
+    }
+}
+
+impl<T: ParseFromJSON> Encrypted<T> {
+    /// Decrypts the value that is encrypted with the key provider.
+    pub fn decrypt_with(value: &str, provider: &dyn KeyProvider) -> ParseResult<Self> {
+        let plaintext = decrypt(provider, value).map_err(ParseError::custom)?;
+        let value = serde_json::from_slice(&plaintext).map_err(ParseError::custom)?;
+        Ok(Self(
+            T::parse_from_json(value).map_err(ParseError::propagate)?,
+        ))
+    }
+
+    fn decrypt(value: &str) -> ParseResult<Self> {
+        let provider = current_key_provider()
+            .ok_or_else(|| ParseError::custom("no key provider for `Encrypted`"))?;
+        Self::decrypt_with(value, &*provider)
+    }
+}
+
+impl<T: Type> Type for Encrypted<T> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string(encrypted)".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+    }
+
+    fn register(registry: &mut Registry) {
+        registry.uses_encrypted = true;
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl<T: ParseFromJSON> ParseFromJSON for Encrypted<T> {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Self::decrypt(&value)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl<T: ToJSON> ToJSON for Encrypted<T> {
+    fn to_json(&self) -> Value {
+        self.encrypt().map(Value::String).unwrap_or_default()
+    }
+}
+
+impl<T: ToJSON> Serialize for Encrypted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.encrypt().map_err(ser::Error::custom)?)
+    }
+}
+
+impl<'de, T: ParseFromJSON> Deserialize<'de> for Encrypted<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::decrypt(&value).map_err(|err| de::Error::custom(err.into_message()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let provider = LocalKeyProvider::new("k1", [1; 32]);
+        let encrypted = Encrypted(vec![1, 2, 3]).encrypt_with(&provider).unwrap();
+        assert!(encrypted.starts_with("k1:"));
+        assert!(!encrypted.contains("[1,2,3]"));
+        assert_ne!(
+            Encrypted(vec![1, 2, 3]).encrypt_with(&provider).unwrap(),
+            encrypted
+        );
+        assert_eq!(
+            Encrypted::<Vec<i32>>::decrypt_with(&encrypted, &provider)
+                .unwrap()
+                .0,
+            vec![1, 2, 3]
+        );
+
+        // rotate the key
+        let provider = LocalKeyProvider::new("k2", [2; 32]).with_key("k1", [1; 32]);
+        assert_eq!(
+            Encrypted::<Vec<i32>>::decrypt_with(&encrypted, &provider)
+                .unwrap()
+                .0,
+            vec![1, 2, 3]
+        );
+        assert!(Encrypted("abc".to_string())
+            .encrypt_with(&provider)
+            .unwrap()
+            .starts_with("k2:"));
+
+        // tampered
+        let mut tampered = encrypted.clone().into_bytes();
+        let last = tampered.last_mut().unwrap();
+        *last = if *last == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(Encrypted::<Vec<i32>>::decrypt_with(&tampered, &provider).is_err());
+
+        // unknown key
+        let provider = LocalKeyProvider::new("k2", [2; 32]);
+        assert!(Encrypted::<Vec<i32>>::decrypt_with(&encrypted, &provider).is_err());
+        assert_eq!(format!("{:?}", Encrypted(1)), "Encrypted(..)");
+    }
+
+    #[tokio::test]
+    async fn scoped_provider() {
+        let provider: Arc<dyn KeyProvider> = Arc::new(LocalKeyProvider::new("k1", [1; 32]));
+        let value = KEY_PROVIDER
+            .scope(provider.clone(), async { Encrypted(1).to_json() })
+            .await;
+        assert!(value.as_str().unwrap().starts_with("k1:"));
+
+        let json = KEY_PROVIDER
+            .scope(provider.clone(), async {
+                serde_json::to_string(&Encrypted(1)).unwrap()
+            })
+            .await;
+        assert_eq!(
+            KEY_PROVIDER
+                .scope(provider, async {
+                    Encrypted::<i32>::parse_from_json(value).unwrap().0
+                        + serde_json::from_str::<Encrypted<i32>>(&json).unwrap().0
+                })
+                .await,
+            2
+        );
+
+        // no provider
+        assert_eq!(Encrypted(1).to_json(), Value::Null);
+        assert!(serde_json::to_string(&Encrypted(1)).is_err());
+        assert!(Encrypted::<i32>::parse_from_json(Value::String(json)).is_err());
+    }
+}
@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 impl Type for bool {
@@ -53,6 +54,7 @@ impl ParseFromParameter for bool {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for bool {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
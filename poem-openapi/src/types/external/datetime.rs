@@ -1,15 +1,15 @@
 use std::borrow::Cow;
 
 use chrono::{DateTime, FixedOffset, Local, Utc};
+#[cfg(feature = "multipart")]
 use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 macro_rules! impl_datetime_types {
@@ -56,6 +56,7 @@ macro_rules! impl_datetime_types {
             }
         }
 
+        #[cfg(feature = "multipart")]
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
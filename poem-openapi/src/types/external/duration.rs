@@ -0,0 +1,261 @@
+use std::{borrow::Cow, time::Duration};
+
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
+use serde_json::Value;
+
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{
+        human_duration::{format_iso8601, parse_iso8601},
+        ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type,
+    },
+};
+
+impl Type for Duration {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string(duration)".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "duration")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl ParseFromJSON for Duration {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Self::parse_from_parameter(&value)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl ParseFromParameter for Duration {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        parse_iso8601(value).ok_or_else(|| ParseError::custom("invalid ISO 8601 duration"))
+    }
+}
+
+#[cfg(feature = "multipart")]
+#[poem::async_trait]
+impl ParseFromMultipartField for Duration {
+    async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
+        match field {
+            Some(field) => Self::parse_from_parameter(&field.text().await?),
+            None => Err(ParseError::expected_input()),
+        }
+    }
+}
+
+impl ToJSON for Duration {
+    fn to_json(&self) -> Value {
+        Value::String(format_iso8601(*self))
+    }
+}
+
+impl ToHeader for Duration {
+    fn to_header(&self) -> Option<HeaderValue> {
+        HeaderValue::from_str(&format_iso8601(*self)).ok()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type for chrono::Duration {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string(duration)".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "duration")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ParseFromJSON for chrono::Duration {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Self::parse_from_parameter(&value)
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+/// The negative durations are prefixed with `-`, such as `-PT1S`.
+#[cfg(feature = "chrono")]
+impl ParseFromParameter for chrono::Duration {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let duration = parse_iso8601(value)
+            .and_then(|duration| chrono::Duration::from_std(duration).ok())
+            .ok_or_else(|| ParseError::custom("invalid ISO 8601 duration"))?;
+        Ok(if negative { -duration } else { duration })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToJSON for chrono::Duration {
+    fn to_json(&self) -> Value {
+        let s = match self.to_std() {
+            Ok(duration) => format_iso8601(duration),
+            Err(_) => format!("-{}", format_iso8601((-*self).to_std().unwrap_or_default())),
+        };
+        Value::String(s)
+    }
+}
+
+/// Represents the durations as a number of seconds, it is used by the
+/// `#[oai(duration = "seconds")]` attribute of the object fields.
+#[doc(hidden)]
+pub trait DurationSeconds: Sized {
+    fn schema_ref() -> MetaSchemaRef;
+
+    fn parse_from_json(value: Value) -> ParseResult<Self>;
+
+    fn to_json(&self) -> Value;
+}
+
+impl DurationSeconds for Duration {
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("number", "double")))
+    }
+
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value.as_f64() {
+            Some(secs) if secs >= 0.0 && secs < u64::MAX as f64 => {
+                Ok(Duration::from_secs_f64(secs))
+            }
+            Some(_) => Err(ParseError::custom("invalid duration in seconds")),
+            None => Err(ParseError::expected_type(value)),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        if self.subsec_nanos() == 0 {
+            Value::from(self.as_secs())
+        } else {
+            Value::from(self.as_secs_f64())
+        }
+    }
+}
+
+impl DurationSeconds for Option<Duration> {
+    fn schema_ref() -> MetaSchemaRef {
+        <Duration as DurationSeconds>::schema_ref()
+    }
+
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value {
+            Value::Null => Ok(None),
+            value => Ok(Some(
+                <Duration as DurationSeconds>::parse_from_json(value)
+                    .map_err(ParseError::propagate)?,
+            )),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match self {
+            Some(duration) => DurationSeconds::to_json(duration),
+            None => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso8601() {
+        for (duration, s) in [
+            (Duration::ZERO, "PT0S"),
+            (Duration::from_millis(1500), "PT1.5S"),
+            (Duration::from_secs(5400), "PT1H30M"),
+            (Duration::from_secs(26 * 3600 + 5), "P1DT2H5S"),
+            (Duration::from_secs(2 * 86400), "P2D"),
+            (Duration::from_nanos(1), "PT0.000000001S"),
+        ] {
+            assert_eq!(ToJSON::to_json(&duration), Value::String(s.to_string()));
+            assert_eq!(
+                <Duration as ParseFromJSON>::parse_from_json(Value::String(s.to_string())).unwrap(),
+                duration
+            );
+        }
+        assert!(<Duration as ParseFromJSON>::parse_from_json(Value::from(30)).is_err());
+        assert!(Duration::parse_from_parameter("30s").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_iso8601() {
+        for (duration, s) in [
+            (chrono::Duration::seconds(90), "PT1M30S"),
+            (chrono::Duration::milliseconds(-1500), "-PT1.5S"),
+        ] {
+            assert_eq!(ToJSON::to_json(&duration), Value::String(s.to_string()));
+            assert_eq!(chrono::Duration::parse_from_parameter(s).unwrap(), duration);
+        }
+    }
+
+    #[test]
+    fn seconds() {
+        assert_eq!(
+            DurationSeconds::to_json(&Duration::from_secs(30)),
+            Value::from(30)
+        );
+        assert_eq!(
+            DurationSeconds::to_json(&Duration::from_millis(1500)),
+            Value::from(1.5)
+        );
+        assert_eq!(
+            <Duration as DurationSeconds>::parse_from_json(Value::from(1.5)).unwrap(),
+            Duration::from_millis(1500)
+        );
+        assert!(<Duration as DurationSeconds>::parse_from_json(Value::from(-1)).is_err());
+        assert_eq!(
+            <Option<Duration> as DurationSeconds>::parse_from_json(Value::Null).unwrap(),
+            None
+        );
+    }
+}
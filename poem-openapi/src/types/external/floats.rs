@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use serde_json::{Number, Value};
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 macro_rules! impl_type_for_floats {
@@ -59,6 +60,7 @@ macro_rules! impl_type_for_floats {
             }
         }
 
+        #[cfg(feature = "multipart")]
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 macro_rules! impl_type_for_integers {
@@ -68,6 +69,7 @@ macro_rules! impl_type_for_integers {
             }
         }
 
+        #[cfg(feature = "multipart")]
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -154,6 +156,7 @@ macro_rules! impl_type_for_unsigneds {
             }
         }
 
+        #[cfg(feature = "multipart")]
         #[poem::async_trait]
         impl ParseFromMultipartField for $ty {
             async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
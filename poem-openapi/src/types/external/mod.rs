@@ -3,14 +3,24 @@ mod bool;
 mod btreemap;
 #[cfg(feature = "chrono")]
 mod datetime;
+mod duration;
 mod floats;
 mod hashmap;
+#[cfg(feature = "indexmap")]
+mod indexmap;
 mod integers;
 mod optional;
+#[cfg(feature = "regex")]
 mod regex;
+#[cfg(feature = "semver")]
+mod semver;
 mod slice;
+#[cfg(feature = "smallvec")]
+mod smallvec;
 mod string;
 mod uri;
 #[cfg(feature = "uuid")]
 mod uuid;
 mod vec;
+
+pub use duration::DurationSeconds;
@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field as PoemField};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field as PoemField;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchemaRef, Registry},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 impl<T: Type> Type for Option<T> {
@@ -78,6 +79,7 @@ impl<T: ParseFromParameter> ParseFromParameter for Option<T> {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Option<T> {
     async fn parse_from_multipart(value: Option<PoemField>) -> ParseResult<Self> {
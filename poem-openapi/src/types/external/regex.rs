@@ -1,15 +1,16 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use regex::Regex;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 impl Type for Regex {
@@ -54,6 +55,7 @@ impl ParseFromParameter for Regex {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for Regex {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
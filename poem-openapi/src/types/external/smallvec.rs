@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "multipart")]
+use poem::web::Field as PoemField;
+use serde_json::Value;
+use smallvec::{Array, SmallVec};
+
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+impl<A> Type for SmallVec<A>
+where
+    A: Array + Send + Sync,
+    A::Item: Type,
+{
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = <A::Item as Type>::RawValueType;
+
+    fn name() -> Cow<'static, str> {
+        format!("[{}]", A::Item::name()).into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(A::Item::schema_ref())),
+            ..MetaSchema::new("array")
+        }))
+    }
+
+    fn register(registry: &mut Registry) {
+        A::Item::register(registry);
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.iter().filter_map(|item| item.as_raw_value()))
+    }
+}
+
+impl<A> ParseFromJSON for SmallVec<A>
+where
+    A: Array + Send + Sync,
+    A::Item: ParseFromJSON,
+{
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value {
+            Value::Array(values) => {
+                let mut res = SmallVec::with_capacity(values.len());
+                for value in values {
+                    res.push(A::Item::parse_from_json(value).map_err(ParseError::propagate)?);
+                }
+                Ok(res)
+            }
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl<A> ParseFromParameter for SmallVec<A>
+where
+    A: Array + Send + Sync,
+    A::Item: ParseFromParameter,
+{
+    fn parse_from_parameter(_value: &str) -> ParseResult<Self> {
+        unreachable!()
+    }
+
+    fn parse_from_parameters<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        let mut values = SmallVec::new();
+        for s in iter {
+            values.push(
+                A::Item::parse_from_parameters(std::iter::once(s.as_ref()))
+                    .map_err(ParseError::propagate)?,
+            );
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(feature = "multipart")]
+#[poem::async_trait]
+impl<A> ParseFromMultipartField for SmallVec<A>
+where
+    A: Array + Send + Sync,
+    A::Item: ParseFromMultipartField,
+{
+    async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
+        let mut values = SmallVec::new();
+        if let Some(field) = field {
+            values.push(
+                A::Item::parse_from_multipart(Some(field))
+                    .await
+                    .map_err(ParseError::propagate)?,
+            );
+        }
+        Ok(values)
+    }
+
+    async fn parse_from_repeated_field(mut self, field: PoemField) -> ParseResult<Self> {
+        let item = A::Item::parse_from_multipart(Some(field))
+            .await
+            .map_err(ParseError::propagate)?;
+        self.push(item);
+        Ok(self)
+    }
+}
+
+impl<A> ToJSON for SmallVec<A>
+where
+    A: Array + Send + Sync,
+    A::Item: ToJSON,
+{
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(ToJSON::to_json).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smallvec() {
+        type Values = SmallVec<[i32; 4]>;
+
+        assert_eq!(Values::schema_ref(), Vec::<i32>::schema_ref());
+        let values = Values::parse_from_json(serde_json::json!([1, 2, 3])).unwrap();
+        assert_eq!(values.as_slice(), &[1, 2, 3]);
+        assert_eq!(values.to_json(), serde_json::json!([1, 2, 3]));
+        assert_eq!(
+            Values::parse_from_parameters(vec!["100", "200"])
+                .unwrap()
+                .as_slice(),
+            &[100, 200]
+        );
+    }
+}
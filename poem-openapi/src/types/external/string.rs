@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{http::HeaderValue, web::Field};
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 impl Type for String {
@@ -53,6 +54,7 @@ impl ParseFromParameter for String {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for String {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
@@ -109,3 +111,57 @@ impl<'a> ToJSON for &'a str {
         Value::String(self.to_string())
     }
 }
+
+impl<'a> Type for Cow<'a, str> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new("string")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'b>(
+        &'b self,
+    ) -> Box<dyn Iterator<Item = &'b Self::RawElementValueType> + 'b> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl<'a> ParseFromJSON for Cow<'a, str> {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            Ok(Cow::Owned(value))
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl<'a> ParseFromParameter for Cow<'a, str> {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Ok(Cow::Owned(value.to_string()))
+    }
+}
+
+impl<'a> ToJSON for Cow<'a, str> {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl<'a> ToHeader for Cow<'a, str> {
+    fn to_header(&self) -> Option<HeaderValue> {
+        HeaderValue::from_str(self).ok()
+    }
+}
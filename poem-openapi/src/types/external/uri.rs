@@ -1,17 +1,15 @@
 use std::borrow::Cow;
 
-use poem::{
-    http::{HeaderValue, Uri},
-    web::Field,
-};
+use poem::http::{HeaderValue, Uri};
+#[cfg(feature = "multipart")]
+use poem::web::Field;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToHeader, ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToHeader, ToJSON, Type},
 };
 
 impl Type for Uri {
@@ -56,6 +54,7 @@ impl ParseFromParameter for Uri {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl ParseFromMultipartField for Uri {
     async fn parse_from_multipart(field: Option<Field>) -> ParseResult<Self> {
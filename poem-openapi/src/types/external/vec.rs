@@ -1,14 +1,14 @@
 use std::borrow::Cow;
 
+#[cfg(feature = "multipart")]
 use poem::web::Field as PoemField;
 use serde_json::Value;
 
+#[cfg(feature = "multipart")]
+use crate::types::ParseFromMultipartField;
 use crate::{
     registry::{MetaSchema, MetaSchemaRef, Registry},
-    types::{
-        ParseError, ParseFromJSON, ParseFromMultipartField, ParseFromParameter, ParseResult,
-        ToJSON, Type,
-    },
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
 };
 
 impl<T: Type> Type for Vec<T> {
@@ -78,6 +78,7 @@ impl<T: ParseFromParameter> ParseFromParameter for Vec<T> {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Vec<T> {
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
@@ -0,0 +1,156 @@
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    ops::{Deref, DerefMut},
+};
+
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+/// Represents a set of values, such as the permissions of a user.
+///
+/// It is serialized as an array of unique items, and can be parsed from a
+/// comma separated parameter like `read,write`. The duplicate items are
+/// rejected in both forms.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::{types::Flags, Enum, Object};
+///
+/// #[derive(Enum, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+/// enum Permission {
+///     Read,
+///     Write,
+///     Delete,
+/// }
+///
+/// #[derive(Object)]
+/// struct User {
+///     name: String,
+///     permissions: Flags<Permission>,
+/// }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Flags<T: Ord>(pub BTreeSet<T>);
+
+impl<T: Ord> Deref for Flags<T> {
+    type Target = BTreeSet<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Ord> DerefMut for Flags<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Flags<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T: Type + Ord> Type for Flags<T> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = T::RawValueType;
+
+    fn name() -> Cow<'static, str> {
+        format!("flags<{}>", T::name()).into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(T::schema_ref())),
+            unique_items: Some(true),
+            ..MetaSchema::new("array")
+        }))
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.0.iter().filter_map(|item| item.as_raw_value()))
+    }
+}
+
+impl<T: ParseFromJSON + Ord> ParseFromJSON for Flags<T> {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value {
+            Value::Array(values) => {
+                let mut res = BTreeSet::new();
+                for value in values {
+                    if !res.insert(T::parse_from_json(value).map_err(ParseError::propagate)?) {
+                        return Err(ParseError::custom("duplicate items"));
+                    }
+                }
+                Ok(Self(res))
+            }
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl<T: ParseFromParameter + Ord> ParseFromParameter for Flags<T> {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        Self::parse_from_parameters(std::iter::once(value))
+    }
+
+    fn parse_from_parameters<I: IntoIterator<Item = A>, A: AsRef<str>>(
+        iter: I,
+    ) -> ParseResult<Self> {
+        let mut res = BTreeSet::new();
+        for s in iter {
+            for item in s.as_ref().split(',').filter(|item| !item.is_empty()) {
+                if !res.insert(T::parse_from_parameter(item).map_err(ParseError::propagate)?) {
+                    return Err(ParseError::custom("duplicate items"));
+                }
+            }
+        }
+        Ok(Self(res))
+    }
+}
+
+impl<T: ToJSON + Ord> ToJSON for Flags<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.0.iter().map(ToJSON::to_json).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_from_parameters() {
+        let flags = Flags::<i32>::parse_from_parameters(vec!["3,1", "2"]).unwrap();
+        assert_eq!(flags, Flags::from_iter([1, 2, 3]));
+        assert!(Flags::<i32>::parse_from_parameters(vec!["3,1", "1"]).is_err());
+        assert!(Flags::<i32>::parse_from_parameter("2,2").is_err());
+    }
+
+    #[test]
+    fn parse_from_json() {
+        let flags = Flags::<i32>::parse_from_json(serde_json::json!([3, 1])).unwrap();
+        assert_eq!(flags.to_json(), serde_json::json!([1, 3]));
+        assert!(Flags::<i32>::parse_from_json(serde_json::json!([1, 1])).is_err());
+    }
+}
@@ -0,0 +1,446 @@
+//! GeoJSON types, as specified in [RFC 7946](https://datatracker.ietf.org/doc/html/rfc7946).
+
+use std::borrow::Cow;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    registry::{MetaDiscriminatorObject, MetaSchema, MetaSchemaRef, Registry},
+    types::{ParseError, ParseFromJSON, ParseResult, ToJSON, Type},
+};
+
+/// A position, which consists of the longitude, the latitude and an optional
+/// altitude.
+pub type Position = Vec<f64>;
+
+fn position_schema() -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        items: Some(Box::new(f64::schema_ref())),
+        min_items: Some(2),
+        max_items: Some(3),
+        ..MetaSchema::new("array")
+    }))
+}
+
+fn parse_position(value: Value) -> Result<Position, String> {
+    let position = Position::parse_from_json(value).map_err(ParseError::into_message)?;
+    if position.len() < 2 || position.len() > 3 {
+        return Err("a position must contain two or three numbers".to_string());
+    }
+    Ok(position)
+}
+
+fn parse_linear_ring(value: Value) -> Result<Vec<Position>, String> {
+    let positions = match value {
+        Value::Array(values) => values
+            .into_iter()
+            .map(parse_position)
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err("a linear ring must be an array".to_string()),
+    };
+    if positions.len() < 4 || positions.first() != positions.last() {
+        return Err(
+            "a linear ring must be closed, and contain at least four positions".to_string(),
+        );
+    }
+    Ok(positions)
+}
+
+fn type_schema(name: &'static str) -> MetaSchemaRef {
+    MetaSchemaRef::Inline(Box::new(MetaSchema {
+        enum_items: vec![name.into()],
+        ..MetaSchema::new("string")
+    }))
+}
+
+/// Returns the members of the object after checking the `type` member.
+fn expect_object<T: Type>(value: Value, ty: &str) -> Result<Map<String, Value>, ParseError<T>> {
+    match value {
+        Value::Object(obj) if obj.get("type").and_then(Value::as_str) == Some(ty) => Ok(obj),
+        value => Err(ParseError::expected_type(value)),
+    }
+}
+
+macro_rules! impl_geojson_type {
+    ($ty:ident, $($body:tt)*) => {
+        impl Type for $ty {
+            const IS_REQUIRED: bool = true;
+
+            type RawValueType = Self;
+
+            type RawElementValueType = Self;
+
+            fn name() -> Cow<'static, str> {
+                stringify!($ty).into()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Reference(stringify!($ty))
+            }
+
+            $($body)*
+
+            fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                Some(self)
+            }
+
+            fn raw_element_iter<'a>(
+                &'a self,
+            ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                Box::new(self.as_raw_value().into_iter())
+            }
+        }
+    };
+}
+
+/// A GeoJSON point geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Point {
+    /// The position of the point.
+    pub coordinates: Position,
+}
+
+impl_geojson_type!(
+    Point,
+    fn register(registry: &mut Registry) {
+        registry.create_schema::<Self, _>("Point", |_| MetaSchema {
+            required: vec!["type", "coordinates"],
+            properties: vec![
+                ("type", type_schema("Point")),
+                ("coordinates", position_schema()),
+            ],
+            ..MetaSchema::new("object")
+        });
+    }
+);
+
+impl ParseFromJSON for Point {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        let mut obj = expect_object(value, "Point")?;
+        let coordinates = parse_position(obj.remove("coordinates").unwrap_or_default())
+            .map_err(ParseError::custom)?;
+        Ok(Self { coordinates })
+    }
+}
+
+impl ToJSON for Point {
+    fn to_json(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), "Point".into());
+        obj.insert("coordinates".to_string(), self.coordinates.to_json());
+        Value::Object(obj)
+    }
+}
+
+/// A GeoJSON polygon geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    /// The linear rings of the polygon, the first one is the exterior ring
+    /// and the others are the holes.
+    ///
+    /// Each linear ring is closed, that is, its first and last positions are
+    /// equal.
+    pub coordinates: Vec<Vec<Position>>,
+}
+
+impl_geojson_type!(
+    Polygon,
+    fn register(registry: &mut Registry) {
+        registry.create_schema::<Self, _>("Polygon", |_| MetaSchema {
+            required: vec!["type", "coordinates"],
+            properties: vec![
+                ("type", type_schema("Polygon")),
+                (
+                    "coordinates",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        items: Some(Box::new(MetaSchemaRef::Inline(Box::new(MetaSchema {
+                            items: Some(Box::new(position_schema())),
+                            min_items: Some(4),
+                            ..MetaSchema::new("array")
+                        })))),
+                        ..MetaSchema::new("array")
+                    })),
+                ),
+            ],
+            ..MetaSchema::new("object")
+        });
+    }
+);
+
+impl ParseFromJSON for Polygon {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        let mut obj = expect_object(value, "Polygon")?;
+        let coordinates = match obj.remove("coordinates") {
+            Some(Value::Array(rings)) => rings
+                .into_iter()
+                .map(parse_linear_ring)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ParseError::custom)?,
+            _ => return Err(ParseError::custom("the coordinates must be an array")),
+        };
+        Ok(Self { coordinates })
+    }
+}
+
+impl ToJSON for Polygon {
+    fn to_json(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), "Polygon".into());
+        obj.insert("coordinates".to_string(), self.coordinates.to_json());
+        Value::Object(obj)
+    }
+}
+
+/// A GeoJSON geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// A point.
+    Point(Point),
+    /// A polygon.
+    Polygon(Polygon),
+}
+
+impl_geojson_type!(
+    Geometry,
+    fn register(registry: &mut Registry) {
+        Point::register(registry);
+        Polygon::register(registry);
+        registry.create_schema::<Self, _>("Geometry", |_| MetaSchema {
+            one_of: vec![Point::schema_ref(), Polygon::schema_ref()],
+            discriminator: Some(MetaDiscriminatorObject {
+                property_name: "type",
+                mapping: vec![],
+            }),
+            ..MetaSchema::ANY
+        });
+    }
+);
+
+impl ParseFromJSON for Geometry {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        match value.get("type").and_then(Value::as_str) {
+            Some("Point") => Point::parse_from_json(value)
+                .map(Self::Point)
+                .map_err(ParseError::propagate),
+            Some("Polygon") => Polygon::parse_from_json(value)
+                .map(Self::Polygon)
+                .map_err(ParseError::propagate),
+            _ => Err(ParseError::expected_type(value)),
+        }
+    }
+}
+
+impl ToJSON for Geometry {
+    fn to_json(&self) -> Value {
+        match self {
+            Geometry::Point(point) => point.to_json(),
+            Geometry::Polygon(polygon) => polygon.to_json(),
+        }
+    }
+}
+
+/// A GeoJSON feature.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Feature {
+    /// The identifier of the feature, which is a string or a number.
+    pub id: Option<Value>,
+    /// The geometry of the feature.
+    pub geometry: Option<Geometry>,
+    /// The properties of the feature.
+    pub properties: Option<Map<String, Value>>,
+}
+
+impl_geojson_type!(
+    Feature,
+    fn register(registry: &mut Registry) {
+        Geometry::register(registry);
+        registry.create_schema::<Self, _>("Feature", |_| MetaSchema {
+            required: vec!["type", "geometry", "properties"],
+            properties: vec![
+                ("type", type_schema("Feature")),
+                (
+                    "id",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        one_of: vec![String::schema_ref(), f64::schema_ref()],
+                        ..MetaSchema::ANY
+                    })),
+                ),
+                ("geometry", Geometry::schema_ref()),
+                (
+                    "properties",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema::new("object"))),
+                ),
+            ],
+            ..MetaSchema::new("object")
+        });
+    }
+);
+
+impl ParseFromJSON for Feature {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        let mut obj = expect_object(value, "Feature")?;
+
+        let id = match obj.remove("id") {
+            Some(id @ (Value::String(_) | Value::Number(_))) => Some(id),
+            None => None,
+            Some(_) => return Err(ParseError::custom("the id must be a string or a number")),
+        };
+        let geometry = match obj.remove("geometry") {
+            Some(Value::Null) | None => None,
+            Some(geometry) => {
+                Some(Geometry::parse_from_json(geometry).map_err(ParseError::propagate)?)
+            }
+        };
+        let properties = match obj.remove("properties") {
+            Some(Value::Object(properties)) => Some(properties),
+            Some(Value::Null) | None => None,
+            Some(_) => return Err(ParseError::custom("the properties must be an object")),
+        };
+
+        Ok(Self {
+            id,
+            geometry,
+            properties,
+        })
+    }
+}
+
+impl ToJSON for Feature {
+    fn to_json(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), "Feature".into());
+        if let Some(id) = &self.id {
+            obj.insert("id".to_string(), id.clone());
+        }
+        obj.insert(
+            "geometry".to_string(),
+            self.geometry
+                .as_ref()
+                .map(ToJSON::to_json)
+                .unwrap_or_default(),
+        );
+        obj.insert(
+            "properties".to_string(),
+            self.properties
+                .clone()
+                .map(Value::Object)
+                .unwrap_or_default(),
+        );
+        Value::Object(obj)
+    }
+}
+
+/// A GeoJSON feature collection.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FeatureCollection {
+    /// The features.
+    pub features: Vec<Feature>,
+}
+
+impl_geojson_type!(
+    FeatureCollection,
+    fn register(registry: &mut Registry) {
+        Feature::register(registry);
+        registry.create_schema::<Self, _>("FeatureCollection", |_| MetaSchema {
+            required: vec!["type", "features"],
+            properties: vec![
+                ("type", type_schema("FeatureCollection")),
+                ("features", Vec::<Feature>::schema_ref()),
+            ],
+            ..MetaSchema::new("object")
+        });
+    }
+);
+
+impl ParseFromJSON for FeatureCollection {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        let mut obj = expect_object(value, "FeatureCollection")?;
+        let features = Vec::<Feature>::parse_from_json(obj.remove("features").unwrap_or_default())
+            .map_err(ParseError::propagate)?;
+        Ok(Self { features })
+    }
+}
+
+impl ToJSON for FeatureCollection {
+    fn to_json(&self) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), "FeatureCollection".into());
+        obj.insert("features".to_string(), self.features.to_json());
+        Value::Object(obj)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn point() {
+        let value = json!({ "type": "Point", "coordinates": [102.0, 0.5] });
+        let point = Point::parse_from_json(value.clone()).unwrap();
+        assert_eq!(point.coordinates, vec![102.0, 0.5]);
+        assert_eq!(point.to_json(), value);
+
+        assert!(Point::parse_from_json(json!({ "type": "Point", "coordinates": [1.0] })).is_err());
+        assert!(
+            Point::parse_from_json(json!({ "type": "Polygon", "coordinates": [1.0, 2.0] }))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn polygon() {
+        let value = json!({
+            "type": "Polygon",
+            "coordinates": [[[100.0, 0.0], [101.0, 0.0], [101.0, 1.0], [100.0, 0.0]]]
+        });
+        let polygon = Polygon::parse_from_json(value.clone()).unwrap();
+        assert_eq!(polygon.to_json(), value);
+
+        assert!(Polygon::parse_from_json(json!({
+            "type": "Polygon",
+            "coordinates": [[[100.0, 0.0], [101.0, 0.0], [101.0, 1.0], [100.0, 1.0]]]
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn feature_collection() {
+        let value = json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "id": 1,
+                "geometry": { "type": "Point", "coordinates": [102.0, 0.5] },
+                "properties": { "name": "a" }
+            }, {
+                "type": "Feature",
+                "geometry": null,
+                "properties": null
+            }]
+        });
+        let collection = FeatureCollection::parse_from_json(value.clone()).unwrap();
+        assert_eq!(collection.features.len(), 2);
+        assert_eq!(
+            collection.features[0].geometry,
+            Some(Geometry::Point(Point {
+                coordinates: vec![102.0, 0.5]
+            }))
+        );
+        assert_eq!(collection.to_json(), value);
+
+        let mut registry = Registry::new();
+        FeatureCollection::register(&mut registry);
+        for name in [
+            "Point",
+            "Polygon",
+            "Geometry",
+            "Feature",
+            "FeatureCollection",
+        ] {
+            assert!(registry.schemas.contains_key(name));
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    ops::{Deref, DerefMut},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::{
+    registry::{MetaSchema, MetaSchemaRef},
+    types::{ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type},
+};
+
+/// A duration that can be parsed from a human readable string like `30s`,
+/// `1h30m` or `100ms`, or an ISO 8601 duration like `PT1H30M`.
+///
+/// It is serialized with the human readable format.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HumanDuration {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+const UNITS: &[(&str, u64)] = &[
+    ("w", 7 * 24 * 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ns", 1),
+];
+
+fn unit_nanos(unit: &str) -> Option<u64> {
+    UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, nanos)| *nanos)
+}
+
+fn nanos_to_duration(nanos: u128) -> Option<Duration> {
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+/// Parses the `1h30m` format.
+fn parse_human(s: &str) -> Option<Duration> {
+    let mut nanos = 0u128;
+    let mut rest = s.trim();
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let value: u128 = rest[..digits].parse().ok()?;
+        rest = rest[digits..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let unit = unit_nanos(&rest[..unit_len])?;
+        rest = rest[unit_len..].trim_start();
+
+        nanos = nanos.checked_add(value.checked_mul(unit as u128)?)?;
+    }
+
+    nanos_to_duration(nanos)
+}
+
+/// Parses the ISO 8601 format, the years and months are not supported
+/// because their length is not fixed.
+pub(crate) fn parse_iso8601(s: &str) -> Option<Duration> {
+    let s = s.strip_prefix('P')?;
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (s, None),
+    };
+
+    fn parse_components(mut s: &str, units: &[(char, u128)]) -> Option<u128> {
+        let mut nanos = 0u128;
+        let mut units = units.iter();
+
+        while !s.is_empty() {
+            let len = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+            let (value, designator) = (&s[..len], s[len..].chars().next()?);
+            let (_, unit) = units.find(|(name, _)| *name == designator)?;
+            let value = match value.split_once('.') {
+                Some((int, frac)) => {
+                    let frac_nanos = format!("{:0<9}", frac).get(..9)?.parse::<u128>().ok()?;
+                    int.parse::<u128>()
+                        .ok()?
+                        .checked_mul(*unit)?
+                        .checked_add(frac_nanos.checked_mul(*unit)? / 1_000_000_000)?
+                }
+                None => value.parse::<u128>().ok()?.checked_mul(*unit)?,
+            };
+            nanos = nanos.checked_add(value)?;
+            s = &s[len + designator.len_utf8()..];
+        }
+
+        Some(nanos)
+    }
+
+    if date.is_empty() && time.is_none() {
+        return None;
+    }
+
+    let nanos = parse_components(
+        date,
+        &[
+            ('W', 7 * 24 * 60 * 60 * 1_000_000_000),
+            ('D', 24 * 60 * 60 * 1_000_000_000),
+        ],
+    )?
+    .checked_add(match time {
+        Some(time) => parse_components(
+            time,
+            &[
+                ('H', 60 * 60 * 1_000_000_000),
+                ('M', 60 * 1_000_000_000),
+                ('S', 1_000_000_000),
+            ],
+        )?,
+        None => 0,
+    })?;
+
+    nanos_to_duration(nanos)
+}
+
+/// Formats the ISO 8601 format, such as `P1DT2H30M` or `PT0.5S`.
+pub(crate) fn format_iso8601(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    let nanos = duration.subsec_nanos();
+
+    let mut s = "P".to_string();
+    if days > 0 {
+        s.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || secs > 0 || nanos > 0 || days == 0 {
+        s.push('T');
+        if hours > 0 {
+            s.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            s.push_str(&format!("{}M", minutes));
+        }
+        if nanos > 0 {
+            let frac = format!("{:09}", nanos);
+            s.push_str(&format!("{}.{}S", secs, frac.trim_end_matches('0')));
+        } else if secs > 0 || (hours == 0 && minutes == 0 && days == 0) {
+            s.push_str(&format!("{}S", secs));
+        }
+    }
+    s
+}
+
+impl FromStr for HumanDuration {
+    type Err = ParseError<Self>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_iso8601(s)
+            .or_else(|| parse_human(s))
+            .map(Self)
+            .ok_or_else(|| ParseError::custom("invalid duration"))
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+
+        for (name, unit) in UNITS.iter().filter(|(name, _)| *name != "µs") {
+            let unit = *unit as u128;
+            if nanos >= unit {
+                write!(f, "{}{}", nanos / unit, name)?;
+                nanos %= unit;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Type for HumanDuration {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        "string(duration)".into()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format("string", "duration")))
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl ParseFromJSON for HumanDuration {
+    fn parse_from_json(value: Value) -> ParseResult<Self> {
+        if let Value::String(value) = value {
+            value.parse()
+        } else {
+            Err(ParseError::expected_type(value))
+        }
+    }
+}
+
+impl ParseFromParameter for HumanDuration {
+    fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+        value.parse()
+    }
+}
+
+impl ToJSON for HumanDuration {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        for (s, duration) in [
+            ("30s", Duration::from_secs(30)),
+            ("5m", Duration::from_secs(300)),
+            ("1h30m", Duration::from_secs(5400)),
+            ("1h 30m", Duration::from_secs(5400)),
+            ("100ms", Duration::from_millis(100)),
+            ("2d", Duration::from_secs(2 * 24 * 3600)),
+            ("PT1H30M", Duration::from_secs(5400)),
+            ("P1DT2H", Duration::from_secs(26 * 3600)),
+            ("PT0.5S", Duration::from_millis(500)),
+            ("P1W", Duration::from_secs(7 * 24 * 3600)),
+        ] {
+            assert_eq!(s.parse::<HumanDuration>().unwrap().0, duration, "{}", s);
+        }
+
+        for s in ["", "30", "s", "10x", "P1Y", "PT", "P"] {
+            assert!(s.parse::<HumanDuration>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn parse_overflow() {
+        for s in [
+            "PT99999999999999999999999999999999S",
+            "PT99999999999999999999999999999999.5S",
+            "P340282366920938463463374607431768211455W",
+            "99999999999999999999999999999999h",
+        ] {
+            assert!(s.parse::<HumanDuration>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            HumanDuration(Duration::from_secs(5400)).to_json(),
+            Value::String("1h30m".to_string())
+        );
+        assert_eq!(
+            HumanDuration(Duration::from_millis(1500)).to_json(),
+            Value::String("1s500ms".to_string())
+        );
+        assert_eq!(
+            HumanDuration(Duration::ZERO).to_json(),
+            Value::String("0s".to_string())
+        );
+    }
+}
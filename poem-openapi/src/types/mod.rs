@@ -1,29 +1,63 @@
 //! Commonly used data types.
 
 mod any;
+#[cfg(feature = "base64")]
 mod base64_type;
 mod binary;
+mod bounded;
+mod byte_size;
+#[cfg(feature = "encrypted")]
+pub(crate) mod encrypted;
 mod error;
 mod external;
+mod flags;
+mod human_duration;
+mod money;
 mod string_types;
 
+#[cfg(feature = "geojson")]
+#[cfg_attr(docsrs, doc(cfg(feature = "geojson")))]
+pub mod geojson;
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 pub mod multipart;
 
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, cell::Cell, sync::Arc};
 
 pub use any::Any;
+#[cfg(feature = "base64")]
+#[cfg_attr(docsrs, doc(cfg(feature = "base64")))]
 pub use base64_type::Base64;
 pub use binary::Binary;
+pub use bounded::{BoundedI32, BoundedI64, BoundedU32, BoundedU64, NonEmptyString};
+pub use byte_size::ByteSize;
+#[cfg(feature = "encrypted")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encrypted")))]
+pub use encrypted::{EncryptError, Encrypted, KeyProvider, LocalKeyProvider};
 pub use error::{ParseError, ParseResult};
-use poem::{http::HeaderValue, web::Field as PoemField};
+#[doc(hidden)]
+pub use external::DurationSeconds;
+pub use flags::Flags;
+pub use human_duration::HumanDuration;
+pub use money::Money;
+use poem::http::HeaderValue;
+#[cfg(feature = "multipart")]
+use poem::web::Field as PoemField;
 use serde_json::Value;
+#[cfg(feature = "country-code")]
+pub use string_types::CountryCode;
 #[cfg(feature = "email")]
 pub use string_types::Email;
 #[cfg(feature = "hostname")]
 pub use string_types::Hostname;
-pub use string_types::Password;
+#[cfg(feature = "regex")]
+#[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+pub use string_types::LanguageTag;
+#[cfg(feature = "phone-number")]
+pub use string_types::PhoneNumber;
+pub use string_types::{MediaType, Password};
 
-use crate::registry::{MetaSchemaRef, Registry};
+use crate::registry::{MetaSchema, MetaSchemaRef, Registry};
 
 /// Represents a OpenAPI type.
 pub trait Type: Send + Sync {
@@ -93,6 +127,8 @@ pub trait ParseFromParameter: Sized + Type {
 }
 
 /// Represents a type that can parsing from multipart.
+#[cfg(feature = "multipart")]
+#[cfg_attr(docsrs, doc(cfg(feature = "multipart")))]
 #[poem::async_trait]
 pub trait ParseFromMultipartField: Sized + Type {
     /// Parse from multipart field.
@@ -121,6 +157,89 @@ pub trait ToHeader: Type {
     fn to_header(&self) -> Option<HeaderValue>;
 }
 
+/// Returns the schema of the object type with the reference resolved, it is
+/// used by the `flatten` fields of the objects.
+#[doc(hidden)]
+pub fn flatten_schema<T: Type>() -> MetaSchema {
+    match T::schema_ref() {
+        MetaSchemaRef::Inline(schema) => *schema,
+        MetaSchemaRef::Reference(name) => {
+            let mut registry = Registry::new();
+            T::register(&mut registry);
+            registry.schemas.remove(name).unwrap_or(MetaSchema::ANY)
+        }
+    }
+}
+
+/// Removes the property of the object field and its aliases, it is an error
+/// if more than one of them is present.
+#[doc(hidden)]
+pub fn remove_aliased_field<T: Type>(
+    obj: &mut serde_json::Map<String, Value>,
+    name: &str,
+    aliases: &[&str],
+) -> Result<Option<Value>, ParseError<T>> {
+    let mut value = obj.remove(name).map(|value| (name, value));
+    for alias in aliases {
+        if let Some(alias_value) = obj.remove(*alias) {
+            if let Some((name, _)) = &value {
+                return Err(ParseError::custom(format!(
+                    "properties `{}` and `{}` are both present.",
+                    name, alias
+                )));
+            }
+            value = Some((alias, alias_value));
+        }
+    }
+    Ok(value.map(|(_, value)| value))
+}
+
+/// Returns the schema of a computed property of the objects, the type is
+/// inferred from the method that returns the value.
+#[doc(hidden)]
+pub fn computed_schema_ref<S, T: Type>(_method: fn(&S) -> T) -> MetaSchemaRef {
+    T::schema_ref()
+}
+
+/// Returns `true` if the computed property of the objects is required.
+#[doc(hidden)]
+pub fn computed_is_required<S, T: Type>(_method: fn(&S) -> T) -> bool {
+    T::IS_REQUIRED
+}
+
+/// Registers the type of a computed property of the objects.
+#[doc(hidden)]
+pub fn register_computed<S, T: Type>(registry: &mut Registry, _method: fn(&S) -> T) {
+    T::register(registry);
+}
+
+thread_local! {
+    static SKIP_VALIDATION: Cell<bool> = Cell::new(false);
+}
+
+/// Calls the function with the validators of the objects skipped, it is used
+/// to parse the `skip_validation` fields and the fields of the
+/// `shallow_validation` objects.
+#[doc(hidden)]
+pub fn parse_without_validation<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SKIP_VALIDATION.with(|skip| skip.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SKIP_VALIDATION.with(|skip| skip.replace(true)));
+    f()
+}
+
+/// Returns `true` if the validators of the objects are skipped.
+#[doc(hidden)]
+pub fn validation_skipped() -> bool {
+    SKIP_VALIDATION.with(Cell::get)
+}
+
 impl<T: Type> Type for &T {
     const IS_REQUIRED: bool = T::IS_REQUIRED;
 
@@ -279,6 +398,7 @@ impl<T: ParseFromParameter> ParseFromParameter for Box<T> {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[poem::async_trait]
 impl<T: ParseFromMultipartField> ParseFromMultipartField for Box<T> {
     async fn parse_from_multipart(field: Option<PoemField>) -> ParseResult<Self> {
@@ -0,0 +1,119 @@
+use crate::Object;
+
+/// A monetary amount in the minor units of a currency.
+///
+/// The amount is an integer, so `12.34 USD` is represented as `1234` with the
+/// currency `USD`, and no precision is lost in the calculations.
+///
+/// # Example
+///
+/// ```
+/// use poem_openapi::types::Money;
+///
+/// let price = Money::new(1999, "USD");
+/// let shipping = Money::new(500, "USD");
+/// assert_eq!(price.checked_add(&shipping), Some(Money::new(2499, "USD")));
+/// assert_eq!(price.checked_add(&Money::new(500, "EUR")), None);
+/// ```
+#[derive(Object, Debug, Clone, Eq, PartialEq, Hash)]
+#[oai(internal, deny_unknown_fields)]
+pub struct Money {
+    /// The amount in the minor units of the currency, such as cents.
+    pub amount: i64,
+    /// The ISO 4217 currency code, such as `USD`.
+    #[cfg_attr(feature = "regex", oai(validator(pattern = "^[A-Z]{3}$")))]
+    #[cfg_attr(not(feature = "regex"), oai(validator(min_length = 3, max_length = 3)))]
+    pub currency: String,
+}
+
+impl Money {
+    /// Create a monetary amount.
+    pub fn new(amount: i64, currency: impl Into<String>) -> Self {
+        Self {
+            amount,
+            currency: currency.into(),
+        }
+    }
+
+    /// Adds two amounts, returns `None` if the currencies are different or
+    /// overflow occurred.
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(
+            self.amount.checked_add(other.amount)?,
+            self.currency.clone(),
+        ))
+    }
+
+    /// Subtracts two amounts, returns `None` if the currencies are different
+    /// or overflow occurred.
+    pub fn checked_sub(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(
+            self.amount.checked_sub(other.amount)?,
+            self.currency.clone(),
+        ))
+    }
+
+    /// Multiplies the amount by a quantity, returns `None` if overflow
+    /// occurred.
+    pub fn checked_mul(&self, quantity: i64) -> Option<Money> {
+        Some(Money::new(
+            self.amount.checked_mul(quantity)?,
+            self.currency.clone(),
+        ))
+    }
+
+    /// Returns `true` if the amount is zero.
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        registry::Registry,
+        types::{ParseFromJSON, ToJSON, Type},
+    };
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Money::parse_from_json(json!({ "amount": 1234, "currency": "USD" })).unwrap(),
+            Money::new(1234, "USD")
+        );
+        assert!(Money::parse_from_json(json!({ "amount": 12.34, "currency": "USD" })).is_err());
+        #[cfg(feature = "regex")]
+        assert!(Money::parse_from_json(json!({ "amount": 1234, "currency": "usd" })).is_err());
+        assert!(Money::parse_from_json(json!({ "amount": 1234, "currency": "US" })).is_err());
+        assert!(Money::parse_from_json(
+            json!({ "amount": 1234, "currency": "USD", "precision": 2 })
+        )
+        .is_err());
+        assert_eq!(
+            Money::new(1234, "USD").to_json(),
+            json!({ "amount": 1234, "currency": "USD" })
+        );
+
+        let mut registry = Registry::new();
+        Money::register(&mut registry);
+        assert!(registry.schemas.contains_key("Money"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Money::new(100, "EUR");
+        assert_eq!(a.checked_sub(&a).map(|m| m.is_zero()), Some(true));
+        assert_eq!(a.checked_mul(3), Some(Money::new(300, "EUR")));
+        assert_eq!(Money::new(i64::MAX, "EUR").checked_add(&a), None);
+    }
+}
@@ -3,6 +3,10 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
+#[cfg(feature = "regex")]
+use once_cell::sync::Lazy;
+#[cfg(feature = "regex")]
+use regex::Regex;
 use serde_json::Value;
 
 use crate::{
@@ -16,6 +20,10 @@ macro_rules! impl_string_types {
     };
 
     ($(#[$docs:meta])* $ty:ident, $type_name:literal, $format:literal, $validator:expr) => {
+        impl_string_types!($(#[$docs])* $ty, $type_name, $format, $validator, None);
+    };
+
+    ($(#[$docs:meta])* $ty:ident, $type_name:literal, $format:literal, $validator:expr, $pattern:expr) => {
         $(#[$docs])*
         #[derive(Debug, Clone, Eq, PartialEq, Hash)]
         pub struct $ty(pub String);
@@ -52,7 +60,11 @@ macro_rules! impl_string_types {
             }
 
             fn schema_ref() -> MetaSchemaRef {
-                MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format($type_name, $format)))
+                let pattern: Option<&str> = $pattern;
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    pattern: pattern.map(ToString::to_string),
+                    ..MetaSchema::new_with_format($type_name, $format)
+                }))
             }
 
             fn as_raw_value(&self) -> Option<&Self::RawValueType> {
@@ -127,3 +139,103 @@ impl_string_types!(
     "hostname",
     hostname_validator::is_valid
 );
+
+#[cfg(feature = "regex")]
+const LANGUAGE_TAG_PATTERN: &str = r"^([a-zA-Z]{2,3}(-[a-zA-Z]{3}){0,3}|[a-zA-Z]{4,8})(-[a-zA-Z]{4})?(-([a-zA-Z]{2}|[0-9]{3}))?(-([a-zA-Z0-9]{5,8}|[0-9][a-zA-Z0-9]{3}))*(-[0-9a-wyzA-WYZ](-[a-zA-Z0-9]{2,8})+)*(-[xX](-[a-zA-Z0-9]{1,8})+)?$";
+
+#[cfg(feature = "regex")]
+static LANGUAGE_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(LANGUAGE_TAG_PATTERN).unwrap());
+
+#[cfg(feature = "regex")]
+impl_string_types!(
+    /// A BCP 47 language tag type, such as `en-US`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    LanguageTag,
+    "string",
+    "bcp47",
+    |value: &str| LANGUAGE_TAG_REGEX.is_match(value),
+    Some(LANGUAGE_TAG_PATTERN)
+);
+
+impl_string_types!(
+    /// A media type, such as `text/html; charset=utf-8`.
+    MediaType,
+    "string",
+    "media-type",
+    |value: &str| value.parse::<mime::Mime>().is_ok(),
+    Some(r"^[\w!#$&^.+-]+/[\w!#$&^.+-]+(\s*;.*)?$")
+);
+
+#[cfg(feature = "phone-number")]
+impl_string_types!(
+    /// A phone number type in the E.164 format, such as `+14155552671`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "phone-number")))]
+    PhoneNumber,
+    "string",
+    "phone",
+    |value: &str| {
+        match phonenumber::parse(None, value) {
+            Ok(number) => {
+                number.is_valid()
+                    && number.format().mode(phonenumber::Mode::E164).to_string() == value
+            }
+            Err(_) => false,
+        }
+    }
+);
+
+#[cfg(feature = "country-code")]
+impl_string_types!(
+    /// A ISO 3166-1 alpha-2 country code type, such as `US`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "country-code")))]
+    CountryCode,
+    "string",
+    "iso3166-alpha2",
+    |value: &str| isocountry::CountryCode::for_alpha2(value).is_ok()
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn language_tag() {
+        for tag in [
+            "en",
+            "en-US",
+            "zh-Hant-TW",
+            "sr-Latn-RS",
+            "es-419",
+            "de-CH-1901",
+            "en-x-private",
+        ] {
+            assert!(LanguageTag::parse_from_parameter(tag).is_ok(), "{}", tag);
+        }
+        for tag in ["", "e", "en_US", "en-", "toolongtag"] {
+            assert!(LanguageTag::parse_from_parameter(tag).is_err(), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn media_type() {
+        assert!(MediaType::parse_from_parameter("image/png").is_ok());
+        assert!(MediaType::parse_from_parameter("text/html; charset=utf-8").is_ok());
+        assert!(MediaType::parse_from_parameter("image").is_err());
+    }
+
+    #[cfg(feature = "phone-number")]
+    #[test]
+    fn phone_number() {
+        assert!(PhoneNumber::parse_from_parameter("+14155552671").is_ok());
+        assert!(PhoneNumber::parse_from_parameter("+1 415 555 2671").is_err());
+        assert!(PhoneNumber::parse_from_parameter("4155552671").is_err());
+    }
+
+    #[cfg(feature = "country-code")]
+    #[test]
+    fn country_code() {
+        assert!(CountryCode::parse_from_parameter("US").is_ok());
+        assert!(CountryCode::parse_from_parameter("XX").is_err());
+    }
+}
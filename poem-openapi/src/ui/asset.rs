@@ -0,0 +1,157 @@
+use std::io::Read;
+
+use bytes::Bytes;
+use once_cell::sync::OnceCell;
+use poem::{
+    http::{header, StatusCode},
+    Request, Response,
+};
+
+/// The assets are cached by the browsers for a day, and revalidated with the
+/// `ETag` after that.
+const CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// An asset of the UI that is embedded compressed with `br` and `gzip`.
+///
+/// The clients that accept neither of them are served the bytes that are
+/// decompressed on the first request.
+pub(crate) struct Asset {
+    content_type: &'static str,
+    etag: &'static str,
+    br: &'static [u8],
+    gzip: &'static [u8],
+    identity: OnceCell<Bytes>,
+}
+
+impl Asset {
+    pub(crate) const fn new(
+        content_type: &'static str,
+        etag: &'static str,
+        br: &'static [u8],
+        gzip: &'static [u8],
+    ) -> Self {
+        Self {
+            content_type,
+            etag,
+            br,
+            gzip,
+            identity: OnceCell::new(),
+        }
+    }
+
+    fn identity(&self) -> Bytes {
+        self.identity
+            .get_or_init(|| {
+                let mut data = Vec::new();
+                flate2::read::GzDecoder::new(self.gzip)
+                    .read_to_end(&mut data)
+                    .expect("valid gzip asset");
+                data.into()
+            })
+            .clone()
+    }
+
+    pub(crate) fn response(&self, req: &Request) -> Response {
+        let builder = Response::builder()
+            .header(header::CACHE_CONTROL, CACHE_CONTROL)
+            .header(header::ETAG, self.etag)
+            .header(header::VARY, "accept-encoding");
+
+        let not_modified = req
+            .headers()
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|etag| {
+                let etag = etag.trim();
+                etag == "*" || etag.trim_start_matches("W/") == self.etag
+            });
+        if not_modified {
+            return builder.status(StatusCode::NOT_MODIFIED).finish();
+        }
+
+        let builder = builder.content_type(self.content_type);
+        if accepts_encoding(req, "br") {
+            builder.header(header::CONTENT_ENCODING, "br").body(self.br)
+        } else if accepts_encoding(req, "gzip") {
+            builder
+                .header(header::CONTENT_ENCODING, "gzip")
+                .body(self.gzip)
+        } else {
+            builder.body(self.identity())
+        }
+    }
+}
+
+fn accepts_encoding(req: &Request, encoding: &str) -> bool {
+    req.headers()
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|item| {
+            let mut params = item.split(';').map(str::trim);
+            let name = params.next().unwrap_or_default();
+            let rejected = params.any(|param| {
+                param.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+            });
+            (name.eq_ignore_ascii_case(encoding) || name == "*") && !rejected
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{endpoint::make_sync, Endpoint, IntoResponse};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn asset() {
+        static ASSET: Asset = Asset::new(
+            "text/css",
+            "\"1\"",
+            include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui.css.br")),
+            include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui.css.gz")),
+        );
+        let ep = make_sync(|req| ASSET.response(&req));
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("accept-encoding", "gzip, br")
+                    .finish(),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(resp.headers()[header::ETAG], "\"1\"");
+        assert_eq!(resp.headers()[header::CACHE_CONTROL], CACHE_CONTROL);
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("accept-encoding", "gzip, br;q=0")
+                    .finish(),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let resp = ep.call(Request::default()).await.unwrap().into_response();
+        assert!(resp.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            include_str!("swagger_ui/swagger-ui.css")
+        );
+
+        let resp = ep
+            .call(Request::builder().header("if-none-match", "\"1\"").finish())
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    }
+}
@@ -1,6 +1,112 @@
+#[cfg(feature = "swagger-ui")]
+mod asset;
 #[cfg(feature = "rapidoc")]
 pub(crate) mod rapidoc;
 #[cfg(feature = "redoc")]
 pub(crate) mod redoc;
 #[cfg(feature = "swagger-ui")]
 pub(crate) mod swagger_ui;
+
+use poem::{
+    endpoint::make_sync,
+    web::{CspNonce, Html},
+    Endpoint,
+};
+
+/// Create an endpoint that serves the HTML page, the `{:nonce}` placeholders
+/// of the template are replaced with the `nonce` attribute if the request has
+/// a [`CspNonce`].
+///
+/// The template is split before the other placeholders are replaced, so that
+/// the `{:nonce}` in the inlined scripts are not replaced.
+pub(crate) fn html_endpoint(template: &str, render: &dyn Fn(&str) -> String) -> impl Endpoint {
+    let parts = template.split("{:nonce}").map(render).collect::<Vec<_>>();
+
+    make_sync(move |req| {
+        let nonce = req
+            .data::<CspNonce>()
+            .map(|nonce| format!(" nonce=\"{}\"", escape_html(nonce.as_str())))
+            .unwrap_or_default();
+        Html(parts.join(&nonce))
+    })
+}
+
+/// Escapes the text to be interpolated into the HTML text or the quoted
+/// attribute values.
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes the JSON document to be inlined in a `<script>` element, `<` can
+/// only appear in the strings of JSON, where it is replaced with `\u003c`, so
+/// that the document cannot close the element.
+pub(crate) fn script_json(document: &str) -> String {
+    document.replace('<', "\\u003c")
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{IntoResponse, Request};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn nonce() {
+        let ep = html_endpoint("<script{:nonce}>{:script}</script>", &|part| {
+            part.replace("{:script}", "let a = '{:nonce}';")
+        });
+
+        let resp = ep.call(Request::default()).await.unwrap();
+        assert_eq!(
+            resp.into_response()
+                .into_body()
+                .into_string()
+                .await
+                .unwrap(),
+            "<script>let a = '{:nonce}';</script>"
+        );
+
+        let mut req = Request::default();
+        req.extensions_mut().insert(CspNonce("abc".to_string()));
+        let resp = ep.call(req).await.unwrap();
+        assert_eq!(
+            resp.into_response()
+                .into_body()
+                .into_string()
+                .await
+                .unwrap(),
+            "<script nonce=\"abc\">let a = '{:nonce}';</script>"
+        );
+    }
+
+    #[tokio::test]
+    async fn escape() {
+        let ep = html_endpoint("<script{:nonce}>let spec = {:spec};</script>", &|part| {
+            part.replace("{:spec}", &script_json(r#"{"a":"</script>"}"#))
+        });
+
+        let mut req = Request::default();
+        req.extensions_mut()
+            .insert(CspNonce("\"><script>".to_string()));
+        let resp = ep.call(req).await.unwrap();
+        assert_eq!(
+            resp.into_response()
+                .into_body()
+                .into_string()
+                .await
+                .unwrap(),
+            r#"<script nonce="&quot;&gt;&lt;script&gt;">let spec = {"a":"\u003c/script>"};</script>"#
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::Endpoint;
+
+use crate::ui::{html_endpoint, script_json};
 
 const RAPIDOC_JS: &str = include_str!("rapidoc-min.js");
 const OAUTH_RECEIVER_HTML: &str = include_str!("oauth-receiver.html");
@@ -10,7 +12,7 @@ const RAPIDOC_TEMPLATE: &str = r#"
     <meta name="viewport" content="width=device-width, minimum-scale=1, initial-scale=1, user-scalable=yes">
     <link href="https://fonts.googleapis.com/css2?family=Open+Sans:wght@300;600&family=Roboto+Mono&display=swap" rel="stylesheet">
     <title>RapiDoc</title>
-    <script charset="UTF-8">{:script}</script>
+    <script{:nonce} charset="UTF-8">{:script}</script>
 </head>
 </html>
 <body>
@@ -28,7 +30,7 @@ const RAPIDOC_TEMPLATE: &str = r#"
         font-size = "large"
     >
     </rapi-doc>
-    <script>
+    <script{:nonce}>
     document.addEventListener('DOMContentLoaded', (event) => {
         let docEl = document.getElementById("thedoc");
         docEl.loadSpec({:spec});
@@ -39,15 +41,19 @@ const RAPIDOC_TEMPLATE: &str = r#"
 "#;
 
 pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = RAPIDOC_TEMPLATE
-        .replace("{:script}", RAPIDOC_JS)
-        .replace("{:spec}", document);
-    let oauth_receiver_html = OAUTH_RECEIVER_HTML.replace("{:script}", RAPIDOC_JS);
-
+    let document = script_json(document);
     poem::Route::new()
-        .at("/", make_sync(move |_| Html(ui_html.clone())))
+        .at(
+            "/",
+            html_endpoint(RAPIDOC_TEMPLATE, &|part| {
+                part.replace("{:script}", RAPIDOC_JS)
+                    .replace("{:spec}", &document)
+            }),
+        )
         .at(
             "/oauth-receiver.html",
-            make_sync(move |_| Html(oauth_receiver_html.clone())),
+            html_endpoint(OAUTH_RECEIVER_HTML, &|part| {
+                part.replace("{:script}", RAPIDOC_JS)
+            }),
         )
 }
@@ -1,4 +1,6 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::Endpoint;
+
+use crate::ui::{html_endpoint, script_json};
 
 const REDOC_JS: &str = include_str!("redoc.standalone.js");
 
@@ -15,18 +17,18 @@ const REDOC_TEMPLATE: &str = r#"
     <!--
     Redoc doesn't change outer page styles
     -->
-    <style>
+    <style{:nonce}>
       body {
         margin: 0;
         padding: 0;
       }
     </style>
-    <script charset="UTF-8">{:script}</script>
+    <script{:nonce} charset="UTF-8">{:script}</script>
   </head>
   <body>
     <div id="redoc-container"></div>
     
-    <script>
+    <script{:nonce}>
         let spec = {:spec};
         Redoc.init(spec, {
           scrollYOffset: 50
@@ -37,9 +39,12 @@ const REDOC_TEMPLATE: &str = r#"
 "#;
 
 pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = REDOC_TEMPLATE
-        .replace("{:script}", REDOC_JS)
-        .replace("{:spec}", document);
-
-    poem::Route::new().at("/", make_sync(move |_| Html(ui_html.clone())))
+    let document = script_json(document);
+    poem::Route::new().at(
+        "/",
+        html_endpoint(REDOC_TEMPLATE, &|part| {
+            part.replace("{:script}", REDOC_JS)
+                .replace("{:spec}", &document)
+        }),
+    )
 }
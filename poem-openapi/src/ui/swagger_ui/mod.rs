@@ -1,7 +1,28 @@
-use poem::{endpoint::make_sync, web::Html, Endpoint};
+use poem::{
+    endpoint::make_sync,
+    error::BadRequest,
+    http::{
+        uri::{InvalidUri, PathAndQuery},
+        Uri,
+    },
+    web::Redirect,
+    Endpoint, EndpointExt, IntoResponse,
+};
 
-const SWAGGER_UI_JS: &str = include_str!("swagger-ui-bundle.js");
-const SWAGGER_UI_CSS: &str = include_str!("swagger-ui.css");
+use crate::ui::{asset::Asset, html_endpoint, script_json};
+
+static SWAGGER_UI_JS: Asset = Asset::new(
+    "application/javascript; charset=utf-8",
+    include_str!(concat!(env!("OUT_DIR"), "/swagger-ui-bundle.js.etag")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui-bundle.js.br")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui-bundle.js.gz")),
+);
+static SWAGGER_UI_CSS: Asset = Asset::new(
+    "text/css; charset=utf-8",
+    include_str!(concat!(env!("OUT_DIR"), "/swagger-ui.css.etag")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui.css.br")),
+    include_bytes!(concat!(env!("OUT_DIR"), "/swagger-ui.css.gz")),
+);
 const OAUTH_RECEIVER_HTML: &str = include_str!("oauth-receiver.html");
 
 const SWAGGER_UI_TEMPLATE: &str = r#"
@@ -9,14 +30,14 @@ const SWAGGER_UI_TEMPLATE: &str = r#"
 <head>
     <meta http-equiv="Content-Type" content="text/html;charset=utf-8">
     <title>Swagger UI</title>
-    <style charset="UTF-8">{:style}</style>
-    <script charset="UTF-8">{:script}</script>
+    <link{:nonce} rel="stylesheet" href="swagger-ui.css">
+    <script{:nonce} charset="UTF-8" src="swagger-ui-bundle.js"></script>
 </head>
 </html>
 <body>
 
 <div id="ui"></div>
-<script>
+<script{:nonce}>
     let spec = {:spec};
     let oauth2RedirectUrl;
 
@@ -43,16 +64,103 @@ const SWAGGER_UI_TEMPLATE: &str = r#"
 </body>
 "#;
 
+/// The assets are relative to the page, so the page is redirected to the path
+/// that ends with `/`, such as `/docs` to `/docs/`.
 pub(crate) fn create_endpoint(document: &str) -> impl Endpoint {
-    let ui_html = SWAGGER_UI_TEMPLATE
-        .replace("{:style}", SWAGGER_UI_CSS)
-        .replace("{:script}", SWAGGER_UI_JS)
-        .replace("{:spec}", document);
+    let document = script_json(document);
+    let page = html_endpoint(SWAGGER_UI_TEMPLATE, &|part| {
+        part.replace("{:spec}", &document)
+    });
 
     poem::Route::new()
-        .at("/", make_sync(move |_| Html(ui_html.clone())))
+        .at(
+            "/",
+            page.around(|ep, req| async move {
+                match dir_location(req.original_uri()) {
+                    Some(location) => {
+                        let location = location.map_err(BadRequest)?;
+                        Ok(Redirect::moved_permanent(location.into()).into_response())
+                    }
+                    None => Ok(ep.call(req).await?.into_response()),
+                }
+            }),
+        )
+        .at(
+            "/swagger-ui-bundle.js",
+            make_sync(|req| SWAGGER_UI_JS.response(&req)),
+        )
+        .at(
+            "/swagger-ui.css",
+            make_sync(|req| SWAGGER_UI_CSS.response(&req)),
+        )
         .at(
             "/oauth-receiver.html",
-            make_sync(move |_| Html(OAUTH_RECEIVER_HTML.to_string())),
+            html_endpoint(OAUTH_RECEIVER_HTML, &ToString::to_string),
         )
 }
+
+/// Returns the path of the page that ends with `/`, or `None` if the path
+/// already ends with `/`.
+fn dir_location(uri: &Uri) -> Option<Result<PathAndQuery, InvalidUri>> {
+    if uri.path().ends_with('/') {
+        return None;
+    }
+    let path = match uri.query() {
+        Some(query) => format!("{}/?{}", uri.path(), query),
+        None => format!("{}/", uri.path()),
+    };
+    Some(PathAndQuery::try_from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{
+        http::{header, StatusCode},
+        Request, Route,
+    };
+
+    use super::*;
+
+    #[test]
+    fn redirect() {
+        assert_eq!(
+            dir_location(&Uri::from_static("/docs?a=1"))
+                .unwrap()
+                .unwrap(),
+            "/docs/?a=1"
+        );
+        assert_eq!(
+            dir_location(&Uri::from_static("/docs")).unwrap().unwrap(),
+            "/docs/"
+        );
+        assert!(dir_location(&Uri::from_static("/docs/")).is_none());
+    }
+
+    #[tokio::test]
+    async fn relative_assets() {
+        let ep = Route::new().nest("/docs", create_endpoint("{}"));
+
+        let resp = ep
+            .call(Request::builder().uri(Uri::from_static("/docs/")).finish())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let html = resp.into_body().into_string().await.unwrap();
+        assert!(html.contains(r#"href="swagger-ui.css""#));
+        assert!(html.contains(r#"src="swagger-ui-bundle.js""#));
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .uri(Uri::from_static("/docs/swagger-ui.css"))
+                    .finish(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[header::ETAG],
+            include_str!(concat!(env!("OUT_DIR"), "/swagger-ui.css.etag"))
+        );
+    }
+}
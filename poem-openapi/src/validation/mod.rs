@@ -9,6 +9,7 @@ mod min_length;
 mod min_properties;
 mod minimum;
 mod multiple_of;
+#[cfg(feature = "regex")]
 mod pattern;
 mod unique_items;
 
@@ -21,6 +22,7 @@ pub use min_length::MinLength;
 pub use min_properties::MinProperties;
 pub use minimum::Minimum;
 pub use multiple_of::MultipleOf;
+#[cfg(feature = "regex")]
 pub use pattern::Pattern;
 pub use unique_items::UniqueItems;
 
@@ -0,0 +1,292 @@
+//! Webhook payload signing and verification.
+//!
+//! The sender signs the payload with [`WebhookSigner`] and sends the
+//! signature in the `Webhook-Signature` header, and the receiver verifies it
+//! with the [`WebhookSignature`] extractor.
+//!
+//! The value of the header is `t=<timestamp>,v1=<signature>`, where the
+//! signature is the hex encoded HMAC-SHA256 of `<timestamp>.<payload>`. When
+//! the signing secret is being rotated, the header contains a `v1` signature
+//! for each secret, so that the receivers with either secret can verify it.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use poem::{error::Unauthorized, Result};
+//! use poem_openapi::{
+//!     error::AuthorizationError,
+//!     payload::{Binary, Json},
+//!     webhook::{WebhookSignature, WebhookSigner},
+//!     Object, OpenApi, Webhook,
+//! };
+//!
+//! #[derive(Object)]
+//! struct Pet {
+//!     id: i64,
+//!     name: String,
+//! }
+//!
+//! #[Webhook]
+//! trait MyWebhooks: Sync {
+//!     /// Fired when a pet is created.
+//!     #[oai(method = "post")]
+//!     async fn new_pet(&self, signature: WebhookSignature, pet: Json<Pet>);
+//! }
+//!
+//! struct Receiver;
+//!
+//! #[OpenApi]
+//! impl Receiver {
+//!     #[oai(path = "/webhooks/new_pet", method = "post")]
+//!     async fn new_pet(&self, signature: WebhookSignature, body: Binary<Vec<u8>>) -> Result<()> {
+//!         if !signature.verify(&[b"secret"], &body, Duration::from_secs(300)) {
+//!             return Err(Unauthorized(AuthorizationError));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let signer = WebhookSigner::new(b"secret".to_vec());
+//! let header_value = signer.sign(br#"{"id":1,"name":"dog"}"#);
+//! ```
+
+use std::time::Duration;
+
+use libhmac::{Hmac, Mac};
+use poem::{Request, RequestBody, Result};
+use sha2::Sha256;
+
+use crate::{
+    auth::hmac::{from_hex, now, to_hex},
+    error::ParseParamError,
+    registry::{MetaParamIn, MetaSchema, MetaSchemaRef},
+    ApiExtractor, ApiExtractorType, ExtractParamOptions,
+};
+
+/// The header that contains the signature of the webhook payload.
+pub const SIGNATURE_HEADER: &str = "Webhook-Signature";
+
+/// The version of the signature scheme.
+const SCHEME: &str = "v1";
+
+fn mac(secret: &[u8], timestamp: i64, payload: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("any key length is valid");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    mac
+}
+
+/// Signs the outgoing webhook payloads.
+///
+/// The first secret is the current one, use [`WebhookSigner::with_secret`] to
+/// add the secrets that are still accepted by the receivers during a key
+/// rotation.
+#[derive(Debug, Clone)]
+pub struct WebhookSigner {
+    secrets: Vec<Vec<u8>>,
+}
+
+impl WebhookSigner {
+    /// Create a webhook signer with the secret.
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secrets: vec![secret.into()],
+        }
+    }
+
+    /// Adds a secret, the payload is also signed with it.
+    #[must_use]
+    pub fn with_secret(mut self, secret: impl Into<Vec<u8>>) -> Self {
+        self.secrets.push(secret.into());
+        self
+    }
+
+    /// Signs the payload with the current time, returns the value of the
+    /// `Webhook-Signature` header.
+    pub fn sign(&self, payload: &[u8]) -> String {
+        self.sign_with_timestamp(now(), payload)
+    }
+
+    /// Signs the payload with the Unix timestamp in seconds, returns the value
+    /// of the `Webhook-Signature` header.
+    pub fn sign_with_timestamp(&self, timestamp: i64, payload: &[u8]) -> String {
+        let mut value = format!("t={}", timestamp);
+        for secret in &self.secrets {
+            let signature = mac(secret, timestamp, payload).finalize().into_bytes();
+            value.push_str(&format!(",{}={}", SCHEME, to_hex(&signature)));
+        }
+        value
+    }
+}
+
+/// Used to extract the signature of an inbound webhook from the
+/// `Webhook-Signature` header.
+///
+/// The header is documented as a required parameter of the operation, use
+/// [`WebhookSignature::verify`] with the raw body to verify it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WebhookSignature {
+    /// The Unix timestamp of the signature in seconds.
+    pub timestamp: i64,
+    /// The signatures.
+    pub signatures: Vec<Vec<u8>>,
+}
+
+impl WebhookSignature {
+    /// Parses the value of the `Webhook-Signature` header.
+    ///
+    /// The signatures of unknown schemes are ignored.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+
+        for item in value.split(',') {
+            let (key, value) = item.trim().split_once('=')?;
+            match key {
+                "t" => timestamp = Some(value.parse().ok()?),
+                SCHEME => signatures.push(from_hex(value)?),
+                _ => {}
+            }
+        }
+
+        if signatures.is_empty() {
+            return None;
+        }
+        Some(Self {
+            timestamp: timestamp?,
+            signatures,
+        })
+    }
+
+    /// Returns `true` if any of the signatures is signed with any of the
+    /// `secrets`, and the timestamp is within `tolerance` of the current
+    /// time.
+    ///
+    /// Pass both the old and the new secret during a key rotation.
+    pub fn verify<K: AsRef<[u8]>>(
+        &self,
+        secrets: &[K],
+        payload: &[u8],
+        tolerance: Duration,
+    ) -> bool {
+        // The timestamp is only trusted after the signature is verified.
+        let signed = secrets.iter().any(|secret| {
+            self.signatures.iter().any(|signature| {
+                mac(secret.as_ref(), self.timestamp, payload)
+                    .verify_slice(signature)
+                    .is_ok()
+            })
+        });
+        signed
+            && now()
+                .checked_sub(self.timestamp)
+                .map_or(false, |skew| skew.unsigned_abs() <= tolerance.as_secs())
+    }
+}
+
+#[poem::async_trait]
+impl<'a> ApiExtractor<'a> for WebhookSignature {
+    const TYPE: ApiExtractorType = ApiExtractorType::Parameter;
+    const PARAM_IS_REQUIRED: bool = true;
+
+    type ParamType = ();
+    type ParamRawType = ();
+
+    fn param_in() -> Option<MetaParamIn> {
+        Some(MetaParamIn::Header)
+    }
+
+    fn param_name() -> Option<&'static str> {
+        Some(SIGNATURE_HEADER)
+    }
+
+    fn param_schema_ref() -> Option<MetaSchemaRef> {
+        Some(MetaSchemaRef::Inline(Box::new(MetaSchema::new("string"))))
+    }
+
+    async fn from_request(
+        request: &'a Request,
+        _body: &mut RequestBody,
+        _param_opts: ExtractParamOptions<Self::ParamType>,
+    ) -> Result<Self> {
+        let value = request
+            .header(SIGNATURE_HEADER)
+            .ok_or_else(|| ParseParamError {
+                name: SIGNATURE_HEADER,
+                reason: "Type \"WebhookSignature\" expects an input value.".to_string(),
+            })?;
+        Self::parse(value).ok_or_else(|| {
+            ParseParamError {
+                name: SIGNATURE_HEADER,
+                reason: format!("Invalid webhook signature: {}", value),
+            }
+            .into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_and_verify() {
+        let payload = b"hello";
+        let timestamp = now();
+        let value = WebhookSigner::new(b"a".to_vec()).sign_with_timestamp(timestamp, payload);
+        let signature = WebhookSignature::parse(&value).unwrap();
+        assert_eq!(signature.timestamp, timestamp);
+        assert_eq!(signature.signatures.len(), 1);
+
+        let tolerance = Duration::from_secs(300);
+        assert!(signature.verify(&[b"a"], payload, tolerance));
+        assert!(!signature.verify(&[b"b"], payload, tolerance));
+        assert!(!signature.verify(&[b"a"], b"world", tolerance));
+
+        let value = WebhookSigner::new(b"a".to_vec()).sign_with_timestamp(timestamp - 600, payload);
+        let signature = WebhookSignature::parse(&value).unwrap();
+        assert!(!signature.verify(&[b"a"], payload, tolerance));
+
+        for timestamp in [i64::MIN, i64::MAX] {
+            let value = WebhookSigner::new(b"a".to_vec()).sign_with_timestamp(timestamp, payload);
+            let signature = WebhookSignature::parse(&value).unwrap();
+            assert!(!signature.verify(&[b"a"], payload, tolerance));
+            assert!(!signature.verify(&[b"b"], payload, tolerance));
+        }
+    }
+
+    #[test]
+    fn rotation() {
+        let payload = b"hello";
+        let value = WebhookSigner::new(b"new".to_vec())
+            .with_secret(b"old".to_vec())
+            .sign(payload);
+        let signature = WebhookSignature::parse(&value).unwrap();
+        assert_eq!(signature.signatures.len(), 2);
+
+        let tolerance = Duration::from_secs(300);
+        assert!(signature.verify(&[b"old"], payload, tolerance));
+        assert!(signature.verify(&[b"new"], payload, tolerance));
+        assert!(signature.verify(&[b"other".as_ref(), b"new"], payload, tolerance));
+        assert!(!signature.verify(&[b"other"], payload, tolerance));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            WebhookSignature::parse("t=100,v1=00ff,v0=zz"),
+            Some(WebhookSignature {
+                timestamp: 100,
+                signatures: vec![vec![0, 0xff]],
+            })
+        );
+        assert_eq!(WebhookSignature::parse("t=100"), None);
+        assert_eq!(WebhookSignature::parse("v1=00ff"), None);
+        assert_eq!(WebhookSignature::parse("t=abc,v1=00ff"), None);
+        assert_eq!(WebhookSignature::parse("t=100,v1=0"), None);
+        assert_eq!(WebhookSignature::parse(""), None);
+    }
+}
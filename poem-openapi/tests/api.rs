@@ -4,6 +4,7 @@ use poem::{
     Endpoint, EndpointExt, Error, IntoEndpoint,
 };
 use poem_openapi::{
+    error::ParseParamError,
     param::Query,
     payload::{Binary, Json, PlainText},
     registry::{MetaApi, MetaExternalDocument, MetaSchema},
@@ -522,6 +523,127 @@ async fn bad_request_handler_for_validator() {
     );
 }
 
+#[tokio::test]
+async fn error_message_provider() {
+    #[derive(ApiResponse)]
+    #[oai(bad_request_handler = "bad_request_handler")]
+    enum MyResponse {
+        /// Ok
+        #[oai(status = 200)]
+        Ok(PlainText<String>),
+        /// Already exists
+        #[oai(status = 400)]
+        BadRequest(PlainText<String>),
+    }
+
+    fn bad_request_handler(err: Error) -> MyResponse {
+        MyResponse::BadRequest(PlainText(format!("!!! {}", err.to_string())))
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/a", method = "get")]
+        async fn a(&self, code: Query<u16>) -> PlainText<String> {
+            PlainText(format!("code: {}", code.0))
+        }
+
+        #[oai(path = "/b", method = "get")]
+        async fn b(
+            &self,
+            #[oai(validator(maximum(value = "100")))] code: Query<u16>,
+        ) -> MyResponse {
+            MyResponse::Ok(PlainText(format!("code: {}", code.0)))
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .error_message_provider(|req: &poem::Request, err: &Error| {
+            let err = err.downcast_ref::<ParseParamError>()?;
+            match req.header("accept-language") {
+                Some("zh-CN") => Some(format!("参数 `{}` 解析失败", err.name)),
+                _ => None,
+            }
+        })
+        .into_endpoint();
+
+    let err = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/a"))
+                .header("accept-language", "zh-CN")
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+    assert_eq!(err.to_string(), "参数 `code` 解析失败");
+
+    let err = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/a"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.is::<ParseParamError>());
+
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/b?code=200"))
+                .header("accept-language", "zh-CN")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "!!! 参数 `code` 解析失败"
+    );
+}
+
+#[tokio::test]
+async fn error_message_provider_keeps_source() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/a", method = "get")]
+        async fn a(&self, code: Query<u16>) -> PlainText<String> {
+            PlainText(format!("code: {}", code.0))
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .error_message_provider(|_: &poem::Request, err: &Error| {
+            let err = err.downcast_ref::<ParseParamError>()?;
+            Some(format!("invalid `{}`", err.name))
+        })
+        .into_endpoint();
+
+    let err = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/a?code=abc"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "invalid `code`");
+    assert_eq!(err.downcast_ref::<ParseParamError>().unwrap().name, "code");
+
+    let resp = err.as_response();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        resp.into_body().into_string().await.unwrap(),
+        "invalid `code`"
+    );
+}
+
 #[tokio::test]
 async fn poem_extract() {
     struct Api;
@@ -665,3 +787,1185 @@ async fn external_docs() {
         })
     );
 }
+
+#[tokio::test]
+async fn scoped_middleware() {
+    #[derive(Tags)]
+    enum MyTags {
+        Admin,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get", tag = "MyTags::Admin")]
+        async fn user(&self, id: poem_openapi::param::Path<i32>) -> PlainText<String> {
+            PlainText(id.0.to_string())
+        }
+
+        #[oai(path = "/users/:id", method = "post")]
+        async fn update_user(&self, id: poem_openapi::param::Path<i32>) -> PlainText<String> {
+            PlainText(id.0.to_string())
+        }
+
+        #[oai(path = "/internal/status", method = "get")]
+        async fn status(&self) {}
+
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .with_on(
+            MyTags::Admin,
+            poem::middleware::SetHeader::new().appending("x-scope", "admin"),
+        )
+        .with_on(
+            poem_openapi::MiddlewareScope::path_prefix("/internal"),
+            poem::middleware::SetHeader::new().appending("x-scope", "internal"),
+        )
+        .into_endpoint();
+
+    for (method, uri, scope) in [
+        (Method::GET, "/users/1", Some("admin")),
+        (Method::POST, "/users/1", None),
+        (Method::GET, "/internal/status", Some("internal")),
+        (Method::GET, "/hello", None),
+    ] {
+        let resp = ep
+            .call(
+                poem::Request::builder()
+                    .method(method)
+                    .uri_str(uri)
+                    .finish(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.header("x-scope"), scope, "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn scoped_middleware_operation_info() {
+    use poem::{endpoint::BoxEndpoint, http::HeaderValue, Response};
+    use poem_openapi::OperationInfo;
+
+    #[derive(Tags)]
+    enum MyTags {
+        Users,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get", tag = "MyTags::Users")]
+        async fn user(&self, id: poem_openapi::param::Path<i32>) -> PlainText<String> {
+            PlainText(id.0.to_string())
+        }
+
+        #[oai(path = "/users/me", method = "get")]
+        async fn me(&self) {}
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .with_on(
+            MyTags::Users,
+            poem::middleware::SetHeader::new().appending("x-scope", "users"),
+        )
+        .with_on(
+            poem_openapi::MiddlewareScope::path_prefix("/users"),
+            poem::middleware::make(|ep: BoxEndpoint<'static, Response>| {
+                ep.around(|ep, req| async move {
+                    let path = req.extensions().get::<OperationInfo>().unwrap().path();
+                    let mut resp = ep.call(req).await?;
+                    resp.headers_mut()
+                        .insert("x-operation", HeaderValue::from_static(path));
+                    Ok(resp)
+                })
+            }),
+        )
+        .into_endpoint();
+
+    for (uri, scope, operation) in [
+        ("/users/1", Some("users"), "/users/{id}"),
+        ("/users/me", None, "/users/me"),
+    ] {
+        let resp = ep
+            .call(poem::Request::builder().uri_str(uri).finish())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.header("x-scope"), scope, "{}", uri);
+        assert_eq!(resp.header("x-operation"), Some(operation), "{}", uri);
+    }
+}
+
+#[test]
+fn scoped_middleware_send_sync() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").with_on(
+        poem_openapi::MiddlewareScope::path_prefix("/hello"),
+        poem::middleware::SetHeader::new().appending("x-scope", "hello"),
+    );
+    assert_send_sync(&api_service);
+}
+
+#[tokio::test]
+async fn maintenance() {
+    use poem::middleware::{Maintenance, MaintenanceSwitch};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/health", method = "get")]
+        async fn health(&self) {}
+
+        #[oai(path = "/users", method = "post")]
+        async fn create_user(&self) {}
+    }
+
+    let switch = MaintenanceSwitch::new();
+    let api_service = OpenApiService::new(Api, "test", "1.0").maintenance(
+        Maintenance::new(switch.clone())
+            .retry_after(std::time::Duration::from_secs(60))
+            .allow_path("/health"),
+    );
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert!(spec["paths"]["/health"]["get"]["responses"]["503"].is_null());
+    let resp = &spec["paths"]["/users"]["post"]["responses"]["503"];
+    assert_eq!(
+        resp["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/MaintenanceError"
+    );
+    assert!(resp["headers"]["Retry-After"].is_object());
+    assert!(spec["components"]["schemas"]["MaintenanceError"].is_object());
+
+    let ep = api_service.into_endpoint();
+    let request = || {
+        poem::Request::builder()
+            .method(Method::POST)
+            .uri_str("/users")
+            .finish()
+    };
+    assert!(ep.call(request()).await.is_ok());
+
+    switch.enable();
+    let resp = ep.call(request()).await.unwrap_err().as_response();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(resp.header("Retry-After"), Some("60"));
+    let resp = ep
+        .call(poem::Request::builder().uri_str("/health").finish())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn deserializers() {
+    use poem_openapi::{error::ContentTypeError, Object};
+
+    #[derive(Object)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/point", method = "post")]
+        async fn point(&self, point: Json<Point>) -> PlainText<String> {
+            PlainText(format!("{},{}", point.x, point.y))
+        }
+
+        #[oai(path = "/text", method = "post")]
+        async fn text(&self, text: Json<String>) -> PlainText<String> {
+            PlainText(text.0)
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0")
+        .deserializer("text/plain", |data| {
+            std::str::from_utf8(data).map(serde_json::Value::from)
+        })
+        .deserializer_for::<Point, _, _>("application/x-point", |data| {
+            let s = std::str::from_utf8(data).map_err(|err| err.to_string())?;
+            let (x, y) = s.split_once(',').ok_or("expected `x,y`")?;
+            Ok::<_, String>(
+                serde_json::json!({ "x": x.parse::<i64>().ok(), "y": y.parse::<i64>().ok() }),
+            )
+        });
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    let content = &spec["paths"]["/point"]["post"]["requestBody"]["content"];
+    assert!(content["application/x-point"].is_object());
+    assert!(content["text/plain"].is_object());
+    let content = &spec["paths"]["/text"]["post"]["requestBody"]["content"];
+    assert!(content["application/x-point"].is_null());
+    assert!(content["text/plain"].is_object());
+
+    let ep = api_service.into_endpoint();
+    let request = |path: &str, content_type: &str, body: &'static str| {
+        poem::Request::builder()
+            .method(Method::POST)
+            .uri_str(path)
+            .content_type(content_type)
+            .body(body)
+    };
+
+    let resp = ep
+        .call(request("/point", "application/x-point", "1,2"))
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "1,2");
+    let resp = ep
+        .call(request("/point", "application/json", r#"{"x": 3, "y": 4}"#))
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "3,4");
+    let resp = ep
+        .call(request("/text", "text/plain", "hello"))
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "hello");
+
+    let err = ep
+        .call(request("/point", "application/x-point", "1"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+    let err = ep
+        .call(request("/point", "application/x-point", "1,a"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+    assert!(ep
+        .call(request("/text", "application/x-point", "1,2"))
+        .await
+        .unwrap_err()
+        .is::<ContentTypeError>());
+}
+
+#[tokio::test]
+async fn strict_content_type() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/value", method = "post")]
+        async fn value(&self, value: Json<i32>) -> Json<i32> {
+            value
+        }
+
+        #[oai(path = "/ping", method = "get")]
+        async fn ping(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").strict_content_type();
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert!(spec["paths"]["/value"]["post"]["responses"]["415"].is_object());
+    assert!(spec["paths"]["/ping"]["get"]["responses"]["415"].is_null());
+
+    let ep = api_service.into_endpoint();
+    let request = |content_type: &str| {
+        poem::Request::builder()
+            .method(Method::POST)
+            .uri_str("/value")
+            .content_type(content_type)
+            .body("1")
+    };
+    let resp = ep.call(request("application/vnd.api+json")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let err = ep.call(request("text/plain")).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the `Content-Type` requested by the client is not supported: text/plain, the supported content types are: application/json"
+    );
+    let resp = err.as_response();
+    assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let err = ep
+        .call(request("application/json; charset=latin1"))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.as_response().status(),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+}
+
+#[tokio::test]
+async fn strict_accept() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/value/:id", method = "get")]
+        async fn value(&self, id: poem_openapi::param::Path<i32>) -> Json<i32> {
+            Json(id.0)
+        }
+
+        #[oai(path = "/ping", method = "post")]
+        async fn ping(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0").strict_accept();
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert!(spec["paths"]["/value/{id}"]["get"]["responses"]["406"].is_object());
+    assert!(spec["paths"]["/ping"]["post"]["responses"]["406"].is_null());
+
+    let ep = api_service.into_endpoint();
+    let request = |accept: Option<&str>| {
+        let mut builder = poem::Request::builder().uri_str("/value/1");
+        if let Some(accept) = accept {
+            builder = builder.header("accept", accept);
+        }
+        builder.finish()
+    };
+    for accept in [
+        None,
+        Some("application/json"),
+        Some("text/html, */*;q=0.1"),
+        Some("application/*"),
+    ] {
+        let resp = ep.call(request(accept)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    for accept in ["text/html", "*/*, application/json;q=0"] {
+        let err = ep.call(request(Some(accept))).await.unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri_str("/ping")
+                .header("accept", "text/html")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn scheduler() {
+    use std::sync::Arc;
+
+    use poem::middleware::{Priority, Scheduler};
+    use tokio::sync::Notify;
+
+    struct Api(Arc<Notify>);
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/slow", method = "get", priority = "high")]
+        async fn slow(&self) {
+            self.0.notified().await;
+        }
+
+        #[oai(path = "/recommendations", method = "get", priority = "low")]
+        async fn recommendations(&self) {}
+
+        #[oai(path = "/users", method = "get")]
+        async fn users(&self) {}
+    }
+
+    let notify = Arc::new(Notify::new());
+    let scheduler = Scheduler::new(1).shed(Priority::Low);
+    let api_service =
+        OpenApiService::new(Api(notify.clone()), "test", "1.0").scheduler(scheduler.clone());
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["paths"]["/slow"]["get"]["x-priority"], "high");
+    assert_eq!(
+        spec["paths"]["/recommendations"]["get"]["x-priority"],
+        "low"
+    );
+    assert!(spec["paths"]["/users"]["get"]["x-priority"].is_null());
+
+    let ep = Arc::new(api_service.into_endpoint());
+    let request = |path| poem::Request::builder().uri_str(path).finish();
+
+    assert!(ep.call(request("/recommendations")).await.is_ok());
+
+    let slow = tokio::spawn({
+        let ep = ep.clone();
+        async move { ep.call(request("/slow")).await.unwrap().status() }
+    });
+    while scheduler.running() != 1 {
+        tokio::task::yield_now().await;
+    }
+
+    // the low-priority operation is shed, the others wait
+    let resp = ep
+        .call(request("/recommendations"))
+        .await
+        .unwrap_err()
+        .as_response();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let users = tokio::spawn({
+        let ep = ep.clone();
+        async move { ep.call(request("/users")).await.unwrap().status() }
+    });
+    while scheduler.queued() != 1 {
+        tokio::task::yield_now().await;
+    }
+
+    notify.notify_one();
+    assert_eq!(slow.await.unwrap(), StatusCode::OK);
+    assert_eq!(users.await.unwrap(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn raw_request_and_modify_response() {
+    use poem_openapi::payload::ModifyResponse;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(
+            &self,
+            req: &poem::Request,
+            name: Query<String>,
+        ) -> ModifyResponse<PlainText<String>> {
+            let tenant = req.header("X-Tenant").unwrap_or_default().to_string();
+            ModifyResponse::new(PlainText(format!("hello, {}", name.0)), move |resp| {
+                resp.headers_mut()
+                    .insert("X-Tenant", tenant.parse().unwrap());
+            })
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(operation.params.len(), 1);
+    assert_eq!(operation.params[0].name, "name");
+    assert_eq!(
+        operation.responses.responses[0].content[0].content_type,
+        "text/plain"
+    );
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/hello?name=sunli"))
+                .header("X-Tenant", "acme")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.header("X-Tenant"), Some("acme"));
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "hello, sunli"
+    );
+}
+
+#[tokio::test]
+async fn operation_info() {
+    use poem_openapi::OperationInfo;
+
+    #[derive(Tags)]
+    enum MyTags {
+        Users,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get", tag = "MyTags::Users")]
+        async fn user(
+            &self,
+            info: OperationInfo,
+            id: poem_openapi::param::Path<i64>,
+        ) -> PlainText<String> {
+            PlainText(format!(
+                "{} {} {:?} {}",
+                info.method(),
+                info.path(),
+                info.tags(),
+                id.0
+            ))
+        }
+
+        #[oai(path = "/users/me", method = "get", operation_id = "me")]
+        async fn me(&self) {}
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let mut resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/1"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        "GET /users/{id} [\"Users\"] 1"
+    );
+    let info = resp.extensions().get::<OperationInfo>().unwrap();
+    assert_eq!(info.operation_id(), None);
+    assert_eq!(info.tags(), ["Users"]);
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/me"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    let info = resp.extensions().get::<OperationInfo>().unwrap();
+    assert_eq!(info.path(), "/users/me");
+    assert_eq!(info.operation_id(), Some("me"));
+
+    let err = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn writable_schemas() {
+    use poem_openapi::Object;
+
+    #[derive(Object)]
+    struct Address {
+        #[oai(read_only)]
+        id: i64,
+        city: String,
+    }
+
+    #[derive(Object)]
+    #[allow(dead_code)]
+    struct User {
+        #[oai(read_only)]
+        id: i64,
+        name: String,
+        #[oai(write_only)]
+        password: String,
+        address: Address,
+    }
+
+    #[derive(Object)]
+    struct Tag {
+        name: String,
+        children: Vec<Tag>,
+    }
+
+    #[derive(Object)]
+    struct Category {
+        #[oai(read_only)]
+        id: i64,
+        parent: Option<Box<Category>>,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "post")]
+        async fn create_user(&self, user: Json<User>) -> Json<User> {
+            user
+        }
+
+        #[oai(path = "/tags", method = "post")]
+        async fn create_tag(&self, tag: Json<Tag>) -> Json<Tag> {
+            tag
+        }
+
+        #[oai(path = "/categories", method = "post")]
+        async fn create_category(&self, category: Json<Category>) -> Json<Category> {
+            category
+        }
+    }
+
+    let spec: serde_json::Value =
+        serde_json::from_str(&OpenApiService::new(Api, "test", "1.0").spec()).unwrap();
+    let schema_ref = |path: &str, body: &serde_json::Value| {
+        body["content"]["application/json"]["schema"]["$ref"]
+            .as_str()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| panic!("{}", path))
+    };
+    let operation = &spec["paths"]["/users"]["post"];
+    assert_eq!(
+        schema_ref("request", &operation["requestBody"]),
+        "#/components/schemas/UserWritable"
+    );
+    assert_eq!(
+        schema_ref("response", &operation["responses"]["200"]),
+        "#/components/schemas/User"
+    );
+    let operation = &spec["paths"]["/tags"]["post"];
+    assert_eq!(
+        schema_ref("request", &operation["requestBody"]),
+        "#/components/schemas/Tag"
+    );
+
+    let schemas = &spec["components"]["schemas"];
+    assert!(schemas["User"]["properties"]["id"].is_object());
+    assert!(schemas["UserWritable"]["properties"]["id"].is_null());
+    assert!(schemas["UserWritable"]["properties"]["password"].is_object());
+    assert_eq!(
+        schemas["UserWritable"]["required"],
+        serde_json::json!(["name", "password", "address"])
+    );
+    assert_eq!(
+        schemas["UserWritable"]["properties"]["address"]["$ref"],
+        "#/components/schemas/AddressWritable"
+    );
+    assert!(schemas["AddressWritable"]["properties"]["id"].is_null());
+    assert!(schemas["TagWritable"].is_null());
+    assert_eq!(
+        schemas["Tag"]["properties"]["children"]["items"]["$ref"],
+        "#/components/schemas/Tag"
+    );
+    assert!(schemas["CategoryWritable"]["properties"]["id"].is_null());
+    assert_eq!(
+        schemas["CategoryWritable"]["properties"]["parent"]["$ref"],
+        "#/components/schemas/CategoryWritable"
+    );
+}
+
+#[test]
+fn lint() {
+    use poem_openapi::{
+        param::{Header, Path},
+        LintWarning, Object,
+    };
+
+    #[derive(Object)]
+    struct Pagination {
+        page: u32,
+    }
+
+    #[derive(Object)]
+    struct Items {
+        items: Vec<String>,
+        #[oai(flatten)]
+        pagination: Pagination,
+    }
+
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum ItemsResponse {
+        #[oai(status = 200)]
+        Ok(Json<Items>),
+        #[oai(status = 400)]
+        BadRequest,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get", operation_id = "get")]
+        async fn user(&self, id: Path<i64>) -> PlainText<String> {
+            PlainText(id.0.to_string())
+        }
+
+        /// List the items
+        #[oai(path = "/items", method = "get", operation_id = "get")]
+        async fn items(
+            &self,
+            #[oai(name = "Accept")] _accept: Header<Option<String>>,
+        ) -> ItemsResponse {
+            ItemsResponse::BadRequest
+        }
+
+        /// Ping
+        #[oai(path = "/ping", method = "get")]
+        async fn ping(&self) {}
+    }
+
+    let warnings = OpenApiService::new(Api, "test", "1.0").lint();
+    assert_eq!(
+        warnings,
+        vec![
+            LintWarning::MissingDescription {
+                method: Method::GET,
+                path: "/users/{id}",
+            },
+            LintWarning::MissingClientErrorResponse {
+                method: Method::GET,
+                path: "/users/{id}",
+            },
+            LintWarning::ShadowedHeader {
+                method: Method::GET,
+                path: "/items",
+                name: "Accept",
+            },
+            LintWarning::DuplicateOperationId {
+                operation_id: "get"
+            },
+            LintWarning::UnusedSchema { name: "Pagination" },
+        ]
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "GET /users/{id}: the operation does not document a 4xx response"
+    );
+}
+
+#[tokio::test]
+async fn generic_impl() {
+    use poem_openapi::param::Path;
+
+    trait Store {
+        fn get(&self, id: i64) -> Option<String>;
+    }
+
+    struct Memory;
+
+    impl Store for Memory {
+        fn get(&self, id: i64) -> Option<String> {
+            (id == 1).then(|| "memory".to_string())
+        }
+    }
+
+    struct Fixed(&'static str);
+
+    impl Store for Fixed {
+        fn get(&self, _id: i64) -> Option<String> {
+            Some(self.0.to_string())
+        }
+    }
+
+    #[derive(ApiResponse)]
+    enum UserResponse {
+        #[oai(status = 200)]
+        Ok(PlainText<String>),
+        #[oai(status = 404)]
+        NotFound,
+    }
+
+    struct Api<S> {
+        store: S,
+    }
+
+    #[OpenApi]
+    impl<S> Api<S>
+    where
+        S: Store + Send + Sync,
+    {
+        #[oai(path = "/users/:id", method = "get")]
+        async fn user(&self, id: Path<i64>) -> UserResponse {
+            match self.store.get(id.0) {
+                Some(name) => UserResponse::Ok(PlainText(name)),
+                None => UserResponse::NotFound,
+            }
+        }
+    }
+
+    let meta: MetaApi = Api::<Memory>::meta().remove(0);
+    assert_eq!(meta.paths[0].path, "/users/{id}");
+
+    let ep = OpenApiService::new(Api { store: Memory }, "test", "1.0").into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/1"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "memory");
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/2"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let ep = OpenApiService::new(
+        Api {
+            store: Fixed("fixed"),
+        },
+        "test",
+        "1.0",
+    )
+    .into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/2"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "fixed");
+}
+
+#[tokio::test]
+async fn delegate() {
+    use std::sync::Arc;
+
+    use poem_openapi::param::Path;
+
+    #[derive(ApiResponse)]
+    enum UserResponse {
+        #[oai(status = 200)]
+        Ok(PlainText<String>),
+        #[oai(status = 404)]
+        NotFound,
+    }
+
+    #[poem::async_trait]
+    trait UserService: Send + Sync {
+        async fn user(&self, id: Path<i64>, name: Query<Option<String>>) -> UserResponse;
+    }
+
+    struct Users;
+
+    #[poem::async_trait]
+    impl UserService for Users {
+        async fn user(&self, id: Path<i64>, name: Query<Option<String>>) -> UserResponse {
+            match id.0 {
+                1 => UserResponse::Ok(PlainText(name.0.unwrap_or_else(|| "alice".to_string()))),
+                _ => UserResponse::NotFound,
+            }
+        }
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users/:id", method = "get", delegate = "dyn UserService")]
+        async fn user(
+            &self,
+            id: Path<i64>,
+            #[oai(validator(max_length = 8))] name: Query<Option<String>>,
+        ) -> UserResponse {
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    let operation = &meta.paths[0].operations[0];
+    assert_eq!(
+        operation
+            .params
+            .iter()
+            .map(|param| param.name)
+            .collect::<Vec<_>>(),
+        vec!["id", "name"]
+    );
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .into_endpoint()
+        .data(Arc::new(Users) as Arc<dyn UserService>);
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/1"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "alice");
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/2"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let err = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/users/1?name=abcdefghijk"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn cfg_operations() {
+    use poem_openapi::{registry::Registry, Object};
+
+    #[derive(Object)]
+    #[allow(dead_code)]
+    struct Disabled {
+        value: i32,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/a", method = "get")]
+        async fn a(&self) -> PlainText<&'static str> {
+            PlainText("a")
+        }
+
+        #[cfg(not(test))]
+        #[oai(path = "/a", method = "post")]
+        async fn a_post(&self, value: Json<Disabled>) -> Json<i32> {
+            Json(value.0.value)
+        }
+
+        #[cfg(not(test))]
+        #[oai(path = "/b", method = "get")]
+        async fn b(&self) {}
+
+        #[cfg(test)]
+        #[oai(path = "/c", method = "get")]
+        async fn c(&self) -> PlainText<&'static str> {
+            PlainText("c")
+        }
+
+        #[cfg(not(test))]
+        #[oai(path = "/c", method = "get")]
+        async fn c_disabled(&self) -> PlainText<&'static str> {
+            PlainText("disabled")
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths
+            .iter()
+            .map(|path| (path.path, path.operations.len()))
+            .collect::<Vec<_>>(),
+        vec![("/a", 1), ("/c", 1)]
+    );
+
+    let mut registry = Registry::new();
+    Api::register(&mut registry);
+    assert!(registry.schemas.is_empty());
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .method(Method::POST)
+                .uri(Uri::from_static("/a"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(resp.as_response().status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/b"))
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(resp.as_response().status(), StatusCode::NOT_FOUND);
+
+    let resp = ep
+        .call(
+            poem::Request::builder()
+                .uri(Uri::from_static("/c"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "c");
+}
+
+#[test]
+fn spec_overrides() {
+    use poem_openapi::ServerObject;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0")
+        .server("http://localhost:3000")
+        .servers(vec![
+            ServerObject::new("https://api.example.com").description("Production"),
+            ServerObject::new("https://backup.example.com"),
+        ])
+        .version("1.0.3")
+        .extension("x-environment", "production")
+        .info_extension("x-build", serde_json::json!({ "commit": "abc123" }));
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["info"]["version"], "1.0.3");
+    assert_eq!(spec["info"]["x-build"]["commit"], "abc123");
+    assert_eq!(spec["x-environment"], "production");
+    assert_eq!(
+        spec["servers"],
+        serde_json::json!([
+            { "url": "https://api.example.com", "description": "Production" },
+            { "url": "https://backup.example.com" },
+        ])
+    );
+}
+
+#[test]
+#[should_panic(expected = "must start with `x-`")]
+fn spec_extension_name() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) {}
+    }
+
+    let _ = OpenApiService::new(Api, "test", "1.0").extension("environment", "production");
+}
+
+#[test]
+fn map_spec() {
+    use poem_openapi::{LintWarning, Object};
+
+    #[derive(Object)]
+    struct User {
+        name: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/users", method = "get")]
+        async fn users(&self) -> Json<Vec<User>> {
+            Json(vec![])
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0")
+        .map_spec(|doc| {
+            doc.info.version = format!("{}-beta", doc.info.version);
+            doc.registry.schemas.get_mut("User").unwrap().description = Some("A user");
+        })
+        .map_spec(|doc| {
+            doc.info.version.push_str(".1");
+            for operation in doc
+                .apis
+                .iter_mut()
+                .flat_map(|api| &mut api.paths)
+                .flat_map(|path| &mut path.operations)
+            {
+                operation.summary.get_or_insert("Undocumented");
+            }
+        });
+
+    let spec: serde_json::Value = serde_json::from_str(&api_service.spec()).unwrap();
+    assert_eq!(spec["info"]["version"], "1.0-beta.1");
+    assert_eq!(
+        spec["components"]["schemas"]["User"]["description"],
+        "A user"
+    );
+    assert_eq!(spec["paths"]["/users"]["get"]["summary"], "Undocumented");
+    assert!(!api_service
+        .lint()
+        .iter()
+        .any(|warning| matches!(warning, LintWarning::MissingDescription { .. })));
+}
+
+#[test]
+fn deserialize_spec() {
+    use poem_openapi::{
+        param::Path, registry::OpenApiDocument, ApiResponse, Enum, Object, SecurityScheme,
+    };
+
+    #[derive(Enum)]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(Object)]
+    #[oai(deny_unknown_fields)]
+    struct User {
+        #[oai(read_only)]
+        id: i64,
+        /// The login of the user
+        #[oai(validator(max_length = 16))]
+        login: String,
+        role: Role,
+        tags: Vec<String>,
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "api_key", key_name = "X-API-Key", in = "header")]
+    struct ApiKey(poem_openapi::auth::ApiKey);
+
+    #[derive(ApiResponse)]
+    #[allow(dead_code)]
+    enum UserResponse {
+        #[oai(status = 200)]
+        Ok(Json<User>, #[oai(header = "X-Request-Id")] String),
+        /// The user is not found
+        #[oai(status = 404)]
+        NotFound,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        /// Get the user
+        #[oai(path = "/users/:id", method = "get", operation_id = "getUser")]
+        async fn user(&self, _key: ApiKey, _id: Path<i64>) -> UserResponse {
+            todo!()
+        }
+
+        #[oai(path = "/users", method = "post", deprecated)]
+        async fn create(&self, _user: Json<User>) -> Json<Vec<User>> {
+            todo!()
+        }
+    }
+
+    let api_service = OpenApiService::new(Api, "test", "1.0")
+        .server("http://localhost:3000")
+        .extension("x-audience", "internal");
+    let spec = api_service.spec();
+    let doc: OpenApiDocument<String> = serde_json::from_str(&spec).unwrap();
+    assert_eq!(doc.info.title, "test");
+    assert_eq!(doc.apis[0].paths.len(), 2);
+    assert!(doc.registry.schemas["User"].deny_additional_properties);
+    assert_eq!(
+        serde_json::to_value(&doc).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&spec).unwrap()
+    );
+}
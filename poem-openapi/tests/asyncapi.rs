@@ -0,0 +1,152 @@
+#![cfg(feature = "sse")]
+
+use futures_util::stream::BoxStream;
+use poem_openapi::{
+    payload::{EventStream, Json, PlainText},
+    Object, OpenApi, OpenApiService, Webhook,
+};
+use serde_json::{json, Value};
+
+#[derive(Object)]
+struct OrderEvent {
+    id: i32,
+}
+
+#[test]
+fn event_endpoints() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        /// Order events
+        #[oai(path = "/orders/events", method = "get", operation_id = "orderEvents")]
+        async fn events(&self) -> EventStream<BoxStream<'static, OrderEvent>> {
+            todo!()
+        }
+
+        #[oai(path = "/hello", method = "get")]
+        async fn hello(&self) -> PlainText<String> {
+            todo!()
+        }
+    }
+
+    #[Webhook]
+    #[allow(dead_code)]
+    trait MyWebhooks: Sync {
+        /// An order is created
+        #[oai(name = "orderCreated", method = "post")]
+        async fn order_created(&self, order: Json<OrderEvent>);
+    }
+
+    let spec: Value = serde_json::from_str(
+        &OpenApiService::new(Api, "Orders", "1.0")
+            .description("The orders")
+            .webhooks::<&dyn MyWebhooks>()
+            .asyncapi_spec(),
+    )
+    .unwrap();
+
+    assert_eq!(spec["asyncapi"], json!("2.6.0"));
+    assert_eq!(
+        spec["info"],
+        json!({
+            "title": "Orders",
+            "version": "1.0",
+            "description": "The orders",
+        })
+    );
+    assert_eq!(
+        spec["channels"],
+        json!({
+            "/orders/events": {
+                "subscribe": {
+                    "operationId": "orderEvents",
+                    "summary": "Order events",
+                    "message": {
+                        "contentType": "text/event-stream",
+                        "payload": { "$ref": "#/components/schemas/OrderEvent" },
+                    },
+                },
+            },
+            "orderCreated": {
+                "subscribe": {
+                    "summary": "An order is created",
+                    "message": {
+                        "contentType": "application/json",
+                        "payload": { "$ref": "#/components/schemas/OrderEvent" },
+                    },
+                },
+            },
+        })
+    );
+    assert!(spec["components"]["schemas"]["OrderEvent"].is_object());
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn websocket() {
+    use poem::web::websocket::WebSocket;
+    use poem_openapi::payload::TypedWebSocket;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/ws", method = "get")]
+        async fn ws(&self, ws: WebSocket) -> TypedWebSocket<OrderEvent, String> {
+            TypedWebSocket::new(ws, |_| async move {})
+        }
+    }
+
+    let spec: Value =
+        serde_json::from_str(&OpenApiService::new(Api, "Orders", "1.0").asyncapi_spec()).unwrap();
+    assert_eq!(
+        spec["channels"]["/ws"],
+        json!({
+            "publish": {
+                "message": {
+                    "payload": { "$ref": "#/components/schemas/OrderEvent" },
+                },
+            },
+            "subscribe": {
+                "message": {
+                    "payload": { "type": "string" },
+                },
+            },
+        })
+    );
+}
+
+#[cfg(feature = "websocket")]
+#[test]
+fn merge_channels() {
+    use poem::web::websocket::WebSocket;
+    use poem_openapi::payload::TypedWebSocket;
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/orders", method = "get")]
+        async fn ws(&self, ws: WebSocket) -> TypedWebSocket<OrderEvent, String> {
+            TypedWebSocket::new(ws, |_| async move {})
+        }
+
+        #[oai(path = "/orders", method = "post")]
+        async fn events(&self) -> EventStream<BoxStream<'static, OrderEvent>> {
+            todo!()
+        }
+    }
+
+    let spec: Value =
+        serde_json::from_str(&OpenApiService::new(Api, "Orders", "1.0").asyncapi_spec()).unwrap();
+    let channel = &spec["channels"]["/orders"];
+    assert_eq!(
+        channel["publish"]["message"]["payload"],
+        json!({ "$ref": "#/components/schemas/OrderEvent" })
+    );
+    assert_eq!(
+        channel["subscribe"]["message"]["contentType"],
+        json!("text/event-stream")
+    );
+}
@@ -0,0 +1,222 @@
+#![cfg(feature = "base64")]
+
+use poem::{
+    http::{Method, StatusCode},
+    Endpoint, IntoEndpoint, Request, Response,
+};
+use poem_openapi::{
+    audit::{Audit, AuditEvent},
+    auth::Basic,
+    param::Path,
+    payload::{Json, PlainText},
+    Object, OpenApi, OpenApiService, SecurityScheme,
+};
+use serde_json::json;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+#[derive(SecurityScheme)]
+#[oai(type = "basic")]
+struct MyAuth(Basic);
+
+#[derive(Object)]
+struct Card {
+    number: String,
+    exp: String,
+}
+
+#[derive(Object)]
+struct CreateUser {
+    name: String,
+    password: String,
+    card: Card,
+}
+
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/users", method = "get")]
+    async fn users(&self, _auth: MyAuth) -> PlainText<&'static str> {
+        PlainText("users")
+    }
+
+    #[oai(path = "/users", method = "post", operation_id = "create_user")]
+    async fn create_user(&self, _auth: MyAuth, user: Json<CreateUser>) -> PlainText<String> {
+        PlainText(user.0.name)
+    }
+
+    #[oai(path = "/users/:id", method = "delete")]
+    async fn delete_user(&self, id: Path<i64>) -> PlainText<String> {
+        PlainText(id.0.to_string())
+    }
+}
+
+fn service() -> (
+    impl Endpoint<Output = Response>,
+    UnboundedReceiver<AuditEvent>,
+) {
+    let (tx, rx) = unbounded_channel();
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .audit(
+            Audit::new(tx)
+                .redact("password")
+                .redact("card.number")
+                .max_body_size(128),
+        )
+        .into_endpoint();
+    (ep, rx)
+}
+
+fn create_user(body: String, content_length: bool) -> Request {
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .uri("/users".parse().unwrap())
+        .header("Authorization", "Basic YWxpY2U6MTIz")
+        .content_type("application/json");
+    if content_length {
+        builder = builder.header("Content-Length", body.len());
+    }
+    builder.body(body)
+}
+
+#[tokio::test]
+async fn audit_mutating_operations() {
+    let (ep, mut rx) = service();
+
+    let resp = ep
+        .call(create_user(
+            json!({
+                "name": "bob",
+                "password": "secret",
+                "card": { "number": "4242", "exp": "12/30" },
+            })
+            .to_string(),
+            true,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "bob");
+
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.principal.as_deref(), Some("alice"));
+    assert_eq!(event.operation_id, Some("create_user"));
+    assert_eq!(event.method, "POST");
+    assert_eq!(event.path, "/users");
+    assert!(event.path_params.is_empty());
+    assert_eq!(event.status, 200);
+    assert_eq!(
+        event.body,
+        Some(json!({
+            "name": "bob",
+            "password": "[REDACTED]",
+            "card": { "number": "[REDACTED]", "exp": "12/30" },
+        }))
+    );
+
+    let err = ep
+        .call(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri("/users/abc".parse().unwrap())
+                .finish(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.principal, None);
+    assert_eq!(event.operation_id, None);
+    assert_eq!(event.path_params.get("id").map(String::as_str), Some("abc"));
+    assert_eq!(event.status, 400);
+    assert_eq!(event.body, None);
+}
+
+#[tokio::test]
+async fn audit_skips_safe_methods() {
+    let (ep, mut rx) = service();
+
+    let resp = ep
+        .call(
+            Request::builder()
+                .uri("/users".parse().unwrap())
+                .header("Authorization", "Basic YWxpY2U6MTIz")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn audit_skips_large_and_streaming_bodies() {
+    let (ep, mut rx) = service();
+
+    let name = "a".repeat(128);
+    let body = json!({
+        "name": name,
+        "password": "secret",
+        "card": { "number": "4242", "exp": "12/30" },
+    })
+    .to_string();
+
+    for content_length in [true, false] {
+        let resp = ep
+            .call(create_user(body.clone(), content_length))
+            .await
+            .unwrap();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), name);
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.operation_id, Some("create_user"));
+        assert_eq!(event.status, 200);
+        assert_eq!(event.body, None);
+    }
+}
+
+#[tokio::test]
+async fn audit_sensitive_fields() {
+    #[derive(Object)]
+    struct Login {
+        name: String,
+        #[oai(sensitive)]
+        password: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/login", method = "post")]
+        async fn login(&self, login: Json<Login>) -> PlainText<String> {
+            PlainText(login.0.name)
+        }
+    }
+
+    let (tx, mut rx) = unbounded_channel();
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .audit(Audit::new(tx))
+        .into_endpoint();
+
+    let body = json!({ "name": "bob", "password": "secret" }).to_string();
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/login".parse().unwrap())
+                .content_type("application/json")
+                .header("Content-Length", body.len())
+                .body(body),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "bob");
+
+    let event = rx.try_recv().unwrap();
+    assert_eq!(
+        event.body,
+        Some(json!({ "name": "bob", "password": "[REDACTED]" }))
+    );
+}
@@ -0,0 +1,169 @@
+use poem::{
+    http::{Method, StatusCode},
+    web::Data,
+    Endpoint, EndpointExt, IntoEndpoint, Request,
+};
+use poem_openapi::{
+    auth::ApiKey,
+    param::Path,
+    payload::{Json, PlainText},
+    OpenApi, OpenApiService, SecurityScheme,
+};
+use serde_json::{json, Value};
+
+#[derive(SecurityScheme)]
+#[oai(type = "api_key", key_name = "X-API-Key", in = "header")]
+struct MySecurityScheme(ApiKey);
+
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/hello/:name", method = "get")]
+    async fn hello(&self, name: Path<String>) -> PlainText<String> {
+        PlainText(format!("hello, {}", name.0))
+    }
+
+    #[oai(path = "/add", method = "post")]
+    async fn add(&self, auth: MySecurityScheme, values: Json<Vec<i32>>) -> Json<Value> {
+        Json(json!({ "key": auth.0.key, "sum": values.0.iter().sum::<i32>() }))
+    }
+}
+
+#[tokio::test]
+async fn batch() {
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .batch("/batch")
+        .into_endpoint();
+
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri_str("/batch")
+                .header("X-API-Key", "abc")
+                .content_type("application/json")
+                .body(
+                    json!([
+                        { "method": "GET", "path": "/hello/sunli" },
+                        { "method": "post", "path": "/add", "body": [1, 2, 3] },
+                        { "method": "POST", "path": "/add", "headers": { "X-API-Key": "def" }, "body": [1] },
+                        { "method": "GET", "path": "/not-found" },
+                        { "method": "POST", "path": "/batch", "body": [] },
+                    ])
+                    .to_string(),
+                ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let value: Value = resp.into_body().into_json().await.unwrap();
+    let responses = value.as_array().unwrap();
+    assert_eq!(responses.len(), 5);
+
+    assert_eq!(responses[0]["status"], json!(200));
+    assert_eq!(responses[0]["body"], json!("hello, sunli"));
+    assert_eq!(responses[0]["headers"]["content-type"], json!("text/plain"));
+    assert_eq!(responses[1]["status"], json!(200));
+    assert_eq!(responses[1]["body"], json!({ "key": "abc", "sum": 6 }));
+    assert_eq!(responses[2]["body"], json!({ "key": "def", "sum": 1 }));
+    assert_eq!(responses[3]["status"], json!(404));
+    assert_eq!(responses[4]["status"], json!(400));
+}
+
+#[tokio::test]
+async fn batch_unauthorized() {
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .batch("/batch")
+        .into_endpoint();
+
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri_str("/batch")
+                .content_type("application/json")
+                .body(json!([{ "method": "POST", "path": "/add", "body": [1] }]).to_string()),
+        )
+        .await
+        .unwrap();
+    let value: Value = resp.into_body().into_json().await.unwrap();
+    assert_eq!(value[0]["status"], json!(401));
+}
+
+#[test]
+fn spec() {
+    let spec: Value = serde_json::from_str(
+        &OpenApiService::new(Api, "test", "1.0")
+            .batch("/batch")
+            .spec(),
+    )
+    .unwrap();
+    assert!(spec["paths"]["/batch"]["post"].is_object());
+    assert!(spec["components"]["schemas"]["BatchRequest"].is_object());
+    assert!(spec["components"]["schemas"]["BatchResponse"].is_object());
+}
+
+#[tokio::test]
+async fn batch_context() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/context", method = "get")]
+        async fn context(&self, data: Data<&i32>, auth: MySecurityScheme) -> PlainText<String> {
+            PlainText(format!("{} {}", data.0, auth.0.key))
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .batch("/batch")
+        .into_endpoint()
+        .data(100i32);
+
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri_str("/batch")
+                .header("X-API-Key", "abc")
+                .content_type("application/json")
+                .body(json!([{ "method": "GET", "path": "/context" }]).to_string()),
+        )
+        .await
+        .unwrap();
+    let value: Value = resp.into_body().into_json().await.unwrap();
+    assert_eq!(value[0]["status"], json!(200));
+    assert_eq!(value[0]["body"], json!("100 abc"));
+}
+
+#[tokio::test]
+async fn batch_max_requests() {
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .batch("/batch")
+        .batch_max_requests(2)
+        .into_endpoint();
+
+    let call = |count: usize| {
+        ep.call(
+            Request::builder()
+                .method(Method::POST)
+                .uri_str("/batch")
+                .content_type("application/json")
+                .body(
+                    Value::Array(vec![
+                        json!({ "method": "GET", "path": "/hello/sunli" });
+                        count
+                    ])
+                    .to_string(),
+                ),
+        )
+    };
+
+    let resp = call(2).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let err = call(3).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use poem::{
+    http::{Method, StatusCode},
+    Endpoint, IntoEndpoint, Request, Result,
+};
+use poem_openapi::{crud::CrudStorage, crud_api, Object, OpenApi, OpenApiService, Tags};
+use serde_json::{json, Value};
+
+#[derive(Tags)]
+enum MyTags {
+    Users,
+}
+
+#[derive(Object, Debug, Clone, PartialEq)]
+struct User {
+    #[oai(read_only)]
+    id: i64,
+    #[oai(validator(max_length = 8))]
+    name: String,
+}
+
+#[derive(Default)]
+struct UserStorage(Mutex<BTreeMap<i64, User>>);
+
+#[poem::async_trait]
+impl CrudStorage for UserStorage {
+    type Object = User;
+    type Id = i64;
+
+    async fn get(&self, id: i64) -> Result<Option<User>> {
+        Ok(self.0.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn list(&self, offset: u64, limit: u64) -> Result<(Vec<User>, u64)> {
+        let users = self.0.lock().unwrap();
+        let items = users
+            .values()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        Ok((items, users.len() as u64))
+    }
+
+    async fn create(&self, mut user: User) -> Result<User> {
+        let mut users = self.0.lock().unwrap();
+        user.id = users.keys().last().copied().unwrap_or_default() + 1;
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn update(&self, id: i64, mut user: User) -> Result<Option<User>> {
+        let mut users = self.0.lock().unwrap();
+        Ok(users.get_mut(&id).map(|current| {
+            user.id = id;
+            *current = user.clone();
+            user
+        }))
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool> {
+        Ok(self.0.lock().unwrap().remove(&id).is_some())
+    }
+}
+
+crud_api! {
+    /// User management.
+    pub struct UserApi(UserStorage) at "/users", tag = "MyTags::Users";
+}
+
+#[test]
+fn meta() {
+    let meta = UserApi::meta();
+    let paths = &meta[0].paths;
+    assert_eq!(paths[0].path, "/users");
+    assert_eq!(paths[1].path, "/users/{id}");
+
+    let operations = paths
+        .iter()
+        .flat_map(|path| path.operations.iter())
+        .collect::<Vec<_>>();
+    assert_eq!(operations.len(), 5);
+    assert!(operations
+        .iter()
+        .all(|operation| operation.tags == vec!["Users"]));
+    assert_eq!(operations[0].params[1].name, "limit");
+    assert_eq!(
+        operations[2].responses.responses[1].status,
+        Some(404),
+        "get responds with 404"
+    );
+}
+
+#[tokio::test]
+async fn crud() {
+    let ep = OpenApiService::new(UserApi(UserStorage::default()), "test", "1.0").into_endpoint();
+
+    let call = |method: Method, uri: &str, body: Option<Value>| {
+        let req = Request::builder().method(method).uri_str(uri);
+        let req = match body {
+            Some(body) => req.content_type("application/json").body(body.to_string()),
+            None => req.finish(),
+        };
+        ep.get_response(req)
+    };
+
+    let resp = call(Method::POST, "/users", Some(json!({ "name": "sunli" }))).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(
+        resp.into_body().into_json::<Value>().await.unwrap(),
+        json!({ "id": 1, "name": "sunli" })
+    );
+
+    let resp = call(
+        Method::POST,
+        "/users",
+        Some(json!({ "name": "too long name" })),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    call(Method::POST, "/users", Some(json!({ "name": "alice" }))).await;
+
+    let resp = call(Method::GET, "/users?offset=1&limit=10", None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.header("x-total-count"), Some("2"));
+    assert_eq!(
+        resp.into_body().into_json::<Value>().await.unwrap(),
+        json!([{ "id": 2, "name": "alice" }])
+    );
+
+    let resp = call(Method::GET, "/users?limit=1000", None).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = call(Method::PUT, "/users/1", Some(json!({ "name": "bob" }))).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = call(Method::GET, "/users/1", None).await;
+    assert_eq!(
+        resp.into_body().into_json::<Value>().await.unwrap(),
+        json!({ "id": 1, "name": "bob" })
+    );
+
+    let resp = call(Method::DELETE, "/users/1", None).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    for method in [Method::GET, Method::DELETE] {
+        let resp = call(method, "/users/1", None).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+    let resp = call(Method::PUT, "/users/1", Some(json!({ "name": "bob" }))).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "encrypted")]
+
+use poem::{http::Method, Endpoint, IntoEndpoint, Request};
+use poem_openapi::{
+    payload::Json,
+    types::{Encrypted, LocalKeyProvider},
+    Object, OpenApi, OpenApiService,
+};
+
+#[derive(Debug, Object)]
+struct Customer {
+    tax_id: Encrypted<String>,
+}
+
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/customers", method = "post")]
+    async fn create(&self, customer: Json<Customer>) -> Json<Customer> {
+        assert_eq!(customer.tax_id.as_str(), "123-45-6789");
+        customer
+    }
+}
+
+#[tokio::test]
+async fn key_provider() {
+    let provider = LocalKeyProvider::new("k1", [1; 32]);
+    let tax_id = Encrypted("123-45-6789".to_string())
+        .encrypt_with(&provider)
+        .unwrap();
+    let ep = OpenApiService::new(Api, "test", "1.0")
+        .key_provider(provider)
+        .into_endpoint();
+
+    let resp = ep
+        .call(
+            Request::builder()
+                .method(Method::POST)
+                .uri("/customers".parse().unwrap())
+                .content_type("application/json")
+                .body(serde_json::json!({ "tax_id": tax_id }).to_string()),
+        )
+        .await
+        .unwrap();
+    let value: serde_json::Value = resp.into_body().into_json().await.unwrap();
+    let tax_id = value["tax_id"].as_str().unwrap();
+    assert!(tax_id.starts_with("k1:"));
+    assert_eq!(
+        Encrypted::<String>::decrypt_with(tax_id, &LocalKeyProvider::new("k1", [1; 32]))
+            .unwrap()
+            .as_str(),
+        "123-45-6789"
+    );
+}
+
+#[test]
+#[should_panic(expected = "no key provider")]
+fn missing_key_provider() {
+    let _ = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+}
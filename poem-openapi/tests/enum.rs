@@ -1,6 +1,6 @@
 use poem_openapi::{
     registry::{MetaExternalDocument, MetaSchemaRef, Registry},
-    types::{ParseFromJSON, ToJSON, Type},
+    types::{ParseFromJSON, ParseFromParameter, ToJSON, Type},
     Enum,
 };
 use serde_json::{json, Value};
@@ -219,3 +219,198 @@ async fn external_docs() {
         })
     );
 }
+
+#[test]
+fn fallback() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(rename_all = "lowercase")]
+    enum MyEnum {
+        A,
+        B,
+        #[oai(fallback)]
+        Unknown(String),
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert!(meta.enum_items.is_empty());
+    assert_eq!(meta.extensible_enum_items, vec![json!("a"), json!("b")]);
+
+    assert_eq!(MyEnum::parse_from_json(json!("a")).unwrap(), MyEnum::A);
+    assert_eq!(
+        MyEnum::parse_from_json(json!("c")).unwrap(),
+        MyEnum::Unknown("c".to_string())
+    );
+    assert_eq!(
+        MyEnum::parse_from_parameter("d").unwrap(),
+        MyEnum::Unknown("d".to_string())
+    );
+    assert!(MyEnum::parse_from_json(json!(1)).is_err());
+
+    assert_eq!(MyEnum::B.to_json(), json!("b"));
+    assert_eq!(MyEnum::Unknown("c".to_string()).to_json(), json!("c"));
+}
+
+#[test]
+fn varnames_and_descriptions() {
+    #[derive(Enum)]
+    #[oai(rename_all = "snake_case")]
+    enum MyEnum {
+        /// The user is created
+        CreateUser,
+        DeleteUser,
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(meta.enum_varnames, vec!["CreateUser", "DeleteUser"]);
+    assert_eq!(meta.enum_descriptions, vec!["The user is created", ""]);
+
+    #[derive(Enum)]
+    enum MyEnumB {
+        A,
+        B,
+    }
+
+    let mut registry = Registry::new();
+    MyEnumB::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnumB").unwrap();
+    assert_eq!(meta.enum_varnames, vec!["A", "B"]);
+    assert!(meta.enum_descriptions.is_empty());
+}
+
+#[test]
+fn repr() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(repr = "i32")]
+    enum MyEnum {
+        A = 0,
+        B = 1,
+        C = -2,
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(meta.ty, "integer");
+    assert_eq!(meta.format, Some("int32"));
+    assert_eq!(meta.enum_items, vec![json!(0), json!(1), json!(-2)]);
+    assert_eq!(meta.enum_varnames, vec!["A", "B", "C"]);
+
+    assert_eq!(MyEnum::parse_from_json(json!(1)).unwrap(), MyEnum::B);
+    assert_eq!(MyEnum::parse_from_json(json!(-2)).unwrap(), MyEnum::C);
+    assert!(MyEnum::parse_from_json(json!(3)).is_err());
+    assert!(MyEnum::parse_from_json(json!("A")).is_err());
+    assert_eq!(MyEnum::parse_from_parameter("0").unwrap(), MyEnum::A);
+    assert!(MyEnum::parse_from_parameter("A").is_err());
+
+    assert_eq!(MyEnum::C.to_json(), json!(-2));
+}
+
+#[test]
+fn one_of() {
+    #[derive(Enum)]
+    #[oai(rename_all = "lowercase", one_of)]
+    enum MyEnum {
+        /// The order is created
+        Created,
+        Paid,
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(meta.enum_items, vec![json!("created"), json!("paid")]);
+    assert_eq!(
+        serde_json::to_value(&meta.one_of).unwrap(),
+        json!([
+            {
+                "type": "string",
+                "title": "Created",
+                "description": "The order is created",
+                "enum": ["created"],
+            },
+            {
+                "type": "string",
+                "title": "Paid",
+                "enum": ["paid"],
+            },
+        ])
+    );
+}
+
+#[test]
+fn case_insensitive() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(rename_all = "lowercase", case_insensitive)]
+    enum MyEnum {
+        Active,
+        Inactive,
+        #[oai(fallback)]
+        Unknown(String),
+    }
+
+    for value in ["ACTIVE", "active", "Active"] {
+        assert_eq!(
+            MyEnum::parse_from_json(json!(value)).unwrap(),
+            MyEnum::Active
+        );
+        assert_eq!(MyEnum::parse_from_parameter(value).unwrap(), MyEnum::Active);
+    }
+    assert_eq!(
+        MyEnum::parse_from_parameter("Disabled").unwrap(),
+        MyEnum::Unknown("Disabled".to_string())
+    );
+    assert_eq!(MyEnum::Active.to_json(), json!("active"));
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert_eq!(
+        meta.extensible_enum_items,
+        vec![json!("active"), json!("inactive")]
+    );
+}
+
+#[test]
+fn other() {
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    #[oai(rename_all = "lowercase")]
+    enum MyEnum {
+        A,
+        B,
+        #[oai(other)]
+        Other,
+    }
+
+    let mut registry = Registry::new();
+    MyEnum::register(&mut registry);
+    let meta = registry.schemas.remove("MyEnum").unwrap();
+    assert!(meta.enum_items.is_empty());
+    assert_eq!(
+        meta.extensible_enum_items,
+        vec![json!("a"), json!("b"), json!("other")]
+    );
+    assert_eq!(meta.enum_varnames, vec!["A", "B", "Other"]);
+
+    assert_eq!(MyEnum::parse_from_json(json!("a")).unwrap(), MyEnum::A);
+    assert_eq!(MyEnum::parse_from_json(json!("c")).unwrap(), MyEnum::Other);
+    assert_eq!(MyEnum::parse_from_parameter("d").unwrap(), MyEnum::Other);
+    assert!(MyEnum::parse_from_json(json!(1)).is_err());
+    assert_eq!(MyEnum::Other.to_json(), json!("other"));
+
+    #[derive(Enum, Debug, Eq, PartialEq)]
+    enum MyEnumB {
+        A,
+        #[oai(other)]
+        Unknown(String),
+    }
+
+    assert_eq!(
+        MyEnumB::parse_from_json(json!("b")).unwrap(),
+        MyEnumB::Unknown("b".to_string())
+    );
+    assert_eq!(MyEnumB::Unknown("b".to_string()).to_json(), json!("b"));
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "fake")]
+
+use std::collections::HashMap;
+
+use poem_openapi::{fake::Faker, Enum, Object};
+
+#[derive(Debug, Enum, Copy, Clone, Eq, PartialEq)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Debug, Object)]
+struct Address {
+    #[oai(validator(pattern = r"^[0-9]{5}(-[0-9]{4})?$"))]
+    zip: String,
+    #[oai(validator(min_length = 2, max_length = 4))]
+    city: String,
+}
+
+#[derive(Debug, Object)]
+struct User {
+    #[oai(read_only)]
+    id: u64,
+    #[oai(validator(pattern = r"^[a-z][a-z0-9_]{2,15}$"))]
+    login: String,
+    #[oai(validator(minimum(value = "18"), maximum(value = "99")))]
+    age: u8,
+    #[oai(validator(minimum(value = "0"), maximum(value = "1", exclusive)))]
+    score: f64,
+    role: Role,
+    #[oai(validator(min_items = 1, max_items = 3, unique_items))]
+    tags: Vec<String>,
+    address: Option<Address>,
+    attributes: HashMap<String, i32>,
+}
+
+#[test]
+fn fake() {
+    let mut faker = Faker::new().seed(1);
+    for _ in 0..100 {
+        let user = faker.fake::<User>().unwrap();
+        assert_eq!(user.id, 0);
+        assert!(user.login.starts_with(|c: char| c.is_ascii_lowercase()));
+        assert!((3..=16).contains(&user.login.len()));
+        assert!((18..=99).contains(&user.age));
+        assert!((0.0..1.0).contains(&user.score));
+        assert!((1..=3).contains(&user.tags.len()));
+        if let Some(address) = user.address {
+            assert!(address.zip.len() == 5 || address.zip.len() == 10);
+            assert!((2..=4).contains(&address.city.len()));
+        }
+    }
+}
+
+#[test]
+fn fake_values() {
+    let mut faker = Faker::new().seed(1);
+    let roles = (0..20).map(|_| faker.value::<Role>()).collect::<Vec<_>>();
+    assert!(roles.contains(&serde_json::json!("Admin")));
+    assert!(roles.contains(&serde_json::json!("Member")));
+
+    let value = faker.value::<User>();
+    assert!(value.get("id").is_none());
+    assert_eq!(
+        Faker::new().seed(2).value::<User>(),
+        Faker::new().seed(2).value::<User>()
+    );
+
+    assert!(faker.value::<Vec<u8>>().is_array());
+    assert!(faker.fake::<u8>().is_ok());
+}
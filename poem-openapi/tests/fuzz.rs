@@ -0,0 +1,132 @@
+#![cfg(feature = "fuzz")]
+
+use poem::{http::StatusCode, Error};
+use poem_openapi::{
+    fuzz::Fuzzer,
+    param::{Header, Path, Query},
+    payload::{Json, PlainText},
+    ApiResponse, Enum, Object, OpenApi, OpenApiService,
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+enum Role {
+    Admin,
+    Member,
+}
+
+#[derive(Object)]
+struct Address {
+    #[oai(validator(min_length = 1, max_length = 32))]
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Object)]
+struct User {
+    #[oai(read_only)]
+    id: i64,
+    #[oai(validator(max_length = 16))]
+    name: String,
+    role: Role,
+    #[oai(validator(minimum(value = "0"), maximum(value = "150")))]
+    age: u8,
+    tags: Vec<String>,
+    address: Option<Address>,
+}
+
+#[derive(ApiResponse)]
+enum GetUserResponse {
+    #[oai(status = 200)]
+    Ok(Json<User>),
+    #[oai(status = 404)]
+    NotFound,
+}
+
+struct Api;
+
+#[OpenApi]
+impl Api {
+    #[oai(path = "/users", method = "post")]
+    async fn create_user(&self, mut user: Json<User>) -> Json<User> {
+        user.id = 1;
+        user
+    }
+
+    #[oai(path = "/users/:id", method = "get")]
+    async fn get_user(
+        &self,
+        id: Path<i64>,
+        #[oai(name = "X-Tenant")] _tenant: Header<Option<String>>,
+    ) -> GetUserResponse {
+        if id.0 != 1 {
+            return GetUserResponse::NotFound;
+        }
+        GetUserResponse::Ok(Json(User {
+            id: 1,
+            name: "alice".to_string(),
+            role: Role::Admin,
+            age: 30,
+            tags: vec![],
+            address: None,
+        }))
+    }
+
+    #[oai(path = "/search", method = "get")]
+    async fn search(
+        &self,
+        #[oai(validator(max_length = 8))] q: Query<String>,
+        #[oai(validator(minimum(value = "1"), maximum(value = "100")))] limit: Query<Option<u32>>,
+    ) -> PlainText<String> {
+        PlainText(format!("{} {:?}", q.0, limit.0))
+    }
+}
+
+#[tokio::test]
+async fn fuzz() {
+    let report = Fuzzer::new(OpenApiService::new(Api, "test", "1.0"))
+        .iterations(100)
+        .seed(1)
+        .run()
+        .await;
+    report.assert_ok();
+    assert_eq!(report.requests, 600);
+    assert!(report.skipped.is_empty());
+}
+
+#[tokio::test]
+async fn fuzz_failures() {
+    struct BuggyApi;
+
+    #[OpenApi]
+    impl BuggyApi {
+        #[oai(path = "/divide", method = "get")]
+        async fn divide(&self, a: Query<i32>, b: Query<i32>) -> poem::Result<PlainText<String>> {
+            if b.0 == 0 {
+                return Err(Error::from_status(StatusCode::INTERNAL_SERVER_ERROR));
+            }
+            Ok(PlainText((a.0 / b.0).to_string()))
+        }
+
+        #[oai(path = "/items/:id", method = "get")]
+        async fn item(&self, id: Path<String>) -> poem::Result<PlainText<String>> {
+            if id.0.len() > 4 {
+                return Err(Error::from_status(StatusCode::NOT_FOUND));
+            }
+            Ok(PlainText(id.0))
+        }
+    }
+
+    let report = Fuzzer::new(OpenApiService::new(BuggyApi, "test", "1.0"))
+        .iterations(1000)
+        .run()
+        .await;
+    assert!(!report.is_ok());
+    assert!(report.failures.iter().any(|failure| failure.status
+        == StatusCode::INTERNAL_SERVER_ERROR
+        && failure.reason == "server error"));
+    assert!(report
+        .failures
+        .iter()
+        .any(|failure| failure.status == StatusCode::NOT_FOUND
+            && failure.reason == "the status is not documented"));
+}
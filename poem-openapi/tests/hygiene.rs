@@ -103,6 +103,7 @@ impl Api {
     }
 }
 
+#[cfg(feature = "multipart")]
 #[derive(::poem_openapi::Multipart, Debug, Eq, PartialEq)]
 #[oai(rename_all = "UPPERCASE")]
 struct A {
@@ -135,6 +136,7 @@ enum MyTags {
     PetOperations,
 }
 
+#[cfg(feature = "base64")]
 #[derive(::poem_openapi::SecurityScheme)]
 #[oai(type = "basic")]
 struct BasicSecurityScheme(::poem_openapi::auth::Basic);
@@ -1,3 +1,5 @@
+#![cfg(feature = "multipart")]
+
 use std::io::Write;
 
 use poem::{Request, RequestBody};
@@ -518,4 +520,126 @@ async fn deny_unknown_fields() {
     .await
     .unwrap_err();
     assert_eq!(err.to_string(), "parse multipart error: unknown field `c`");
+
+    let schema_ref: MetaSchemaRef = A::schema_ref();
+    assert!(schema_ref.unwrap_inline().deny_additional_properties);
+}
+
+#[tokio::test]
+async fn content_type() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        #[oai(content_type = "image/png", content_type = "text/*")]
+        file: Upload,
+    }
+
+    let schema_ref: MetaSchemaRef = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(
+        schema.properties[0].1.unwrap_inline().one_of,
+        vec![
+            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                content_media_type: Some("image/png".to_string()),
+                ..MetaSchema::ANY
+            })),
+            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                content_media_type: Some("text/*".to_string()),
+                ..MetaSchema::ANY
+            })),
+        ]
+    );
+
+    let create_data = |content_type: &str| {
+        format!(
+            "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"1.png\"\r\nContent-Type: {}\r\n\r\nabc\r\n--X-BOUNDARY--\r\n",
+            content_type
+        )
+    };
+
+    for content_type in ["image/png", "text/plain; charset=utf-8"] {
+        let a = A::from_request(
+            &Request::builder()
+                .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+                .finish(),
+            &mut RequestBody::new(create_data(content_type).into()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(a.file.into_vec().await.unwrap(), b"abc");
+    }
+
+    let err = A::from_request(
+        &Request::builder()
+            .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+            .finish(),
+        &mut RequestBody::new(create_data("image/jpeg").into()),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the content type of field `file` is not supported: image/jpeg"
+    );
+    assert_eq!(
+        err.as_response().status(),
+        poem::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+}
+
+#[tokio::test]
+async fn content_type_essence() {
+    #[derive(Multipart, Debug)]
+    struct A {
+        #[oai(content_type = "image/png")]
+        image: Option<Upload>,
+        #[oai(content_type = "text/*")]
+        text: Option<Upload>,
+    }
+
+    let schema_ref: MetaSchemaRef = A::schema_ref();
+    let schema: &MetaSchema = schema_ref.unwrap_inline();
+    let image_schema = schema.properties[0].1.unwrap_inline();
+    assert_eq!(
+        image_schema.content_media_type.as_deref(),
+        Some("image/png")
+    );
+    assert!(image_schema.one_of.is_empty());
+
+    let parse = |name: &str, content_type: Option<&str>| {
+        let content_type = content_type
+            .map(|content_type| format!("Content-Type: {}\r\n", content_type))
+            .unwrap_or_default();
+        let data = format!(
+            "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"1\"\r\n{}\r\nabc\r\n--X-BOUNDARY--\r\n",
+            name, content_type
+        );
+        async move {
+            A::from_request(
+                &Request::builder()
+                    .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+                    .finish(),
+                &mut RequestBody::new(data.into()),
+            )
+            .await
+        }
+    };
+
+    let a = parse("image", Some("IMAGE/PNG; name=1.png")).await.unwrap();
+    assert!(a.image.is_some());
+    assert!(a.text.is_none());
+    assert!(parse("image", Some("image/jpeg")).await.is_err());
+    let a = parse("text", Some("text/csv; charset=utf-8"))
+        .await
+        .unwrap();
+    assert_eq!(a.text.unwrap().into_vec().await.unwrap(), b"abc");
+
+    // The parts without the `Content-Type` header are `text/plain`.
+    let a = parse("text", None).await.unwrap();
+    assert!(a.image.is_none());
+    assert!(a.text.is_some());
+    let err = parse("image", None).await.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the content type of field `image` is not supported: text/plain"
+    );
 }
@@ -1,4 +1,5 @@
 use poem_openapi::{
+    audit::redact_sensitive,
     registry::{MetaExternalDocument, MetaSchema, MetaSchemaRef, Registry},
     types::{ParseFromJSON, ToJSON, Type},
     Enum, Object,
@@ -177,6 +178,49 @@ fn field_skip() {
     );
 }
 
+#[test]
+fn field_skip_with_value() {
+    fn default_b() -> i32 {
+        100
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        a: i32,
+        #[oai(skip = "default_b")]
+        b: i32,
+        #[oai(skip = "|| vec![1, 2]")]
+        c: Vec<i32>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties.len(), 1);
+
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "a": 10,
+            "b": 20,
+        }))
+        .unwrap(),
+        Obj {
+            a: 10,
+            b: 100,
+            c: vec![1, 2]
+        }
+    );
+    assert_eq!(
+        Obj {
+            a: 10,
+            b: 0,
+            c: vec![]
+        }
+        .to_json(),
+        json!({
+            "a": 10,
+        })
+    );
+}
+
 #[test]
 fn field_rename() {
     #[derive(Object)]
@@ -189,6 +233,140 @@ fn field_rename() {
     assert_eq!(meta.properties[0].0, "b");
 }
 
+#[test]
+fn field_alias() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    #[oai(deny_unknown_fields)]
+    struct Obj {
+        #[oai(alias = "old_name", alias = "legacy_name")]
+        name: String,
+        #[oai(alias = "old_count", default)]
+        count: i32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["name", "count"]
+    );
+
+    let obj = Obj {
+        name: "abc".to_string(),
+        count: 3,
+    };
+    for value in [
+        json!({ "name": "abc", "count": 3 }),
+        json!({ "old_name": "abc", "old_count": 3 }),
+        json!({ "legacy_name": "abc", "count": 3 }),
+    ] {
+        assert_eq!(Obj::parse_from_json(value).unwrap(), obj);
+    }
+    assert_eq!(obj.to_json(), json!({ "name": "abc", "count": 3 }));
+
+    assert_eq!(
+        Obj::parse_from_json(json!({ "old_name": "abc" })).unwrap(),
+        Obj {
+            name: "abc".to_string(),
+            count: 0,
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "name": "abc", "old_name": "def" }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": properties `name` and `old_name` are both present."
+    );
+}
+
+#[test]
+fn field_deprecated() {
+    #[derive(Object)]
+    #[allow(dead_code)]
+    struct Obj {
+        #[oai(deprecated)]
+        a: i32,
+        #[oai(deprecation_note = "Use `d` instead.", removed_in = "v3")]
+        b: i32,
+        #[oai(removed_in = "v3")]
+        c: Pagination,
+        d: i32,
+    }
+
+    #[derive(Object)]
+    #[allow(dead_code)]
+    struct Pagination {
+        page: u32,
+    }
+
+    let meta = get_meta::<Obj>();
+
+    let field_meta = meta.properties[0].1.unwrap_inline();
+    assert!(field_meta.deprecated);
+    assert_eq!(field_meta.deprecation_note, None);
+
+    let field_meta = meta.properties[1].1.unwrap_inline();
+    assert!(field_meta.deprecated);
+    assert_eq!(field_meta.deprecation_note, Some("Use `d` instead."));
+    assert_eq!(field_meta.removed_in, Some("v3"));
+    assert_eq!(
+        serde_json::to_value(field_meta).unwrap(),
+        json!({
+            "type": "integer",
+            "format": "int32",
+            "deprecated": true,
+            "x-deprecated-note": "Use `d` instead.",
+            "x-removed-in": "v3",
+        })
+    );
+
+    let field_meta = meta.properties[2].1.unwrap_inline();
+    assert_eq!(field_meta.all_of[0], MetaSchemaRef::Reference("Pagination"));
+    let patch = field_meta.all_of[1].unwrap_inline();
+    assert!(patch.deprecated);
+    assert_eq!(patch.removed_in, Some("v3"));
+
+    assert!(!meta.properties[3].1.unwrap_inline().deprecated);
+}
+
+#[test]
+fn xml() {
+    #[derive(Object)]
+    #[oai(xml(name = "user", namespace = "https://example.com/schema/user"))]
+    #[allow(dead_code)]
+    struct Obj {
+        #[oai(xml(attribute))]
+        id: i64,
+        #[oai(xml(name = "full-name"))]
+        name: String,
+        #[oai(xml(name = "tags", wrapped))]
+        tags: Vec<String>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        serde_json::to_value(&meta.xml).unwrap(),
+        json!({
+            "name": "user",
+            "namespace": "https://example.com/schema/user",
+        })
+    );
+    assert_eq!(
+        serde_json::to_value(&meta.properties[0].1.unwrap_inline().xml).unwrap(),
+        json!({ "attribute": true })
+    );
+    assert_eq!(
+        serde_json::to_value(&meta.properties[1].1.unwrap_inline().xml).unwrap(),
+        json!({ "name": "full-name" })
+    );
+    assert_eq!(
+        serde_json::to_value(&meta.properties[2].1.unwrap_inline().xml).unwrap(),
+        json!({ "name": "tags", "wrapped": true })
+    );
+}
+
 #[test]
 fn recursive_register() {
     #[derive(Object)]
@@ -218,6 +396,42 @@ fn recursive_register() {
     assert_eq!(meta_b.properties[0].1.unwrap_inline().format, Some("int64"));
 }
 
+#[test]
+fn self_referential_register() {
+    #[derive(Object)]
+    struct Node {
+        children: Vec<Node>,
+        parent: Option<Box<Node>>,
+        tree: Option<Box<Tree<i32>>>,
+    }
+
+    #[derive(Object)]
+    #[oai(concrete(name = "IntTree", params(i32)))]
+    struct Tree<T: ParseFromJSON + ToJSON> {
+        value: T,
+        nodes: Vec<Node>,
+        left: Option<Box<Tree<i32>>>,
+    }
+
+    let mut registry = Registry::default();
+    Node::register(&mut registry);
+
+    let meta_node = registry.schemas.remove("Node").unwrap();
+    assert_eq!(
+        meta_node.properties[0].1.unwrap_inline().items.as_deref(),
+        Some(&MetaSchemaRef::Reference("Node"))
+    );
+    assert_eq!(meta_node.properties[1].1.unwrap_reference(), "Node");
+    assert_eq!(meta_node.properties[2].1.unwrap_reference(), "IntTree");
+
+    let meta_tree = registry.schemas.remove("IntTree").unwrap();
+    assert_eq!(
+        meta_tree.properties[1].1.unwrap_inline().items.as_deref(),
+        Some(&MetaSchemaRef::Reference("Node"))
+    );
+    assert_eq!(meta_tree.properties[2].1.unwrap_reference(), "IntTree");
+}
+
 #[test]
 fn description() {
     /// A
@@ -318,6 +532,82 @@ fn field_default() {
     );
 }
 
+#[test]
+fn field_default_closure() {
+    mod defaults {
+        pub fn page_size() -> u32 {
+            20
+        }
+    }
+
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(default = "|| 1")]
+        page: u32,
+        #[oai(default = "defaults::page_size")]
+        page_size: u32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties[0].1.unwrap_inline().default, Some(json!(1)));
+    assert_eq!(
+        meta.properties[1].1.unwrap_inline().default,
+        Some(json!(20))
+    );
+
+    assert_eq!(
+        Obj::parse_from_json(json!({})).unwrap(),
+        Obj {
+            page: 1,
+            page_size: 20
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "page": 3 })).unwrap(),
+        Obj {
+            page: 3,
+            page_size: 20
+        }
+    );
+}
+
+#[test]
+fn field_transform() {
+    #[derive(Object, Debug, Eq, PartialEq)]
+    struct Obj {
+        #[oai(transform(trim, lowercase), validator(max_length = 5))]
+        a: String,
+        #[oai(transform(custom = "remove_dashes", uppercase))]
+        b: Option<String>,
+        #[oai(transform(trim))]
+        c: Vec<String>,
+    }
+
+    fn remove_dashes(s: &mut String) {
+        s.retain(|c| c != '-');
+    }
+
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "a": "  HeLLo ",
+            "b": "a-b-c",
+            "c": [" x", "y "],
+        }))
+        .unwrap(),
+        Obj {
+            a: "hello".to_string(),
+            b: Some("ABC".to_string()),
+            c: vec!["x".to_string(), "y".to_string()],
+        }
+    );
+
+    assert!(Obj::parse_from_json(json!({
+        "a": " abcdef ",
+        "c": [],
+    }))
+    .is_err());
+}
+
 #[test]
 fn serde() {
     #[derive(Object, Debug, Eq, PartialEq)]
@@ -418,112 +708,492 @@ fn write_only() {
 }
 
 #[test]
-fn inline_fields() {
-    #[derive(Object)]
+fn duration() {
+    use std::time::Duration;
+
+    #[derive(Debug, Object, PartialEq)]
     struct Obj {
-        /// Inner Obj
-        #[oai(default)]
-        inner_obj: InlineObj,
-        /// Inner Enum
-        #[oai(default)]
-        inner_enum: InlineEnum,
+        latency: Duration,
+        #[oai(duration = "seconds")]
+        retention: Duration,
+        #[oai(duration = "seconds")]
+        timeout: Option<Duration>,
     }
 
-    #[derive(Object)]
-    struct InlineObj {
-        v: i32,
-    }
+    let meta = get_meta::<Obj>();
+    let schema = meta.properties[0].1.unwrap_inline();
+    assert_eq!(schema.ty, "string");
+    assert_eq!(schema.format, Some("duration"));
+    let schema = meta.properties[1].1.unwrap_inline();
+    assert_eq!(schema.ty, "number");
+    assert_eq!(meta.required, vec!["latency", "retention"]);
+
+    let obj = Obj {
+        latency: Duration::from_millis(1500),
+        retention: Duration::from_secs(86400),
+        timeout: Some(Duration::from_millis(250)),
+    };
+    let value = serde_json::json!({
+        "latency": "PT1.5S",
+        "retention": 86400,
+        "timeout": 0.25,
+    });
+    assert_eq!(obj.to_json(), value);
+    assert_eq!(Obj::parse_from_json(value).unwrap(), obj);
 
-    impl Default for InlineObj {
-        fn default() -> Self {
-            Self { v: 100 }
+    assert_eq!(
+        Obj::parse_from_json(serde_json::json!({
+            "latency": "P1D",
+            "retention": 1.5,
+        }))
+        .unwrap(),
+        Obj {
+            latency: Duration::from_secs(86400),
+            retention: Duration::from_millis(1500),
+            timeout: None,
         }
+    );
+    assert!(Obj::parse_from_json(serde_json::json!({
+        "latency": 1,
+        "retention": 1,
+    }))
+    .is_err());
+}
+
+#[test]
+fn flatten() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Pagination {
+        page: u32,
+        per_page: Option<u32>,
     }
 
-    #[derive(Enum)]
-    enum InlineEnum {
-        A,
-        B,
-        C,
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(deny_unknown_fields)]
+    struct Users {
+        users: Vec<String>,
+        #[oai(flatten)]
+        pagination: Pagination,
     }
 
-    impl Default for InlineEnum {
-        fn default() -> Self {
-            Self::B
-        }
+    let meta = get_meta::<Users>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["users", "page", "per_page"]
+    );
+    assert_eq!(meta.required, vec!["users", "page"]);
+
+    let obj = Users {
+        users: vec!["alice".to_string()],
+        pagination: Pagination {
+            page: 2,
+            per_page: Some(10),
+        },
+    };
+    let value = serde_json::json!({
+        "users": ["alice"],
+        "page": 2,
+        "per_page": 10,
+    });
+    assert_eq!(obj.to_json(), value);
+    assert_eq!(Users::parse_from_json(value).unwrap(), obj);
+
+    assert!(Users::parse_from_json(serde_json::json!({
+        "users": [],
+    }))
+    .is_err());
+    assert!(Users::parse_from_json(serde_json::json!({
+        "users": [],
+        "page": 1,
+        "extra": 1,
+    }))
+    .is_err());
+}
+
+#[test]
+fn additional_properties() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Pagination {
+        page: u32,
     }
 
-    let meta = get_meta::<Obj>();
-    assert_eq!(meta.properties[0].0, "inner_obj");
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        name: String,
+        #[oai(flatten)]
+        pagination: Pagination,
+        #[oai(additional_properties)]
+        extra: HashMap<String, i32>,
+    }
 
-    let meta_inner_obj = meta.properties[0].1.unwrap_inline();
+    let meta = get_meta::<Obj>();
     assert_eq!(
-        meta_inner_obj.all_of[0],
-        MetaSchemaRef::Reference("InlineObj")
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["name", "page"]
     );
+    assert_eq!(meta.required, vec!["name", "page"]);
     assert_eq!(
-        meta_inner_obj.all_of[1],
-        MetaSchemaRef::Inline(Box::new(MetaSchema {
-            title: Some("Inner Obj"),
-            default: Some(serde_json::json!({
-                "v": 100,
-            })),
-            ..MetaSchema::ANY
-        }))
+        meta.additional_properties.as_deref(),
+        Some(&i32::schema_ref())
     );
 
-    let meta_inner_enum = meta.properties[1].1.unwrap_inline();
-    assert_eq!(
-        meta_inner_enum.all_of[0],
-        MetaSchemaRef::Reference("InlineEnum")
-    );
+    let obj = Obj {
+        name: "abc".to_string(),
+        pagination: Pagination { page: 1 },
+        extra: [("a".to_string(), 1), ("b".to_string(), 2)]
+            .into_iter()
+            .collect(),
+    };
+    let value = json!({ "name": "abc", "page": 1, "a": 1, "b": 2 });
+    assert_eq!(Obj::parse_from_json(value.clone()).unwrap(), obj);
+    assert_eq!(obj.to_json(), value);
+
     assert_eq!(
-        meta_inner_enum.all_of[1],
-        MetaSchemaRef::Inline(Box::new(MetaSchema {
-            title: Some("Inner Enum"),
-            default: Some(serde_json::json!("B")),
-            ..MetaSchema::ANY
-        }))
+        Obj::parse_from_json(json!({ "name": "abc", "page": 1 }))
+            .unwrap()
+            .extra,
+        HashMap::new()
     );
+    assert!(Obj::parse_from_json(json!({ "name": "abc", "page": 1, "a": "x" })).is_err());
+
+    let obj = Obj {
+        name: "abc".to_string(),
+        pagination: Pagination { page: 1 },
+        extra: [("name".to_string(), 1)].into_iter().collect(),
+    };
+    assert_eq!(obj.to_json(), json!({ "name": "abc", "page": 1 }));
 }
 
 #[test]
-fn inline() {
-    #[derive(Object)]
-    #[oai(inline)]
-    struct Obj {
-        a: i32,
+fn skip_serializing_if() {
+    fn is_zero(value: &i32) -> bool {
+        *value == 0
     }
 
-    let schema_ref = Obj::schema_ref();
-    let meta: &MetaSchema = schema_ref.unwrap_inline();
-    assert_eq!(meta.properties[0].0, "a");
-
-    #[derive(Object)]
-    #[oai(inline)]
-    struct ObjGeneric<T: ParseFromJSON + ToJSON> {
-        a: T,
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        #[oai(skip_serializing_if = "Option::is_none")]
+        a: Option<i32>,
+        #[oai(skip_serializing_if = "is_zero")]
+        b: i32,
+        c: i32,
     }
 
-    let schema_ref = ObjGeneric::<String>::schema_ref();
-    let meta: &MetaSchema = schema_ref.unwrap_inline();
-    assert_eq!(meta.properties[0].0, "a");
-    assert_eq!(meta.properties[0].1.unwrap_inline().ty, "string");
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties.len(), 3);
+    assert_eq!(meta.required, vec!["c"]);
+
+    let obj = Obj {
+        a: None,
+        b: 0,
+        c: 0,
+    };
+    assert_eq!(obj.to_json(), serde_json::json!({ "c": 0 }));
+
+    let obj = Obj {
+        a: Some(1),
+        b: 2,
+        c: 3,
+    };
+    let value = serde_json::json!({ "a": 1, "b": 2, "c": 3 });
+    assert_eq!(obj.to_json(), value);
+    assert_eq!(Obj::parse_from_json(value).unwrap(), obj);
 }
 
 #[test]
-#[should_panic]
-fn duplicate_name() {
+fn field_example() {
     #[derive(Object)]
-    struct ObjA {
-        value1: i32,
+    #[allow(dead_code)]
+    struct Obj {
+        #[oai(example = "String::from(\"alice\")")]
+        name: String,
+        #[oai(example = "vec![1, 2]")]
+        values: Vec<i32>,
+        #[oai(example = "Some(30)", validator(maximum(value = "150")))]
+        age: Option<u8>,
+        other: i32,
     }
 
-    mod t {
-        use super::*;
-
-        #[derive(Object)]
-        pub struct ObjA {
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().example,
+        Some(serde_json::json!("alice"))
+    );
+    assert_eq!(
+        meta.properties[1].1.unwrap_inline().example,
+        Some(serde_json::json!([1, 2]))
+    );
+    let age = meta.properties[2].1.unwrap_inline();
+    assert_eq!(age.example, Some(serde_json::json!(30)));
+    assert_eq!(age.maximum, Some(150.0));
+    assert_eq!(meta.properties[3].1.unwrap_inline().example, None);
+}
+
+#[test]
+fn serialize_with_and_deserialize_with() {
+    #[derive(Debug, PartialEq)]
+    struct Timestamp(u64);
+
+    fn timestamp_to_json(value: &Timestamp) -> serde_json::Value {
+        json!(value.0 * 1000)
+    }
+
+    fn timestamp_from_json(value: serde_json::Value) -> Result<Timestamp, String> {
+        value
+            .as_u64()
+            .map(|millis| Timestamp(millis / 1000))
+            .ok_or_else(|| format!("invalid timestamp {}", value))
+    }
+
+    fn uppercase(value: &str) -> serde_json::Value {
+        json!(value.to_uppercase())
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        #[oai(
+            serialize_with = "timestamp_to_json",
+            deserialize_with = "timestamp_from_json",
+            schema_type = "u64"
+        )]
+        created_at: Timestamp,
+        #[oai(serialize_with = "uppercase")]
+        name: String,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.required, vec!["created_at", "name"]);
+    let created_at = meta.properties[0].1.unwrap_inline();
+    assert_eq!(created_at.ty, "integer");
+    assert_eq!(created_at.format, Some("uint64"));
+
+    let obj = Obj {
+        created_at: Timestamp(1),
+        name: "abc".to_string(),
+    };
+    assert_eq!(obj.to_json(), json!({ "created_at": 1000, "name": "ABC" }));
+    assert_eq!(
+        Obj::parse_from_json(json!({ "created_at": 2000, "name": "abc" })).unwrap(),
+        Obj {
+            created_at: Timestamp(2),
+            name: "abc".to_string(),
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "created_at": "x", "name": "abc" }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `created_at`: invalid timestamp \"x\""
+    );
+}
+
+#[test]
+fn generics() {
+    #[derive(Debug, Object, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Page<T: Type> {
+        items: Vec<T>,
+        total: u64,
+    }
+
+    assert_eq!(Page::<User>::name(), "Page_User");
+    assert_eq!(Page::<i32>::name(), "Page_integer_int32");
+    assert_eq!(Page::<Vec<User>>::name(), "Page_Array_User");
+    assert_eq!(
+        Page::<User>::schema_ref(),
+        MetaSchemaRef::Reference("Page_User")
+    );
+
+    let mut registry = Registry::new();
+    Page::<User>::register(&mut registry);
+    Page::<i32>::register(&mut registry);
+    assert!(registry.schemas.contains_key("User"));
+    assert_eq!(
+        registry.schemas["Page_User"].properties[0].1,
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(MetaSchemaRef::Reference("User"))),
+            ..MetaSchema::new("array")
+        }))
+    );
+    assert_eq!(
+        registry.schemas["Page_integer_int32"].properties[0].1,
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            items: Some(Box::new(i32::schema_ref())),
+            ..MetaSchema::new("array")
+        }))
+    );
+
+    let page = Page {
+        items: vec![User {
+            name: "alice".to_string(),
+        }],
+        total: 1,
+    };
+    let value = json!({ "items": [{ "name": "alice" }], "total": 1 });
+    assert_eq!(page.to_json(), value);
+    assert_eq!(Page::<User>::parse_from_json(value).unwrap(), page);
+}
+
+#[test]
+fn sensitive() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Inner {
+        #[oai(sensitive)]
+        token: String,
+        name: String,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        id: i32,
+        #[oai(sensitive)]
+        value: i32,
+        inner: Vec<Inner>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert!(!meta.properties[0].1.unwrap_inline().sensitive);
+    assert!(meta.properties[1].1.unwrap_inline().sensitive);
+
+    let mut value = Obj {
+        id: 99,
+        value: 100,
+        inner: vec![Inner {
+            token: "abc".to_string(),
+            name: "a".to_string(),
+        }],
+    }
+    .to_json();
+    redact_sensitive::<Obj>(&mut value);
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "id": 99,
+            "value": "[REDACTED]",
+            "inner": [{ "token": "[REDACTED]", "name": "a" }],
+        })
+    );
+}
+
+#[test]
+fn inline_fields() {
+    #[derive(Object)]
+    struct Obj {
+        /// Inner Obj
+        #[oai(default)]
+        inner_obj: InlineObj,
+        /// Inner Enum
+        #[oai(default)]
+        inner_enum: InlineEnum,
+    }
+
+    #[derive(Object)]
+    struct InlineObj {
+        v: i32,
+    }
+
+    impl Default for InlineObj {
+        fn default() -> Self {
+            Self { v: 100 }
+        }
+    }
+
+    #[derive(Enum)]
+    enum InlineEnum {
+        A,
+        B,
+        C,
+    }
+
+    impl Default for InlineEnum {
+        fn default() -> Self {
+            Self::B
+        }
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.properties[0].0, "inner_obj");
+
+    let meta_inner_obj = meta.properties[0].1.unwrap_inline();
+    assert_eq!(
+        meta_inner_obj.all_of[0],
+        MetaSchemaRef::Reference("InlineObj")
+    );
+    assert_eq!(
+        meta_inner_obj.all_of[1],
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            title: Some("Inner Obj"),
+            default: Some(serde_json::json!({
+                "v": 100,
+            })),
+            ..MetaSchema::ANY
+        }))
+    );
+
+    let meta_inner_enum = meta.properties[1].1.unwrap_inline();
+    assert_eq!(
+        meta_inner_enum.all_of[0],
+        MetaSchemaRef::Reference("InlineEnum")
+    );
+    assert_eq!(
+        meta_inner_enum.all_of[1],
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            title: Some("Inner Enum"),
+            default: Some(serde_json::json!("B")),
+            ..MetaSchema::ANY
+        }))
+    );
+}
+
+#[test]
+fn inline() {
+    #[derive(Object)]
+    #[oai(inline)]
+    struct Obj {
+        a: i32,
+    }
+
+    let schema_ref = Obj::schema_ref();
+    let meta: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(meta.properties[0].0, "a");
+
+    #[derive(Object)]
+    #[oai(inline)]
+    struct ObjGeneric<T: ParseFromJSON + ToJSON> {
+        a: T,
+    }
+
+    let schema_ref = ObjGeneric::<String>::schema_ref();
+    let meta: &MetaSchema = schema_ref.unwrap_inline();
+    assert_eq!(meta.properties[0].0, "a");
+    assert_eq!(meta.properties[0].1.unwrap_inline().ty, "string");
+}
+
+#[test]
+#[should_panic]
+fn duplicate_name() {
+    #[derive(Object)]
+    struct ObjA {
+        value1: i32,
+    }
+
+    mod t {
+        use super::*;
+
+        #[derive(Object)]
+        pub struct ObjA {
             value2: i32,
         }
     }
@@ -634,6 +1304,23 @@ fn deny_unknown_fields() {
         .into_message(),
         "failed to parse \"Obj\": unknown field `c`."
     );
+
+    let meta = get_meta::<Obj>();
+    assert!(meta.deny_additional_properties);
+    assert_eq!(
+        serde_json::to_value(meta).unwrap()["additionalProperties"],
+        json!(false)
+    );
+
+    #[derive(Object)]
+    #[allow(dead_code)]
+    struct Other {
+        a: i32,
+    }
+    assert!(serde_json::to_value(get_meta::<Other>())
+        .unwrap()
+        .get("additionalProperties")
+        .is_none());
 }
 
 #[test]
@@ -670,3 +1357,556 @@ async fn external_docs() {
         })
     );
 }
+
+#[test]
+fn field_nullable() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Obj {
+        #[oai(nullable)]
+        a: Option<i32>,
+        b: Option<i32>,
+        #[oai(nullable)]
+        c: Option<User>,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(meta.required, vec!["a", "c"]);
+    let schema = meta.properties[0].1.unwrap_inline();
+    assert_eq!(schema.ty, "integer");
+    assert!(schema.nullable);
+    assert!(!meta.properties[1].1.unwrap_inline().nullable);
+    assert_eq!(
+        meta.properties[2].1,
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            one_of: vec![
+                MetaSchemaRef::Reference("User"),
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    nullable: true,
+                    enum_items: vec![serde_json::Value::Null],
+                    ..MetaSchema::ANY
+                })),
+            ],
+            ..MetaSchema::ANY
+        }))
+    );
+
+    assert_eq!(
+        Obj::parse_from_json(json!({ "a": null, "c": null })).unwrap(),
+        Obj {
+            a: None,
+            b: None,
+            c: None
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "a": 1, "c": { "name": "sunli" } })).unwrap(),
+        Obj {
+            a: Some(1),
+            b: None,
+            c: Some(User {
+                name: "sunli".to_string()
+            })
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({ "c": null }))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `a` is required."
+    );
+    assert_eq!(
+        Obj {
+            a: None,
+            b: None,
+            c: None
+        }
+        .to_json(),
+        json!({ "a": null, "b": null, "c": null })
+    );
+}
+
+#[test]
+fn properties_order() {
+    #[derive(Object)]
+    struct Timestamps {
+        updated_at: i64,
+        created_at: i64,
+    }
+
+    #[derive(Object)]
+    struct Pet {
+        name: String,
+        #[oai(flatten)]
+        timestamps: Timestamps,
+        age: Option<u8>,
+        id: i64,
+    }
+
+    let meta = get_meta::<Pet>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["name", "updated_at", "created_at", "age", "id"]
+    );
+    assert_eq!(
+        meta.required,
+        vec!["name", "updated_at", "created_at", "id"]
+    );
+
+    #[derive(Object)]
+    #[oai(sort_properties)]
+    struct SortedPet {
+        name: String,
+        #[oai(flatten)]
+        timestamps: Timestamps,
+        age: Option<u8>,
+        id: i64,
+    }
+
+    let meta = get_meta::<SortedPet>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["age", "created_at", "id", "name", "updated_at"]
+    );
+    assert_eq!(
+        meta.required,
+        vec!["created_at", "id", "name", "updated_at"]
+    );
+}
+
+#[test]
+fn generic_names() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, Object, PartialEq)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(rename = "{T}Page")]
+    struct Page<T: Type> {
+        items: Vec<T>,
+    }
+
+    assert_eq!(Page::<User>::name(), "UserPage");
+    assert_eq!(Page::<Vec<i32>>::name(), "Array_integer_int32Page");
+
+    fn envelope_name(params: &[Cow<'static, str>]) -> String {
+        format!("{}Envelope", params[0])
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(rename_via = "envelope_name")]
+    struct Envelope<T: Type> {
+        data: T,
+    }
+
+    assert_eq!(Envelope::<User>::name(), "UserEnvelope");
+    assert_eq!(
+        Envelope::<User>::schema_ref(),
+        MetaSchemaRef::Reference("UserEnvelope")
+    );
+    let mut registry = Registry::new();
+    Envelope::<User>::register(&mut registry);
+    assert!(registry.schemas.contains_key("UserEnvelope"));
+}
+
+#[test]
+fn tuple_struct() {
+    /// The id
+    #[derive(Debug, Object, Eq, PartialEq)]
+    struct Id(i64);
+
+    assert_eq!(Id::name(), "integer(int64)");
+    let schema_ref = Id::schema_ref();
+    let meta = schema_ref.unwrap_inline();
+    assert_eq!(meta.ty, "integer");
+    assert_eq!(meta.title, Some("The id"));
+    assert_eq!(Id::parse_from_json(json!(1)).unwrap(), Id(1));
+    assert_eq!(Id(1).to_json(), json!(1));
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Point(f64, f64, Option<String>);
+
+    let meta = get_meta::<Point>();
+    assert_eq!(meta.ty, "array");
+    assert_eq!(
+        meta.prefix_items,
+        vec![f64::schema_ref(), f64::schema_ref(), String::schema_ref()]
+    );
+    assert_eq!(meta.min_items, Some(3));
+    assert_eq!(meta.max_items, Some(3));
+
+    assert_eq!(
+        Point::parse_from_json(json!([1.0, 2.5, null])).unwrap(),
+        Point(1.0, 2.5, None)
+    );
+    assert_eq!(
+        Point(1.0, 2.5, Some("a".to_string())).to_json(),
+        json!([1.0, 2.5, "a"])
+    );
+    assert_eq!(
+        Point::parse_from_json(json!([1.0, 2.5]))
+            .unwrap_err()
+            .into_message(),
+        r#"failed to parse "Point": expected 3 items, found 2."#
+    );
+    assert_eq!(
+        Point::parse_from_json(json!([1.0, "a", null]))
+            .unwrap_err()
+            .into_message(),
+        r#"Expected input type "number(double)", found "a". (occurred while parsing "Point")"#
+    );
+}
+
+#[test]
+fn enum_values() {
+    #[derive(Debug, Object, Eq, PartialEq)]
+    struct Obj {
+        #[oai(enum_values("draft", "published"))]
+        status: String,
+        #[oai(const = "v1")]
+        version: String,
+        #[oai(enum_values(1, 2))]
+        level: Option<i32>,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().enum_items,
+        vec![json!("draft"), json!("published")]
+    );
+    assert_eq!(
+        meta.properties[1].1.unwrap_inline().enum_items,
+        vec![json!("v1")]
+    );
+    assert_eq!(
+        meta.properties[2].1.unwrap_inline().enum_items,
+        vec![json!(1), json!(2)]
+    );
+
+    assert_eq!(
+        Obj::parse_from_json(json!({"status": "draft", "version": "v1"})).unwrap(),
+        Obj {
+            status: "draft".to_string(),
+            version: "v1".to_string(),
+            level: None,
+        }
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({"status": "deleted", "version": "v1"}))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `status` verification failed. the value is not one of the enum values."
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({"status": "draft", "version": "v2", "level": 1}))
+            .unwrap_err()
+            .into_message(),
+        "failed to parse \"Obj\": field `version` verification failed. the value is not one of the enum values."
+    );
+    assert!(Obj::parse_from_json(json!({"status": "draft", "version": "v1", "level": 3})).is_err());
+}
+
+#[test]
+fn borrowed_fields() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Tag<'a> {
+        name: Cow<'a, str>,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Article<'a> {
+        title: Cow<'a, str>,
+        #[oai(validator(max_length = 5))]
+        body: Cow<'a, str>,
+        tags: Vec<Tag<'a>>,
+    }
+
+    let meta = get_meta::<Article>();
+    assert_eq!(meta.properties[0].1.unwrap_inline().ty, "string");
+    assert_eq!(meta.properties[2].1.unwrap_inline().ty, "array");
+
+    let content = String::from("hello");
+    let article = Article {
+        title: Cow::Borrowed(&content),
+        body: Cow::Borrowed(&content[..2]),
+        tags: vec![Tag {
+            name: Cow::Borrowed(&content[1..]),
+        }],
+    };
+    let value = json!({
+        "title": "hello",
+        "body": "he",
+        "tags": [{"name": "ello"}],
+    });
+    assert_eq!(article.to_json(), value);
+    assert_eq!(Article::parse_from_json(value).unwrap(), article);
+
+    assert_eq!(
+        Article::parse_from_json(json!({
+            "title": "hello",
+            "body": "hello world",
+            "tags": [],
+        }))
+        .unwrap_err()
+        .into_message(),
+        "failed to parse \"Article\": field `body` verification failed. maxLength(5)"
+    );
+}
+
+#[test]
+fn extensions() {
+    #[derive(Object)]
+    #[oai(
+        extension(name = "x-internal", value = "true"),
+        extension(name = "x-owner", value = "billing")
+    )]
+    #[allow(dead_code)]
+    struct Obj {
+        #[oai(extension(name = "x-order", value = "{\"weight\": 1}"))]
+        a: i32,
+        b: i32,
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.extensions,
+        vec![
+            ("x-internal".to_string(), json!(true)),
+            ("x-owner".to_string(), json!("billing"))
+        ]
+    );
+    assert_eq!(
+        meta.properties[0].1.unwrap_inline().extensions,
+        vec![("x-order".to_string(), json!({ "weight": 1 }))]
+    );
+    assert!(meta.properties[1].1.unwrap_inline().extensions.is_empty());
+
+    let value = serde_json::to_value(&meta).unwrap();
+    assert_eq!(value["x-internal"], json!(true));
+    assert_eq!(value["properties"]["a"]["x-order"], json!({ "weight": 1 }));
+    let schema: MetaSchema<String> = serde_json::from_value(value).unwrap();
+    assert_eq!(schema.extensions, meta.extensions);
+}
+
+#[test]
+fn skip_validation() {
+    #[derive(Debug, Object, PartialEq)]
+    struct Inner {
+        #[oai(validator(maximum(value = "10")))]
+        value: i32,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    struct Outer {
+        #[oai(validator(max_length = 3))]
+        name: String,
+        inner: Inner,
+        #[oai(skip_validation, validator(max_length = 3))]
+        trusted_name: String,
+        #[oai(skip_validation)]
+        trusted_inner: Inner,
+    }
+
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(shallow_validation)]
+    struct Shallow {
+        #[oai(validator(max_length = 3))]
+        name: String,
+        inner: Inner,
+        items: Vec<Inner>,
+    }
+
+    assert_eq!(
+        Outer::parse_from_json(json!({
+            "name": "abc",
+            "inner": {"value": 1},
+            "trusted_name": "abcdef",
+            "trusted_inner": {"value": 100},
+        }))
+        .unwrap(),
+        Outer {
+            name: "abc".to_string(),
+            inner: Inner { value: 1 },
+            trusted_name: "abcdef".to_string(),
+            trusted_inner: Inner { value: 100 },
+        }
+    );
+    assert_eq!(
+        Outer::parse_from_json(json!({
+            "name": "abc",
+            "inner": {"value": 100},
+            "trusted_name": "abc",
+            "trusted_inner": {"value": 1},
+        }))
+        .unwrap_err()
+        .into_message(),
+        "failed to parse \"Inner\": field `value` verification failed. maximum(10, exclusive: false) (occurred while parsing \"Outer\")"
+    );
+
+    assert_eq!(
+        Shallow::parse_from_json(json!({
+            "name": "abc",
+            "inner": {"value": 100},
+            "items": [{"value": 100}],
+        }))
+        .unwrap(),
+        Shallow {
+            name: "abc".to_string(),
+            inner: Inner { value: 100 },
+            items: vec![Inner { value: 100 }],
+        }
+    );
+    assert_eq!(
+        Shallow::parse_from_json(json!({
+            "name": "abcdef",
+            "inner": {"value": 1},
+            "items": [],
+        }))
+        .unwrap_err()
+        .into_message(),
+        "failed to parse \"Shallow\": field `name` verification failed. maxLength(3)"
+    );
+    // The validators of the objects parsed afterwards are not skipped.
+    assert!(Inner::parse_from_json(json!({"value": 100})).is_err());
+
+    let meta = get_meta::<Outer>();
+    assert_eq!(meta.properties[2].1.unwrap_inline().max_length, Some(3));
+}
+
+#[test]
+fn serde_compat() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Object, Serialize, Deserialize, PartialEq)]
+    #[oai(serde_compat)]
+    #[serde(rename = "Account", rename_all = "camelCase")]
+    struct Obj {
+        user_name: String,
+        #[serde(rename = "mail")]
+        email: String,
+        #[serde(rename = "ignored")]
+        #[oai(rename = "phone")]
+        phone_number: String,
+        #[serde(skip)]
+        cache: i32,
+    }
+
+    #[derive(Object, Serialize)]
+    #[serde(rename = "Ignored")]
+    #[allow(dead_code)]
+    struct NoCompat {
+        #[serde(rename = "b")]
+        a: i32,
+    }
+
+    assert_eq!(Obj::name(), "Account");
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["userName", "mail", "phone"]
+    );
+
+    let obj = Obj {
+        user_name: "sunli".to_string(),
+        email: "sunli@example.com".to_string(),
+        phone_number: "123".to_string(),
+        cache: 0,
+    };
+    let value = json!({
+        "userName": "sunli",
+        "mail": "sunli@example.com",
+        "phone": "123",
+    });
+    assert_eq!(obj.to_json(), value);
+    assert_eq!(Obj::parse_from_json(value).unwrap(), obj);
+
+    assert_eq!(NoCompat::name(), "NoCompat");
+    assert_eq!(get_meta::<NoCompat>().properties[0].0, "a");
+}
+
+#[test]
+fn computed() {
+    #[derive(Debug, Object, PartialEq)]
+    #[oai(
+        rename_all = "camelCase",
+        computed = "Self::full_name",
+        computed = "Self::nickname"
+    )]
+    struct Obj {
+        first_name: String,
+        last_name: String,
+    }
+
+    impl Obj {
+        fn full_name(&self) -> String {
+            format!("{} {}", self.first_name, self.last_name)
+        }
+
+        fn nickname(&self) -> Option<String> {
+            None
+        }
+    }
+
+    let meta = get_meta::<Obj>();
+    assert_eq!(
+        meta.properties
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>(),
+        vec!["firstName", "lastName", "fullName", "nickname"]
+    );
+    let full_name = meta.properties[2].1.unwrap_inline();
+    assert_eq!(full_name.ty, "string");
+    assert!(full_name.read_only);
+    assert_eq!(meta.required, vec!["firstName", "lastName", "fullName"]);
+
+    let obj = Obj {
+        first_name: "Jane".to_string(),
+        last_name: "Doe".to_string(),
+    };
+    assert_eq!(
+        obj.to_json(),
+        json!({
+            "firstName": "Jane",
+            "lastName": "Doe",
+            "fullName": "Jane Doe",
+            "nickname": null,
+        })
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "firstName": "Jane",
+            "lastName": "Doe",
+        }))
+        .unwrap(),
+        obj
+    );
+    assert_eq!(
+        Obj::parse_from_json(json!({
+            "firstName": "Jane",
+            "lastName": "Doe",
+            "fullName": "John Doe",
+        }))
+        .unwrap_err()
+        .into_message(),
+        "failed to parse \"Obj\": properties `fullName` is read only."
+    );
+}
@@ -233,3 +233,175 @@ async fn external_docs() {
         })
     );
 }
+
+#[test]
+fn other() {
+    #[derive(OneOf, Debug, PartialEq)]
+    #[oai(property_name = "type")]
+    enum MyObj {
+        A(A),
+        #[oai(other)]
+        Other(serde_json::Value),
+    }
+
+    let schema_ref = MyObj::schema_ref();
+    let schema = schema_ref.unwrap_inline();
+    assert_eq!(schema.one_of, vec![MetaSchemaRef::Reference("A")]);
+    let property = schema.properties[0].1.unwrap_inline();
+    assert!(property.enum_items.is_empty());
+    assert_eq!(property.extensible_enum_items, vec![json!("A")]);
+
+    assert_eq!(
+        MyObj::parse_from_json(json!({ "type": "A", "v1": 100, "v2": "hello" })).unwrap(),
+        MyObj::A(A {
+            v1: 100,
+            v2: "hello".to_string()
+        })
+    );
+
+    let value = json!({ "type": "C", "v4": true });
+    assert_eq!(
+        MyObj::parse_from_json(value.clone()).unwrap(),
+        MyObj::Other(value.clone())
+    );
+    assert_eq!(MyObj::Other(value.clone()).to_json(), value);
+
+    assert!(MyObj::parse_from_json(json!({ "v4": true })).is_err());
+}
+
+#[test]
+fn nested_property_name() {
+    #[derive(OneOf, Debug, PartialEq)]
+    #[oai(property_name = "/meta/type")]
+    enum MyObj {
+        A(A),
+        B(B),
+    }
+
+    let schema_ref = MyObj::schema_ref();
+    let schema = schema_ref.unwrap_inline();
+    assert_eq!(schema.discriminator, None);
+    assert_eq!(schema.properties[0].0, "meta");
+    let meta = schema.properties[0].1.unwrap_inline();
+    assert_eq!(meta.ty, "object");
+    assert_eq!(
+        meta.properties,
+        vec![(
+            "type",
+            MetaSchemaRef::Inline(Box::new(MetaSchema {
+                enum_items: vec!["A".into(), "B".into()],
+                ..MetaSchema::new("string")
+            }))
+        )]
+    );
+
+    assert_eq!(
+        MyObj::parse_from_json(json!({
+            "meta": { "type": "B" },
+            "v3": 1.5,
+        }))
+        .unwrap(),
+        MyObj::B(B { v3: 1.5 })
+    );
+    assert!(MyObj::parse_from_json(json!({ "type": "B", "v3": 1.5 })).is_err());
+
+    assert_eq!(
+        MyObj::B(B { v3: 1.5 }).to_json(),
+        json!({
+            "meta": { "type": "B" },
+            "v3": 1.5,
+        })
+    );
+}
+
+#[test]
+fn externally_tagged() {
+    #[derive(OneOf, Debug, PartialEq)]
+    #[oai(external)]
+    enum MyObj {
+        A(A),
+        #[oai(mapping = "b")]
+        B(B),
+        #[oai(other)]
+        Other(serde_json::Value),
+    }
+
+    assert_eq!(
+        MyObj::schema_ref(),
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            one_of: vec![
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    required: vec!["A"],
+                    properties: vec![("A", MetaSchemaRef::Reference("A"))],
+                    ..MetaSchema::new("object")
+                })),
+                MetaSchemaRef::Inline(Box::new(MetaSchema {
+                    required: vec!["b"],
+                    properties: vec![("b", MetaSchemaRef::Reference("B"))],
+                    ..MetaSchema::new("object")
+                })),
+            ],
+            ..MetaSchema::new("object")
+        }))
+    );
+
+    let mut registry = Registry::new();
+    MyObj::register(&mut registry);
+    assert!(registry.schemas.contains_key("A"));
+    assert!(registry.schemas.contains_key("B"));
+
+    assert_eq!(
+        MyObj::parse_from_json(json!({ "b": { "v3": 1.5 } })).unwrap(),
+        MyObj::B(B { v3: 1.5 })
+    );
+    assert_eq!(
+        MyObj::parse_from_json(json!({ "c": 1 })).unwrap(),
+        MyObj::Other(json!({ "c": 1 }))
+    );
+    assert!(MyObj::parse_from_json(json!({ "b": { "v3": 1.5 }, "c": 1 })).is_err());
+
+    assert_eq!(
+        MyObj::B(B { v3: 1.5 }).to_json(),
+        json!({ "b": { "v3": 1.5 } })
+    );
+}
+
+#[test]
+fn adjacently_tagged() {
+    #[derive(OneOf, Debug, PartialEq)]
+    #[oai(property_name = "type", content = "data")]
+    enum MyObj {
+        A(A),
+        B(B),
+    }
+
+    assert_eq!(
+        MyObj::schema_ref().unwrap_inline().one_of[1],
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            required: vec!["type", "data"],
+            properties: vec![
+                (
+                    "type",
+                    MetaSchemaRef::Inline(Box::new(MetaSchema {
+                        enum_items: vec!["B".into()],
+                        ..MetaSchema::new("string")
+                    }))
+                ),
+                ("data", MetaSchemaRef::Reference("B")),
+            ],
+            ..MetaSchema::new("object")
+        }))
+    );
+
+    assert_eq!(
+        MyObj::parse_from_json(json!({ "type": "B", "data": { "v3": 1.5 } })).unwrap(),
+        MyObj::B(B { v3: 1.5 })
+    );
+    assert!(MyObj::parse_from_json(json!({ "type": "C", "data": {} })).is_err());
+    assert!(MyObj::parse_from_json(json!({ "type": "B" })).is_err());
+
+    assert_eq!(
+        MyObj::B(B { v3: 1.5 }).to_json(),
+        json!({ "type": "B", "data": { "v3": 1.5 } })
+    );
+}
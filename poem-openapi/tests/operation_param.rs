@@ -70,6 +70,30 @@ async fn query() {
     assert_eq!(resp.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn query_transform() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(transform(trim, uppercase))] v: Query<Vec<String>>) {
+            assert_eq!(v.0, vec!["AB".to_string(), "CD".to_string()]);
+        }
+    }
+
+    let api = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let resp = api
+        .call(
+            Request::builder()
+                .uri(Uri::from_static("/?v=%20ab&v=cd%20"))
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 async fn query_multiple_values() {
     struct Api;
@@ -140,6 +164,32 @@ async fn query_default() {
     assert_eq!(resp.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn query_default_closure() {
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(default = "|| 10")] v: Query<i32>) {
+            assert_eq!(v.0, 10);
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0]
+            .schema
+            .unwrap_inline()
+            .default,
+        Some(json!(10))
+    );
+
+    let api = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let resp = api.call(Request::default()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
 #[tokio::test]
 async fn header() {
     struct Api;
@@ -160,6 +210,127 @@ async fn header() {
     assert_eq!(resp.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn header_vary() {
+    use poem::{middleware::Vary, EndpointExt};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, #[oai(name = "X-Tenant")] _tenant: Header<String>) {}
+    }
+
+    let api = OpenApiService::new(Api, "test", "1.0")
+        .into_endpoint()
+        .with(Vary::new());
+    let resp = api
+        .call(Request::builder().header("X-Tenant", "a").finish())
+        .await
+        .unwrap();
+    assert_eq!(resp.header(header::VARY), Some("X-Tenant"));
+}
+
+#[cfg(feature = "i18n")]
+#[tokio::test]
+async fn locale() {
+    use poem::{i18n::I18NResources, EndpointExt};
+    use poem_openapi::{param::Locale, payload::PlainText};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, locale: Locale) -> PlainText<String> {
+            PlainText(locale.text("hello-world").unwrap())
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].in_type,
+        MetaParamIn::Header
+    );
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].name,
+        "Accept-Language"
+    );
+    assert_eq!(meta.paths[0].operations[0].params[0].required, false);
+
+    let resources = I18NResources::builder()
+        .add_ftl("en-US", "hello-world = hello world!")
+        .add_ftl("zh-CN", "hello-world = 你好世界！")
+        .build()
+        .unwrap();
+    let api = OpenApiService::new(Api, "test", "1.0")
+        .into_endpoint()
+        .data(resources);
+
+    let resp = api
+        .call(
+            Request::builder()
+                .header(header::ACCEPT_LANGUAGE, "en-US;q=0.5, zh-CN;q=0.8")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "你好世界！");
+
+    let resp = api.call(Request::builder().finish()).await.unwrap();
+    assert_eq!(
+        resp.into_body().into_string().await.unwrap(),
+        "hello world!"
+    );
+}
+
+#[tokio::test]
+async fn tenant() {
+    use poem::{middleware::Tenancy, EndpointExt};
+    use poem_openapi::{param::Tenant, payload::PlainText};
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "get")]
+        async fn test(&self, tenant: Tenant) -> PlainText<String> {
+            PlainText(tenant.scoped_key("key"))
+        }
+    }
+
+    let meta: MetaApi = Api::meta().remove(0);
+    assert_eq!(
+        meta.paths[0].operations[0].params[0].in_type,
+        MetaParamIn::Header
+    );
+    assert_eq!(meta.paths[0].operations[0].params[0].name, "X-Tenant-Id");
+    assert!(meta.paths[0].operations[0].params[0].required);
+
+    let api = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let resp = api
+        .call(Request::builder().header("X-Tenant-Id", "acme").finish())
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "acme:key");
+
+    let err = api.call(Request::builder().finish()).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+
+    let api = api.with(Tenancy::new().subdomain("example.com"));
+    let resp = api
+        .call(
+            Request::builder()
+                .header(header::HOST, "other.example.com")
+                .header("X-Tenant-Id", "acme")
+                .finish(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "other:key");
+}
+
 #[tokio::test]
 async fn header_multiple_values() {
     struct Api;
@@ -1,11 +1,12 @@
 use poem::{
-    http::{StatusCode, Uri},
+    http::{Method, StatusCode, Uri},
     Endpoint, Error, IntoEndpoint, Request,
 };
 use poem_openapi::{
     param::Query,
-    payload::{Json, Response},
-    ApiResponse, OpenApi, OpenApiService,
+    payload::{HasVersion, Json, Response, Versioned},
+    registry::MetaParamIn,
+    ApiResponse, Object, OpenApi, OpenApiService,
 };
 
 #[tokio::test]
@@ -66,3 +67,70 @@ async fn response_wrapper() {
     assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     assert_eq!(resp.header("MY-HEADER1"), Some("def"));
 }
+
+#[tokio::test]
+async fn versioned() {
+    #[derive(Object, Debug, Clone, Eq, PartialEq)]
+    struct Document {
+        #[oai(read_only, version)]
+        revision: i32,
+        content: String,
+    }
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "put")]
+        async fn update(&self, doc: Versioned<Document>) -> poem::Result<Versioned<Document>> {
+            let current = Document {
+                revision: 1,
+                content: "abc".to_string(),
+            };
+            doc.check(&current)?;
+            Ok(Versioned::new(Document {
+                revision: current.revision + 1,
+                content: doc.into_inner().content,
+            }))
+        }
+    }
+
+    let doc = Document {
+        revision: 1,
+        content: String::new(),
+    };
+    assert_eq!(doc.version(), "1");
+
+    let meta = Api::meta();
+    let responses = &meta[0].paths[0].operations[0].responses.responses;
+    assert_eq!(responses[0].headers[0].name, "ETag");
+    assert_eq!(responses[1].status, Some(412));
+    let params = &meta[0].paths[0].operations[0].params;
+    assert_eq!(params[0].name, "If-Match");
+    assert_eq!(params[0].in_type, MetaParamIn::Header);
+    assert!(!params[0].required);
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let update = |if_match: Option<&'static str>| {
+        let mut req = Request::builder()
+            .method(Method::PUT)
+            .content_type("application/json");
+        if let Some(if_match) = if_match {
+            req = req.header("if-match", if_match);
+        }
+        req.body(r#"{"content":"def"}"#)
+    };
+
+    for if_match in [None, Some("\"1\""), Some("\"0\", \"1\""), Some("*")] {
+        let resp = ep.call(update(if_match)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.header("etag"), Some("\"2\""));
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap(),
+            r#"{"content":"def","revision":2}"#
+        );
+    }
+
+    let err = ep.call(update(Some("\"0\""))).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::PRECONDITION_FAILED);
+}
@@ -160,6 +160,40 @@ async fn match_essence() {
     );
 }
 
+#[tokio::test]
+async fn match_suffix_and_charset() {
+    #[derive(Debug, ApiRequest, Eq, PartialEq)]
+    enum Req {
+        Create(Json<i32>),
+        #[oai(content_type = "application/merge-patch+json")]
+        Patch(Json<i32>),
+    }
+
+    for (content_type, expected) in [
+        ("application/problem+json", Some(Req::Create(Json(100)))),
+        ("application/merge-patch+json", Some(Req::Patch(Json(100)))),
+        (
+            "application/json; charset=UTF-8",
+            Some(Req::Create(Json(100))),
+        ),
+        ("application/json; charset=latin1", None),
+        ("application/xml+json2", None),
+    ] {
+        let request = poem::Request::builder()
+            .content_type(content_type)
+            .body("100".to_string());
+        let (request, mut body) = request.split();
+        assert_eq!(
+            Req::from_request(&request, &mut body, Default::default())
+                .await
+                .ok(),
+            expected,
+            "{}",
+            content_type
+        );
+    }
+}
+
 #[tokio::test]
 async fn match_star() {
     #[derive(Debug, ApiRequest, Eq, PartialEq)]
@@ -0,0 +1,117 @@
+use poem::{
+    http::{header, Method, StatusCode},
+    Endpoint, IntoEndpoint, Request,
+};
+use poem_openapi::{
+    error::BuildRequestError,
+    param::{Cookie, CookiePrivate, Header, Path, Query},
+    payload::{Json, PlainText},
+    Object, OpenApi, OpenApiService,
+};
+
+#[derive(Debug, Object)]
+struct User {
+    name: String,
+}
+
+struct Api;
+
+#[OpenApi(request_builders)]
+impl Api {
+    #[oai(path = "/users/:id", method = "put")]
+    async fn update_user(
+        &self,
+        id: Path<String>,
+        tags: Query<Vec<String>>,
+        #[oai(name = "X-Request-Id")] request_id: Header<Option<String>>,
+        session: Cookie<String>,
+        user: Json<User>,
+    ) -> PlainText<String> {
+        PlainText(format!(
+            "{} {:?} {:?} {} {}",
+            id.0, tags.0, request_id.0, session.0, user.0.name
+        ))
+    }
+
+    #[oai(path = "/session", method = "get")]
+    async fn session(&self, session: CookiePrivate<String>) -> PlainText<String> {
+        PlainText(session.0)
+    }
+}
+
+#[tokio::test]
+async fn build() {
+    let req = UpdateUserRequest::new()
+        .server("http://users/")
+        .id("u1")
+        .tags(vec!["x".to_string(), "y".to_string()])
+        .session("s1")
+        .user(Json(User {
+            name: "sunli".to_string(),
+        }))
+        .build()
+        .unwrap();
+    assert_eq!(req.method(), Method::PUT);
+    assert_eq!(req.uri(), "http://users/users/u1?tags=x&tags=y");
+    assert_eq!(req.headers()[header::COOKIE], "session=s1");
+    assert_eq!(req.headers()[header::CONTENT_TYPE], "application/json");
+    assert!(req.headers().get("X-Request-Id").is_none());
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let mut builder = Request::builder().method(req.method().clone()).uri(
+        req.uri()
+            .path_and_query()
+            .unwrap()
+            .as_str()
+            .parse()
+            .unwrap(),
+    );
+    for (name, value) in req.headers() {
+        builder = builder.header(name, value);
+    }
+    let mut resp = ep.call(builder.body(req.into_body())).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.take_body().into_string().await.unwrap(),
+        r#"u1 ["x", "y"] None s1 sunli"#
+    );
+}
+
+#[test]
+fn encode() {
+    let req = UpdateUserRequest::new()
+        .id("a b")
+        .tags(Vec::new())
+        .session("s1")
+        .request_id("r1".to_string())
+        .user(Json(User {
+            name: "sunli".to_string(),
+        }))
+        .build()
+        .unwrap();
+    assert_eq!(req.uri(), "/users/a%20b");
+    assert_eq!(req.headers()["X-Request-Id"], "r1");
+    assert_eq!(req.body(), br#"{"name":"sunli"}"#);
+}
+
+#[test]
+fn errors() {
+    let builder = UpdateUserRequest::new();
+    assert!(matches!(
+        builder.clone().session("s1").build(),
+        Err(BuildRequestError::MissingParam { name: "id" })
+    ));
+    assert!(matches!(
+        builder.id("1").tags(Vec::new()).session("s1").build(),
+        Err(BuildRequestError::MissingBody)
+    ));
+
+    assert!(matches!(
+        SessionRequest::new().build(),
+        Err(BuildRequestError::MissingParam { name: "session" })
+    ));
+    assert!(matches!(
+        SessionRequest::new().session("s1").build(),
+        Err(BuildRequestError::UnsupportedParam { name: "session" })
+    ));
+}
@@ -330,3 +330,213 @@ async fn header_deprecated() {
     let meta: MetaResponses = Resp::meta();
     assert_eq!(meta.responses[0].headers[0].deprecated, true);
 }
+
+#[tokio::test]
+async fn either() {
+    use poem_openapi::{payload::Either, types::Type};
+
+    #[derive(ApiResponse)]
+    enum UserResponse {
+        /// The user
+        #[oai(status = 200)]
+        Ok(Json<i32>, #[oai(header = "X-ID")] i32),
+        #[oai(status = 404)]
+        NotFound,
+    }
+
+    #[derive(ApiResponse)]
+    #[oai(bad_request_handler = "bad_request_handler")]
+    enum GuestResponse {
+        /// The guest
+        #[oai(status = 200)]
+        Ok(
+            Json<String>,
+            #[oai(header = "X-ID")] i32,
+            #[oai(header = "X-GUEST")] String,
+        ),
+        #[oai(status = 400)]
+        BadRequest,
+    }
+
+    fn bad_request_handler(_: Error) -> GuestResponse {
+        GuestResponse::BadRequest
+    }
+
+    type Resp = Either<UserResponse, GuestResponse>;
+
+    let meta = Resp::meta();
+    assert_eq!(meta.responses.len(), 3);
+    let ok = &meta.responses[0];
+    assert_eq!(ok.status, Some(200));
+    assert_eq!(ok.description, "The user");
+    assert_eq!(ok.content.len(), 1);
+    assert_eq!(
+        ok.content[0].schema,
+        MetaSchemaRef::Inline(Box::new(MetaSchema {
+            one_of: vec![i32::schema_ref(), String::schema_ref()],
+            ..MetaSchema::ANY
+        }))
+    );
+    assert_eq!(ok.headers.len(), 2);
+    assert_eq!(ok.headers[0].name, "X-ID");
+    assert!(ok.headers[0].required);
+    assert_eq!(ok.headers[1].name, "X-GUEST");
+    assert!(!ok.headers[1].required);
+    assert_eq!(meta.responses[1].status, Some(404));
+    assert_eq!(meta.responses[2].status, Some(400));
+
+    assert_eq!(
+        Either::<PlainText<&str>, PlainText<&str>>::meta().responses[0]
+            .content
+            .len(),
+        1
+    );
+
+    assert!(matches!(
+        Resp::from_parse_request_error(Error::from_status(StatusCode::BAD_REQUEST)),
+        Either::B(GuestResponse::BadRequest)
+    ));
+
+    let resp = Resp::A(UserResponse::NotFound).into_response();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let mut resp = Resp::A(UserResponse::Ok(Json(1), 1)).into_response();
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "1");
+    let mut resp = Resp::B(GuestResponse::Ok(
+        Json("bob".to_string()),
+        1,
+        "1".to_string(),
+    ))
+    .into_response();
+    assert_eq!(resp.header("X-GUEST"), Some("1"));
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "\"bob\"");
+}
+
+#[tokio::test]
+async fn redirect() {
+    use poem::http::Uri;
+    use poem_openapi::payload::{Found, LocationTemplate, PermanentRedirect, SeeOther};
+
+    struct Callback;
+
+    impl LocationTemplate for Callback {
+        const TEMPLATE: &'static str = "{redirect_uri}?code={code}";
+    }
+
+    let meta = SeeOther::<Callback>::meta();
+    assert_eq!(meta.responses[0].status, Some(303));
+    assert_eq!(meta.responses[0].description, "See Other");
+    let header = &meta.responses[0].headers[0];
+    assert_eq!(header.name, "Location");
+    assert_eq!(header.description, Some("{redirect_uri}?code={code}"));
+    assert!(header.required);
+    assert_eq!(
+        header.schema,
+        MetaSchemaRef::Inline(Box::new(MetaSchema::new_with_format(
+            "string",
+            "uri-reference"
+        )))
+    );
+    assert_eq!(
+        PermanentRedirect::<()>::meta().responses[0].headers[0].description,
+        Some("The URI to redirect to.")
+    );
+
+    let resp = Found::<()>::new(Uri::from_static("/s/abc")).into_response();
+    assert_eq!(resp.status(), StatusCode::FOUND);
+    assert_eq!(resp.header("Location"), Some("/s/abc"));
+}
+
+#[tokio::test]
+async fn status_responses() {
+    use poem::http::Uri;
+    use poem_openapi::payload::{Accepted, Created, NoContent};
+
+    let meta = Created::<Json<i32>>::meta();
+    let resp = &meta.responses[0];
+    assert_eq!(resp.status, Some(201));
+    assert_eq!(resp.description, "Created");
+    assert_eq!(resp.content[0].content_type, "application/json");
+    assert_eq!(resp.headers[0].name, "Location");
+    assert!(resp.headers[0].required);
+
+    let meta = Accepted::<()>::meta();
+    assert_eq!(meta.responses[0].status, Some(202));
+    assert!(!meta.responses[0].headers[0].required);
+    assert_eq!(
+        NoContent::meta().responses[0],
+        MetaResponse {
+            description: "No Content",
+            status: Some(204),
+            content: vec![],
+            headers: vec![],
+        }
+    );
+
+    let mut resp = Created::new(Json(1), Uri::from_static("/users/1")).into_response();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(resp.header("Location"), Some("/users/1"));
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "1");
+
+    let resp = Accepted::new(()).into_response();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    assert_eq!(resp.header("Location"), None);
+    let resp = Accepted::new(())
+        .location(Uri::from_static("/jobs/1"))
+        .into_response();
+    assert_eq!(resp.header("Location"), Some("/jobs/1"));
+
+    assert_eq!(NoContent.into_response().status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn with_cookie() {
+    use poem::web::cookie::{Cookie, CookieJar, CookieKey, SameSite};
+    use poem_openapi::payload::WithCookie;
+
+    let meta = WithCookie::<PlainText<String>>::meta();
+    let resp = &meta.responses[0];
+    assert_eq!(resp.status, Some(200));
+    assert_eq!(resp.headers[0].name, "Set-Cookie");
+    assert!(!resp.headers[0].required);
+
+    let key = CookieKey::generate();
+    let mut cookie = Cookie::new_with_str("a", "1");
+    cookie.set_same_site(SameSite::Strict);
+    cookie.set_max_age(std::time::Duration::from_secs(60));
+    cookie.set_secure(true);
+    let resp = WithCookie::new(PlainText("hello"))
+        .cookie(cookie)
+        .signed_cookie(Cookie::new_with_str("b", "2"), &key)
+        .private_cookie(Cookie::new_with_str("c", "3"), &key)
+        .remove_cookie("d")
+        .into_response();
+
+    let cookies = resp
+        .headers()
+        .get_all("Set-Cookie")
+        .iter()
+        .map(|value| Cookie::parse(value.to_str().unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(cookies.len(), 4);
+    assert_eq!(cookies[0].value_str(), "1");
+    assert_eq!(cookies[0].same_site(), Some(SameSite::Strict));
+    assert_eq!(
+        cookies[0].max_age(),
+        Some(std::time::Duration::from_secs(60))
+    );
+    assert!(cookies[0].secure());
+    assert_eq!(cookies[3].name(), "d");
+    assert_eq!(cookies[3].max_age(), Some(std::time::Duration::ZERO));
+
+    let jar = CookieJar::default();
+    for cookie in cookies {
+        jar.add(cookie);
+    }
+    assert_ne!(jar.get("b").unwrap().value_str(), "2");
+    assert_eq!(jar.signed_with_key(&key).get("b").unwrap().value_str(), "2");
+    assert_ne!(jar.get("c").unwrap().value_str(), "3");
+    assert_eq!(
+        jar.private_with_key(&key).get("c").unwrap().value_str(),
+        "3"
+    );
+}
@@ -3,8 +3,10 @@ use poem::{
     web::cookie::Cookie,
     Endpoint, IntoEndpoint,
 };
+#[cfg(feature = "base64")]
+use poem_openapi::auth::Basic;
 use poem_openapi::{
-    auth::{ApiKey, Basic, Bearer},
+    auth::{ApiKey, Bearer},
     payload::PlainText,
     registry::{MetaOAuthFlow, MetaOAuthFlows, MetaOAuthScope, MetaSecurityScheme, Registry},
     ApiExtractor, OAuthScopes, OpenApi, OpenApiService, SecurityScheme,
@@ -12,6 +14,7 @@ use poem_openapi::{
 use typed_headers::{http::StatusCode, Token68};
 
 #[test]
+#[cfg(feature = "base64")]
 fn rename() {
     #[derive(SecurityScheme)]
     #[oai(rename = "ABC", type = "basic")]
@@ -21,6 +24,7 @@ fn rename() {
 }
 
 #[test]
+#[cfg(feature = "base64")]
 fn default_rename() {
     #[derive(SecurityScheme)]
     #[oai(type = "basic")]
@@ -33,6 +37,7 @@ fn default_rename() {
 }
 
 #[test]
+#[cfg(feature = "base64")]
 fn desc() {
     /// ABC
     ///
@@ -74,6 +79,7 @@ async fn no_auth() {
 }
 
 #[tokio::test]
+#[cfg(feature = "base64")]
 async fn basic_auth() {
     #[derive(SecurityScheme)]
     #[oai(type = "basic")]
@@ -463,3 +469,317 @@ async fn oauth2_auth() {
         }
     );
 }
+
+#[cfg(feature = "hmac")]
+#[tokio::test]
+async fn hmac_auth() {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use poem::{http::Method, Request};
+    use poem_openapi::auth::{
+        hmac::{self, MemoryNonceStore},
+        HmacSignature,
+    };
+
+    static STORE: once_cell::sync::Lazy<MemoryNonceStore> =
+        once_cell::sync::Lazy::new(MemoryNonceStore::new);
+
+    async fn verify(_req: &Request, signature: HmacSignature) -> Option<String> {
+        if signature.key_id != "key1" {
+            return None;
+        }
+        signature
+            .verify(b"secret", Duration::from_secs(300), &*STORE)
+            .await
+            .then_some(signature.key_id)
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "hmac", checker = "verify")]
+    struct MySecurityScheme(String);
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, auth: MySecurityScheme) -> PlainText<String> {
+            PlainText(auth.0)
+        }
+
+        #[oai(path = "/upload", method = "post")]
+        async fn upload(
+            &self,
+            auth: MySecurityScheme,
+            body: PlainText<String>,
+        ) -> PlainText<String> {
+            PlainText(format!("{}: {}", auth.0, body.0))
+        }
+    }
+
+    let service = OpenApiService::new(MyApi, "test", "1.0");
+    let spec = serde_json::from_str::<serde_json::Value>(&service.spec()).unwrap();
+    assert_eq!(
+        spec["components"]["securitySchemes"]["MySecurityScheme"],
+        serde_json::json!({
+            "type": "apiKey",
+            "name": "X-Signature",
+            "in": "header",
+        })
+    );
+    let params = spec["paths"]["/test"]["get"]["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| {
+            (
+                param["name"].as_str().unwrap(),
+                param["required"].as_bool().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        params,
+        vec![
+            ("X-Key-Id", true),
+            ("X-Timestamp", true),
+            ("X-Nonce", true),
+            ("X-Content-SHA256", false)
+        ]
+    );
+
+    let ep = service.into_endpoint();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let request = |key_id: &str, timestamp: i64, nonce: &str| {
+        let uri = Uri::from_static("/test?b=2&a=1");
+        let signature = hmac::sign(
+            b"secret",
+            &hmac::canonical_request(&Method::GET, &uri, timestamp, nonce, None),
+        );
+        Request::builder()
+            .uri(uri)
+            .header(hmac::KEY_ID_HEADER, key_id)
+            .header(hmac::TIMESTAMP_HEADER, timestamp)
+            .header(hmac::NONCE_HEADER, nonce)
+            .header(hmac::SIGNATURE_HEADER, signature)
+            .finish()
+    };
+
+    let mut resp = ep.call(request("key1", timestamp, "n1")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "key1");
+
+    // replay
+    let err = ep.call(request("key1", timestamp, "n1")).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    // expired
+    let err = ep
+        .call(request("key1", timestamp - 1000, "n2"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    // unknown key
+    let err = ep.call(request("key2", timestamp, "n3")).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    // bad signature
+    let mut req = request("key1", timestamp, "n4");
+    req.headers_mut()
+        .insert(hmac::SIGNATURE_HEADER, "00".parse().unwrap());
+    let err = ep.call(req).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    let upload = |nonce: &str, content_sha256: Option<&str>, body: &'static str| {
+        let uri = Uri::from_static("/upload");
+        let signature = hmac::sign(
+            b"secret",
+            &hmac::canonical_request(&Method::POST, &uri, timestamp, nonce, content_sha256),
+        );
+        let mut builder = Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .content_type("text/plain")
+            .header(hmac::KEY_ID_HEADER, "key1")
+            .header(hmac::TIMESTAMP_HEADER, timestamp)
+            .header(hmac::NONCE_HEADER, nonce)
+            .header(hmac::SIGNATURE_HEADER, signature);
+        if let Some(content_sha256) = content_sha256 {
+            builder = builder.header(hmac::CONTENT_SHA256_HEADER, content_sha256);
+        }
+        builder.body(body)
+    };
+
+    let digest = hmac::content_sha256(b"hello");
+    let mut resp = ep.call(upload("n5", Some(&digest), "hello")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.take_body().into_string().await.unwrap(), "key1: hello");
+
+    // the body does not match the digest
+    let err = ep
+        .call(upload("n6", Some(&digest), "world"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    // the body is not signed
+    let err = ep.call(upload("n7", None, "hello")).await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+}
+
+#[cfg(feature = "key-store")]
+#[tokio::test]
+async fn api_key_store() {
+    use std::sync::Arc;
+
+    use poem::{EndpointExt, Request};
+    use poem_openapi::auth::key_store::{check_api_key, ApiKeyInfo, KeyStore, MemoryKeyStore};
+
+    #[derive(SecurityScheme)]
+    #[oai(
+        type = "api_key",
+        key_name = "X-API-Key",
+        in = "header",
+        checker = "check_api_key"
+    )]
+    struct MySecurityScheme(ApiKeyInfo);
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/test", method = "get")]
+        async fn test(&self, auth: MySecurityScheme) -> PlainText<String> {
+            PlainText(format!("{}:{}", auth.0.key_id, auth.0.has_scope("read")))
+        }
+    }
+
+    let store = Arc::new(MemoryKeyStore::new());
+    store
+        .insert("abc", ApiKeyInfo::new("key1").scope("read"))
+        .await
+        .unwrap();
+    let service =
+        OpenApiService::new(MyApi, "test", "1.0").data(store.clone() as Arc<dyn KeyStore>);
+    let call = |key: &'static str| {
+        service.call(
+            Request::builder()
+                .uri(Uri::from_static("/test"))
+                .header("X-API-Key", key)
+                .finish(),
+        )
+    };
+
+    let resp = call("abc").await.unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "key1:true");
+    assert_eq!(store.usage("key1").await, 1);
+
+    let err = call("abd").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    store.revoke("key1").await.unwrap();
+    let err = call("abc").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(store.usage("key1").await, 1);
+}
+
+#[tokio::test]
+async fn require_permission() {
+    use std::collections::HashSet;
+
+    use poem::{http::Method, Request};
+    use poem_openapi::error::PermissionDeniedError;
+
+    async fn check(_req: &poem::Request, bearer: Bearer) -> Option<HashSet<String>> {
+        match bearer.token.as_str() {
+            "reader" => Some(["orders:read".to_string()].into_iter().collect()),
+            "writer" => Some(
+                ["orders:read".to_string(), "orders:write".to_string()]
+                    .into_iter()
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "bearer", checker = "check")]
+    struct MySecurityScheme(HashSet<String>);
+
+    #[derive(SecurityScheme)]
+    #[oai(type = "bearer")]
+    struct NoPermissions(Bearer);
+
+    struct MyApi;
+
+    #[OpenApi]
+    impl MyApi {
+        #[oai(path = "/read", method = "get", require_permission = "orders:read")]
+        async fn read(&self, _auth: MySecurityScheme) -> PlainText<&'static str> {
+            PlainText("read")
+        }
+
+        #[oai(
+            path = "/write",
+            method = "post",
+            require_permission = "orders:read",
+            require_permission = "orders:write"
+        )]
+        async fn write(&self, _auth: MySecurityScheme) -> PlainText<&'static str> {
+            PlainText("write")
+        }
+
+        #[oai(path = "/other", method = "get", require_permission = "orders:read")]
+        async fn other(&self, _auth: NoPermissions) -> PlainText<&'static str> {
+            PlainText("other")
+        }
+    }
+
+    let service = OpenApiService::new(MyApi, "test", "1.0");
+    let spec = serde_json::from_str::<serde_json::Value>(&service.spec()).unwrap();
+    assert_eq!(
+        spec["paths"]["/write"]["post"]["responses"]["403"],
+        serde_json::json!({
+            "description": "Forbidden, requires the permissions: orders:read, orders:write",
+        })
+    );
+
+    let ep = service.into_endpoint();
+    let call = |method: Method, path: &'static str, token: &'static str| {
+        ep.call(
+            Request::builder()
+                .method(method)
+                .uri(Uri::from_static(path))
+                .header(
+                    header::AUTHORIZATION,
+                    typed_headers::Credentials::bearer(Token68::new(token).unwrap()).to_string(),
+                )
+                .finish(),
+        )
+    };
+
+    let resp = call(Method::GET, "/read", "reader").await.unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "read");
+
+    let err = call(Method::POST, "/write", "reader").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        err.downcast_ref::<PermissionDeniedError>()
+            .unwrap()
+            .permission,
+        "orders:write"
+    );
+
+    let resp = call(Method::POST, "/write", "writer").await.unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "write");
+
+    let err = call(Method::POST, "/write", "other").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::UNAUTHORIZED);
+
+    let err = call(Method::GET, "/other", "reader").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::FORBIDDEN);
+}
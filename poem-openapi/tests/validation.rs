@@ -10,12 +10,11 @@ use poem::{
 };
 use poem_openapi::{
     param::Query,
-    payload::Payload,
     registry::{MetaApi, MetaSchema, Registry},
-    types::{multipart::JsonField, ParseFromJSON, Type},
+    types::{ParseFromJSON, Type},
     validation,
     validation::ValidatorMeta,
-    Multipart, Object, OpenApi, OpenApiService, Validator,
+    Object, OpenApi, OpenApiService, Validator,
 };
 use serde_json::json;
 
@@ -163,6 +162,7 @@ fn test_min_length() {
 }
 
 #[test]
+#[cfg(feature = "regex")]
 fn test_pattern() {
     #[derive(Object, Debug, Eq, PartialEq)]
     struct A {
@@ -488,7 +488,10 @@ fn test_list_on_object() {
 }
 
 #[test]
+#[cfg(feature = "multipart")]
 fn test_list_on_multipart() {
+    use poem_openapi::{payload::Payload, types::multipart::JsonField, Multipart};
+
     #[derive(Multipart, Debug, Eq, PartialEq)]
     struct A {
         #[oai(validator(maximum(value = "32")))]
@@ -132,6 +132,77 @@ async fn parameters() {
     );
 }
 
+#[cfg(feature = "hmac")]
+#[tokio::test]
+async fn signature() {
+    use std::time::Duration;
+
+    use poem::{http::StatusCode, Endpoint, IntoEndpoint, Request};
+    use poem_openapi::{
+        payload::{Binary, PlainText},
+        webhook::{WebhookSignature, WebhookSigner, SIGNATURE_HEADER},
+        OpenApi,
+    };
+
+    #[Webhook]
+    #[allow(dead_code)]
+    trait MyWebhooks: Sync {
+        #[oai(method = "post")]
+        async fn test(&self, signature: WebhookSignature, req: Json<i32>);
+    }
+
+    assert_eq!(
+        <&dyn MyWebhooks>::meta()[0].operation.params,
+        vec![MetaOperationParam {
+            name: "Webhook-Signature",
+            schema: String::schema_ref(),
+            in_type: MetaParamIn::Header,
+            description: None,
+            required: true,
+            deprecated: false
+        }]
+    );
+
+    struct Api;
+
+    #[OpenApi]
+    impl Api {
+        #[oai(path = "/", method = "post")]
+        async fn receive(
+            &self,
+            signature: WebhookSignature,
+            body: Binary<Vec<u8>>,
+        ) -> PlainText<&'static str> {
+            if signature.verify(&[b"old", b"new"], &body, Duration::from_secs(300)) {
+                PlainText("valid")
+            } else {
+                PlainText("invalid")
+            }
+        }
+    }
+
+    let ep = OpenApiService::new(Api, "test", "1.0").into_endpoint();
+    let call = |signature: String, body: &'static str| {
+        ep.call(
+            Request::builder()
+                .method(Method::POST)
+                .header(SIGNATURE_HEADER, signature)
+                .content_type("application/octet-stream")
+                .body(body),
+        )
+    };
+
+    let signer = WebhookSigner::new(b"new".to_vec()).with_secret(b"old".to_vec());
+    let resp = call(signer.sign(b"100"), "100").await.unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "valid");
+
+    let resp = call(signer.sign(b"100"), "200").await.unwrap();
+    assert_eq!(resp.into_body().into_string().await.unwrap(), "invalid");
+
+    let err = call("abc".to_string(), "100").await.unwrap_err();
+    assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn request_body() {
     #[Webhook]
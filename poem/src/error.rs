@@ -184,6 +184,7 @@ impl AsResponse {
 pub struct Error {
     as_response: AsResponse,
     source: ErrorSource,
+    msg: Option<String>,
 }
 
 impl Debug for Error {
@@ -196,6 +197,10 @@ impl Debug for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(msg) = &self.msg {
+            return f.write_str(msg);
+        }
+
         match &self.source {
             ErrorSource::BoxedError(err) => Display::fmt(err, f),
             #[cfg(feature = "anyhow")]
@@ -215,6 +220,7 @@ impl<T: ResponseError + StdError + Send + Sync + 'static> From<T> for Error {
         Error {
             as_response: AsResponse::from_type::<T>(),
             source: ErrorSource::BoxedError(Box::new(err)),
+            msg: None,
         }
     }
 }
@@ -230,6 +236,7 @@ impl From<(StatusCode, Box<dyn StdError + Send + Sync>)> for Error {
         Error {
             as_response: AsResponse::from_status(status),
             source: ErrorSource::BoxedError(err),
+            msg: None,
         }
     }
 }
@@ -240,6 +247,7 @@ impl From<anyhow::Error> for Error {
         Error {
             as_response: AsResponse::from_status(StatusCode::INTERNAL_SERVER_ERROR),
             source: ErrorSource::Anyhow(err),
+            msg: None,
         }
     }
 }
@@ -250,6 +258,7 @@ impl From<(StatusCode, anyhow::Error)> for Error {
         Error {
             as_response: AsResponse::from_status(status),
             source: ErrorSource::Anyhow(err),
+            msg: None,
         }
     }
 }
@@ -268,6 +277,7 @@ impl Error {
         Self {
             as_response: AsResponse::from_status(status),
             source: ErrorSource::BoxedError(Box::new(err)),
+            msg: None,
         }
     }
 
@@ -310,6 +320,7 @@ impl Error {
     #[inline]
     pub fn downcast<T: StdError + Send + Sync + 'static>(self) -> Result<T, Error> {
         let as_response = self.as_response;
+        let msg = self.msg;
 
         match self.source {
             ErrorSource::BoxedError(err) => match err.downcast::<T>() {
@@ -317,6 +328,7 @@ impl Error {
                 Err(err) => Err(Error {
                     as_response,
                     source: ErrorSource::BoxedError(err),
+                    msg,
                 }),
             },
             #[cfg(feature = "anyhow")]
@@ -325,6 +337,7 @@ impl Error {
                 Err(err) => Err(Error {
                     as_response,
                     source: ErrorSource::Anyhow(err),
+                    msg,
                 }),
             },
         }
@@ -340,9 +353,21 @@ impl Error {
         }
     }
 
+    /// Replace the message of this error, the message is used as the body of
+    /// the response while the source error, the status code and the headers of
+    /// the response are kept.
+    pub fn set_error_message(&mut self, msg: impl Into<String>) -> &mut Self {
+        self.msg = Some(msg.into());
+        self
+    }
+
     /// Consumes this to return a response object.
     pub fn as_response(&self) -> Response {
-        self.as_response.as_response(self)
+        let mut resp = self.as_response.as_response(self);
+        if let Some(msg) = &self.msg {
+            resp.set_body(msg.clone());
+        }
+        resp
     }
 }
 
@@ -779,6 +804,127 @@ impl ResponseError for SizedLimitError {
     }
 }
 
+/// A possible error value occurred in the `RequestDeadline` middleware.
+#[derive(Debug, thiserror::Error, Copy, Clone, Eq, PartialEq)]
+#[error("request deadline exceeded")]
+pub struct DeadlineExceededError;
+
+impl ResponseError for DeadlineExceededError {
+    fn status(&self) -> StatusCode {
+        StatusCode::REQUEST_TIMEOUT
+    }
+}
+
+/// A possible error value occurred in the `Tenancy` middleware or the
+/// `Tenant` extractor.
+#[derive(Debug, thiserror::Error, Copy, Clone, Eq, PartialEq)]
+#[error("missing tenant")]
+pub struct MissingTenantError;
+
+impl ResponseError for MissingTenantError {
+    fn status(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// A possible error value occurred in the `Maintenance` middleware.
+///
+/// The response is `503 Service Unavailable` with the `Retry-After` header
+/// and a JSON body, such as
+/// `{"code": "maintenance", "message": "...", "retry_after": 60}`.
+#[derive(Debug, thiserror::Error, Clone, Eq, PartialEq)]
+#[error("{message}")]
+pub struct MaintenanceError {
+    /// The message of the response.
+    pub message: String,
+    /// The number of seconds after which the client can retry.
+    pub retry_after: Option<u64>,
+}
+
+impl ResponseError for MaintenanceError {
+    fn status(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+
+    fn as_response(&self) -> Response {
+        let body = serde_json::json!({
+            "code": "maintenance",
+            "message": self.message,
+            "retry_after": self.retry_after,
+        });
+        let mut builder = Response::builder()
+            .status(self.status())
+            .content_type("application/json");
+        if let Some(retry_after) = self.retry_after {
+            builder = builder.header(http::header::RETRY_AFTER, retry_after);
+        }
+        builder.body(body.to_string())
+    }
+}
+
+/// A possible error value occurred in the `Scheduler` middleware.
+///
+/// The request is shed because the service is saturated.
+#[derive(Debug, thiserror::Error, Copy, Clone, Eq, PartialEq)]
+#[error("the service is overloaded")]
+pub struct OverloadedError;
+
+impl ResponseError for OverloadedError {
+    fn status(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// A possible error value occurred in the `TrustedHost` middleware.
+#[derive(Debug, thiserror::Error, Copy, Clone, Eq, PartialEq)]
+pub enum TrustedHostError {
+    /// The `Host` header is missing or not allowed.
+    #[error("untrusted host")]
+    UntrustedHost,
+
+    /// The `Origin` header is not allowed.
+    #[error("untrusted origin")]
+    UntrustedOrigin,
+}
+
+impl ResponseError for TrustedHostError {
+    fn status(&self) -> StatusCode {
+        match self {
+            TrustedHostError::UntrustedHost => StatusCode::BAD_REQUEST,
+            TrustedHostError::UntrustedOrigin => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// A possible error value occurred in the `Decompression` middleware.
+#[cfg(feature = "compression")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+pub enum DecompressionError {
+    /// Unsupported `Content-Encoding`
+    #[error("unsupported content encoding: {0}")]
+    UnsupportedEncoding(String),
+
+    /// Multiple `Content-Encoding` codings are applied
+    #[error("multiple content encodings are not supported: {0}")]
+    MultipleEncodings(String),
+
+    /// The decompressed payload exceeds the limits
+    #[error("decompressed payload too large")]
+    PayloadTooLarge,
+}
+
+#[cfg(feature = "compression")]
+impl ResponseError for DecompressionError {
+    fn status(&self) -> StatusCode {
+        match self {
+            DecompressionError::UnsupportedEncoding(_)
+            | DecompressionError::MultipleEncodings(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            DecompressionError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
 /// A possible error value occurred when adding a route.
 #[derive(Debug, thiserror::Error, Eq, PartialEq)]
 pub enum RouteError {
@@ -933,4 +1079,22 @@ mod tests {
             "my error message"
         );
     }
+
+    #[tokio::test]
+    async fn test_set_error_message() {
+        let mut err = Error::new(
+            IoError::new(ErrorKind::AlreadyExists, "aaa"),
+            StatusCode::BAD_GATEWAY,
+        );
+        err.set_error_message("bbb");
+        assert_eq!(err.to_string(), "bbb");
+        assert_eq!(
+            err.downcast_ref::<IoError>().unwrap().kind(),
+            ErrorKind::AlreadyExists
+        );
+
+        let resp = err.as_response();
+        assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "bbb");
+    }
 }
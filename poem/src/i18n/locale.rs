@@ -7,6 +7,7 @@ use unic_langid::LanguageIdentifier;
 use crate::{
     error::I18NError,
     i18n::{I18NArgs, I18NBundle, I18NResources},
+    web::VaryHeaders,
     FromRequest, Request, RequestBody, Result,
 };
 
@@ -85,11 +86,11 @@ impl Locale {
 impl<'a> FromRequest<'a> for Locale {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         let resources = req
-            .extensions()
-            .get::<I18NResources>()
+            .data::<I18NResources>()
             .expect("To use the `Locale` extractor, the `I18NResources` data is required.")
             .clone();
 
+        VaryHeaders::record(req, header::ACCEPT_LANGUAGE);
         let accept_languages = req
             .headers()
             .get(header::ACCEPT_LANGUAGE)
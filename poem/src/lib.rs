@@ -235,6 +235,7 @@
 //! |prometheus        | Support for Prometheus       |
 //! |redis-session     | Support for RedisSession     |
 //! |rustls            | Support for HTTP server over TLS with [`rustls`](https://crates.io/crates/rustls)  |
+//! |security-headers  | Support for the security headers, such as `Content-Security-Policy` |
 //! |session           | Support for session    |
 //! |sse               | Support Server-Sent Events (SSE)       |
 //! |tempfile          | Support for [`tempfile`](https://crates.io/crates/tempfile) |
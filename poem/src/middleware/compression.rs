@@ -4,7 +4,7 @@ use typed_headers::{AcceptEncoding, ContentCoding, HeaderMapExt};
 
 use crate::{
     http::header,
-    web::{Compress, CompressionAlgo},
+    web::{vary::append_vary, Compress, CompressionAlgo},
     Body, Endpoint, IntoResponse, Middleware, Request, Response, Result,
 };
 
@@ -70,10 +70,12 @@ impl<E: Endpoint> Endpoint for CompressionEndpoint<E> {
             }
         }
 
-        match compress_algo {
-            Some(algo) => Ok(Compress::new(self.ep.call(req).await?, algo).into_response()),
-            None => Ok(self.ep.call(req).await?.into_response()),
-        }
+        let mut resp = match compress_algo {
+            Some(algo) => Compress::new(self.ep.call(req).await?, algo).into_response(),
+            None => self.ep.call(req).await?.into_response(),
+        };
+        append_vary(resp.headers_mut(), &header::ACCEPT_ENCODING);
+        Ok(resp)
     }
 }
 
@@ -110,6 +112,7 @@ mod tests {
                 .and_then(|value| value.to_str().ok()),
             Some(algo.as_str())
         );
+        assert_eq!(resp.header(header::VARY), Some("Accept-Encoding"));
 
         let mut data = Vec::new();
         let mut reader = algo.decompress(resp.take_body().into_async_read());
@@ -15,6 +15,7 @@ use crate::{
     middleware::Middleware,
     request::Request,
     response::Response,
+    web::vary::append_vary,
     IntoResponse, Result,
 };
 
@@ -387,8 +388,7 @@ impl<E: Endpoint> Endpoint for CorsEndpoint<E> {
         }
 
         if vary_header {
-            resp.headers_mut()
-                .insert(header::VARY, HeaderValue::from_static("Origin"));
+            append_vary(resp.headers_mut(), &header::ORIGIN);
         }
 
         Ok(resp)
@@ -0,0 +1,490 @@
+use std::{
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    pin::Pin,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use async_compression::tokio::bufread::ZstdDecoder;
+use tokio::io::{AsyncRead, BufReader, ReadBuf};
+
+use crate::{
+    error::DecompressionError, http::header, web::CompressionAlgo, Body, Endpoint, Middleware,
+    Request, Result,
+};
+
+const DEFAULT_MAX_SIZE: u64 = 16 * 1024 * 1024;
+const DEFAULT_MAX_RATIO: u64 = 100;
+
+/// Middleware for decompress request body.
+///
+/// It selects the decompression algorithm according to the request
+/// `Content-Encoding` header (`gzip`, `br`, `deflate` or `zstd`), and
+/// protects against decompression bombs with the maximum decompressed size
+/// and the maximum compression ratio, which are `16MiB` and `100` by
+/// default.
+///
+/// The requests with multiple codings, such as `Content-Encoding: gzip, br`,
+/// are rejected.
+///
+/// # Errors
+///
+/// - [`DecompressionError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, middleware::Decompression, EndpointExt};
+///
+/// #[handler]
+/// fn index(data: String) -> String {
+///     data
+/// }
+///
+/// let app = index.with(
+///     Decompression::new()
+///         .max_size(16 * 1024 * 1024)
+///         .max_ratio(100),
+/// );
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+pub struct Decompression {
+    max_size: Option<u64>,
+    max_ratio: Option<u64>,
+}
+
+impl Default for Decompression {
+    fn default() -> Self {
+        Self {
+            max_size: Some(DEFAULT_MAX_SIZE),
+            max_ratio: Some(DEFAULT_MAX_RATIO),
+        }
+    }
+}
+
+impl Decompression {
+    /// Creates a new `Decompression` middleware.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of the decompressed body, the default is `16MiB`.
+    #[must_use]
+    pub fn max_size(self, max_size: u64) -> Self {
+        Self {
+            max_size: Some(max_size),
+            ..self
+        }
+    }
+
+    /// Sets the maximum ratio of the decompressed size to the compressed size,
+    /// the default is `100`.
+    #[must_use]
+    pub fn max_ratio(self, max_ratio: u64) -> Self {
+        Self {
+            max_ratio: Some(max_ratio),
+            ..self
+        }
+    }
+
+    /// Removes the limits of the decompressed size and the compression ratio.
+    ///
+    /// Only use it when the body is limited by other means, the request bodies
+    /// are otherwise exposed to decompression bombs.
+    #[must_use]
+    pub fn unlimited(self) -> Self {
+        Self {
+            max_size: None,
+            max_ratio: None,
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Decompression {
+    type Output = DecompressionEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        DecompressionEndpoint {
+            ep,
+            max_size: self.max_size,
+            max_ratio: self.max_ratio,
+        }
+    }
+}
+
+/// The content codings of the request bodies, `zstd` is only decompressed
+/// and it is not one of the [`CompressionAlgo`] of the responses.
+#[derive(Debug, Copy, Clone)]
+enum ContentCoding {
+    Algo(CompressionAlgo),
+    Zstd,
+}
+
+impl FromStr for ContentCoding {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zstd" => Ok(ContentCoding::Zstd),
+            _ => CompressionAlgo::from_str(s).map(ContentCoding::Algo),
+        }
+    }
+}
+
+impl ContentCoding {
+    fn decompress<'a>(
+        self,
+        reader: impl AsyncRead + Send + Unpin + 'a,
+    ) -> Pin<Box<dyn AsyncRead + Send + 'a>> {
+        match self {
+            ContentCoding::Algo(algo) => algo.decompress(reader),
+            ContentCoding::Zstd => Box::pin(ZstdDecoder::new(BufReader::new(reader))),
+        }
+    }
+}
+
+/// Endpoint for Decompression middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+pub struct DecompressionEndpoint<E: Endpoint> {
+    ep: E,
+    max_size: Option<u64>,
+    max_ratio: Option<u64>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for DecompressionEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        // The codings are listed in the order they were applied, possibly in
+        // multiple header lines, decompressing only one of them would pass a
+        // compressed body to the endpoint.
+        let mut encodings = req
+            .headers()
+            .get_all(header::CONTENT_ENCODING)
+            .iter()
+            .flat_map(|value| {
+                String::from_utf8_lossy(value.as_bytes())
+                    .split(',')
+                    .map(|encoding| encoding.trim().to_lowercase())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|encoding| !encoding.is_empty() && encoding != "identity")
+            .collect::<Vec<_>>();
+        let encoding = match encodings.len() {
+            0 => return self.ep.call(req).await,
+            1 => encodings.remove(0),
+            _ => return Err(DecompressionError::MultipleEncodings(encodings.join(", ")).into()),
+        };
+        let coding = ContentCoding::from_str(&encoding)
+            .map_err(|_| DecompressionError::UnsupportedEncoding(encoding))?;
+
+        let compressed_size = Arc::new(AtomicU64::new(0));
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let reader = coding.decompress(CountingReader {
+            inner: req.take_body().into_async_read(),
+            count: compressed_size.clone(),
+        });
+        req.set_body(Body::from_async_read(LimitedReader {
+            inner: reader,
+            size: 0,
+            compressed_size,
+            max_size: self.max_size,
+            max_ratio: self.max_ratio,
+            exceeded: exceeded.clone(),
+        }));
+        req.headers_mut().remove(header::CONTENT_ENCODING);
+        req.headers_mut().remove(header::CONTENT_LENGTH);
+
+        match self.ep.call(req).await {
+            Err(_) if exceeded.load(Ordering::SeqCst) => {
+                Err(DecompressionError::PayloadTooLarge.into())
+            }
+            res => res,
+        }
+    }
+}
+
+struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        let filled = buf.filled().len();
+        let res = Pin::new(&mut self.inner).poll_read(cx, buf);
+        self.count
+            .fetch_add((buf.filled().len() - filled) as u64, Ordering::SeqCst);
+        res
+    }
+}
+
+struct LimitedReader {
+    inner: Pin<Box<dyn AsyncRead + Send>>,
+    size: u64,
+    compressed_size: Arc<AtomicU64>,
+    max_size: Option<u64>,
+    max_ratio: Option<u64>,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl LimitedReader {
+    fn is_exceeded(&self) -> bool {
+        if matches!(self.max_size, Some(max_size) if self.size > max_size) {
+            return true;
+        }
+        match self.max_ratio {
+            Some(max_ratio) => {
+                let compressed_size = self.compressed_size.load(Ordering::SeqCst);
+                self.size > compressed_size.max(1).saturating_mul(max_ratio)
+            }
+            None => false,
+        }
+    }
+}
+
+impl AsyncRead for LimitedReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        if self.exceeded.load(Ordering::SeqCst) {
+            return Poll::Ready(Err(IoError::new(
+                ErrorKind::Other,
+                DecompressionError::PayloadTooLarge,
+            )));
+        }
+
+        let filled = buf.filled().len();
+        match self.inner.as_mut().poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => {
+                self.size += (buf.filled().len() - filled) as u64;
+                if self.is_exceeded() {
+                    self.exceeded.store(true, Ordering::SeqCst);
+                    return Poll::Ready(Err(IoError::new(
+                        ErrorKind::Other,
+                        DecompressionError::PayloadTooLarge,
+                    )));
+                }
+                Poll::Ready(Ok(()))
+            }
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_compression::tokio::bufread::ZstdEncoder;
+    use http::StatusCode;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::{handler, EndpointExt, IntoResponse};
+
+    const DATA: &str = "abcdefghijklmnopqrstuvwxyz1234567890";
+
+    #[handler(internal)]
+    async fn index(data: String) -> String {
+        data
+    }
+
+    async fn compress(algo: CompressionAlgo, data: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        algo.compress(data).read_to_end(&mut output).await.unwrap();
+        output
+    }
+
+    #[tokio::test]
+    async fn decompression() {
+        let ep = index.with(Decompression::new());
+
+        for algo in [
+            CompressionAlgo::BR,
+            CompressionAlgo::DEFLATE,
+            CompressionAlgo::GZIP,
+        ] {
+            let resp = ep
+                .call(
+                    Request::builder()
+                        .header("Content-Encoding", algo.as_str())
+                        .body(compress(algo, DATA.as_bytes()).await),
+                )
+                .await
+                .unwrap()
+                .into_response();
+            assert_eq!(resp.into_body().into_string().await.unwrap(), DATA);
+        }
+
+        let mut data = Vec::new();
+        ZstdEncoder::new(DATA.as_bytes())
+            .read_to_end(&mut data)
+            .await
+            .unwrap();
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "zstd")
+                    .body(data),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), DATA);
+
+        let resp = ep
+            .call(Request::builder().body(DATA))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), DATA);
+
+        let err = ep
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "compress")
+                    .body(DATA),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DecompressionError>(),
+            Some(&DecompressionError::UnsupportedEncoding(
+                "compress".to_string()
+            ))
+        );
+        assert_eq!(
+            err.as_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    #[tokio::test]
+    async fn limits() {
+        let data = vec![b'a'; 1024 * 1024];
+        let compressed = compress(CompressionAlgo::GZIP, &data).await;
+
+        for ep in [
+            index.with(Decompression::new().max_size(1024)).boxed(),
+            index.with(Decompression::new().max_ratio(10)).boxed(),
+        ] {
+            let err = ep
+                .call(
+                    Request::builder()
+                        .header("Content-Encoding", "gzip")
+                        .body(compressed.clone()),
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<DecompressionError>(),
+                Some(&DecompressionError::PayloadTooLarge)
+            );
+            assert_eq!(err.as_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        let ep = index.with(Decompression::new().max_size(1024 * 1024).max_ratio(2000));
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "gzip")
+                    .body(compressed),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap().len(),
+            data.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn default_limits() {
+        let data = vec![b'a'; 1024 * 1024];
+        let compressed = compress(CompressionAlgo::GZIP, &data).await;
+
+        let err = index
+            .with(Decompression::new())
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "gzip")
+                    .body(compressed.clone()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DecompressionError>(),
+            Some(&DecompressionError::PayloadTooLarge)
+        );
+
+        let resp = index
+            .with(Decompression::new().unlimited())
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "gzip")
+                    .body(compressed),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(
+            resp.into_body().into_string().await.unwrap().len(),
+            data.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn multiple_encodings() {
+        let ep = index.with(Decompression::new());
+        let data = compress(
+            CompressionAlgo::BR,
+            &compress(CompressionAlgo::GZIP, DATA.as_bytes()).await,
+        )
+        .await;
+
+        for req in [
+            Request::builder()
+                .header("Content-Encoding", "gzip, br")
+                .body(data.clone()),
+            Request::builder()
+                .header("Content-Encoding", "gzip")
+                .header("Content-Encoding", "br")
+                .body(data.clone()),
+        ] {
+            let err = ep.call(req).await.unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<DecompressionError>(),
+                Some(&DecompressionError::MultipleEncodings(
+                    "gzip, br".to_string()
+                ))
+            );
+            assert_eq!(
+                err.as_response().status(),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            );
+        }
+
+        let resp = ep
+            .call(
+                Request::builder()
+                    .header("Content-Encoding", "identity, gzip")
+                    .body(compress(CompressionAlgo::GZIP, DATA.as_bytes()).await),
+            )
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), DATA);
+    }
+}
@@ -0,0 +1,268 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::watch;
+
+use crate::{error::MaintenanceError, Endpoint, Middleware, Request, Result};
+
+/// A switch that turns the maintenance mode on and off at runtime.
+///
+/// It can be cloned and shared, for example with an admin endpoint or a
+/// signal handler, and the changes can be watched with
+/// [`MaintenanceSwitch::subscribe`].
+///
+/// See also: [`Maintenance`]
+#[derive(Debug, Clone)]
+pub struct MaintenanceSwitch(Arc<watch::Sender<bool>>);
+
+impl Default for MaintenanceSwitch {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl MaintenanceSwitch {
+    /// Create a switch, the maintenance mode is off.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Turns the maintenance mode on.
+    pub fn enable(&self) {
+        self.set(true);
+    }
+
+    /// Turns the maintenance mode off.
+    pub fn disable(&self) {
+        self.set(false);
+    }
+
+    /// Turns the maintenance mode on or off.
+    pub fn set(&self, enabled: bool) {
+        self.0.send_replace(enabled);
+    }
+
+    /// Returns `true` if the maintenance mode is on.
+    pub fn is_enabled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Returns a receiver that is notified when the maintenance mode changes.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.0.subscribe()
+    }
+}
+
+/// Middleware for the maintenance mode.
+///
+/// When the [`MaintenanceSwitch`] is on, the requests are rejected with
+/// [`MaintenanceError`], except for the allowed paths, such as the health
+/// checks.
+///
+/// # Errors
+///
+/// - [`MaintenanceError`]
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     handler,
+///     http::StatusCode,
+///     middleware::{Maintenance, MaintenanceSwitch},
+///     Endpoint, EndpointExt, Request, Route,
+/// };
+///
+/// #[handler]
+/// fn index() {}
+///
+/// #[handler]
+/// fn health() {}
+///
+/// let switch = MaintenanceSwitch::new();
+/// let app = Route::new().at("/", index).at("/health", health).with(
+///     Maintenance::new(switch.clone())
+///         .retry_after(Duration::from_secs(300))
+///         .allow_path("/health"),
+/// );
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// switch.enable();
+///
+/// let err = app.call(Request::default()).await.unwrap_err();
+/// let resp = err.as_response();
+/// assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+/// assert_eq!(resp.header("Retry-After"), Some("300"));
+///
+/// let resp = app
+///     .call(Request::builder().uri_str("/health").finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::OK);
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Maintenance {
+    switch: MaintenanceSwitch,
+    message: String,
+    retry_after: Option<Duration>,
+    allowed_paths: Vec<String>,
+}
+
+impl Maintenance {
+    /// Create `Maintenance` middleware with the switch.
+    pub fn new(switch: MaintenanceSwitch) -> Self {
+        Self {
+            switch,
+            message: "The service is under maintenance.".to_string(),
+            retry_after: None,
+            allowed_paths: Vec::new(),
+        }
+    }
+
+    /// Sets the message of the response.
+    #[must_use]
+    pub fn message(self, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..self
+        }
+    }
+
+    /// Sets the `Retry-After` header of the response.
+    #[must_use]
+    pub fn retry_after(self, duration: Duration) -> Self {
+        Self {
+            retry_after: Some(duration),
+            ..self
+        }
+    }
+
+    /// Allows the requests to the path during the maintenance, a path that
+    /// ends with `*` allows all the paths with the prefix.
+    #[must_use]
+    pub fn allow_path(mut self, path: impl Into<String>) -> Self {
+        self.allowed_paths.push(path.into());
+        self
+    }
+
+    /// Returns the switch of this middleware.
+    pub fn switch(&self) -> &MaintenanceSwitch {
+        &self.switch
+    }
+
+    /// Returns `true` if the path is allowed during the maintenance.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.allowed_paths
+            .iter()
+            .any(|allowed| match allowed.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => allowed == path,
+            })
+    }
+
+    /// Returns the error that is returned during the maintenance.
+    pub fn error(&self) -> MaintenanceError {
+        MaintenanceError {
+            message: self.message.clone(),
+            retry_after: self.retry_after.map(|duration| duration.as_secs()),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Maintenance {
+    type Output = MaintenanceEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        MaintenanceEndpoint {
+            inner: ep,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for Maintenance middleware.
+pub struct MaintenanceEndpoint<E> {
+    inner: E,
+    config: Maintenance,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for MaintenanceEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if self.config.switch.is_enabled() && !self.config.is_allowed(req.uri().path()) {
+            return Err(self.config.error().into());
+        }
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, EndpointExt};
+
+    #[handler(internal)]
+    fn index() {}
+
+    #[tokio::test]
+    async fn maintenance() {
+        let switch = MaintenanceSwitch::new();
+        let mut rx = switch.subscribe();
+        let ep = index.with(
+            Maintenance::new(switch.clone())
+                .message("back soon")
+                .allow_path("/health")
+                .allow_path("/admin/*"),
+        );
+
+        assert!(ep.call(Request::default()).await.is_ok());
+
+        switch.enable();
+        assert!(rx.has_changed().unwrap());
+        assert!(*rx.borrow_and_update());
+
+        let err = ep.call(Request::default()).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<MaintenanceError>(),
+            Some(&MaintenanceError {
+                message: "back soon".to_string(),
+                retry_after: None,
+            })
+        );
+        let resp = err.as_response();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.header("Retry-After"), None);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(
+                &resp.into_body().into_string().await.unwrap()
+            )
+            .unwrap(),
+            serde_json::json!({
+                "code": "maintenance",
+                "message": "back soon",
+                "retry_after": null,
+            })
+        );
+
+        for path in ["/health", "/admin/users"] {
+            assert!(ep
+                .call(Request::builder().uri_str(path).finish())
+                .await
+                .is_ok());
+        }
+        assert!(ep
+            .call(Request::builder().uri_str("/healthz").finish())
+            .await
+            .is_err());
+
+        switch.disable();
+        assert!(!switch.is_enabled());
+        assert!(ep.call(Request::default()).await.is_ok());
+    }
+}
@@ -8,19 +8,29 @@ mod cookie_jar_manager;
 mod cors;
 #[cfg(feature = "csrf")]
 mod csrf;
+#[cfg(feature = "compression")]
+mod decompression;
 mod force_https;
+mod maintenance;
 mod normalize_path;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_metrics;
 #[cfg(feature = "opentelemetry")]
 mod opentelemetry_tracing;
 mod propagate_header;
+mod request_deadline;
+mod scheduler;
+#[cfg(feature = "security-headers")]
+mod security_headers;
 mod sensitive_header;
 mod set_header;
 mod size_limit;
+mod tenancy;
 #[cfg(feature = "tower-compat")]
 mod tower_compat;
 mod tracing_mw;
+mod trusted_host;
+mod vary;
 
 pub use add_data::{AddData, AddDataEndpoint};
 #[cfg(feature = "compression")]
@@ -30,19 +40,32 @@ pub use cookie_jar_manager::{CookieJarManager, CookieJarManagerEndpoint};
 pub use cors::{Cors, CorsEndpoint};
 #[cfg(feature = "csrf")]
 pub use csrf::{Csrf, CsrfEndpoint};
+#[cfg(feature = "compression")]
+pub use decompression::{Decompression, DecompressionEndpoint};
 pub use force_https::ForceHttps;
+pub use maintenance::{Maintenance, MaintenanceEndpoint, MaintenanceSwitch};
 pub use normalize_path::{NormalizePath, NormalizePathEndpoint, TrailingSlash};
 #[cfg(feature = "opentelemetry")]
 pub use opentelemetry_metrics::{OpenTelemetryMetrics, OpenTelemetryMetricsEndpoint};
 #[cfg(feature = "opentelemetry")]
 pub use opentelemetry_tracing::{OpenTelemetryTracing, OpenTelemetryTracingEndpoint};
 pub use propagate_header::{PropagateHeader, PropagateHeaderEndpoint};
+pub use request_deadline::{RequestDeadline, RequestDeadlineEndpoint};
+pub use scheduler::{Priority, Scheduler, SchedulerEndpoint};
+#[cfg(feature = "security-headers")]
+pub use security_headers::{
+    ContentSecurityPolicy, ReferrerPolicy, SecurityHeaders, SecurityHeadersEndpoint,
+    StrictTransportSecurity,
+};
 pub use sensitive_header::{SensitiveHeader, SensitiveHeaderEndpoint};
 pub use set_header::{SetHeader, SetHeaderEndpoint};
 pub use size_limit::{SizeLimit, SizeLimitEndpoint};
+pub use tenancy::{Tenancy, TenancyEndpoint};
 #[cfg(feature = "tower-compat")]
 pub use tower_compat::TowerLayerCompatExt;
 pub use tracing_mw::{Tracing, TracingEndpoint};
+pub use trusted_host::{TrustedHost, TrustedHostEndpoint};
+pub use vary::{Vary, VaryEndpoint};
 
 use crate::endpoint::Endpoint;
 
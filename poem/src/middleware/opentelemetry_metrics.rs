@@ -1,19 +1,31 @@
-use std::time::Instant;
+use std::{collections::HashSet, sync::Arc, time::Instant};
 
 use libopentelemetry::{
     global,
     metrics::{Counter, Unit, ValueRecorder},
+    Key,
 };
 use opentelemetry_semantic_conventions::trace;
 
-use crate::{Endpoint, IntoResponse, Middleware, Request, Response, Result};
+use crate::{web::Tenant, Endpoint, IntoResponse, Middleware, Request, Response, Result};
+
+const TENANT_KEY: Key = Key::from_static_str("tenant");
+const OTHER_TENANT: &str = "other";
 
 /// Middleware for metrics with OpenTelemetry.
+///
+/// If the [`Tenant`] has been resolved by the
+/// [`Tenancy`](crate::middleware::Tenancy) middleware applied after this
+/// middleware, the metrics are labeled with the `tenant`. The tenant ids are
+/// supplied by the clients, so only the tenants added with
+/// [`OpenTelemetryMetrics::tenants`] are used as the label, the other tenants
+/// are labeled with `other`.
 #[cfg_attr(docsrs, doc(cfg(feature = "opentelemetry")))]
 pub struct OpenTelemetryMetrics {
     request_count: Counter<u64>,
     error_count: Counter<u64>,
     duration: ValueRecorder<f64>,
+    tenants: Arc<HashSet<String>>,
 }
 
 impl Default for OpenTelemetryMetrics {
@@ -42,6 +54,21 @@ impl OpenTelemetryMetrics {
                     "request duration histogram (in milliseconds, since start of service)",
                 )
                 .init(),
+            tenants: Default::default(),
+        }
+    }
+
+    /// Sets the known tenants that are used as the `tenant` label of the
+    /// metrics.
+    #[must_use]
+    pub fn tenants<I, T>(self, tenants: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            tenants: Arc::new(tenants.into_iter().map(Into::into).collect()),
+            ..self
         }
     }
 }
@@ -54,6 +81,7 @@ impl<E: Endpoint> Middleware<E> for OpenTelemetryMetrics {
             request_count: self.request_count.clone(),
             error_count: self.error_count.clone(),
             duration: self.duration.clone(),
+            tenants: self.tenants.clone(),
             inner: ep,
         }
     }
@@ -65,6 +93,7 @@ pub struct OpenTelemetryMetricsEndpoint<E> {
     request_count: Counter<u64>,
     error_count: Counter<u64>,
     duration: ValueRecorder<f64>,
+    tenants: Arc<HashSet<String>>,
     inner: E,
 }
 
@@ -73,9 +102,17 @@ impl<E: Endpoint> Endpoint for OpenTelemetryMetricsEndpoint<E> {
     type Output = Response;
 
     async fn call(&self, req: Request) -> Result<Self::Output> {
-        let mut labels = Vec::with_capacity(3);
+        let mut labels = Vec::with_capacity(4);
         labels.push(trace::HTTP_METHOD.string(req.method().to_string()));
         labels.push(trace::HTTP_TARGET.string(req.uri().path().to_string()));
+        if let Some(tenant) = req.data::<Tenant>() {
+            let tenant = if self.tenants.contains(tenant.id()) {
+                tenant.id()
+            } else {
+                OTHER_TENANT
+            };
+            labels.push(TENANT_KEY.string(tenant.to_string()));
+        }
 
         let s = Instant::now();
         let res = self.inner.call(req).await.map(IntoResponse::into_response);
@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use crate::{
+    error::DeadlineExceededError,
+    web::{deadline::parse_timeout, Deadline},
+    Endpoint, Middleware, Request, Result,
+};
+
+/// Middleware for enforcing the deadline of the request specified by the
+/// client.
+///
+/// The deadline is read from the `Request-Timeout` or `grpc-timeout` headers
+/// and inserted into the request as a [`Deadline`]. When it is exceeded, the
+/// inner endpoint is aborted, the
+/// [`CancellationToken`](tokio_util::sync::CancellationToken) of the deadline
+/// is cancelled and `408 Request Timeout` is returned.
+///
+/// # Errors
+///
+/// - [`DeadlineExceededError`]
+#[derive(Default)]
+pub struct RequestDeadline {
+    default_timeout: Option<Duration>,
+    max_timeout: Option<Duration>,
+}
+
+impl RequestDeadline {
+    /// Create `RequestDeadline` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the timeout used when the request does not specify a deadline.
+    #[must_use]
+    pub fn default_timeout(self, timeout: Duration) -> Self {
+        Self {
+            default_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sets the maximum timeout, the deadlines specified by the clients are
+    /// capped to it, and it is also used when the request does not specify a
+    /// deadline and there is no default timeout.
+    #[must_use]
+    pub fn max_timeout(self, timeout: Duration) -> Self {
+        Self {
+            max_timeout: Some(timeout),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestDeadline {
+    type Output = RequestDeadlineEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestDeadlineEndpoint {
+            inner: ep,
+            default_timeout: self.default_timeout,
+            max_timeout: self.max_timeout,
+        }
+    }
+}
+
+/// Endpoint for RequestDeadline middleware.
+pub struct RequestDeadlineEndpoint<E> {
+    inner: E,
+    default_timeout: Option<Duration>,
+    max_timeout: Option<Duration>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for RequestDeadlineEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let timeout = match (
+            parse_timeout(req.headers()).or(self.default_timeout),
+            self.max_timeout,
+        ) {
+            (Some(timeout), Some(max_timeout)) => Some(timeout.min(max_timeout)),
+            (timeout, max_timeout) => timeout.or(max_timeout),
+        };
+
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => {
+                req.extensions_mut().insert(Deadline::new(None));
+                return self.inner.call(req).await;
+            }
+        };
+
+        let deadline = Deadline::new(Some(timeout));
+        let token = deadline.cancellation_token().clone();
+        req.extensions_mut().insert(deadline);
+        if timeout.is_zero() {
+            token.cancel();
+            return Err(DeadlineExceededError.into());
+        }
+
+        tokio::select! {
+            res = self.inner.call(req) => res,
+            _ = tokio::time::sleep(timeout) => {
+                token.cancel();
+                Err(DeadlineExceededError.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, EndpointExt, IntoResponse};
+
+    #[handler(internal)]
+    async fn index(deadline: Deadline) -> String {
+        let token = deadline.cancellation_token().clone();
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(200)) => "done".to_string(),
+            _ = token.cancelled() => "cancelled".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn request_deadline() {
+        let ep = index.with(RequestDeadline::new());
+
+        let mut resp = ep.call(Request::default()).await.unwrap().into_response();
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "done");
+
+        let mut resp = ep
+            .call(Request::builder().header("request-timeout", "1").finish())
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(resp.take_body().into_string().await.unwrap(), "done");
+
+        let err = ep
+            .call(Request::builder().header("grpc-timeout", "10m").finish())
+            .await
+            .unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::REQUEST_TIMEOUT);
+        assert!(err.is::<DeadlineExceededError>());
+
+        let err = ep
+            .call(Request::builder().header("request-timeout", "0").finish())
+            .await
+            .unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn max_timeout() {
+        let ep = index.with(RequestDeadline::new().max_timeout(Duration::from_millis(10)));
+        let err = ep
+            .call(Request::builder().header("grpc-timeout", "5S").finish())
+            .await
+            .unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::REQUEST_TIMEOUT);
+
+        let ep = index.with(RequestDeadline::new().default_timeout(Duration::from_millis(10)));
+        let err = ep.call(Request::default()).await.unwrap_err();
+        assert_eq!(err.as_response().status(), StatusCode::REQUEST_TIMEOUT);
+    }
+}
@@ -0,0 +1,454 @@
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use crate::{error::OverloadedError, Endpoint, Middleware, Request, Result};
+
+/// The priority of a request in the [`Scheduler`] middleware.
+///
+/// The [`Scheduler`] reads it from the extensions of the request, the
+/// requests without the priority are [`Priority::Normal`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Low priority, shed first.
+    Low,
+    /// Normal priority.
+    Normal,
+    /// High priority.
+    High,
+    /// Critical priority, shed last.
+    Critical,
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+type ClassifyFn = Arc<dyn Fn(&Request) -> Priority + Send + Sync>;
+
+struct State {
+    running: usize,
+    seq: u64,
+    /// The waiting requests, the last one is the oldest request with the
+    /// highest priority.
+    queue: BTreeMap<(Priority, Reverse<u64>), oneshot::Sender<()>>,
+}
+
+struct Shared {
+    max_concurrency: usize,
+    state: Mutex<State>,
+}
+
+impl Shared {
+    /// Hands the permit to the next waiting request, or returns it if there is
+    /// none.
+    fn release(&self) {
+        let mut state = self.state.lock();
+        while let Some(key) = state.queue.keys().next_back().copied() {
+            let tx = state.queue.remove(&key).unwrap();
+            if tx.send(()).is_ok() {
+                return;
+            }
+        }
+        state.running -= 1;
+    }
+}
+
+struct Permit(Arc<Shared>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.release();
+    }
+}
+
+struct Waiter {
+    shared: Arc<Shared>,
+    key: (Priority, Reverse<u64>),
+    rx: oneshot::Receiver<()>,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        // If the permit was handed over after the request gave up, it must be
+        // passed on.
+        let granted = {
+            let mut state = self.shared.state.lock();
+            state.queue.remove(&self.key).is_none() && self.rx.try_recv().is_ok()
+        };
+        if granted {
+            self.shared.release();
+        }
+    }
+}
+
+/// Middleware for scheduling the requests by their [`Priority`] when the
+/// service is saturated.
+///
+/// At most `max_concurrency` requests are processed concurrently. When the
+/// limit is reached, the new requests wait in a queue and are started in
+/// order of priority, the oldest first. When the queue is full, a new request
+/// evicts the newest waiting request with a lower priority, or is shed if
+/// there is none. The requests at or below the [`Scheduler::shed`] priority
+/// are shed immediately instead of waiting.
+///
+/// The priority is read from the extensions of the request, or computed with
+/// [`Scheduler::classify`]. The endpoints wrapped with the same `Scheduler`
+/// share the limit.
+///
+/// # Errors
+///
+/// - [`OverloadedError`]
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{
+///     handler,
+///     middleware::{Priority, Scheduler},
+///     EndpointExt, Route,
+/// };
+///
+/// #[handler]
+/// fn checkout() {}
+///
+/// #[handler]
+/// fn recommendations() {}
+///
+/// let app = Route::new()
+///     .at("/checkout", checkout)
+///     .at("/recommendations", recommendations)
+///     .with(
+///         Scheduler::new(64)
+///             .max_queue(256)
+///             .queue_timeout(Duration::from_secs(5))
+///             .shed(Priority::Low)
+///             .classify(|req| match req.uri().path() {
+///                 "/checkout" => Priority::High,
+///                 "/recommendations" => Priority::Low,
+///                 _ => Priority::Normal,
+///             }),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct Scheduler {
+    shared: Arc<Shared>,
+    max_queue: usize,
+    queue_timeout: Option<Duration>,
+    shed: Option<Priority>,
+    classify: Option<ClassifyFn>,
+}
+
+impl Debug for Scheduler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("max_concurrency", &self.shared.max_concurrency)
+            .field("max_queue", &self.max_queue)
+            .field("queue_timeout", &self.queue_timeout)
+            .field("shed", &self.shed)
+            .finish()
+    }
+}
+
+impl Scheduler {
+    /// Create `Scheduler` middleware that processes at most `max_concurrency`
+    /// requests concurrently.
+    ///
+    /// The maximum length of the queue is `max_concurrency` by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_concurrency` is zero.
+    pub fn new(max_concurrency: usize) -> Self {
+        assert!(max_concurrency > 0, "`max_concurrency` must be positive");
+        Self {
+            shared: Arc::new(Shared {
+                max_concurrency,
+                state: Mutex::new(State {
+                    running: 0,
+                    seq: 0,
+                    queue: BTreeMap::new(),
+                }),
+            }),
+            max_queue: max_concurrency,
+            queue_timeout: None,
+            shed: None,
+            classify: None,
+        }
+    }
+
+    /// Sets the maximum number of the waiting requests.
+    #[must_use]
+    pub fn max_queue(self, max_queue: usize) -> Self {
+        Self { max_queue, ..self }
+    }
+
+    /// Sets how long a request waits in the queue before it is shed.
+    #[must_use]
+    pub fn queue_timeout(self, timeout: impl Into<Option<Duration>>) -> Self {
+        Self {
+            queue_timeout: timeout.into(),
+            ..self
+        }
+    }
+
+    /// Sheds the requests at or below the priority immediately when the
+    /// service is saturated, instead of queuing them.
+    #[must_use]
+    pub fn shed(self, priority: impl Into<Option<Priority>>) -> Self {
+        Self {
+            shed: priority.into(),
+            ..self
+        }
+    }
+
+    /// Sets the function that computes the priority of the requests, it
+    /// overrides the priority in the extensions of the request.
+    #[must_use]
+    pub fn classify(self, f: impl Fn(&Request) -> Priority + Send + Sync + 'static) -> Self {
+        Self {
+            classify: Some(Arc::new(f)),
+            ..self
+        }
+    }
+
+    /// Returns the number of the requests that are being processed.
+    pub fn running(&self) -> usize {
+        self.shared.state.lock().running
+    }
+
+    /// Returns the number of the waiting requests.
+    pub fn queued(&self) -> usize {
+        self.shared.state.lock().queue.len()
+    }
+
+    async fn acquire(&self, priority: Priority) -> Result<Permit, OverloadedError> {
+        let (key, rx) = {
+            let mut state = self.shared.state.lock();
+            if state.running < self.shared.max_concurrency {
+                state.running += 1;
+                return Ok(Permit(self.shared.clone()));
+            }
+            if matches!(self.shed, Some(shed) if priority <= shed) {
+                return Err(OverloadedError);
+            }
+            if state.queue.len() >= self.max_queue {
+                // Dropping the sender sheds the evicted request.
+                match state.queue.keys().next().copied() {
+                    Some(lowest) if lowest.0 < priority => {
+                        state.queue.remove(&lowest);
+                    }
+                    _ => return Err(OverloadedError),
+                }
+            }
+            state.seq += 1;
+            let key = (priority, Reverse(state.seq));
+            let (tx, rx) = oneshot::channel();
+            state.queue.insert(key, tx);
+            (key, rx)
+        };
+
+        let mut waiter = Waiter {
+            shared: self.shared.clone(),
+            key,
+            rx,
+        };
+        let granted = match self.queue_timeout {
+            Some(timeout) => matches!(
+                tokio::time::timeout(timeout, &mut waiter.rx).await,
+                Ok(Ok(()))
+            ),
+            None => (&mut waiter.rx).await.is_ok(),
+        };
+        if granted {
+            Ok(Permit(self.shared.clone()))
+        } else {
+            Err(OverloadedError)
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Scheduler {
+    type Output = SchedulerEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        SchedulerEndpoint {
+            inner: ep,
+            scheduler: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for Scheduler middleware.
+pub struct SchedulerEndpoint<E> {
+    inner: E,
+    scheduler: Scheduler,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for SchedulerEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let priority = match &self.scheduler.classify {
+            Some(classify) => classify(&req),
+            None => req.data().copied().unwrap_or_default(),
+        };
+        let _permit = self.scheduler.acquire(priority).await?;
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use http::StatusCode;
+    use tokio::sync::{mpsc, Semaphore};
+
+    use super::*;
+    use crate::{handler, web::Data, EndpointExt};
+
+    #[handler(internal)]
+    async fn index(req: &Request, Data(gate): Data<&Arc<Semaphore>>) -> String {
+        gate.acquire().await.unwrap().forget();
+        req.uri().path().to_string()
+    }
+
+    async fn wait_queued(scheduler: &Scheduler, n: usize) {
+        while scheduler.queued() != n {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn priority_order() {
+        let gate = Arc::new(Semaphore::new(0));
+        let scheduler = Scheduler::new(1).max_queue(3).shed(Priority::Low);
+        let ep = Arc::new(index.data(gate.clone()).with(scheduler.clone()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let spawn = |path: &'static str, priority: Priority| {
+            let ep = ep.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let mut req = Request::builder().uri_str(path).finish();
+                req.extensions_mut().insert(priority);
+                let res = match ep.call(req).await {
+                    Ok(resp) => Ok(resp.into_body().into_string().await.unwrap()),
+                    Err(err) => Err(err.as_response().status()),
+                };
+                tx.send((path, res)).unwrap();
+            })
+        };
+
+        spawn("/running", Priority::Normal);
+        while scheduler.running() != 1 {
+            tokio::task::yield_now().await;
+        }
+
+        // shed immediately
+        spawn("/low", Priority::Low);
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            ("/low", Err(StatusCode::SERVICE_UNAVAILABLE))
+        );
+
+        spawn("/normal1", Priority::Normal);
+        wait_queued(&scheduler, 1).await;
+        spawn("/normal2", Priority::Normal);
+        wait_queued(&scheduler, 2).await;
+        spawn("/high", Priority::High);
+        wait_queued(&scheduler, 3).await;
+
+        // evicts the newest normal request
+        spawn("/critical", Priority::Critical);
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            ("/normal2", Err(StatusCode::SERVICE_UNAVAILABLE))
+        );
+        wait_queued(&scheduler, 3).await;
+
+        // the queue is full of requests with the same or higher priority
+        spawn("/normal3", Priority::Normal);
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            ("/normal3", Err(StatusCode::SERVICE_UNAVAILABLE))
+        );
+
+        gate.add_permits(4);
+        for path in ["/running", "/critical", "/high", "/normal1"] {
+            assert_eq!(rx.recv().await.unwrap(), (path, Ok(path.to_string())));
+        }
+        assert_eq!(scheduler.running(), 0);
+        assert_eq!(scheduler.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn queue_timeout() {
+        let gate = Arc::new(Semaphore::new(0));
+        let scheduler = Scheduler::new(1)
+            .queue_timeout(Duration::from_millis(50))
+            .classify(|_| Priority::High);
+        let ep = Arc::new(index.data(gate.clone()).with(scheduler.clone()));
+
+        let running = tokio::spawn({
+            let ep = ep.clone();
+            async move { ep.call(Request::default()).await.is_ok() }
+        });
+        while scheduler.running() != 1 {
+            tokio::task::yield_now().await;
+        }
+
+        let err = ep.call(Request::default()).await.unwrap_err();
+        assert!(err.is::<OverloadedError>());
+        assert_eq!(scheduler.queued(), 0);
+
+        gate.add_permits(2);
+        assert!(running.await.unwrap());
+        assert!(ep.call(Request::default()).await.is_ok());
+        assert_eq!(scheduler.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn priority_of_sub_request() {
+        let gate = Arc::new(Semaphore::new(0));
+        let scheduler = Scheduler::new(1)
+            .shed(Priority::Normal)
+            .queue_timeout(Duration::from_millis(50));
+        let ep = Arc::new(index.data(gate.clone()).with(scheduler.clone()));
+
+        let running = tokio::spawn({
+            let ep = ep.clone();
+            async move { ep.call(Request::default()).await.is_ok() }
+        });
+        while scheduler.running() != 1 {
+            tokio::task::yield_now().await;
+        }
+
+        // the priority of the parent request queues it until the timeout
+        // instead of shedding it
+        let mut req = Request::default();
+        req.extensions_mut().insert(Priority::High);
+        let start = Instant::now();
+        let err = ep.call(req.sub_request().finish()).await.unwrap_err();
+        assert!(err.is::<OverloadedError>());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        gate.add_permits(1);
+        assert!(running.await.unwrap());
+    }
+}
@@ -0,0 +1,532 @@
+use std::time::Duration;
+
+use rand::RngCore;
+
+use crate::{
+    http::{header, HeaderMap, HeaderValue},
+    web::CspNonce,
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// A `Content-Security-Policy`.
+///
+/// The default policy is `default-src 'self'`.
+#[cfg_attr(docsrs, doc(cfg(feature = "security-headers")))]
+#[derive(Debug, Clone)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<(String, String)>,
+    nonce: bool,
+}
+
+impl Default for ContentSecurityPolicy {
+    fn default() -> Self {
+        Self::empty().directive("default-src", "'self'")
+    }
+}
+
+impl ContentSecurityPolicy {
+    /// Create the default policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a policy without any directives.
+    pub fn empty() -> Self {
+        Self {
+            directives: Vec::new(),
+            nonce: false,
+        }
+    }
+
+    /// Sets a directive, such as `script-src`, it replaces the previous value
+    /// of the directive.
+    #[must_use]
+    pub fn directive(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        let value = value.into();
+        match self.directives.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.directives.push((name, value)),
+        }
+        self
+    }
+
+    /// Generates a nonce for each request and allows it in `script-src` and
+    /// `style-src`.
+    ///
+    /// If these directives are not set, they are created from `default-src`.
+    /// The nonce can be extracted with [`CspNonce`].
+    #[must_use]
+    pub fn nonce(self) -> Self {
+        Self {
+            nonce: true,
+            ..self
+        }
+    }
+
+    fn to_header_value(&self, nonce: Option<&str>) -> Option<HeaderValue> {
+        let mut directives = self.directives.clone();
+        if let Some(nonce) = nonce {
+            let default_src = directives
+                .iter()
+                .find(|(name, _)| name == "default-src")
+                .map(|(_, value)| value.clone())
+                .unwrap_or_else(|| "'self'".to_string());
+            for name in ["script-src", "style-src"] {
+                let source = format!("'nonce-{}'", nonce);
+                match directives.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, value)) => {
+                        value.push(' ');
+                        value.push_str(&source);
+                    }
+                    None => {
+                        directives.push((name.to_string(), format!("{} {}", default_src, source)))
+                    }
+                }
+            }
+        }
+
+        let value = directives
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        HeaderValue::from_str(&value).ok()
+    }
+}
+
+/// A `Strict-Transport-Security` policy.
+///
+/// The default policy is `max-age=31536000; includeSubDomains`.
+#[cfg_attr(docsrs, doc(cfg(feature = "security-headers")))]
+#[derive(Debug, Clone)]
+pub struct StrictTransportSecurity {
+    max_age: Duration,
+    include_subdomains: bool,
+    preload: bool,
+}
+
+impl Default for StrictTransportSecurity {
+    fn default() -> Self {
+        Self {
+            max_age: Duration::from_secs(365 * 24 * 60 * 60),
+            include_subdomains: true,
+            preload: false,
+        }
+    }
+}
+
+impl StrictTransportSecurity {
+    /// Create the default policy.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the time that the browser should only use HTTPS.
+    #[must_use]
+    pub fn max_age(self, max_age: Duration) -> Self {
+        Self { max_age, ..self }
+    }
+
+    /// Sets whether the policy applies to the subdomains. Default is `true`.
+    #[must_use]
+    pub fn include_subdomains(self, value: bool) -> Self {
+        Self {
+            include_subdomains: value,
+            ..self
+        }
+    }
+
+    /// Sets whether to add the `preload` directive. Default is `false`.
+    #[must_use]
+    pub fn preload(self, value: bool) -> Self {
+        Self {
+            preload: value,
+            ..self
+        }
+    }
+
+    fn to_header_value(&self) -> HeaderValue {
+        let mut value = format!("max-age={}", self.max_age.as_secs());
+        if self.include_subdomains {
+            value.push_str("; includeSubDomains");
+        }
+        if self.preload {
+            value.push_str("; preload");
+        }
+        HeaderValue::from_str(&value).unwrap()
+    }
+}
+
+/// A `Referrer-Policy`.
+#[cfg_attr(docsrs, doc(cfg(feature = "security-headers")))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ReferrerPolicy {
+    /// `no-referrer`
+    NoReferrer,
+    /// `no-referrer-when-downgrade`
+    NoReferrerWhenDowngrade,
+    /// `origin`
+    Origin,
+    /// `origin-when-cross-origin`
+    OriginWhenCrossOrigin,
+    /// `same-origin`
+    SameOrigin,
+    /// `strict-origin`
+    StrictOrigin,
+    /// `strict-origin-when-cross-origin`
+    StrictOriginWhenCrossOrigin,
+    /// `unsafe-url`
+    UnsafeUrl,
+}
+
+impl ReferrerPolicy {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReferrerPolicy::NoReferrer => "no-referrer",
+            ReferrerPolicy::NoReferrerWhenDowngrade => "no-referrer-when-downgrade",
+            ReferrerPolicy::Origin => "origin",
+            ReferrerPolicy::OriginWhenCrossOrigin => "origin-when-cross-origin",
+            ReferrerPolicy::SameOrigin => "same-origin",
+            ReferrerPolicy::StrictOrigin => "strict-origin",
+            ReferrerPolicy::StrictOriginWhenCrossOrigin => "strict-origin-when-cross-origin",
+            ReferrerPolicy::UnsafeUrl => "unsafe-url",
+        }
+    }
+}
+
+/// Middleware for setting the security headers of the responses.
+///
+/// By default, it sets:
+///
+/// - `Content-Security-Policy: default-src 'self'`
+/// - `Strict-Transport-Security: max-age=31536000; includeSubDomains`
+/// - `X-Content-Type-Options: nosniff`
+/// - `Referrer-Policy: strict-origin-when-cross-origin`
+///
+/// The headers that have already been set on the response are not
+/// replaced, so a route can override the headers by applying another
+/// `SecurityHeaders` to it. The inner middleware reuses the [`CspNonce`] of
+/// the outer one.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     middleware::{ContentSecurityPolicy, SecurityHeaders},
+///     web::{CspNonce, Html},
+///     Endpoint, EndpointExt, Request, Route,
+/// };
+///
+/// #[handler]
+/// fn index(nonce: &CspNonce) -> Html<String> {
+///     Html(format!(r#"<script nonce="{}">alert(1)</script>"#, nonce.as_str()))
+/// }
+///
+/// #[handler]
+/// fn api() -> &'static str {
+///     "hello"
+/// }
+///
+/// let app = Route::new()
+///     .at(
+///         "/",
+///         index.with(SecurityHeaders::new().content_security_policy(
+///             ContentSecurityPolicy::new().nonce(),
+///         )),
+///     )
+///     .at("/api", api)
+///     .with(SecurityHeaders::new());
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = app.call(Request::builder().uri_str("/api").finish()).await.unwrap();
+/// assert_eq!(
+///     resp.headers().get("Content-Security-Policy").unwrap(),
+///     "default-src 'self'"
+/// );
+/// # });
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "security-headers")))]
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    csp: Option<ContentSecurityPolicy>,
+    hsts: Option<StrictTransportSecurity>,
+    content_type_options: bool,
+    referrer_policy: Option<ReferrerPolicy>,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            csp: Some(ContentSecurityPolicy::new()),
+            hsts: Some(StrictTransportSecurity::new()),
+            content_type_options: true,
+            referrer_policy: Some(ReferrerPolicy::StrictOriginWhenCrossOrigin),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    /// Create `SecurityHeaders` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the `Content-Security-Policy`, `None` to not set the header.
+    #[must_use]
+    pub fn content_security_policy(self, csp: impl Into<Option<ContentSecurityPolicy>>) -> Self {
+        Self {
+            csp: csp.into(),
+            ..self
+        }
+    }
+
+    /// Sets the `Strict-Transport-Security`, `None` to not set the header.
+    #[must_use]
+    pub fn strict_transport_security(
+        self,
+        hsts: impl Into<Option<StrictTransportSecurity>>,
+    ) -> Self {
+        Self {
+            hsts: hsts.into(),
+            ..self
+        }
+    }
+
+    /// Sets whether to set `X-Content-Type-Options: nosniff`. Default is
+    /// `true`.
+    #[must_use]
+    pub fn content_type_options(self, value: bool) -> Self {
+        Self {
+            content_type_options: value,
+            ..self
+        }
+    }
+
+    /// Sets the `Referrer-Policy`, `None` to not set the header. Default is
+    /// [`ReferrerPolicy::StrictOriginWhenCrossOrigin`].
+    #[must_use]
+    pub fn referrer_policy(self, policy: impl Into<Option<ReferrerPolicy>>) -> Self {
+        Self {
+            referrer_policy: policy.into(),
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for SecurityHeaders {
+    type Output = SecurityHeadersEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        let mut headers = HeaderMap::new();
+        if let Some(hsts) = &self.hsts {
+            headers.insert(header::STRICT_TRANSPORT_SECURITY, hsts.to_header_value());
+        }
+        if self.content_type_options {
+            headers.insert(
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            );
+        }
+        if let Some(policy) = self.referrer_policy {
+            headers.insert(
+                header::REFERRER_POLICY,
+                HeaderValue::from_static(policy.as_str()),
+            );
+        }
+
+        SecurityHeadersEndpoint {
+            inner: ep,
+            csp: self.csp.clone(),
+            headers,
+        }
+    }
+}
+
+/// Endpoint for SecurityHeaders middleware.
+#[cfg_attr(docsrs, doc(cfg(feature = "security-headers")))]
+pub struct SecurityHeadersEndpoint<E> {
+    inner: E,
+    csp: Option<ContentSecurityPolicy>,
+    headers: HeaderMap,
+}
+
+fn generate_nonce() -> String {
+    let mut nonce = [0; 16];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    base64::encode(nonce)
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for SecurityHeadersEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let nonce = match &self.csp {
+            Some(csp) if csp.nonce => {
+                let nonce = match req.data::<CspNonce>() {
+                    Some(nonce) => nonce.0.clone(),
+                    None => generate_nonce(),
+                };
+                req.extensions_mut().insert(CspNonce(nonce.clone()));
+                Some(nonce)
+            }
+            _ => None,
+        };
+
+        let mut resp = self.inner.call(req).await?.into_response();
+        let headers = resp.headers_mut();
+
+        if let Some(csp) = &self.csp {
+            if !headers.contains_key(header::CONTENT_SECURITY_POLICY) {
+                if let Some(value) = csp.to_header_value(nonce.as_deref()) {
+                    headers.insert(header::CONTENT_SECURITY_POLICY, value);
+                }
+            }
+        }
+        for (name, value) in &self.headers {
+            if !headers.contains_key(name) {
+                headers.insert(name, value.clone());
+            }
+        }
+
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{handler, EndpointExt, Route};
+
+    #[handler(internal)]
+    fn index() -> &'static str {
+        "hello"
+    }
+
+    #[handler(internal)]
+    fn page(nonce: &CspNonce) -> String {
+        nonce.0.clone()
+    }
+
+    #[tokio::test]
+    async fn default_headers() {
+        let ep = index.with(SecurityHeaders::new());
+        let resp = ep.call(Request::default()).await.unwrap();
+        assert_eq!(
+            resp.headers().get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            "default-src 'self'"
+        );
+        assert_eq!(
+            resp.headers()
+                .get(header::STRICT_TRANSPORT_SECURITY)
+                .unwrap(),
+            "max-age=31536000; includeSubDomains"
+        );
+        assert_eq!(
+            resp.headers().get(header::X_CONTENT_TYPE_OPTIONS).unwrap(),
+            "nosniff"
+        );
+        assert_eq!(
+            resp.headers().get(header::REFERRER_POLICY).unwrap(),
+            "strict-origin-when-cross-origin"
+        );
+    }
+
+    #[tokio::test]
+    async fn custom_headers() {
+        let ep = index.with(
+            SecurityHeaders::new()
+                .content_security_policy(
+                    ContentSecurityPolicy::empty()
+                        .directive("default-src", "'none'")
+                        .directive("img-src", "*"),
+                )
+                .strict_transport_security(
+                    StrictTransportSecurity::new()
+                        .max_age(Duration::from_secs(60))
+                        .include_subdomains(false)
+                        .preload(true),
+                )
+                .content_type_options(false)
+                .referrer_policy(None),
+        );
+        let resp = ep.call(Request::default()).await.unwrap();
+        assert_eq!(
+            resp.headers().get(header::CONTENT_SECURITY_POLICY).unwrap(),
+            "default-src 'none'; img-src *"
+        );
+        assert_eq!(
+            resp.headers()
+                .get(header::STRICT_TRANSPORT_SECURITY)
+                .unwrap(),
+            "max-age=60; preload"
+        );
+        assert!(!resp.headers().contains_key(header::X_CONTENT_TYPE_OPTIONS));
+        assert!(!resp.headers().contains_key(header::REFERRER_POLICY));
+    }
+
+    #[tokio::test]
+    async fn nonce_and_override() {
+        let app = Route::new()
+            .at(
+                "/page",
+                page.with(
+                    SecurityHeaders::new()
+                        .content_security_policy(
+                            ContentSecurityPolicy::new()
+                                .directive("style-src", "'self' https://cdn.example.com")
+                                .nonce(),
+                        )
+                        .referrer_policy(ReferrerPolicy::NoReferrer),
+                ),
+            )
+            .at("/", index)
+            .with(
+                SecurityHeaders::new()
+                    .content_security_policy(ContentSecurityPolicy::new().nonce()),
+            );
+
+        let resp = app
+            .call(Request::builder().uri_str("/page").finish())
+            .await
+            .unwrap();
+        let csp = resp
+            .headers()
+            .get(header::CONTENT_SECURITY_POLICY)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            resp.headers().get(header::REFERRER_POLICY).unwrap(),
+            "no-referrer"
+        );
+        let nonce = resp.into_body().into_string().await.unwrap();
+        assert_eq!(
+            csp,
+            format!(
+                "default-src 'self'; style-src 'self' https://cdn.example.com 'nonce-{0}'; \
+                 script-src 'self' 'nonce-{0}'",
+                nonce
+            )
+        );
+
+        let resp = app.call(Request::default()).await.unwrap();
+        let csp = resp.headers().get(header::CONTENT_SECURITY_POLICY).unwrap();
+        assert!(!csp.to_str().unwrap().contains(&nonce));
+        assert_eq!(
+            resp.headers().get(header::REFERRER_POLICY).unwrap(),
+            "strict-origin-when-cross-origin"
+        );
+    }
+
+    #[tokio::test]
+    async fn sub_request_nonce() {
+        let mut req = Request::default();
+        req.extensions_mut().insert(CspNonce("abc".to_string()));
+        let resp = page.call(req.sub_request().finish()).await.unwrap();
+        assert_eq!(resp.into_body().into_string().await.unwrap(), "abc");
+    }
+}
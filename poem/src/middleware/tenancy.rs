@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use crate::{
+    error::MissingTenantError, http::header, web::Tenant, Endpoint, Middleware, Request, Result,
+};
+
+type TenantResolver = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// Middleware for resolving the [`Tenant`] of the request.
+///
+/// The sources are tried in the order they are added, the first one that
+/// resolves a tenant wins. Use [`Tenancy::with_fn`] to read the tenant from
+/// a claim of a token.
+///
+/// If no tenant is resolved, the request is rejected with
+/// [`MissingTenantError`], unless [`Tenancy::optional`] is called.
+///
+/// # Errors
+///
+/// - [`MissingTenantError`]
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::header, middleware::Tenancy, web::Tenant, Endpoint, EndpointExt, Request,
+/// };
+///
+/// #[handler]
+/// fn index(tenant: Tenant) -> String {
+///     tenant.id().to_string()
+/// }
+///
+/// let app = index.with(
+///     Tenancy::new()
+///         .subdomain("example.com")
+///         .header(Tenant::HEADER),
+/// );
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut resp = app
+///     .call(
+///         Request::builder()
+///             .header(header::HOST, "acme.example.com")
+///             .finish(),
+///     )
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "acme");
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct Tenancy {
+    resolvers: Vec<TenantResolver>,
+    required: bool,
+}
+
+impl Default for Tenancy {
+    fn default() -> Self {
+        Self {
+            resolvers: Vec::new(),
+            required: true,
+        }
+    }
+}
+
+impl Tenancy {
+    /// Create `Tenancy` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Resolves the tenant from the header.
+    #[must_use]
+    pub fn header(self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        self.with_fn(move |req| req.header(&name).map(ToString::to_string))
+    }
+
+    /// Resolves the tenant from the subdomain of the base domain, for example
+    /// `acme` from `acme.example.com`.
+    #[must_use]
+    pub fn subdomain(self, base_domain: impl Into<String>) -> Self {
+        let suffix = format!(".{}", base_domain.into().to_ascii_lowercase());
+        self.with_fn(move |req| {
+            let host = req
+                .headers()
+                .get(header::HOST)
+                .and_then(|value| value.to_str().ok())
+                .or_else(|| req.uri().host())?;
+            let host = host.split(':').next().unwrap_or_default();
+            let tenant = host.to_ascii_lowercase().strip_suffix(&suffix)?.to_string();
+            (!tenant.contains('.')).then(|| tenant)
+        })
+    }
+
+    /// Resolves the tenant with a function, such as reading a claim of a
+    /// token.
+    #[must_use]
+    pub fn with_fn<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    {
+        self.resolvers.push(Arc::new(f));
+        self
+    }
+
+    /// Do not reject the requests without a tenant.
+    #[must_use]
+    pub fn optional(self) -> Self {
+        Self {
+            required: false,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Tenancy {
+    type Output = TenancyEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TenancyEndpoint {
+            inner: ep,
+            resolvers: self.resolvers.clone(),
+            required: self.required,
+        }
+    }
+}
+
+/// Endpoint for Tenancy middleware.
+pub struct TenancyEndpoint<E> {
+    inner: E,
+    resolvers: Vec<TenantResolver>,
+    required: bool,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for TenancyEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let tenant = self.resolvers.iter().find_map(|resolve| {
+            resolve(&req)
+                .map(|tenant| tenant.trim().to_string())
+                .filter(|tenant| !tenant.is_empty())
+        });
+
+        match tenant {
+            Some(tenant) => {
+                req.extensions_mut().insert(Tenant::new(tenant));
+            }
+            None if self.required => return Err(MissingTenantError.into()),
+            None => {}
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, EndpointExt, IntoResponse};
+
+    #[handler(internal)]
+    fn index(tenant: Option<Tenant>) -> String {
+        tenant
+            .map(|tenant| tenant.scoped_key("key"))
+            .unwrap_or_default()
+    }
+
+    async fn call(ep: &impl Endpoint, req: Request) -> Result<String> {
+        let resp = ep.call(req).await?.into_response();
+        Ok(resp.into_body().into_string().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn sources() {
+        let ep = index.with(
+            Tenancy::new()
+                .subdomain("example.com")
+                .header(Tenant::HEADER)
+                .with_fn(|req| req.uri().query().map(ToString::to_string)),
+        );
+
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::HOST, "Acme.example.com:8080")
+                    .header(Tenant::HEADER, "other")
+                    .finish()
+            )
+            .await
+            .unwrap(),
+            "acme:key"
+        );
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .header(header::HOST, "a.b.example.com")
+                    .header(Tenant::HEADER, "other")
+                    .finish()
+            )
+            .await
+            .unwrap(),
+            "other:key"
+        );
+        assert_eq!(
+            call(
+                &ep,
+                Request::builder()
+                    .uri("/?claim".parse().unwrap())
+                    .header(Tenant::HEADER, " ")
+                    .finish()
+            )
+            .await
+            .unwrap(),
+            "claim:key"
+        );
+
+        let err = call(&ep, Request::builder().finish()).await.unwrap_err();
+        assert!(err.is::<MissingTenantError>());
+        assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn scoped_key() {
+        assert_eq!(Tenant::new("acme").scoped_key("users"), "acme:users");
+        assert_eq!(Tenant::new("a:b").scoped_key("c"), "a\\:b:c");
+        assert_eq!(Tenant::new("a").scoped_key("b:c"), "a:b:c");
+        assert_eq!(Tenant::new("a\\").scoped_key("c"), "a\\\\:c");
+    }
+
+    #[tokio::test]
+    async fn optional() {
+        let ep = index.with(Tenancy::new().header(Tenant::HEADER).optional());
+        assert_eq!(call(&ep, Request::builder().finish()).await.unwrap(), "");
+    }
+}
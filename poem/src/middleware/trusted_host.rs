@@ -0,0 +1,248 @@
+use crate::{
+    error::TrustedHostError,
+    http::{header, Uri},
+    Endpoint, Middleware, Request, Result,
+};
+
+/// Middleware for rejecting the requests whose `Host` is not in the
+/// allowlist, so that the untrusted hosts do not affect the absolute URLs
+/// generated from the request.
+///
+/// The patterns are the exact host such as `example.com`, `*.example.com`
+/// for any subdomain of `example.com`, or `*` for any host. The port is
+/// ignored and the comparison is case-insensitive.
+///
+/// If [`TrustedHost::check_origin`] is enabled, the host of the `Origin`
+/// header sent by the browsers must also match the patterns.
+///
+/// # Errors
+///
+/// - [`TrustedHostError::UntrustedHost`] (`400 Bad Request`)
+/// - [`TrustedHostError::UntrustedOrigin`] (`403 Forbidden`)
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, http::StatusCode, middleware::TrustedHost, Endpoint, EndpointExt, Request,
+/// };
+///
+/// #[handler]
+/// fn index() {}
+///
+/// let app = index.with(
+///     TrustedHost::new()
+///         .allow_host("example.com")
+///         .allow_host("*.example.com"),
+/// );
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = app
+///     .call(Request::builder().header("Host", "api.example.com").finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.status(), StatusCode::OK);
+///
+/// let err = app
+///     .call(Request::builder().header("Host", "evil.com").finish())
+///     .await
+///     .unwrap_err();
+/// assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+/// # });
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TrustedHost {
+    hosts: Vec<String>,
+    check_origin: bool,
+}
+
+impl TrustedHost {
+    /// Create `TrustedHost` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Allows the hosts that match the pattern.
+    #[must_use]
+    pub fn allow_host(mut self, pattern: impl Into<String>) -> Self {
+        self.hosts.push(pattern.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Allows the hosts that match the patterns.
+    #[must_use]
+    pub fn allow_hosts<I, T>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        patterns.into_iter().fold(self, |trusted_host, pattern| {
+            trusted_host.allow_host(pattern)
+        })
+    }
+
+    /// Sets whether to check the host of the `Origin` header if it is present.
+    /// Default is `false`.
+    #[must_use]
+    pub fn check_origin(self, value: bool) -> Self {
+        Self {
+            check_origin: value,
+            ..self
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for TrustedHost {
+    type Output = TrustedHostEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        TrustedHostEndpoint {
+            inner: ep,
+            hosts: self.hosts.clone(),
+            check_origin: self.check_origin,
+        }
+    }
+}
+
+/// Endpoint for TrustedHost middleware.
+pub struct TrustedHostEndpoint<E> {
+    inner: E,
+    hosts: Vec<String>,
+    check_origin: bool,
+}
+
+/// Removes the port from the host, such as `example.com:8080` or
+/// `[::1]:8080`.
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return match host.find(']') {
+            Some(idx) => &host[..=idx],
+            None => host,
+        };
+    }
+    host.split(':').next().unwrap_or_default()
+}
+
+impl<E> TrustedHostEndpoint<E> {
+    fn is_trusted(&self, host: &str) -> bool {
+        let host = strip_port(host).to_ascii_lowercase();
+        !host.is_empty()
+            && self
+                .hosts
+                .iter()
+                .any(|pattern| match pattern.strip_prefix('*') {
+                    Some("") => true,
+                    Some(suffix) if suffix.starts_with('.') => host.ends_with(suffix),
+                    _ => *pattern == host,
+                })
+    }
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for TrustedHostEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let host = req
+            .header(header::HOST)
+            .or_else(|| req.uri().authority().map(|authority| authority.as_str()));
+        if !host.map(|host| self.is_trusted(host)).unwrap_or_default() {
+            return Err(TrustedHostError::UntrustedHost.into());
+        }
+
+        if self.check_origin {
+            if let Some(origin) = req
+                .header(header::ORIGIN)
+                .filter(|origin| *origin != "null")
+            {
+                let trusted = origin
+                    .parse::<Uri>()
+                    .ok()
+                    .and_then(|uri| {
+                        uri.authority()
+                            .map(|authority| self.is_trusted(authority.as_str()))
+                    })
+                    .unwrap_or_default();
+                if !trusted {
+                    return Err(TrustedHostError::UntrustedOrigin.into());
+                }
+            }
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::{handler, EndpointExt};
+
+    #[handler(internal)]
+    fn index() {}
+
+    fn request(host: &str, origin: Option<&str>) -> Request {
+        let mut builder = Request::builder().header(header::HOST, host);
+        if let Some(origin) = origin {
+            builder = builder.header(header::ORIGIN, origin);
+        }
+        builder.finish()
+    }
+
+    #[tokio::test]
+    async fn hosts() {
+        let ep = index.with(TrustedHost::new().allow_hosts(["Example.com", "*.example.com"]));
+
+        for host in [
+            "example.com",
+            "EXAMPLE.com:8080",
+            "api.example.com",
+            "a.b.example.com",
+        ] {
+            assert!(ep.call(request(host, None)).await.is_ok(), "{}", host);
+        }
+        for host in ["evil.com", "example.com.evil.com", "evilexample.com", ""] {
+            let err = ep.call(request(host, None)).await.unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<TrustedHostError>(),
+                Some(&TrustedHostError::UntrustedHost),
+                "{}",
+                host
+            );
+            assert_eq!(err.as_response().status(), StatusCode::BAD_REQUEST);
+        }
+        assert!(ep.call(Request::default()).await.is_err());
+
+        let ep = index.with(TrustedHost::new().allow_hosts(["[::1]", "*"]));
+        assert!(ep.call(request("anything", None)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn origin() {
+        let ep = index.with(
+            TrustedHost::new()
+                .allow_host("example.com")
+                .allow_host("*.example.com")
+                .check_origin(true),
+        );
+
+        assert!(ep.call(request("example.com", None)).await.is_ok());
+        assert!(ep
+            .call(request("example.com", Some("https://app.example.com")))
+            .await
+            .is_ok());
+        assert!(ep.call(request("example.com", Some("null"))).await.is_ok());
+
+        let err = ep
+            .call(request("example.com", Some("https://evil.com")))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<TrustedHostError>(),
+            Some(&TrustedHostError::UntrustedOrigin)
+        );
+        assert_eq!(err.as_response().status(), StatusCode::FORBIDDEN);
+    }
+}
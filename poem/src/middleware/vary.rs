@@ -0,0 +1,141 @@
+use std::convert::TryInto;
+
+use crate::{
+    http::header::HeaderName,
+    web::{vary::append_vary, VaryHeaders},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+
+/// Middleware for the `Vary` header of the response.
+///
+/// The request headers that the extractors negotiated on are recorded in
+/// [`VaryHeaders`] and added to the `Vary` header, so that the responses can
+/// be cached safely by the shared caches.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler,
+///     http::header,
+///     middleware::Vary,
+///     web::{headers::UserAgent, TypedHeader},
+///     Endpoint, EndpointExt, Request,
+/// };
+///
+/// #[handler]
+/// fn index(TypedHeader(user_agent): TypedHeader<UserAgent>) -> String {
+///     user_agent.to_string()
+/// }
+///
+/// let app = index.with(Vary::new().header("X-Tenant"));
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let resp = app
+///     .call(
+///         Request::builder()
+///             .header(header::USER_AGENT, "curl")
+///             .finish(),
+///     )
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.header(header::VARY), Some("X-Tenant, User-Agent"));
+/// # });
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Vary {
+    headers: Vec<HeaderName>,
+    ignored: Vec<HeaderName>,
+}
+
+impl Vary {
+    /// Create `Vary` middleware.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Always adds the header to the `Vary` header.
+    #[must_use]
+    pub fn header<T: TryInto<HeaderName>>(mut self, name: T) -> Self {
+        if let Ok(name) = name.try_into() {
+            self.headers.push(name);
+        }
+        self
+    }
+
+    /// Does not add the header to the `Vary` header even if it is recorded,
+    /// for example the `Authorization` header if the responses are never
+    /// cached by the shared caches.
+    #[must_use]
+    pub fn ignore<T: TryInto<HeaderName>>(mut self, name: T) -> Self {
+        if let Ok(name) = name.try_into() {
+            self.ignored.push(name);
+        }
+        self
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for Vary {
+    type Output = VaryEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        VaryEndpoint {
+            inner: ep,
+            config: self.clone(),
+        }
+    }
+}
+
+/// Endpoint for Vary middleware.
+pub struct VaryEndpoint<E> {
+    inner: E,
+    config: Vary,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for VaryEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let recorded = req
+            .extensions()
+            .get::<VaryHeaders>()
+            .cloned()
+            .unwrap_or_default();
+        req.extensions_mut().insert(recorded.clone());
+
+        let mut resp = self.inner.call(req).await?.into_response();
+        for name in self.config.headers.iter().chain(&recorded.headers()) {
+            if !self.config.ignored.contains(name) {
+                append_vary(resp.headers_mut(), name);
+            }
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header;
+
+    use super::*;
+    use crate::{handler, EndpointExt};
+
+    #[tokio::test]
+    async fn vary() {
+        #[handler(internal)]
+        fn index(req: &Request) -> Response {
+            VaryHeaders::record(req, header::ACCEPT);
+            VaryHeaders::record(req, header::AUTHORIZATION);
+            VaryHeaders::record(req, header::ACCEPT);
+            Response::builder().header(header::VARY, "Origin").finish()
+        }
+
+        let ep = index.with(Vary::new().header("X-Tenant").ignore(header::AUTHORIZATION));
+        let resp = ep.call(Request::default()).await.unwrap();
+        assert_eq!(resp.header(header::VARY), Some("Origin, X-Tenant, Accept"));
+
+        // the recorded headers are ignored without the middleware
+        let resp = index.call(Request::default()).await.unwrap();
+        assert_eq!(resp.header(header::VARY), Some("Origin"));
+    }
+}
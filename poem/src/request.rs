@@ -5,6 +5,7 @@ use std::{
     io::Error,
     pin::Pin,
     str::FromStr,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -30,6 +31,21 @@ use crate::{
     RequestBody,
 };
 
+/// The extensions of the parent requests, which are shared with the
+/// sub-requests.
+pub(crate) struct ParentData {
+    extensions: Extensions,
+    parent: Option<Arc<ParentData>>,
+}
+
+impl ParentData {
+    fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.extensions
+            .get()
+            .or_else(|| self.parent.as_ref()?.get())
+    }
+}
+
 pub(crate) struct RequestState {
     pub(crate) local_addr: LocalAddr,
     pub(crate) remote_addr: RemoteAddr,
@@ -39,6 +55,7 @@ pub(crate) struct RequestState {
     #[cfg(feature = "cookie")]
     pub(crate) cookie_jar: Option<CookieJar>,
     pub(crate) on_upgrade: Mutex<Option<OnUpgrade>>,
+    pub(crate) parent_data: Option<Arc<ParentData>>,
 }
 
 impl Default for RequestState {
@@ -52,6 +69,7 @@ impl Default for RequestState {
             #[cfg(feature = "cookie")]
             cookie_jar: None,
             on_upgrade: Default::default(),
+            parent_data: None,
         }
     }
 }
@@ -141,6 +159,7 @@ impl From<(http::Request<hyper::Body>, LocalAddr, RemoteAddr, Scheme)> for Reque
                 #[cfg(feature = "cookie")]
                 cookie_jar: None,
                 on_upgrade,
+                parent_data: None,
             },
         }
     }
@@ -182,6 +201,7 @@ impl Request {
             version: Default::default(),
             headers: Default::default(),
             extensions: Default::default(),
+            state: Default::default(),
         }
     }
 
@@ -369,9 +389,14 @@ impl Request {
     }
 
     /// Get a reference from extensions, similar to `self.extensions().get()`.
+    ///
+    /// The data of the parent requests are also returned for the sub-requests
+    /// created with [`Request::sub_request`].
     #[inline]
     pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
-        self.extensions.get()
+        self.extensions
+            .get()
+            .or_else(|| self.state.parent_data.as_ref()?.get())
     }
 
     /// Inserts a value to extensions, similar to
@@ -403,6 +428,58 @@ impl Request {
         )
     }
 
+    /// Creates a builder of a sub-request of this request, for example a
+    /// request of a batch, which has the version, the remote address, the
+    /// local address, the scheme and the cookie jar of this request.
+    ///
+    /// # Extensions
+    ///
+    /// The extensions can not be cloned, so **the extensions of this request
+    /// are moved** to a storage that is shared with the sub-requests, and
+    /// [`Request::extensions`] of this request no longer returns them. Use
+    /// [`Request::data`] or the [`Data`](crate::web::Data) extractor instead,
+    /// which read the extensions of the request and then the shared storage,
+    /// on this request and on the sub-requests alike. The extensions that are
+    /// inserted into this request afterwards are not shared with the
+    /// sub-requests that are already created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use poem::Request;
+    ///
+    /// let mut req = Request::builder().extension(100i32).finish();
+    /// let sub_req = req.sub_request().uri_str("/a").finish();
+    /// assert_eq!(sub_req.data::<i32>(), Some(&100));
+    /// assert_eq!(req.data::<i32>(), Some(&100));
+    /// assert_eq!(req.extensions().get::<i32>(), None);
+    /// ```
+    pub fn sub_request(&mut self) -> RequestBuilder {
+        if !self.extensions.is_empty() {
+            self.state.parent_data = Some(Arc::new(ParentData {
+                extensions: std::mem::take(&mut self.extensions),
+                parent: self.state.parent_data.take(),
+            }));
+        }
+
+        RequestBuilder {
+            method: Method::GET,
+            uri: Default::default(),
+            version: self.version,
+            headers: Default::default(),
+            extensions: Default::default(),
+            state: RequestState {
+                local_addr: self.state.local_addr.clone(),
+                remote_addr: self.state.remote_addr.clone(),
+                scheme: self.state.scheme.clone(),
+                #[cfg(feature = "cookie")]
+                cookie_jar: self.state.cookie_jar.clone(),
+                parent_data: self.state.parent_data.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
     /// Sets the body for this request.
     pub fn set_body(&mut self, body: impl Into<Body>) {
         self.body = body.into();
@@ -527,6 +604,7 @@ pub struct RequestBuilder {
     version: Version,
     headers: HeaderMap,
     extensions: Extensions,
+    state: RequestState,
 }
 
 impl RequestBuilder {
@@ -618,7 +696,7 @@ impl RequestBuilder {
             headers: self.headers,
             extensions: self.extensions,
             body: body.into(),
-            state: Default::default(),
+            state: self.state,
         }
     }
 
@@ -1,18 +1,23 @@
 use std::{
     convert::Infallible,
     future::Future,
+    io::{Error as IoError, ErrorKind, IoSlice},
+    pin::Pin,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    task::{Context, Poll},
 };
 
+use bytes::Bytes;
+use futures_util::Stream;
 use http::uri::Scheme;
 use hyper::server::conn::Http;
 use tokio::{
-    io::{AsyncRead, AsyncWrite, Result as IoResult},
-    sync::Notify,
-    time::Duration,
+    io::{AsyncRead, AsyncWrite, ReadBuf, Result as IoResult},
+    sync::{Notify, Semaphore},
+    time::{Duration, Sleep},
 };
 
 use crate::{
@@ -26,10 +31,66 @@ enum Either<L, A> {
     Acceptor(A),
 }
 
+/// The minimum size of the buffer of the HTTP/1 connections.
+const MIN_MAX_HEADER_SIZE: usize = 8192;
+
+#[derive(Debug, Clone)]
+struct ConnectionConfig {
+    max_connections: Option<usize>,
+    max_requests_per_connection: Option<usize>,
+    max_header_size: Option<usize>,
+    header_read_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    keep_alive: bool,
+    http2_keep_alive_interval: Option<Duration>,
+    http2_keep_alive_timeout: Option<Duration>,
+    http2_max_concurrent_streams: Option<u32>,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: None,
+            max_requests_per_connection: None,
+            max_header_size: None,
+            header_read_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            idle_timeout: None,
+            keep_alive: true,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: None,
+            http2_max_concurrent_streams: None,
+        }
+    }
+}
+
 /// An HTTP Server.
+///
+/// The limits of the connections protect the server against the slow or
+/// idle clients, such as the slowloris attacks.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use poem::{listener::TcpListener, Server};
+///
+/// let server = Server::new(TcpListener::bind("127.0.0.1:3000"))
+///     .max_connections(10000)
+///     .max_requests_per_connection(1000)
+///     .header_read_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(30))
+///     .write_timeout(Duration::from_secs(30))
+///     .idle_timeout(Duration::from_secs(60));
+/// ```
 pub struct Server<L, A> {
     listener: Either<L, A>,
     name: Option<String>,
+    config: ConnectionConfig,
 }
 
 impl<L: Listener> Server<L, Infallible> {
@@ -38,6 +99,7 @@ impl<L: Listener> Server<L, Infallible> {
         Self {
             listener: Either::Listener(listener),
             name: None,
+            config: ConnectionConfig::default(),
         }
     }
 }
@@ -48,6 +110,7 @@ impl<A: Acceptor> Server<Infallible, A> {
         Self {
             listener: Either::Acceptor(acceptor),
             name: None,
+            config: ConnectionConfig::default(),
         }
     }
 }
@@ -67,6 +130,110 @@ where
         }
     }
 
+    /// Sets the maximum number of the concurrent connections, the new
+    /// connections are not accepted until the others are closed.
+    #[must_use]
+    pub fn max_connections(mut self, max: usize) -> Self {
+        self.config.max_connections = Some(max);
+        self
+    }
+
+    /// Sets the maximum number of the requests served by a connection, the
+    /// connection is closed gracefully after the last request is served.
+    #[must_use]
+    pub fn max_requests_per_connection(mut self, max: usize) -> Self {
+        self.config.max_requests_per_connection = Some(max);
+        self
+    }
+
+    /// Sets the maximum size of the headers of the HTTP/1 requests, the
+    /// minimum is `8192`.
+    ///
+    /// Default is ~400kb.
+    #[must_use]
+    pub fn max_header_size(mut self, max: usize) -> Self {
+        self.config.max_header_size = Some(max.max(MIN_MAX_HEADER_SIZE));
+        self
+    }
+
+    /// Sets the timeout to read the headers of the HTTP/1 requests, the
+    /// connection is closed if the headers are not received in time.
+    #[must_use]
+    pub fn header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.config.header_read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the reads of the requests.
+    ///
+    /// The connection is closed if the headers of an HTTP/1 request are not
+    /// received in time, unless [`Server::header_read_timeout`] is set, and
+    /// reading the body of a request fails if no data is received in time.
+    /// The time that the handlers take to process the requests is not
+    /// limited, see [`Server::idle_timeout`] for the idle connections.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.config.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the writes to the connections, the connection is
+    /// closed if the client does not receive the data in time.
+    #[must_use]
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.config.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout of the idle connections, the connection is closed
+    /// gracefully if no requests are processed in time.
+    #[must_use]
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets whether the HTTP/1 connections are kept alive after the
+    /// responses.
+    ///
+    /// Default is `true`.
+    #[must_use]
+    pub fn keep_alive(mut self, enabled: bool) -> Self {
+        self.config.keep_alive = enabled;
+        self
+    }
+
+    /// Sets the interval of the HTTP/2 ping frames that keep the connections
+    /// alive.
+    ///
+    /// Default is disabled.
+    #[must_use]
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.config.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Sets the timeout to receive the acknowledgement of the HTTP/2 ping
+    /// frames, the connection is closed if it is not received in time.
+    ///
+    /// Default is 20 seconds, it does nothing if
+    /// [`Server::http2_keep_alive_interval`] is not set.
+    #[must_use]
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.config.http2_keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the maximum number of the concurrent streams of the HTTP/2
+    /// connections.
+    ///
+    /// Default is no limit.
+    #[must_use]
+    pub fn http2_max_concurrent_streams(mut self, max: u32) -> Self {
+        self.config.http2_max_concurrent_streams = Some(max);
+        self
+    }
+
     /// Run this server.
     pub async fn run<E>(self, ep: E) -> IoResult<()>
     where
@@ -89,8 +256,16 @@ where
         E::Endpoint: 'static,
     {
         let ep = Arc::new(ep.into_endpoint().map_to_response());
-        let Server { listener, name } = self;
+        let Server {
+            listener,
+            name,
+            config,
+        } = self;
         let name = name.as_deref();
+        let config = Arc::new(config);
+        let semaphore = config
+            .max_connections
+            .map(|max| Arc::new(Semaphore::new(max)));
         let alive_connections = Arc::new(AtomicUsize::new(0));
         let notify = Arc::new(Notify::new());
         let timeout_notify = Arc::new(Notify::new());
@@ -127,23 +302,25 @@ where
                     }
                     break;
                 },
-                res = acceptor.accept() => {
-                    if let Ok((socket, local_addr, remote_addr, scheme)) = res {
+                res = accept(&mut acceptor, semaphore.as_ref()) => {
+                    if let Ok(((socket, local_addr, remote_addr, scheme), permit)) = res {
                         let ep = ep.clone();
+                        let config = config.clone();
                         let alive_connections = alive_connections.clone();
                         let notify = notify.clone();
                         let timeout_notify = timeout_notify.clone();
 
                         tokio::spawn(async move {
+                            let _permit = permit;
                             alive_connections.fetch_add(1, Ordering::SeqCst);
 
                             if timeout.is_some() {
                                 tokio::select! {
-                                    _ = serve_connection(socket, local_addr, remote_addr, scheme, ep) => {}
+                                    _ = serve_connection(socket, local_addr, remote_addr, scheme, ep, &config) => {}
                                     _ = timeout_notify.notified() => {}
                                 }
                             } else {
-                                serve_connection(socket, local_addr, remote_addr, scheme, ep).await;
+                                serve_connection(socket, local_addr, remote_addr, scheme, ep, &config).await;
                             }
 
                             if alive_connections.fetch_sub(1, Ordering::SeqCst) == 1 {
@@ -166,31 +343,417 @@ where
     }
 }
 
+/// Accepts a connection after a permit of the maximum number of the
+/// connections is acquired.
+async fn accept<A: Acceptor + ?Sized>(
+    acceptor: &mut A,
+    semaphore: Option<&Arc<Semaphore>>,
+) -> IoResult<(
+    (A::Io, LocalAddr, RemoteAddr, Scheme),
+    Option<tokio::sync::OwnedSemaphorePermit>,
+)> {
+    let permit = match semaphore {
+        Some(semaphore) => Some(
+            semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("the semaphore is never closed"),
+        ),
+        None => None,
+    };
+    Ok((acceptor.accept().await?, permit))
+}
+
+/// The state of a connection that is shared with the requests.
+#[derive(Default)]
+struct ConnectionState {
+    requests: AtomicUsize,
+    in_flight: AtomicUsize,
+    activity: Notify,
+    closing: Notify,
+}
+
+struct InFlightGuard(Arc<ConnectionState>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.0.activity.notify_one();
+    }
+}
+
 async fn serve_connection(
     socket: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
     local_addr: LocalAddr,
     remote_addr: RemoteAddr,
     scheme: Scheme,
     ep: Arc<dyn Endpoint<Output = Response>>,
+    config: &ConnectionConfig,
 ) {
+    let state = Arc::new(ConnectionState::default());
+    let max_requests = config.max_requests_per_connection;
+    let read_timeout = config.read_timeout;
     let service = hyper::service::service_fn({
+        let state = state.clone();
         move |req: hyper::Request<hyper::Body>| {
             let ep = ep.clone();
             let local_addr = local_addr.clone();
             let remote_addr = remote_addr.clone();
             let scheme = scheme.clone();
+
+            let requests = state.requests.fetch_add(1, Ordering::SeqCst) + 1;
+            if max_requests.map(|max| requests >= max).unwrap_or_default() {
+                state.closing.notify_one();
+            }
+            state.in_flight.fetch_add(1, Ordering::SeqCst);
+            state.activity.notify_one();
+            let guard = InFlightGuard(state.clone());
+
+            let req = match read_timeout {
+                Some(timeout) => req.map(|body| {
+                    hyper::Body::wrap_stream(TimeoutBody {
+                        inner: body,
+                        timeout,
+                        deadline: None,
+                    })
+                }),
+                None => req,
+            };
+
             async move {
-                Ok::<http::Response<_>, Infallible>(
-                    ep.get_response((req, local_addr, remote_addr, scheme).into())
-                        .await
-                        .into(),
-                )
+                let resp = ep
+                    .get_response((req, local_addr, remote_addr, scheme).into())
+                    .await;
+                drop(guard);
+                Ok::<http::Response<_>, Infallible>(resp.into())
             }
         }
     });
 
-    let conn = Http::new()
-        .serve_connection(socket, service)
-        .with_upgrades();
+    let mut http = Http::new();
+    http.http1_keep_alive(config.keep_alive);
+    if let Some(max) = config.max_header_size {
+        http.max_buf_size(max);
+    }
+    if let Some(timeout) = config.header_read_timeout.or(config.read_timeout) {
+        http.http1_header_read_timeout(timeout);
+    }
+    if let Some(interval) = config.http2_keep_alive_interval {
+        http.http2_keep_alive_interval(interval);
+    }
+    if let Some(timeout) = config.http2_keep_alive_timeout {
+        http.http2_keep_alive_timeout(timeout);
+    }
+    if let Some(max) = config.http2_max_concurrent_streams {
+        http.http2_max_concurrent_streams(max);
+    }
+
+    let socket = TimeoutStream {
+        inner: socket,
+        write_timeout: config.write_timeout,
+        write_deadline: None,
+    };
+    let conn = http.serve_connection(socket, service).with_upgrades();
+    tokio::pin!(conn);
+
+    loop {
+        let idle = async {
+            match config.idle_timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => futures_util::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            _ = &mut conn => return,
+            _ = state.closing.notified() => break,
+            _ = state.activity.notified() => {}
+            _ = idle => {
+                if state.in_flight.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    conn.as_mut().graceful_shutdown();
     let _ = conn.await;
 }
+
+/// A stream that fails with [`ErrorKind::TimedOut`] if a write does not make
+/// progress in time.
+///
+/// The reads are not limited, because the connection is also read while the
+/// requests are being processed, the headers are limited by
+/// `http1_header_read_timeout` and the bodies by [`TimeoutBody`].
+struct TimeoutStream<S> {
+    inner: S,
+    write_timeout: Option<Duration>,
+    write_deadline: Option<Pin<Box<Sleep>>>,
+}
+
+/// The body of a request that fails with [`ErrorKind::TimedOut`] if a read
+/// does not make progress in time.
+struct TimeoutBody {
+    inner: hyper::Body,
+    timeout: Duration,
+    deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl Stream for TimeoutBody {
+    type Item = IoResult<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_next(cx).map(Ok);
+        match poll_timeout(res, Some(this.timeout), &mut this.deadline, cx) {
+            Poll::Ready(Ok(item)) => {
+                Poll::Ready(item.map(|res| res.map_err(|err| IoError::new(ErrorKind::Other, err))))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+fn poll_timeout<T>(
+    res: Poll<IoResult<T>>,
+    timeout: Option<Duration>,
+    deadline: &mut Option<Pin<Box<Sleep>>>,
+    cx: &mut Context<'_>,
+) -> Poll<IoResult<T>> {
+    match (res, timeout) {
+        (Poll::Pending, Some(timeout)) => {
+            let sleep = deadline.get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+            match sleep.as_mut().poll(cx) {
+                Poll::Ready(()) => {
+                    *deadline = None;
+                    Poll::Ready(Err(IoError::new(
+                        ErrorKind::TimedOut,
+                        "connection timed out",
+                    )))
+                }
+                Poll::Pending => Poll::Pending,
+            }
+        }
+        (Poll::Pending, None) => Poll::Pending,
+        (res, _) => {
+            *deadline = None;
+            res
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TimeoutStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<IoResult<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TimeoutStream<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<IoResult<usize>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_write(cx, buf);
+        poll_timeout(res, this.write_timeout, &mut this.write_deadline, cx)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_flush(cx);
+        poll_timeout(res, this.write_timeout, &mut this.write_deadline, cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<IoResult<()>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_shutdown(cx);
+        poll_timeout(res, this.write_timeout, &mut this.write_deadline, cx)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<IoResult<usize>> {
+        let this = self.get_mut();
+        let res = Pin::new(&mut this.inner).poll_write_vectored(cx, bufs);
+        poll_timeout(res, this.write_timeout, &mut this.write_deadline, cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use super::*;
+    use crate::{
+        handler,
+        listener::{BoxAcceptor, TcpListener},
+    };
+
+    #[handler(internal)]
+    fn index() -> &'static str {
+        "hello"
+    }
+
+    #[handler(internal)]
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        "hello"
+    }
+
+    #[handler(internal)]
+    fn echo(body: String) -> String {
+        body
+    }
+
+    async fn serve(
+        config: impl FnOnce(Server<Infallible, BoxAcceptor>) -> Server<Infallible, BoxAcceptor>,
+    ) -> SocketAddr {
+        serve_endpoint(index, config).await
+    }
+
+    async fn serve_endpoint(
+        ep: impl IntoEndpoint<Endpoint = impl Endpoint + 'static> + Send + 'static,
+        config: impl FnOnce(Server<Infallible, BoxAcceptor>) -> Server<Infallible, BoxAcceptor>,
+    ) -> SocketAddr {
+        let acceptor = TcpListener::bind("127.0.0.1:0")
+            .into_acceptor()
+            .await
+            .unwrap();
+        let addr = acceptor
+            .local_addr()
+            .remove(0)
+            .as_socket_addr()
+            .cloned()
+            .unwrap();
+        let server = config(Server::new_with_acceptor(acceptor.boxed()));
+        tokio::spawn(async move {
+            let _ = server.run(ep).await;
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn max_requests_per_connection() {
+        let addr = serve(|server| server.max_requests_per_connection(1)).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut resp = String::new();
+        stream.read_to_string(&mut resp).await.unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+        assert!(resp.ends_with("hello"));
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        let addr = serve(|server| server.read_timeout(Duration::from_millis(100))).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+
+        let mut resp = Vec::new();
+        let res = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut resp)).await;
+        assert!(res.is_ok());
+        assert!(resp.is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_timeout_slow_handler() {
+        let addr = serve_endpoint(slow, |server| {
+            server.read_timeout(Duration::from_millis(100))
+        })
+        .await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut buf = [0; 1024];
+        let n = stream.read(&mut buf).await.unwrap();
+        let resp = std::str::from_utf8(&buf[..n]).unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK"));
+        assert!(resp.ends_with("hello"));
+    }
+
+    #[tokio::test]
+    async fn read_timeout_body() {
+        let addr = serve_endpoint(echo, |server| {
+            server.read_timeout(Duration::from_millis(100))
+        })
+        .await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nhost: localhost\r\ncontent-length: 10\r\n\r\nab")
+            .await
+            .unwrap();
+
+        let mut buf = [0; 1024];
+        let n = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf))
+            .await
+            .unwrap()
+            .unwrap();
+        let resp = std::str::from_utf8(&buf[..n]).unwrap();
+        assert!(resp.starts_with("HTTP/1.1 "));
+        assert!(!resp.starts_with("HTTP/1.1 200"));
+    }
+
+    #[tokio::test]
+    async fn idle_timeout() {
+        let addr = serve(|server| server.idle_timeout(Duration::from_millis(100))).await;
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut resp = String::new();
+        let res =
+            tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut resp)).await;
+        assert!(res.is_ok());
+        assert!(resp.ends_with("hello"));
+    }
+
+    #[tokio::test]
+    async fn max_connections() {
+        let addr = serve(|server| server.max_connections(1)).await;
+        let mut stream1 = TcpStream::connect(addr).await.unwrap();
+        stream1
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buf = [0; 1024];
+        assert!(stream1.read(&mut buf).await.unwrap() > 0);
+
+        let mut stream2 = TcpStream::connect(addr).await.unwrap();
+        stream2
+            .write_all(b"GET / HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), stream2.read(&mut buf))
+                .await
+                .is_err()
+        );
+
+        drop(stream1);
+        assert!(stream2.read(&mut buf).await.unwrap() > 0);
+    }
+}
@@ -144,8 +144,7 @@ impl Session {
 impl<'a> FromRequest<'a> for &'a Session {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         Ok(req
-            .extensions()
-            .get::<Session>()
+            .data::<Session>()
             .expect("To use the `Session` extractor, the `CookieSession` middleware is required."))
     }
 }
@@ -0,0 +1,356 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use parking_lot::Mutex;
+use tokio::sync::{broadcast, watch};
+
+const CHANNEL_CAPACITY: usize = 16;
+
+/// An event sent to the connections of a [`ConnectionRegistry`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConnectionEvent<T> {
+    /// An event sent by [`ConnectionRegistry::broadcast`].
+    Broadcast(T),
+    /// The server is going away, the connection should be closed after
+    /// sending this event to the client.
+    Shutdown(T),
+}
+
+impl<T> ConnectionEvent<T> {
+    /// Consumes this event to return the inner value.
+    pub fn into_inner(self) -> T {
+        match self {
+            ConnectionEvent::Broadcast(value) => value,
+            ConnectionEvent::Shutdown(value) => value,
+        }
+    }
+}
+
+struct Inner<T> {
+    sender: broadcast::Sender<ConnectionEvent<T>>,
+    shutdown: Mutex<Option<T>>,
+    count: AtomicUsize,
+    count_sender: watch::Sender<usize>,
+    count_receiver: watch::Receiver<usize>,
+}
+
+/// A registry that tracks the long-lived connections, such as SSE streams and
+/// WebSockets.
+///
+/// Events can be broadcast to all the connections, and when the server is
+/// going away, [`ConnectionRegistry::shutdown`] sends a final event to every
+/// connection and closes them, so that the streaming clients are not
+/// hard-cut when draining the server.
+///
+/// Use [`ConnectionRegistry::graceful_shutdown`] to integrate with
+/// [`Server::run_with_graceful_shutdown`](crate::Server::run_with_graceful_shutdown).
+///
+/// # Example
+///
+/// ```
+/// use std::{future::Future, time::Duration};
+///
+/// use bytes::Bytes;
+/// use futures_util::{stream, StreamExt};
+/// use poem::{
+///     get, handler, listener::TcpListener, web::{ConnectionRegistry, Data}, Body, EndpointExt,
+///     Route, Server,
+/// };
+///
+/// #[handler]
+/// fn events(registry: Data<&ConnectionRegistry<Bytes>>) -> Body {
+///     Body::from_bytes_stream(
+///         registry
+///             .track(stream::pending())
+///             .map(Ok::<_, std::io::Error>),
+///     )
+/// }
+///
+/// # async fn run(signal: impl Future<Output = ()>) -> std::io::Result<()> {
+/// let registry = ConnectionRegistry::new();
+/// let app = Route::new()
+///     .at("/events", get(events))
+///     .data(registry.clone());
+///
+/// Server::new(TcpListener::bind("127.0.0.1:3000"))
+///     .run_with_graceful_shutdown(
+///         app,
+///         registry.graceful_shutdown(signal, Bytes::from_static(b"going away\n")),
+///         Some(Duration::from_secs(5)),
+///     )
+///     .await
+/// # }
+/// ```
+pub struct ConnectionRegistry<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for ConnectionRegistry<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Send + 'static> Default for ConnectionRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Send + 'static> ConnectionRegistry<T> {
+    /// Create a connection registry.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let (count_sender, count_receiver) = watch::channel(0);
+        Self {
+            inner: Arc::new(Inner {
+                sender,
+                shutdown: Mutex::new(None),
+                count: AtomicUsize::new(0),
+                count_sender,
+                count_receiver,
+            }),
+        }
+    }
+
+    /// Registers a connection.
+    ///
+    /// The returned [`Connection`] is a stream of the events sent to the
+    /// connection, it is unregistered when dropped.
+    pub fn register(&self) -> Connection<T> {
+        let receiver = self.inner.sender.subscribe();
+        let shutdown = self.inner.shutdown.lock().clone();
+        let count = self.inner.count.fetch_add(1, Ordering::SeqCst) + 1;
+        let _ = self.inner.count_sender.send(count);
+
+        Connection {
+            inner: self.inner.clone(),
+            events: events_stream(shutdown, receiver),
+            finished: false,
+        }
+    }
+
+    /// Wraps a stream of a long-lived connection, the broadcast events are
+    /// merged into the stream, and the stream ends after the shutdown event.
+    pub fn track<S>(&self, stream: S) -> TrackedStream<S, T>
+    where
+        S: Stream<Item = T>,
+    {
+        TrackedStream {
+            inner: stream,
+            connection: self.register(),
+        }
+    }
+
+    /// Returns the number of the alive connections.
+    pub fn connections(&self) -> usize {
+        self.inner.count.load(Ordering::SeqCst)
+    }
+
+    /// Sends an event to all connections, returns the number of the
+    /// connections that received it.
+    pub fn broadcast(&self, event: T) -> usize {
+        self.inner
+            .sender
+            .send(ConnectionEvent::Broadcast(event))
+            .unwrap_or_default()
+    }
+
+    /// Sends the shutdown event to all connections, and the connections
+    /// registered afterwards receive it immediately.
+    pub fn shutdown(&self, event: T) {
+        let mut shutdown = self.inner.shutdown.lock();
+        if shutdown.is_none() {
+            *shutdown = Some(event.clone());
+            let _ = self.inner.sender.send(ConnectionEvent::Shutdown(event));
+        }
+    }
+
+    /// Returns `true` if [`ConnectionRegistry::shutdown`] has been called.
+    pub fn is_shutting_down(&self) -> bool {
+        self.inner.shutdown.lock().is_some()
+    }
+
+    /// Waits for all connections to be closed.
+    pub async fn wait_closed(&self) {
+        let mut receiver = self.inner.count_receiver.clone();
+        while *receiver.borrow() > 0 {
+            if receiver.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Returns a signal for
+    /// [`Server::run_with_graceful_shutdown`](crate::Server::run_with_graceful_shutdown),
+    /// it sends the shutdown event to all connections when the `signal`
+    /// completes, then the server waits for them to close.
+    pub fn graceful_shutdown(
+        &self,
+        signal: impl Future<Output = ()>,
+        event: T,
+    ) -> impl Future<Output = ()> {
+        let registry = self.clone();
+        async move {
+            signal.await;
+            registry.shutdown(event);
+        }
+    }
+}
+
+fn events_stream<T: Clone + Send + 'static>(
+    shutdown: Option<T>,
+    receiver: broadcast::Receiver<ConnectionEvent<T>>,
+) -> BoxStream<'static, ConnectionEvent<T>> {
+    futures_util::stream::unfold(
+        (shutdown, receiver),
+        |(shutdown, mut receiver)| async move {
+            if let Some(event) = shutdown {
+                return Some((ConnectionEvent::Shutdown(event), (None, receiver)));
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, (None, receiver))),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+/// A connection registered in a [`ConnectionRegistry`].
+///
+/// It is a stream of the events sent to the connection, and ends after the
+/// shutdown event. The connection is unregistered when dropped.
+pub struct Connection<T> {
+    inner: Arc<Inner<T>>,
+    events: BoxStream<'static, ConnectionEvent<T>>,
+    finished: bool,
+}
+
+impl<T> Drop for Connection<T> {
+    fn drop(&mut self) {
+        let count = self.inner.count.fetch_sub(1, Ordering::SeqCst) - 1;
+        let _ = self.inner.count_sender.send(count);
+    }
+}
+
+impl<T> Stream for Connection<T> {
+    type Item = ConnectionEvent<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        match self.events.poll_next_unpin(cx) {
+            Poll::Ready(Some(event)) => {
+                if matches!(event, ConnectionEvent::Shutdown(_)) {
+                    self.finished = true;
+                }
+                Poll::Ready(Some(event))
+            }
+            Poll::Ready(None) => {
+                self.finished = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// A stream tracked by a [`ConnectionRegistry`].
+    ///
+    /// See also: [`ConnectionRegistry::track`]
+    pub struct TrackedStream<S, T> {
+        #[pin]
+        inner: S,
+        connection: Connection<T>,
+    }
+}
+
+impl<S, T> Stream for TrackedStream<S, T>
+where
+    S: Stream<Item = T>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if this.connection.finished {
+            return Poll::Ready(None);
+        }
+        match this.connection.poll_next_unpin(cx) {
+            Poll::Ready(Some(event)) => return Poll::Ready(Some(event.into_inner())),
+            Poll::Ready(None) | Poll::Pending => {}
+        }
+        this.inner.poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn broadcast_and_shutdown() {
+        let registry = ConnectionRegistry::new();
+        let mut stream = registry.track(futures_util::stream::pending::<i32>());
+        let mut connection = registry.register();
+        assert_eq!(registry.connections(), 2);
+
+        assert_eq!(registry.broadcast(1), 2);
+        assert_eq!(stream.next().await, Some(1));
+        assert_eq!(connection.next().await, Some(ConnectionEvent::Broadcast(1)));
+
+        registry.shutdown(100);
+        assert!(registry.is_shutting_down());
+        assert_eq!(stream.next().await, Some(100));
+        assert_eq!(stream.next().await, None);
+        assert_eq!(
+            connection.next().await,
+            Some(ConnectionEvent::Shutdown(100))
+        );
+        assert_eq!(connection.next().await, None);
+
+        drop(stream);
+        let mut stream = registry.track(futures_util::stream::pending::<i32>());
+        assert_eq!(stream.next().await, Some(100));
+        assert_eq!(stream.next().await, None);
+
+        drop(connection);
+        assert_eq!(registry.connections(), 1);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), registry.wait_closed())
+                .await
+                .is_err()
+        );
+        drop(stream);
+        tokio::time::timeout(Duration::from_secs(1), registry.wait_closed())
+            .await
+            .unwrap();
+        assert_eq!(registry.connections(), 0);
+    }
+
+    #[tokio::test]
+    async fn merge_stream() {
+        let registry = ConnectionRegistry::new();
+        let stream = registry.track(futures_util::stream::iter(vec![1, 2]));
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![1, 2]);
+        assert_eq!(registry.connections(), 0);
+    }
+}
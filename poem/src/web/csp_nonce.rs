@@ -0,0 +1,30 @@
+use std::ops::Deref;
+
+use crate::{FromRequest, Request, RequestBody, Result};
+
+/// The nonce of the `Content-Security-Policy` for the current request.
+///
+/// Add it to the `nonce` attribute of the inline `<script>` and `<style>`
+/// elements, so that they are allowed by the policy.
+///
+/// See also [`SecurityHeaders`](crate::middleware::SecurityHeaders)
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CspNonce(pub String);
+
+impl Deref for CspNonce {
+    type Target = String;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for &'a CspNonce {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(req.data::<CspNonce>().expect(
+            "To use the `CspNonce` extractor, the `SecurityHeaders` middleware with the CSP nonce \
+             is required.",
+        ))
+    }
+}
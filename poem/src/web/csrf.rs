@@ -23,8 +23,7 @@ impl Deref for CsrfToken {
 impl<'a> FromRequest<'a> for &'a CsrfToken {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         Ok(req
-            .extensions()
-            .get::<CsrfToken>()
+            .data::<CsrfToken>()
             .expect("To use the `CsrfToken` extractor, the `Csrf` middleware is required."))
     }
 }
@@ -51,8 +50,7 @@ impl CsrfVerifier {
 impl<'a> FromRequest<'a> for &'a CsrfVerifier {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         Ok(req
-            .extensions()
-            .get::<CsrfVerifier>()
+            .data::<CsrfVerifier>()
             .expect("To use the `CsrfVerifier` extractor, the `Csrf` middleware is required."))
     }
 }
@@ -41,8 +41,7 @@ impl<T> Deref for Data<T> {
 impl<'a, T: Send + Sync + 'static> FromRequest<'a> for Data<&'a T> {
     async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
         Ok(Data(
-            req.extensions()
-                .get::<T>()
+            req.data::<T>()
                 .ok_or_else(|| GetDataError(std::any::type_name::<T>()))?,
         ))
     }
@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::{http::HeaderMap, FromRequest, Request, RequestBody, Result};
+
+/// The `Request-Timeout` header, the value is the number of seconds.
+const REQUEST_TIMEOUT_HEADER: &str = "request-timeout";
+
+/// The `grpc-timeout` header, the value is an integer followed by a unit,
+/// such as `100m` (`H`, `M`, `S`, `m`, `u`, `n`).
+const GRPC_TIMEOUT_HEADER: &str = "grpc-timeout";
+
+/// The maximum timeout, the longer timeouts are capped to it. It is the
+/// largest value of the `grpc-timeout` header, `99999999H`.
+const MAX_TIMEOUT: Duration = Duration::from_secs(99_999_999 * 3600);
+
+/// The deadline of the request specified by the client.
+///
+/// The deadline is read from the `Request-Timeout` or `grpc-timeout` headers.
+/// With the [`RequestDeadline`](crate::middleware::RequestDeadline)
+/// middleware, the handler is aborted with `408 Request Timeout` when the
+/// deadline is exceeded, and the [`CancellationToken`] is cancelled so that
+/// the spawned tasks can stop their work.
+///
+/// Use [`Deadline::remaining`] or [`Deadline::grpc_timeout`] to propagate the
+/// remaining budget to the downstream services.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, middleware::RequestDeadline, web::Deadline, Endpoint, EndpointExt, Request,
+/// };
+///
+/// #[handler]
+/// fn index(deadline: Deadline) -> String {
+///     format!("{:?}", deadline.grpc_timeout())
+/// }
+///
+/// let app = index.with(RequestDeadline::new());
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut resp = app
+///     .call(Request::builder().header("grpc-timeout", "5S").finish())
+///     .await
+///     .unwrap();
+/// assert!(resp.take_body().into_string().await.unwrap().starts_with("Some"));
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Deadline {
+    expires_at: Option<Instant>,
+    token: CancellationToken,
+}
+
+impl Default for Deadline {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Deadline {
+    /// Create a deadline that expires after the timeout, or never expires if
+    /// the timeout is `None` or too long to be represented.
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self {
+            expires_at: timeout.and_then(|timeout| Instant::now().checked_add(timeout)),
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Create a deadline from the `Request-Timeout` or `grpc-timeout` headers.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::new(parse_timeout(headers))
+    }
+
+    /// Returns the instant when the deadline expires.
+    #[inline]
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.expires_at
+    }
+
+    /// Returns the remaining time before the deadline expires.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Returns `true` if the deadline is exceeded or the request is cancelled.
+    pub fn is_expired(&self) -> bool {
+        self.token.is_cancelled() || self.remaining() == Some(Duration::ZERO)
+    }
+
+    /// Returns the token that is cancelled when the deadline is exceeded.
+    #[inline]
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Returns the remaining time formatted as the value of the `grpc-timeout`
+    /// header, for propagating the deadline to the downstream services.
+    pub fn grpc_timeout(&self) -> Option<String> {
+        self.remaining().map(format_grpc_timeout)
+    }
+
+    /// Returns the remaining time formatted as the value of the
+    /// `Request-Timeout` header, for propagating the deadline to the
+    /// downstream services.
+    pub fn request_timeout(&self) -> Option<String> {
+        self.remaining()
+            .map(|remaining| format!("{:.3}", remaining.as_secs_f64()))
+    }
+}
+
+/// Parses the timeout from the `Request-Timeout` or `grpc-timeout` headers,
+/// returns the shortest one if both exist.
+///
+/// The timeouts are capped to [`MAX_TIMEOUT`].
+pub(crate) fn parse_timeout(headers: &HeaderMap) -> Option<Duration> {
+    let request_timeout = headers
+        .get(REQUEST_TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .and_then(parse_request_timeout);
+    let grpc_timeout = headers
+        .get(GRPC_TIMEOUT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_grpc_timeout);
+
+    let timeout = match (request_timeout, grpc_timeout) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    timeout.map(|timeout| timeout.min(MAX_TIMEOUT))
+}
+
+fn parse_request_timeout(secs: f64) -> Option<Duration> {
+    if secs.is_nan() || secs < 0.0 {
+        return None;
+    }
+    if secs >= MAX_TIMEOUT.as_secs_f64() {
+        return Some(MAX_TIMEOUT);
+    }
+    Some(Duration::from_secs_f64(secs))
+}
+
+fn parse_grpc_timeout(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.len() < 2 || value.len() > 9 {
+        return None;
+    }
+    let (amount, unit) = value.split_at(value.len() - 1);
+    let amount = amount.parse::<u64>().ok()?;
+    Some(match unit {
+        "H" => Duration::from_secs(amount * 3600),
+        "M" => Duration::from_secs(amount * 60),
+        "S" => Duration::from_secs(amount),
+        "m" => Duration::from_millis(amount),
+        "u" => Duration::from_micros(amount),
+        "n" => Duration::from_nanos(amount),
+        _ => return None,
+    })
+}
+
+/// Formats the timeout in milliseconds, or in the coarser units if it has
+/// more than the 8 digits that are allowed, the value is rounded down so the
+/// downstream services do not exceed the deadline.
+fn format_grpc_timeout(timeout: Duration) -> String {
+    const MAX_AMOUNT: u64 = 99_999_999;
+
+    let millis = timeout.as_millis();
+    if millis <= u128::from(MAX_AMOUNT) {
+        return format!("{}m", millis);
+    }
+    let secs = timeout.as_secs();
+    for (unit, unit_secs) in [("S", 1), ("M", 60), ("H", 3600)] {
+        if secs / unit_secs <= MAX_AMOUNT {
+            return format!("{}{}", secs / unit_secs, unit);
+        }
+    }
+    format!("{}H", MAX_AMOUNT)
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for Deadline {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(match req.data::<Deadline>() {
+            Some(deadline) => deadline.clone(),
+            None => Deadline::from_headers(req.headers()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let timeout = |name: &str, value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                crate::http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+            parse_timeout(&headers)
+        };
+
+        assert_eq!(
+            timeout(REQUEST_TIMEOUT_HEADER, "1.5"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "-1"), None);
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "abc"), None);
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "NaN"), None);
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "-inf"), None);
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "-1e300"), None);
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "1e300"), Some(MAX_TIMEOUT));
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "inf"), Some(MAX_TIMEOUT));
+        assert_eq!(timeout(REQUEST_TIMEOUT_HEADER, "1e12"), Some(MAX_TIMEOUT));
+        assert_eq!(timeout(GRPC_TIMEOUT_HEADER, "99999999H"), Some(MAX_TIMEOUT));
+        assert_eq!(
+            timeout(GRPC_TIMEOUT_HEADER, "2H"),
+            Some(Duration::from_secs(7200))
+        );
+        assert_eq!(
+            timeout(GRPC_TIMEOUT_HEADER, "3M"),
+            Some(Duration::from_secs(180))
+        );
+        assert_eq!(
+            timeout(GRPC_TIMEOUT_HEADER, "100m"),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            timeout(GRPC_TIMEOUT_HEADER, "10u"),
+            Some(Duration::from_micros(10))
+        );
+        assert_eq!(timeout(GRPC_TIMEOUT_HEADER, "10x"), None);
+        assert_eq!(timeout(GRPC_TIMEOUT_HEADER, "123456789S"), None);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_TIMEOUT_HEADER, "10".parse().unwrap());
+        headers.insert(GRPC_TIMEOUT_HEADER, "1S".parse().unwrap());
+        assert_eq!(parse_timeout(&headers), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn format_grpc() {
+        assert_eq!(format_grpc_timeout(Duration::from_millis(1500)), "1500m");
+        assert_eq!(
+            format_grpc_timeout(Duration::from_millis(99_999_999)),
+            "99999999m"
+        );
+        assert_eq!(
+            format_grpc_timeout(Duration::from_millis(100_000_000)),
+            "100000S"
+        );
+        assert_eq!(
+            format_grpc_timeout(Duration::from_secs(100_000_000)),
+            "1666666M"
+        );
+        assert_eq!(
+            format_grpc_timeout(Duration::from_secs(99_999_999 * 60 + 60)),
+            "1666666H"
+        );
+        assert_eq!(format_grpc_timeout(MAX_TIMEOUT), "99999999H");
+
+        for timeout in [Duration::from_millis(100_000_000), MAX_TIMEOUT] {
+            let value = format_grpc_timeout(timeout);
+            assert!(value.len() <= 9);
+            assert!(parse_grpc_timeout(&value).unwrap() <= timeout);
+        }
+    }
+
+    #[tokio::test]
+    async fn extractor() {
+        let req = Request::builder().header("grpc-timeout", "10S").finish();
+        let deadline = Deadline::from_request_without_body(&req).await.unwrap();
+        assert!(deadline.remaining().unwrap() <= Duration::from_secs(10));
+        assert!(!deadline.is_expired());
+
+        let req = Request::builder()
+            .header("request-timeout", "1e300")
+            .finish();
+        let deadline = Deadline::from_request_without_body(&req).await.unwrap();
+        assert!(!deadline.is_expired());
+
+        assert_eq!(Deadline::new(Some(Duration::MAX)).expires_at(), None);
+
+        let req = Request::builder().finish();
+        let deadline = Deadline::from_request_without_body(&req).await.unwrap();
+        assert_eq!(deadline.remaining(), None);
+        assert_eq!(deadline.grpc_timeout(), None);
+    }
+}
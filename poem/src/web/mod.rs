@@ -3,10 +3,13 @@
 mod addr;
 #[cfg(feature = "compression")]
 mod compress;
+mod connection_registry;
 #[cfg(feature = "cookie")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cookie")))]
 pub mod cookie;
+mod csp_nonce;
 mod data;
+pub(crate) mod deadline;
 mod form;
 mod json;
 #[cfg(feature = "multipart")]
@@ -25,7 +28,10 @@ mod tempfile;
 pub use headers;
 #[cfg(feature = "csrf")]
 mod csrf;
+mod tenant;
+mod trace_context;
 mod typed_header;
+pub(crate) mod vary;
 #[cfg(feature = "websocket")]
 #[cfg_attr(docsrs, doc(cfg(feature = "websocket")))]
 pub mod websocket;
@@ -36,9 +42,12 @@ pub use addr::{LocalAddr, RemoteAddr};
 use bytes::Bytes;
 #[cfg(feature = "compression")]
 pub use compress::{Compress, CompressionAlgo};
+pub use connection_registry::{Connection, ConnectionEvent, ConnectionRegistry, TrackedStream};
+pub use csp_nonce::CspNonce;
 #[cfg(feature = "csrf")]
 pub use csrf::{CsrfToken, CsrfVerifier};
 pub use data::Data;
+pub use deadline::Deadline;
 pub use form::Form;
 pub use json::Json;
 #[cfg(feature = "multipart")]
@@ -49,7 +58,10 @@ pub use query::Query;
 pub use redirect::Redirect;
 #[cfg(feature = "static-files")]
 pub use static_file::{StaticFileRequest, StaticFileResponse};
+pub use tenant::Tenant;
+pub use trace_context::{BaggageItem, TraceContext, TraceParent};
 pub use typed_header::TypedHeader;
+pub use vary::VaryHeaders;
 
 #[cfg(feature = "tempfile")]
 pub use self::tempfile::TempFile;
@@ -138,6 +150,25 @@ impl RequestBody {
 ///
 ///    Extracts the [`TypedHeader`] from the incoming request.
 ///
+/// - **Deadline**
+///
+///    Extracts the [`Deadline`] of the request specified by the client.
+///
+/// - **TraceContext**
+///
+///    Extracts the [`TraceContext`] from the `traceparent`, `tracestate` and
+/// `baggage` headers.
+///
+/// - **Tenant**
+///
+///    Extracts the [`Tenant`] resolved by the
+/// [`Tenancy`](crate::middleware::Tenancy) middleware.
+///
+/// - **&CspNonce**
+///
+///    Extracts the [`CspNonce`] generated by the
+/// [`SecurityHeaders`](crate::middleware::SecurityHeaders) middleware.
+///
 /// - **Path&lt;T>**
 ///
 ///    Extracts the [`Path`] from the incoming request.
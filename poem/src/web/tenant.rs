@@ -0,0 +1,94 @@
+use std::{fmt::Display, ops::Deref};
+
+use crate::{error::MissingTenantError, FromRequest, Request, RequestBody, Result};
+
+/// The tenant of the request.
+///
+/// It is resolved by the [`Tenancy`](crate::middleware::Tenancy) middleware
+/// from the subdomain, a header or a token claim, and extracting it fails
+/// with [`MissingTenantError`] if the middleware did not resolve a tenant.
+///
+/// Use [`Tenant::scoped_key`] to prefix the cache keys and rate-limit buckets,
+/// so that the tenants do not share them. The
+/// [`OpenTelemetryMetrics`](crate::middleware::OpenTelemetryMetrics)
+/// middleware adds the `tenant` label to the metrics when the tenant is
+/// resolved before it.
+///
+/// # Example
+///
+/// ```
+/// use poem::{
+///     handler, middleware::Tenancy, web::Tenant, Endpoint, EndpointExt, Request,
+/// };
+///
+/// #[handler]
+/// fn index(tenant: Tenant) -> String {
+///     tenant.scoped_key("users")
+/// }
+///
+/// let app = index.with(Tenancy::new().header(Tenant::HEADER));
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut resp = app
+///     .call(Request::builder().header("X-Tenant-Id", "acme").finish())
+///     .await
+///     .unwrap();
+/// assert_eq!(resp.take_body().into_string().await.unwrap(), "acme:users");
+/// # });
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Tenant(String);
+
+impl Tenant {
+    /// The conventional header that contains the tenant id.
+    pub const HEADER: &'static str = "X-Tenant-Id";
+
+    /// Create a tenant with the id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the id of the tenant.
+    #[inline]
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the key prefixed with the tenant id, such as `acme:users`.
+    ///
+    /// The `\` and `:` in the tenant id are escaped with a `\`, so a tenant id
+    /// cannot end the prefix early and collide with the keys of another
+    /// tenant, for example the keys of `a:b` are prefixed with `a\:b:`.
+    pub fn scoped_key(&self, key: impl Display) -> String {
+        let mut scoped = String::with_capacity(self.0.len() + 1);
+        for c in self.0.chars() {
+            if matches!(c, '\\' | ':') {
+                scoped.push('\\');
+            }
+            scoped.push(c);
+        }
+        scoped.push(':');
+        scoped.push_str(&key.to_string());
+        scoped
+    }
+}
+
+impl Deref for Tenant {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for Tenant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for Tenant {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(req.data::<Tenant>().cloned().ok_or(MissingTenantError)?)
+    }
+}
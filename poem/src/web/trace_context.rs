@@ -0,0 +1,428 @@
+use std::fmt::{self, Display, Formatter};
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::{
+    http::{HeaderMap, HeaderValue},
+    FromRequest, Request, RequestBody, Result,
+};
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+const TRACESTATE_HEADER: &str = "tracestate";
+const BAGGAGE_HEADER: &str = "baggage";
+
+const BAGGAGE_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b',')
+    .add(b';')
+    .add(b'\\')
+    .add(b'%')
+    .add(b'=');
+
+/// The parsed `traceparent` header of the
+/// [W3C Trace Context](https://www.w3.org/TR/trace-context/).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TraceParent {
+    version: u8,
+    trace_id: String,
+    parent_id: String,
+    flags: u8,
+}
+
+impl TraceParent {
+    /// Parses the value of the `traceparent` header, returns `None` if it is
+    /// invalid.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let version = parts.next().filter(|s| is_hex(s, 2))?;
+        let trace_id = parts.next().filter(|s| is_hex(s, 32))?;
+        let parent_id = parts.next().filter(|s| is_hex(s, 16))?;
+        let flags = parts.next().filter(|s| is_hex(s, 2))?;
+
+        let version = u8::from_str_radix(version, 16).ok()?;
+        if version == 0xff || (version == 0 && parts.next().is_some()) {
+            return None;
+        }
+        if trace_id.bytes().all(|b| b == b'0') || parent_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+
+        Some(Self {
+            version,
+            trace_id: trace_id.to_string(),
+            parent_id: parent_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+        })
+    }
+
+    /// Returns the version.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns the trace id, it is 32 lowercase hex characters.
+    #[inline]
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Returns the parent id, it is 16 lowercase hex characters.
+    #[inline]
+    pub fn parent_id(&self) -> &str {
+        &self.parent_id
+    }
+
+    /// Returns the trace flags.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
+    /// Returns `true` if the sampled flag is set.
+    #[inline]
+    pub fn is_sampled(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+}
+
+impl Display for TraceParent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02x}-{}-{}-{:02x}",
+            self.version, self.trace_id, self.parent_id, self.flags
+        )
+    }
+}
+
+/// A baggage item of the
+/// [W3C Baggage](https://www.w3.org/TR/baggage/).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BaggageItem {
+    /// The key.
+    pub key: String,
+    /// The decoded value.
+    pub value: String,
+    /// The properties (the raw string after the first `;`).
+    pub properties: Option<String>,
+}
+
+/// An extractor that extracts the tracing context of the request from the
+/// `traceparent`, `tracestate` and `baggage` headers, without requiring
+/// OpenTelemetry.
+///
+/// The invalid header values are ignored. Use [`TraceContext::inject`] to
+/// propagate the context to the outgoing requests.
+///
+/// # Example
+///
+/// ```
+/// use poem::{handler, http::HeaderMap, web::TraceContext, Endpoint, Request};
+///
+/// #[handler]
+/// fn index(ctx: TraceContext) -> String {
+///     let mut headers = HeaderMap::new();
+///     ctx.inject(&mut headers);
+///     // Attach the headers to the outgoing request, for example with
+///     // `reqwest::RequestBuilder::headers(headers)`.
+///     format!(
+///         "{} {}",
+///         ctx.trace_id().unwrap_or_default(),
+///         ctx.baggage("user").unwrap_or_default()
+///     )
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let mut resp = index
+///     .call(
+///         Request::builder()
+///             .header(
+///                 "traceparent",
+///                 "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+///             )
+///             .header("baggage", "user=alice")
+///             .finish(),
+///     )
+///     .await
+///     .unwrap();
+/// assert_eq!(
+///     resp.take_body().into_string().await.unwrap(),
+///     "0af7651916cd43dd8448eb211c80319c alice"
+/// );
+/// # });
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TraceContext {
+    traceparent: Option<TraceParent>,
+    tracestate: Vec<(String, String)>,
+    baggage: Vec<BaggageItem>,
+}
+
+impl TraceContext {
+    /// Create a tracing context from the `traceparent`, `tracestate` and
+    /// `baggage` headers.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let traceparent = headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(TraceParent::parse);
+
+        // The `tracestate` header is ignored if the `traceparent` header is invalid.
+        let tracestate = match traceparent {
+            Some(_) => list_members(headers, TRACESTATE_HEADER)
+                .filter_map(|member| {
+                    let (key, value) = member.split_once('=')?;
+                    Some((key.trim().to_string(), value.trim().to_string()))
+                })
+                .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let baggage = list_members(headers, BAGGAGE_HEADER)
+            .filter_map(|member| {
+                let (item, properties) = match member.split_once(';') {
+                    Some((item, properties)) => (item, Some(properties.trim().to_string())),
+                    None => (member, None),
+                };
+                let (key, value) = item.split_once('=')?;
+                let key = key.trim();
+                if key.is_empty() {
+                    return None;
+                }
+                Some(BaggageItem {
+                    key: key.to_string(),
+                    value: percent_decode_str(value.trim())
+                        .decode_utf8()
+                        .ok()?
+                        .into_owned(),
+                    properties,
+                })
+            })
+            .collect();
+
+        Self {
+            traceparent,
+            tracestate,
+            baggage,
+        }
+    }
+
+    /// Returns the `traceparent` of the request.
+    #[inline]
+    pub fn traceparent(&self) -> Option<&TraceParent> {
+        self.traceparent.as_ref()
+    }
+
+    /// Returns the trace id of the request.
+    pub fn trace_id(&self) -> Option<&str> {
+        self.traceparent.as_ref().map(TraceParent::trace_id)
+    }
+
+    /// Returns the list members of the `tracestate` header.
+    #[inline]
+    pub fn tracestate(&self) -> &[(String, String)] {
+        &self.tracestate
+    }
+
+    /// Returns the baggage items.
+    #[inline]
+    pub fn baggage_items(&self) -> &[BaggageItem] {
+        &self.baggage
+    }
+
+    /// Returns the value of a baggage item.
+    pub fn baggage(&self, key: &str) -> Option<&str> {
+        self.baggage
+            .iter()
+            .find(|item| item.key == key)
+            .map(|item| item.value.as_str())
+    }
+
+    /// Sets the value of a baggage item, which is propagated to the outgoing
+    /// requests.
+    pub fn set_baggage(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        let key = key.into();
+        let value = value.into();
+        match self.baggage.iter_mut().find(|item| item.key == key) {
+            Some(item) => {
+                item.value = value;
+                item.properties = None;
+            }
+            None => self.baggage.push(BaggageItem {
+                key,
+                value,
+                properties: None,
+            }),
+        }
+    }
+
+    /// Replaces the parent id of the `traceparent`, it should be the id of
+    /// the current span when calling the downstream services.
+    ///
+    /// It does nothing if the request does not have a valid `traceparent`
+    /// header or the parent id is not 16 hex characters.
+    #[must_use]
+    pub fn with_parent_id(mut self, parent_id: &str) -> Self {
+        if let Some(traceparent) = &mut self.traceparent {
+            if is_hex(parent_id, 16) && !parent_id.bytes().all(|b| b == b'0') {
+                traceparent.parent_id = parent_id.to_string();
+            }
+        }
+        self
+    }
+
+    /// Inserts the `traceparent`, `tracestate` and `baggage` headers into a
+    /// header map, for example the headers of an outgoing request.
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        if let Some(traceparent) = &self.traceparent {
+            if let Ok(value) = HeaderValue::from_str(&traceparent.to_string()) {
+                headers.insert(TRACEPARENT_HEADER, value);
+            }
+        }
+
+        if !self.tracestate.is_empty() {
+            let tracestate = self
+                .tracestate
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(",");
+            if let Ok(value) = HeaderValue::from_str(&tracestate) {
+                headers.insert(TRACESTATE_HEADER, value);
+            }
+        }
+
+        if !self.baggage.is_empty() {
+            let baggage = self
+                .baggage
+                .iter()
+                .map(|item| {
+                    let value = utf8_percent_encode(&item.value, BAGGAGE_ENCODE_SET);
+                    match &item.properties {
+                        Some(properties) => format!("{}={};{}", item.key, value, properties),
+                        None => format!("{}={}", item.key, value),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",");
+            if let Ok(value) = HeaderValue::from_str(&baggage) {
+                headers.insert(BAGGAGE_HEADER, value);
+            }
+        }
+    }
+
+    /// Returns the `traceparent`, `tracestate` and `baggage` headers.
+    pub fn to_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        self.inject(&mut headers);
+        headers
+    }
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len
+        && s.bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+fn list_members<'a>(headers: &'a HeaderMap, name: &str) -> impl Iterator<Item = &'a str> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+}
+
+#[async_trait::async_trait]
+impl<'a> FromRequest<'a> for TraceContext {
+    async fn from_request(req: &'a Request, _body: &mut RequestBody) -> Result<Self> {
+        Ok(TraceContext::from_headers(req.headers()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent() {
+        let traceparent =
+            TraceParent::parse("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01").unwrap();
+        assert_eq!(traceparent.version(), 0);
+        assert_eq!(traceparent.trace_id(), "0af7651916cd43dd8448eb211c80319c");
+        assert_eq!(traceparent.parent_id(), "b7ad6b7169203331");
+        assert!(traceparent.is_sampled());
+        assert_eq!(
+            traceparent.to_string(),
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        );
+
+        for value in [
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+            "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01",
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+        ] {
+            assert_eq!(TraceParent::parse(value), None, "{}", value);
+        }
+        assert!(TraceParent::parse(
+            "01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00-extra"
+        )
+        .is_some());
+    }
+
+    #[tokio::test]
+    async fn extractor() {
+        let req = Request::builder()
+            .header(
+                "traceparent",
+                "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00",
+            )
+            .header("tracestate", "rojo=00f067aa0ba902b7, congo=t61rcWkgMzE")
+            .header("baggage", "user=alice%20smith, tenant=a;ttl=10,invalid")
+            .finish();
+        let mut ctx = TraceContext::from_request_without_body(&req).await.unwrap();
+        assert!(!ctx.traceparent().unwrap().is_sampled());
+        assert_eq!(
+            ctx.tracestate(),
+            &[
+                ("rojo".to_string(), "00f067aa0ba902b7".to_string()),
+                ("congo".to_string(), "t61rcWkgMzE".to_string())
+            ]
+        );
+        assert_eq!(ctx.baggage("user"), Some("alice smith"));
+        assert_eq!(ctx.baggage("tenant"), Some("a"));
+        assert_eq!(ctx.baggage_items()[1].properties.as_deref(), Some("ttl=10"));
+        assert_eq!(ctx.baggage("invalid"), None);
+
+        ctx.set_baggage("user", "bob");
+        let headers = ctx.with_parent_id("00f067aa0ba902b7").to_headers();
+        assert_eq!(
+            headers.get("traceparent").unwrap(),
+            "00-0af7651916cd43dd8448eb211c80319c-00f067aa0ba902b7-00"
+        );
+        assert_eq!(
+            headers.get("tracestate").unwrap(),
+            "rojo=00f067aa0ba902b7,congo=t61rcWkgMzE"
+        );
+        assert_eq!(headers.get("baggage").unwrap(), "user=bob,tenant=a;ttl=10");
+    }
+
+    #[tokio::test]
+    async fn invalid_traceparent() {
+        let req = Request::builder()
+            .header("traceparent", "invalid")
+            .header("tracestate", "rojo=00f067aa0ba902b7")
+            .finish();
+        let ctx = TraceContext::from_request_without_body(&req).await.unwrap();
+        assert_eq!(ctx, TraceContext::default());
+        assert!(ctx.to_headers().is_empty());
+    }
+}
@@ -2,7 +2,9 @@ use std::ops::{Deref, DerefMut};
 
 use headers::{Header, HeaderMapExt};
 
-use crate::{error::ParseTypedHeaderError, FromRequest, Request, RequestBody, Result};
+use crate::{
+    error::ParseTypedHeaderError, web::VaryHeaders, FromRequest, Request, RequestBody, Result,
+};
 
 /// An extractor that extracts a typed header value.
 ///
@@ -59,6 +61,7 @@ impl<T> DerefMut for TypedHeader<T> {
 
 impl<T: Header> TypedHeader<T> {
     async fn internal_from_request(req: &Request) -> Result<Self, ParseTypedHeaderError> {
+        VaryHeaders::record(req, T::name().clone());
         let value = req.headers().typed_try_get::<T>()?;
         Ok(Self(value.ok_or_else(|| {
             ParseTypedHeaderError::HeaderRequired(T::name().to_string())
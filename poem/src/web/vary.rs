@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::{
+    http::{header, header::HeaderName, HeaderMap, HeaderValue},
+    Request,
+};
+
+/// The request headers that the response varies on.
+///
+/// The extractors that negotiate on a request header record it, such as
+/// [`TypedHeader`](crate::web::TypedHeader) and `Locale`, and the
+/// [`Vary`](crate::middleware::Vary) middleware adds them to the `Vary`
+/// header of the response. Recording a header without the middleware does
+/// nothing.
+#[derive(Debug, Default, Clone)]
+pub struct VaryHeaders(Arc<Mutex<Vec<HeaderName>>>);
+
+impl VaryHeaders {
+    /// Records that the response varies on the request header.
+    pub fn record(req: &Request, name: HeaderName) {
+        if let Some(vary) = req.data::<VaryHeaders>() {
+            let mut names = vary.0.lock();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    /// Returns the recorded headers.
+    pub fn headers(&self) -> Vec<HeaderName> {
+        self.0.lock().clone()
+    }
+}
+
+/// Appends the header name to the `Vary` header, unless it is already
+/// present or the `Vary` header is `*`.
+pub(crate) fn append_vary(headers: &mut HeaderMap, name: &HeaderName) {
+    let mut names = headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    if names
+        .iter()
+        .any(|item| item == "*" || item.eq_ignore_ascii_case(name.as_str()))
+    {
+        return;
+    }
+
+    // The header names are lowercase, but they are conventionally written in
+    // title case in the `Vary` header.
+    let name = name
+        .as_str()
+        .split('-')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join("-");
+    names.push(name);
+    if let Ok(value) = HeaderValue::from_str(&names.join(", ")) {
+        headers.insert(header::VARY, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append() {
+        let mut headers = HeaderMap::new();
+        append_vary(&mut headers, &header::ORIGIN);
+        append_vary(&mut headers, &header::ACCEPT_ENCODING);
+        append_vary(&mut headers, &HeaderName::from_static("origin"));
+        assert_eq!(
+            headers.get(header::VARY),
+            Some(&HeaderValue::from_static("Origin, Accept-Encoding"))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::VARY, HeaderValue::from_static("*"));
+        append_vary(&mut headers, &header::ORIGIN);
+        assert_eq!(
+            headers.get(header::VARY),
+            Some(&HeaderValue::from_static("*"))
+        );
+    }
+
+    #[test]
+    fn record_in_sub_request() {
+        let vary = VaryHeaders::default();
+        let mut req = Request::default();
+        req.extensions_mut().insert(vary.clone());
+        VaryHeaders::record(&req.sub_request().finish(), header::ACCEPT_LANGUAGE);
+        assert_eq!(vary.headers(), vec![header::ACCEPT_LANGUAGE]);
+    }
+}